@@ -0,0 +1,52 @@
+// Lists the messages the sources ask for, the keys they pass to tr! and msg!
+// as literals, in $OUT_DIR/keys.rs for the i18n tests.
+use std::env;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::Path;
+
+fn main() {
+    let mut keys = Vec::new();
+    keys_in(Path::new("src"), &mut keys);
+    keys.sort();
+    keys.dedup();
+
+    let mut out = String::from("pub const ASKED: &'static [(&'static str, &'static str)] = &[\n");
+    for (key, file) in keys {
+        out.push_str(&format!("    ({:?}, {:?}),\n", key, file));
+    }
+    out.push_str("];\n");
+    let path = Path::new(&env::var("OUT_DIR").unwrap()).join("keys.rs");
+    File::create(&path).and_then(|mut f| f.write_all(out.as_bytes())).expect("writing keys.rs");
+    println!("cargo:rerun-if-changed=src");
+}
+
+fn keys_in(dir: &Path, keys: &mut Vec<(String, String)>) {
+    for entry in fs::read_dir(dir).expect("reading src") {
+        let path = entry.unwrap().path();
+        if path.is_dir() {
+            keys_in(&path, keys);
+            continue;
+        }
+        if path.extension().map_or(true, |e| e != "rs") {
+            continue;
+        }
+        let mut source = String::new();
+        File::open(&path).and_then(|mut f| f.read_to_string(&mut source)).expect("reading a source");
+        println!("cargo:rerun-if-changed={}", path.display());
+        for line in source.lines().filter(|l| !l.trim_left().starts_with("//")) {
+            // tr!(lang, "key", ...) and msg!("key", ...)
+            let calls = line.split("tr!(").skip(1).filter_map(|call| call.find(',').map(|c| &call[c + 1..]))
+                .chain(line.split("msg!(").skip(1));
+            for call in calls {
+                let call = call.trim_left();
+                if !call.starts_with('"') {
+                    continue;
+                }
+                if let Some(end) = call[1..].find('"') {
+                    keys.push((call[1..end + 1].to_owned(), path.display().to_string()));
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,711 @@
+# English, the reference catalog: every other one has the same messages.
+# See src/i18n/fluent.rs for the syntax.
+
+## Shared
+
+no-profile = { $name } doesn't have a profile yet, use !start to create one.
+not-your-deus = You don't own that Deus.
+no-such-deus = There is no Deus { $id }.
+not-in-catalog = { $deus } is not in the catalog.
+vults = { $count ->
+    [one] { $count } vult
+   *[other] { $count } vults
+    }
+and = and
+minutes = { $count ->
+    [one] { $count } minute
+   *[other] { $count } minutes
+    }
+hours = { $count ->
+    [one] { $count } hour
+   *[other] { $count } hours
+    }
+days = { $count ->
+    [one] { $count } day
+   *[other] { $count } days
+    }
+nothing = nothing
+storage-not-found = That doesn't exist, or not anymore.
+storage-exists = That exists already.
+storage-no-vults = You don't have enough vults.
+storage-no-energy = You don't have enough energy.
+storage-closed = That's over already.
+storage-changed = That changed in the meantime, try again.
+storage-backend = Something went wrong on our side, try again in a moment.
+
+## !help
+
+help =
+    Hey, here are the commands:
+    - !start [starter], create your profile if you don't have one.
+    - !language [code], pick the language the bot answers you in.
+    - !profile [@user], show a profile card.
+    - !profile hide|show <section>, choose what others see on your card.
+    - !profile favorite <deus id>, pick the Deus shown on your card.
+    - !team, set up the Deus you take into battles.
+    - !stats <deus id>, see where a Deus' stats come from.
+    - !card <deus id>, show a Deus' card.
+    - !train <deus id> <stat> <points>, spend training points won in battles.
+    - !spells <deus id>, see the spells a Deus takes into battle and the ones it knows.
+    - !learn|!forget <deus id> <spell>, pick the spells a Deus takes into battle.
+    - !fuse <deus id> <deus id> [confirm], fuse two Deus into a new one.
+    - !ascend <deus id> [shards|<copy id>...], raise a Deus' stars with copies of it or Star Shards.
+    - !battle @user, send your active team against theirs.
+    - !battle wild, fight wild Deus for exp, it takes energy.
+    - !explore, look around for vults, it takes energy.
+    - !energy [refill], see your energy or fill it up with vults.
+    - !server, join this server's guild, fund its treasury and fight its guardians.
+    - !war, see how this server is doing in the guild war.
+    - !raid [attack], see the raid boss or attack it with everyone else.
+    - !replay <battle id> [turn|json], watch a battle again turn by turn, with a picture of each turn.
+    - !rank [@user], see where you stand in this ranked season.
+    - !ladder [season], the best rated players of a season.
+    - !tournament, run brackets with entry fees and prizes.
+    - !shop [buy <item> [count]], spend vults on items.
+    - !inventory, see your items.
+    - !dex [name|number], see the species you've come across and how far you are from completing the dex.
+    - !use <item> [deus id], use an item or give it to a Deus to hold.
+    - !quests, see today's and this week's quests.
+    - !achievements [@user], see the achievements unlocked and the ones left.
+
+## !ping
+
+pong = Pong!
+
+## !language
+
+language-current = I answer you in { $language }. Pick another with !language <code>: { $choices }. !language default goes back to your server's.
+language-set = I'll answer you in { $language } from now on.
+language-default = You're back to your server's language, { $language }.
+language-unknown = There's no { $code }, pick one of { $choices }.
+deus-named = { $deus } (id { $id })
+
+## !start
+
+start-starters = Starters are: { $names }.
+start-no-starters = There are no starters.
+start-exists = You already have a profile.
+start-welcome = Welcome! { $deus } (id { $id }) joins you, have a look with !profile and !stats { $id }.
+
+## !achievements
+
+achievements-hidden = { $name } keeps their badges hidden.
+achievements-header = **{ $name }**, { $unlocked } of { $total } achievements
+achievements-backfilled = Awarded { $awarded ->
+    [one] { $awarded } achievement
+   *[other] { $awarded } achievements
+    } to { $players ->
+    [one] { $players } player
+   *[other] { $players } players
+    }.
+
+achievement-unlocked = { $name } unlocked { $badge } **{ $achievement }**: { $criterion }!
+criterion-collection = Collect { $count } Deus
+criterion-stars = Own a { $count } star Deus
+criterion-wins = Win { $count ->
+    [one] a duel
+   *[other] { $count } duels
+    }
+criterion-win-streak = Win { $count } duels in a row
+criterion-level = Raise a Deus to level { $level }
+criterion-evolve-starters = Evolve every starter
+
+## !card and battle pictures
+
+card-usage = Usage: !card <deus id>
+render-level = Lv { $level }
+render-turn = Turn { $turn }
+
+## !ascend
+
+costs-vults = That costs { $price } vults, you have { $have }.
+ascend-usage = Usage: !ascend <deus id> [shards|<copy id>...], see what raising a Deus' stars takes, then pay with shards or copies of it.
+ascend-maxed = { $deus } already has the most stars a Deus gets, { $stars }.
+ascend-not-enough-shards = That takes { $needed } { $item }s, you have { $have }.
+ascend-wrong-copies = That takes { $needed ->
+        [one] { $needed } copy
+       *[other] { $needed } copies
+    } of { $deus }, not { $given }.
+ascend-not-a-copy = { $id } isn't another { $deus } of yours.
+ascend-changed = Those Deus or shards changed, try again.
+ascend-done = { $deus } (id { $id }) ascended to { $stars }! Its stats are at { $multiplier }%.
+ascend-spell-slot = It now takes { $slots } spells into battle.
+ascend-preview = **Ascending { $deus } (id { $id })** to { $stars } costs { $vults } vults and either { $copies ->
+        [one] { $copies } copy
+       *[other] { $copies } copies
+    } of it or { $materials } { $item }s.
+ascend-preview-stats = Its stats go from { $from }% to { $to }%.
+ascend-preview-slots = It takes { $to } spells into battle instead of { $from }.
+ascend-no-copies = You have no other { $deus }.
+ascend-copies = Your other { $deus }: { $copies }. What they hold goes back to your inventory.
+ascend-shards = You have { $count } { $item }s.
+ascend-how = Go ahead with !ascend { $id } shards, or !ascend { $id } followed by the ids of the copies.
+
+## Battle log
+
+battle-whose = { $owner }'s { $deus }
+status = { $kind ->
+        [poison] poison
+        [burn] burn
+        [stun] stun
+        [sleep] sleep
+        [shield] shield
+       *[other] { $kind }
+    }
+status-stage = { $stat } stage
+log-turn = -- Turn { $turn } --
+log-send-out = { $who } steps in.
+log-cast = { $who } casts { $spell }.
+log-missed = { $who } missed.
+log-asleep = { $who } is asleep.
+log-stunned = { $who } is stunned and can't move.
+log-damage = { $who } takes { $amount } damage{ $effect ->
+        [super] , it's super effective!
+        [weak] , it's not very effective.
+       *[normal] .
+    }
+log-absorbed = { $who }'s shield absorbs { $amount } damage.
+log-tick = { $who } takes { $amount } { $status } damage.
+log-wakes = { $who } wakes up.
+log-expired = { $who }'s { $status } wears off.
+log-fainted = { $who } faints!
+log-drank = { $who } drinks its { $item } and heals { $amount }.
+log-won = { $side } wins the battle!
+log-draw = The battle ends in a draw.
+log-immune = { $who } is { $deus_type } and can't get { $status }.
+log-out-of-action = { $who } is already out of action.
+log-capped = { $who }'s { $status } can't go any further.
+log-refreshed = { $who }'s { $status } lasts longer.
+log-poisoned = { $who } is poisoned.
+log-poison-worse = { $who }'s poison gets worse (x{ $stacks }).
+log-burned = { $who } is burned.
+log-stun = { $who } is stunned.
+log-sleep = { $who } falls asleep.
+log-shielded = { $who } is shielded ({ $amount }).
+log-stage = { $who }'s { $stat } is now { $stage }.
+
+## Menus
+
+menu-page = Page { $page }/{ $pages }
+menu-cancelled = Never mind then.
+
+## !battle
+
+battle-usage = Usage: !battle @user, !battle accept @user or !battle wild
+battle-challenged = { $theirs }, { $mine } challenges you to a rated battle! Answer with !battle accept @{ $mine } within { $minutes } minutes.
+battle-no-challenge = { $name } hasn't challenged you in the last { $minutes } minutes, challenge them with !battle @{ $name }.
+battle-yourself = You can't battle yourself.
+battle-wild-side = Wild Deus
+battle-nothing-wild = There's nothing out there to fight.
+battle-no-team = { $name } has no active team, see !team.
+battle-level-up = { $deus } grew to level { $level }!
+battle-evolved = { $from } evolved into { $into }!
+battle-header = **{ $mine }** vs **{ $theirs }**
+battle-won = { $side } wins after { $turns ->
+        [one] { $turns } turn
+       *[other] { $turns } turns
+    }.
+battle-draw = Draw after { $turns ->
+        [one] { $turns } turn
+       *[other] { $turns } turns
+    }.
+battle-ranked = Ranked: { $mine }, { $theirs }.
+battle-new-tier = { $line }, now { $tier }
+battle-replay = Watch it again with !replay { $id }.
+
+## !ladder and !rank
+
+ladder-seasons = Seasons go from 1 to { $current }.
+ladder-empty = Nobody played ranked in season { $season }.
+ladder-line = { $place }. { $name }: { $standing } ({ $rating }), { $record }
+ladder-ends = The season ends in { $days ->
+        [one] { $days } day
+       *[other] { $days } days
+    }.
+ladder-header = **Season { $season } ladder**
+rank-header = **{ $name }**, season { $season } (ends in { $days ->
+        [one] { $days } day
+       *[other] { $days } days
+    })
+rank-unplayed = No ranked games yet, rating { $rating }. Play some with !battle.
+rank-rating = { $standing }, rating { $rating } (peak { $peak })
+rank-place = #{ $place } of { $players } on the ladder
+rank-last-season = Last season: { $tier }, rating { $rating }
+rank-placement = placement { $games }/{ $of }
+rank-record = { $wins ->
+        [one] { $wins } win
+       *[other] { $wins } wins
+    }, { $losses ->
+        [one] { $losses } loss
+       *[other] { $losses } losses
+    }, { $draws ->
+        [one] { $draws } draw
+       *[other] { $draws } draws
+    }
+
+## !energy and !explore
+
+energy-filling = { $energy }/{ $max }, full in { $hours }h { $minutes }m
+energy-left = Energy { $energy }.
+energy-needed = That takes { $cost } energy, you have { $have }.
+energy-refill-offer = Fill it up now for { $price } vults with !energy refill.
+energy-full = Your energy is full already.
+energy-refilled = You paid { $price } vults, energy { $energy }.
+explore-found = You found { $vults }!
+explore-nothing = You looked around and found nothing.
+explore-taxed = { $tax } of them went to the { $guild } treasury.
+
+## !dex
+
+dex-unknown = There's no { $name } in the dex.
+dex-header = **Dex**, seen { $seen } ({ $seen_percent }%), caught { $caught } ({ $caught_percent }%)
+dex-next = Next: { $goal ->
+        [caught] catch
+       *[seen] see
+    } { $percent }% of them for { $vults } vults. See one with !dex <name|number>.
+dex-unseen = **#{ $number } ???**, you haven't come across this one yet.
+dex-evolves-at = { $name } (lvl { $level })
+dex-learns-at = { $spell } at lvl { $level }
+dex-species = **#{ $number } { $name }**, { $types }, { $rarity }, { $state ->
+        [caught] caught
+       *[seen] seen
+    }
+dex-base-stats = Base stats: { $stats }, total { $total }
+dex-evolution = Evolution: { $chain }
+dex-spells = Spells: { $spells }
+dex-learns = Learns: { $spells }
+dex-sources = Found from: { $sources }
+dex-from-wild = the wild, traps and raids
+dex-from-ladder = ladder season rewards
+dex-from-fusion = fusing { $first } and { $second }
+dex-milestone = { $name } has { $goal ->
+        [caught] caught
+       *[seen] seen
+    } { $percent }% of the dex and gets { $vults } vults!
+
+## !fuse
+
+fuse-usage = Usage: !fuse <deus id> <deus id> [confirm], see what fusing two of your Deus gives, then confirm to go ahead.
+fuse-same = Fusing takes two different Deus.
+fuse-not-yours = You don't own those Deus.
+fuse-done = { $first } and { $second } fused into { $stars } { $deus } (id { $id }), lvl { $level }!
+fuse-inherited = It inherited { $spells }.
+fuse-preview = **Fusing { $first } (id { $first_id }) and { $second } (id { $second_id })** costs { $price } vults and uses both up, what they hold goes back to your inventory. It gives:
+fuse-one-of = one of { $spells } from { $parent }
+fuse-outcome = - { $species } ({ $chance }%), inheriting { $spells }
+fuse-child = { $stars }, lvl { $level }, variance { $variance }
+fuse-how = React with { $confirm } to go ahead, or use !fuse { $first } { $second } confirm.
+
+## !inventory, !use and !shop
+
+inventory-header = **{ $name }**, { $vults }
+inventory-empty = No items, have a look at the !shop.
+inventory-held = { $deus } ({ $id }) holds a { $item }
+item-unknown = There's no item called { $name }, see !shop.
+use-usage = Usage: !use <item> [deus id]
+use-missing = You don't have a { $item }, see !shop.
+use-caught = You caught a wild { $deus } (id { $id })!
+use-evolved = { $from } evolved into { $into }!
+use-candy-level = { $deus } got { $exp } exp and grew to level { $level }!
+use-candy = { $deus } got { $exp } exp.
+use-scroll = { $deus } learned { $spell }! See !spells { $id } to take it into battle.
+use-swapped = { $deus } now holds the { $item }, the { $old } is back in your inventory.
+use-held = { $deus } now holds the { $item }.
+use-nothing-to-catch = There's nothing out there to catch.
+use-trap-on-yours = The { $item } catches wild Deus, not yours.
+use-energy-on-deus = The { $item } is for you, not your Deus.
+use-material = The { $item } is used with !ascend.
+use-which-deus = Which Deus? Use it with !use { $item } <deus id>.
+use-max-level = { $deus } can't grow any further.
+use-no-reaction = { $deus } doesn't react to the { $item }.
+use-knows-spell = { $deus } already knows { $spell }.
+use-holds-item = { $deus } already holds a { $item }.
+shop-header = **Shop**
+shop-line = { $item }, { $vults }: { $about }
+shop-how = Buy with !shop buy <item> [count].
+shop-count = You can buy 1 to { $most } at once.
+shop-not-for-sale = The { $item } isn't for sale.
+shop-bought = You bought { $count } { $item } for { $vults }, you have { $have } now.
+
+## !profile, sections keep the names they're hidden with
+
+profile-sections = Sections you can hide: { $sections }.
+profile-hidden = Your { $section } are now { $state ->
+        [hidden] hidden from
+       *[visible] visible to
+    } other players.
+profile-favorite-usage = Usage: !profile favorite <deus id>
+profile-favorite = { $deus } is now your favorite Deus.
+profile-field-hidden = { $field } (hidden)
+profile-field-vults = Vults
+profile-field-joined = Joined
+profile-field-energy = Energy
+profile-field-collection = Collection
+profile-field-favorite = Favorite
+profile-field-pvp = PvP
+profile-field-streak = Win streak
+profile-field-badges = Badges
+profile-lead = { $deus } { $stars } lvl { $level }
+profile-streak = { $streak } (best { $best })
+profile-days-ago = { $days ->
+        [0] today
+        [1] yesterday
+       *[other] { $days } days ago
+    }
+profile-no-deus = No Deus yet
+profile-deus-count = { $count } Deus
+profile-no-duels = No duels yet
+profile-pvp = { $wins }W / { $losses }L ({ $percent }%)
+
+## !quests
+
+quests-header = **{ $name }'s quests**
+quests-period = __{ $period ->
+        [daily] Daily
+       *[weekly] Weekly
+    }__, new ones in { $left }
+quests-done = ✔ { $quest }: { $objective }, done
+quests-open =
+    { $quest }: { $objective }, for { $reward }
+    { $progress }
+
+quest-completed = { $name } completed the { $period } quest { $quest } and got { $reward }!
+objective-win-battles = Win { $count ->
+    [one] a PvP battle
+   *[other] { $count } PvP battles
+    }
+objective-play-battles = Play { $count ->
+    [one] a PvP battle
+   *[other] { $count } PvP battles
+    }
+objective-capture = Catch { $count } Deus
+objective-capture-kind = Catch { $count } { $kind } Deus
+objective-evolve = Evolve { $count } Deus
+objective-spend-vults = Spend { $count } vults
+
+## !raid
+
+raid-boss = Raid boss { $boss }
+raid-nothing-wild = There's nothing out there to raid.
+raid-none = No raid boss is up right now, they show up every few hours.
+raid-header = **Raid boss { $boss }**, lvl { $level }, leaves in { $left }
+raid-life = Life { $life }
+raid-how = Attack it with !raid attack, it takes { $cost } energy.
+raid-gone = { $boss } is gone already.
+raid-attack-header = **{ $name }** vs **Raid boss { $boss }**, { $turns } turns
+raid-dealt = You dealt { $damage } damage, { $boss } has { $life } life left.
+raid-appeared = A raid boss appeared: { $boss }, lvl { $level }, with { $life } life! Attack it together with !raid attack before it leaves in { $left }.
+raid-fallen = The raid boss { $boss } has fallen! Rewards went out to { $players ->
+        [one] { $players } player
+       *[other] { $players } players
+    }.
+raid-escaped = The raid boss { $boss } got away with { $life } life left.
+raid-standing = { $place }. { $name }, { $damage } damage in { $attacks ->
+        [one] { $attacks } attack
+       *[other] { $attacks } attacks
+    }, { $tier }
+
+## Guild wars
+
+war-begun = A guild war has begun! Each guild put { $stake } vults from its treasury in. Win battles against members of the other servers until it ends in { $left }, the winning treasury takes all { $prize } vults. See !war for the scores.
+war-over-nobody = The guild war is over, nobody beat another server. Every guild gets its stake back.
+war-over-won = The guild war is over, { $guild } wins { $prize } vults for its treasury!
+war-over-tied = The guild war is over, { $guilds } tie and get { $prize } vults each!
+war-standing = { $place }. **{ $guild }**, { $wins ->
+        [one] { $wins } win
+       *[other] { $wins } wins
+    }
+
+## !server
+
+server-usage =
+    Usage:
+    - !server, the guild of this server
+    - !server create <name>, make this server a guild and become its admin
+    - !server join|leave
+    - !server contribute <vults>, put vults in the treasury
+    - !server challenge, fight the guardians, it takes energy
+    - !server tax <percent>, for admins, the treasury's cut of what members find exploring here
+    - !server admin @user, for admins, makes a member an admin
+    - !server summon, for admins, a new guardian paid from the treasury
+    - !server channel [off], for admins, hear about guild wars in this channel, or stay out of them
+    - !server language <code>|default, for admins, the language the bot answers in here when players didn't pick one
+server-not-a-server = Guilds are made in servers.
+server-created = { $guild } is a guild now, with you as its admin. Others join with !server join.
+server-exists = This server has a guild already.
+server-managers-only = Only the owner of this server, or someone who can manage it, can create its guild.
+server-no-guild = This server isn't a guild yet, see !server create.
+server-joined = Welcome to { $guild }!
+server-already-in = You're in { $guild } already.
+server-last-admin = You're the last admin, make someone else one before leaving.
+server-left = You left { $guild }.
+server-not-member = You're not in { $guild }.
+server-contributed = You put { $vults } in the { $guild } treasury, thanks!
+server-you-have = You have { $vults }.
+server-no-guardians = { $guild } has no guardians yet.
+server-guardians = { $guild }'s guardians
+server-tax-range = The tax is 0 to { $most } percent.
+server-tax-set = { $guild } now takes { $tax }% of what its members find exploring here.
+server-channel-off = { $guild } stays out of guild wars now.
+server-channel-on = { $guild } hears about guild wars in this channel, and takes part in the next one.
+server-language-set = { $guild } speaks { $language } now, to players who didn't pick a language with !language.
+server-made-admin = { $name } is an admin of { $guild } now.
+server-not-in = { $name } isn't in { $guild }.
+server-guardians-full = { $guild } has { $count } guardians, that's all it can have.
+server-nothing-to-summon = There's nothing out there to summon.
+server-summoned = { $deus } (id { $id }) now guards { $guild }!
+server-guardian-price = A guardian costs { $price } vults, the treasury has { $have }.
+server-join-first = You're not in { $guild }, see !server join.
+server-admins-only = Only admins of { $guild } can do that.
+server-header = **{ $guild }**, { $members ->
+        [one] { $members } member
+       *[other] { $members } members
+    }
+server-treasury = Treasury: { $vults }, { $tax }% tax on exploring
+server-admins = Admins: { $admins }
+server-wars-in = Guild wars in { $channel }
+server-out-of-wars = Out of guild wars, admins join them with !server channel.
+server-language = Language: { $language }
+server-no-guardians-yet = No guardians yet, admins summon one for { $price } vults.
+server-guardian = Guardian { $deus } (id { $id }), lvl { $level }
+
+## !spells, !learn and !forget
+
+spells-usage = Usage: !spells <deus id>
+learn-usage = Usage: !learn <deus id> <spell>
+forget-usage = Usage: !forget <deus id> <spell>
+spells-equipped = **{ $deus } (id { $id })** takes { $count }/{ $slots } spells into battle: { $spells }
+spells-spare = It also knows: { $spells }
+spells-no-spare = It also knows: nothing else
+spells-next = It learns { $spell } at level { $level }.
+spells-how = Change them with !learn { $id } <spell> and !forget { $id } <spell>, scrolls from the !shop teach others.
+spells-changed = { $deus } now takes { $spells } into battle.
+spells-too-many = { $deus } (id { $id }) takes { $count } spells into battle, it only has room for { $slots }.
+spells-gone = { $deus } (id { $id }) knows { $spell }, which isn't a spell anymore.
+spells-cant-take = { $deus } (id { $id }) can't take { $spell } into battle, see !spells { $id }.
+learn-unknown = { $deus } doesn't know { $spell }.
+learn-taken = { $deus } already takes { $spell } into battle.
+learn-no-room = { $deus } has no room for another spell, !forget one first.
+forget-last = { $deus } needs at least one spell.
+forget-not-taken = { $deus } doesn't take { $spell } into battle.
+
+## !stats and !train
+
+stats-usage = Usage: !stats <deus id>
+stats-no-effect = no effect
+stats-header = **{ $deus }** { $stars } lvl { $level }, { $nature } nature ({ $effect })
+stats-base = Base
+stats-variance = Var
+stats-training = Train
+stats-nature = Nature
+stats-total = Total
+stats-training-used = Training: { $used } / { $most } used, { $points } points to spend with !train.
+stats-holds = Holds a { $item }, see !shop.
+train-usage = Usage: !train <deus id> <stat> <points>, stats are { $stats }.
+train-done = { $deus } trained { $stat } by { $spent } ({ $now } now, { $points } points left).
+train-too-few = Train by at least one point.
+train-points = { $deus } only has { $points } training points.
+train-maxed = { $deus } can't train { $stat } any further.
+
+## !team
+
+team-usage =
+    Usage:
+    - !team, show your active team
+    - !team list
+    - !team new|use|delete <name>
+    - !team set <slot> <deus id>
+    - !team remove <slot>
+    - !team swap <slot> <slot>
+    - !team lead <slot>
+    - !team check [format]
+team-none = You don't have a team yet, make one with !team new <name>.
+team-listed = { $team }{ $active ->
+        [yes] {" "}(active)
+       *[no] {""}
+    } ({ $count } Deus)
+team-created = Team { $team } created.
+team-exists = You already have a team called { $team }.
+team-activated = { $team } is now your active team.
+team-deleted = Team { $team } deleted.
+team-set = { $deus } joins { $team } in slot { $slot }.
+team-emptied = Slot { $slot } of { $team } is now empty.
+team-slot-empty = That slot is empty.
+team-in-already = That Deus is already in the team.
+team-swap-empty = Both slots need a Deus to swap them.
+team-empty = Team { $team } is empty.
+team-repeats = Slot { $slot } repeats a Deus.
+team-not-owned = You no longer own the Deus in slot { $slot }.
+team-over-level = { $deus } is over the level { $level } cap of { $format }.
+team-one-species = { $format } allows one { $deus } per team.
+team-formats = Formats are: { $formats }.
+team-ready = { $team } is ready for { $format } battles.
+team-unknown = You don't have a team called { $team }.
+team-pick-first = Pick a team first with !team use <name>.
+team-slots = Slots go from 1 to { $most }.
+team-member = { $slot }. { $deus } { $stars } lvl { $level }
+team-lead = { $line } (lead)
+
+## !war
+
+war-none = No guild war yet. They open on Fridays at 18:00 UTC between guilds with a channel and { $stake } vults in their treasury for the stake, see !server channel.
+war-running = **Guild war**, { $left } left, { $prize } vults for the winning treasury
+war-over = **Guild war**, over
+war-not-in = This server isn't in it, guilds with a channel join the next one, see !server channel.
+
+## !tournament
+
+tournament-usage =
+    Usage:
+    - !tournament, list the tournaments
+    - !tournament create <single|double|swiss> <entry fee> <name>
+    - !tournament join|leave [id]
+    - !tournament start|cancel [id], for the organizer
+    - !tournament bracket [id]
+    - !tournament play [id], check in for your next match
+tournament-created = Tournament { $name } ({ $format ->
+        [single] single elimination
+        [double] double elimination
+       *[swiss] Swiss
+    }) is open, join with !tournament join { $id }. Entry is { $fee } vults.
+tournament-full = { $name } is full.
+tournament-fee-too-high = Entry fees go up to { $max } vults.
+tournament-joined = You're in { $name }.
+tournament-already-in = You already joined { $name }.
+tournament-closed = { $name } doesn't take entrants anymore.
+tournament-left = You left { $name }, your entry fee is back.
+tournament-cant-leave = You can't leave { $name } now.
+tournament-cancelled = { $name } is cancelled, entry fees are refunded.
+tournament-none-start = No tournament right now, start one with !tournament create.
+tournament-listed = { $id }. { $name }, { $format ->
+        [single] single elimination
+        [double] double elimination
+       *[swiss] Swiss
+    } with { $players } players, { $state ->
+        [open] open
+        [running] running
+       *[finished] finished
+    }
+tournament-unknown = There is no tournament { $id }.
+tournament-none = No tournament right now.
+tournament-which = There are a few tournaments, which one? See !tournament list.
+tournament-organizer-only = Only the organizer of { $name } can do that.
+tournament-too-few = { $format ->
+        [single] Single elimination
+        [double] Double elimination
+       *[swiss] Swiss
+    } needs at least { $players } players.
+tournament-started = { $name } has started! Check in for your match with !tournament play.
+tournament-already-started = { $name } has already started.
+tournament-walkover = { $name } goes through match { $number }, time ran out.
+tournament-over = **{ $name }** is over!
+tournament-not-running = { $name } isn't running.
+tournament-not-in = You're not in { $name }.
+tournament-no-match = You have no match left to play.
+tournament-no-opponent = Your next opponent isn't known yet.
+tournament-checked-in = You're checked in for match { $number } against { $foe }. They have { $left } to check in with !tournament play, or you go through.
+tournament-played = Match { $number }: { $winner } beats { $loser }. Watch it with !replay { $replay }.
+tournament-forfeit = Match { $number }: { $name } forfeits, { $why }
+tournament-already-played = Match { $number } was already played.
+tournament-header = **{ $name }**, { $format ->
+        [single] single elimination
+        [double] double elimination
+       *[swiss] Swiss
+    } with { $players } players, { $pool } vults to win
+tournament-now = Now: { $first } vs { $second }, { $left } left
+tournament-replay = replay { $id }
+tournament-walkover-short = walkover
+tournament-result = { $stage }, match { $number }: { $winner } beat { $loser } ({ $how })
+tournament-stage = { $stage ->
+        [winners] Winners round { $round }
+        [losers] Losers round { $round }
+        [final] Grand final
+       *[swiss] Round { $round }
+    }
+
+## !replay
+
+replay-usage = Usage: !replay <battle id> [turn], or !replay <battle id> json
+replay-unknown = There is no battle { $id }.
+replay-file = Battle { $id }
+replay-turns = Battle { $id } goes from turn 1 to { $last }.
+replay-header = **Battle { $id }**: { $first } vs { $second }, turn { $turn } of { $last }
+replay-next = Next: !replay { $id } { $turn }
+replay-spell-gone = This battle can't be played again: { $deus } cast { $spell }, which isn't a spell anymore.
+
+## Items, from data/items.json
+
+item-potion = Potion
+item-potion-about = Held, heals 30% of its Life once it's down to half in battle.
+item-super-potion = Super Potion
+item-super-potion-about = Held, heals 60% of its Life once it's down to half in battle.
+item-exp-candy = Exp Candy
+item-exp-candy-about = Gives a Deus 500 exp.
+item-rare-candy = Rare Candy
+item-rare-candy-about = Gives a Deus 4000 exp.
+item-junk-trap = Junk Trap
+item-junk-trap-about = Catches a wild Deus.
+item-gilded-trap = Gilded Trap
+item-gilded-trap-about = Catches a wild Deus with two stars.
+item-fire-stone = Fire Stone
+item-fire-stone-about = Evolves a Fire Deus now, whatever its level.
+item-water-stone = Water Stone
+item-water-stone-about = Evolves a Water Deus now, whatever its level.
+item-earth-stone = Earth Stone
+item-earth-stone-about = Evolves an Earth Deus now, whatever its level.
+item-wind-stone = Wind Stone
+item-wind-stone-about = Evolves a Wind Deus now, whatever its level.
+item-power-band = Power Band
+item-power-band-about = Held, +10% Atk.
+item-iron-plate = Iron Plate
+item-iron-plate-about = Held, +10% Def.
+item-swift-feather = Swift Feather
+item-swift-feather-about = Held, +10% Speed.
+item-focus-lens = Focus Lens
+item-focus-lens-about = Held, +10% Attr.
+item-life-charm = Life Charm
+item-life-charm-about = Held, +10% Life.
+item-energy-snack = Energy Snack
+item-energy-snack-about = Gives you 30 energy.
+item-energy-drink = Energy Drink
+item-energy-drink-about = Gives you 100 energy.
+item-star-shard = Star Shard
+item-star-shard-about = Raises a Deus' stars with !ascend, in place of a copy.
+item-inferno-scroll = Inferno Scroll
+item-inferno-scroll-about = Teaches a Deus Inferno.
+item-tidal-wave-scroll = Tidal Wave Scroll
+item-tidal-wave-scroll-about = Teaches a Deus Tidal Wave.
+item-quake-scroll = Quake Scroll
+item-quake-scroll-about = Teaches a Deus Quake.
+item-thunderbolt-scroll = Thunderbolt Scroll
+item-thunderbolt-scroll-about = Teaches a Deus Thunderbolt.
+item-radiance-scroll = Radiance Scroll
+item-radiance-scroll-about = Teaches a Deus Radiance.
+item-barrier-scroll = Barrier Scroll
+item-barrier-scroll-about = Teaches a Deus Barrier.
+
+## Quests, from data/quests.json
+
+quest-daily-wins = Warm up
+quest-daily-battles = Sparring
+quest-daily-catch = Trapper
+quest-daily-water = Gone fishing
+quest-daily-evolve = Growth spurt
+quest-daily-spend = Window shopping
+quest-weekly-wins = Champion
+quest-weekly-catch = Collector
+quest-weekly-shadow = Into the dark
+quest-weekly-evolve = Metamorphosis
+quest-weekly-spend = Regular customer
+
+## Achievements, from data/achievements.json
+
+achievement-collector = Collector
+achievement-hoarder = Hoarder
+achievement-rising-star = Rising star
+achievement-superstar = Superstar
+achievement-duelist = Duelist
+achievement-gladiator = Gladiator
+achievement-unstoppable = Unstoppable
+achievement-peak = Peak form
+achievement-full-circle = Full circle
@@ -0,0 +1,723 @@
+# Français, mêmes messages que en.ftl.
+
+## Partagés
+
+no-profile = { $name } n'a pas encore de profil, utilise !start pour en créer un.
+not-your-deus = Ce Deus n'est pas à toi.
+no-such-deus = Il n'y a pas de Deus { $id }.
+not-in-catalog = { $deus } n'est pas dans le catalogue.
+vults = { $count ->
+    [one] { $count } vult
+   *[other] { $count } vults
+    }
+and = et
+minutes = { $count ->
+    [one] { $count } minute
+   *[other] { $count } minutes
+    }
+hours = { $count ->
+    [one] { $count } heure
+   *[other] { $count } heures
+    }
+days = { $count ->
+    [one] { $count } jour
+   *[other] { $count } jours
+    }
+nothing = rien
+storage-not-found = Ça n'existe pas, ou plus.
+storage-exists = Ça existe déjà.
+storage-no-vults = Tu n'as pas assez de vults.
+storage-no-energy = Tu n'as pas assez d'énergie.
+storage-closed = C'est déjà fini.
+storage-changed = Ça a changé entre-temps, réessaie.
+storage-backend = Quelque chose s'est mal passé de notre côté, réessaie dans un instant.
+
+## !help
+
+help =
+    Salut, voici les commandes :
+    - !start [starter], crée ton profil si tu n'en as pas.
+    - !language [code], choisis la langue dans laquelle le bot te répond.
+    - !profile [@user], affiche une carte de profil.
+    - !profile hide|show <section>, choisis ce que les autres voient sur ta carte.
+    - !profile favorite <id deus>, choisis le Deus affiché sur ta carte.
+    - !team, prépare les Deus que tu emmènes au combat.
+    - !stats <id deus>, vois d'où viennent les stats d'un Deus.
+    - !card <id deus>, affiche la carte d'un Deus.
+    - !train <id deus> <stat> <points>, dépense les points d'entraînement gagnés en combat.
+    - !spells <id deus>, vois les sorts qu'un Deus emmène au combat et ceux qu'il connaît.
+    - !learn|!forget <id deus> <sort>, choisis les sorts qu'un Deus emmène au combat.
+    - !fuse <id deus> <id deus> [confirm], fusionne deux Deus en un nouveau.
+    - !ascend <id deus> [shards|<id copie>...], fais monter les étoiles d'un Deus avec des copies ou des Éclats d'étoile.
+    - !battle @user, envoie ton équipe active contre la sienne.
+    - !battle wild, affronte des Deus sauvages pour de l'exp, ça coûte de l'énergie.
+    - !explore, cherche des vults aux alentours, ça coûte de l'énergie.
+    - !energy [refill], vois ton énergie ou recharge-la avec des vults.
+    - !server, rejoins la guilde de ce serveur, alimente son trésor et affronte ses gardiens.
+    - !war, vois où en est ce serveur dans la guerre des guildes.
+    - !raid [attack], vois le boss de raid ou attaque-le avec tout le monde.
+    - !replay <id combat> [tour|json], revois un combat tour par tour, avec une image de chaque tour.
+    - !rank [@user], vois où tu en es dans la saison classée.
+    - !ladder [saison], les meilleurs joueurs d'une saison.
+    - !tournament, organise des tournois avec frais d'inscription et prix.
+    - !shop [buy <objet> [nombre]], dépense tes vults en objets.
+    - !inventory, vois tes objets.
+    - !dex [nom|numéro], vois les espèces que tu as croisées et où tu en es du dex.
+    - !use <objet> [id deus], utilise un objet ou donne-le à tenir à un Deus.
+    - !quests, vois les quêtes du jour et de la semaine.
+    - !achievements [@user], vois les succès débloqués et ceux qui restent.
+
+## !ping
+
+pong = Pong !
+
+## !language
+
+language-current = Je te réponds en { $language }. Choisis-en une autre avec !language <code> : { $choices }. !language default revient à celle de ton serveur.
+language-set = Je te répondrai en { $language } désormais.
+language-default = Tu es revenu à la langue de ton serveur, { $language }.
+language-unknown = Il n'y a pas de { $code }, choisis parmi { $choices }.
+deus-named = { $deus } (id { $id })
+
+## !start
+
+start-starters = Les starters sont : { $names }.
+start-no-starters = Il n'y a pas de starter.
+start-exists = Tu as déjà un profil.
+start-welcome = Bienvenue ! { $deus } (id { $id }) te rejoint, jette un œil avec !profile et !stats { $id }.
+
+## !achievements
+
+achievements-hidden = { $name } garde ses badges cachés.
+achievements-header = **{ $name }**, { $unlocked } succès sur { $total }
+achievements-backfilled = { $awarded ->
+    [one] { $awarded } succès attribué
+   *[other] { $awarded } succès attribués
+    } à { $players ->
+    [one] { $players } joueur
+   *[other] { $players } joueurs
+    }.
+
+achievement-unlocked = { $name } a débloqué { $badge } **{ $achievement }** : { $criterion } !
+criterion-collection = Posséder { $count } Deus
+criterion-stars = Posséder un Deus { $count } étoiles
+criterion-wins = Gagner { $count ->
+    [one] un duel
+   *[other] { $count } duels
+    }
+criterion-win-streak = Gagner { $count } duels d'affilée
+criterion-level = Monter un Deus au niveau { $level }
+criterion-evolve-starters = Faire évoluer chaque Deus de départ
+
+## !card et images de combat
+
+card-usage = Utilisation : !card <id deus>
+render-level = Niv { $level }
+render-turn = Tour { $turn }
+
+## !ascend
+
+costs-vults = Ça coûte { $price } vults, tu en as { $have }.
+ascend-usage = Utilisation : !ascend <id deus> [shards|<id copie>...], vois ce qu'il faut pour faire monter les étoiles d'un Deus, puis paie avec des éclats ou des copies.
+ascend-maxed = { $deus } a déjà le maximum d'étoiles qu'un Deus peut avoir, { $stars }.
+ascend-not-enough-shards = Il faut { $needed } { $item }, tu en as { $have }.
+ascend-wrong-copies = Il faut { $needed ->
+        [one] { $needed } copie
+       *[other] { $needed } copies
+    } de { $deus }, pas { $given }.
+ascend-not-a-copy = { $id } n'est pas un autre { $deus } à toi.
+ascend-changed = Ces Deus ou ces éclats ont changé, réessaie.
+ascend-done = { $deus } (id { $id }) est monté à { $stars } ! Ses stats sont à { $multiplier } %.
+ascend-spell-slot = Il emmène maintenant { $slots } sorts au combat.
+ascend-preview = **Faire monter { $deus } (id { $id })** à { $stars } coûte { $vults } vults et soit { $copies ->
+        [one] { $copies } copie
+       *[other] { $copies } copies
+    } de lui, soit { $materials } { $item }.
+ascend-preview-stats = Ses stats passent de { $from } % à { $to } %.
+ascend-preview-slots = Il emmène { $to } sorts au combat au lieu de { $from }.
+ascend-no-copies = Tu n'as pas d'autre { $deus }.
+ascend-copies = Tes autres { $deus } : { $copies }. Ce qu'ils tiennent retourne dans ton inventaire.
+ascend-shards = Tu as { $count } { $item }.
+ascend-how = Vas-y avec !ascend { $id } shards, ou !ascend { $id } suivi des ids des copies.
+
+## Journal de combat
+
+battle-whose = { $deus } de { $owner }
+status = { $kind ->
+        [poison] poison
+        [burn] brûlure
+        [stun] étourdissement
+        [sleep] sommeil
+        [shield] bouclier
+       *[other] { $kind }
+    }
+status-stage = cran de { $stat }
+log-turn = -- Tour { $turn } --
+log-send-out = { $who } entre en jeu.
+log-cast = { $who } lance { $spell }.
+log-missed = { $who } rate son coup.
+log-asleep = { $who } dort.
+log-stunned = { $who } est étourdi et ne peut pas bouger.
+log-damage = { $who } subit { $amount } dégâts{ $effect ->
+        [super] , c'est super efficace !
+        [weak] , ce n'est pas très efficace.
+       *[normal] .
+    }
+log-absorbed = Le bouclier de { $who } absorbe { $amount } dégâts.
+log-tick = { $who } subit { $amount } dégâts de { $status }.
+log-wakes = { $who } se réveille.
+log-expired = L'effet { $status } de { $who } se dissipe.
+log-fainted = { $who } est K.O. !
+log-drank = { $who } boit son { $item } et récupère { $amount }.
+log-won = { $side } remporte le combat !
+log-draw = Le combat se termine sur une égalité.
+log-immune = { $who } est de type { $deus_type } et ne craint pas l'effet { $status }.
+log-out-of-action = { $who } est déjà hors d'état d'agir.
+log-capped = L'effet { $status } de { $who } ne peut pas aller plus loin.
+log-refreshed = L'effet { $status } de { $who } dure plus longtemps.
+log-poisoned = { $who } est empoisonné.
+log-poison-worse = Le poison de { $who } s'aggrave (x{ $stacks }).
+log-burned = { $who } est brûlé.
+log-stun = { $who } est étourdi.
+log-sleep = { $who } s'endort.
+log-shielded = { $who } est protégé par un bouclier ({ $amount }).
+log-stage = { $stat } de { $who } est maintenant à { $stage }.
+
+## Menus
+
+menu-page = Page { $page } sur { $pages }
+menu-cancelled = Tant pis alors.
+
+## !battle
+
+battle-usage = Utilisation : !battle @user, !battle accept @user ou !battle wild
+battle-challenged = { $theirs }, { $mine } te défie en combat classé ! Réponds avec !battle accept @{ $mine } d'ici { $minutes } minutes.
+battle-no-challenge = { $name } ne t'a pas défié ces { $minutes } dernières minutes, défie-le avec !battle @{ $name }.
+battle-yourself = Tu ne peux pas te combattre toi-même.
+battle-wild-side = Deus sauvages
+battle-nothing-wild = Il n'y a rien à combattre par ici.
+battle-no-team = { $name } n'a pas d'équipe active, voir !team.
+battle-level-up = { $deus } passe au niveau { $level } !
+battle-evolved = { $from } évolue en { $into } !
+battle-header = **{ $mine }** contre **{ $theirs }**
+battle-won = { $side } gagne en { $turns ->
+        [one] { $turns } tour
+       *[other] { $turns } tours
+    }.
+battle-draw = Match nul en { $turns ->
+        [one] { $turns } tour
+       *[other] { $turns } tours
+    }.
+battle-ranked = Classé : { $mine }, { $theirs }.
+battle-new-tier = { $line }, désormais { $tier }
+battle-replay = Revois-le avec !replay { $id }.
+
+## !ladder et !rank
+
+ladder-seasons = Les saisons vont de 1 à { $current }.
+ladder-empty = Personne n'a joué en classé pendant la saison { $season }.
+ladder-line = { $place }. { $name } : { $standing } ({ $rating }), { $record }
+ladder-ends = La saison se termine dans { $days ->
+        [one] { $days } jour
+       *[other] { $days } jours
+    }.
+ladder-header = **Classement de la saison { $season }**
+rank-header = **{ $name }**, saison { $season } (fin dans { $days ->
+        [one] { $days } jour
+       *[other] { $days } jours
+    })
+rank-unplayed = Pas encore de partie classée, cote { $rating }. Joues-en avec !battle.
+rank-rating = { $standing }, cote { $rating } (record { $peak })
+rank-place = { $place ->
+        [1] 1er
+       *[other] { $place }e
+    } sur { $players } au classement
+rank-last-season = Saison dernière : { $tier }, cote { $rating }
+rank-placement = placement { $games }/{ $of }
+rank-record = { $wins ->
+        [one] { $wins } victoire
+       *[other] { $wins } victoires
+    }, { $losses ->
+        [one] { $losses } défaite
+       *[other] { $losses } défaites
+    }, { $draws ->
+        [one] { $draws } nul
+       *[other] { $draws } nuls
+    }
+
+## !energy et !explore
+
+energy-filling = { $energy }/{ $max }, pleine dans { $hours }h{ $minutes }
+energy-left = Énergie { $energy }.
+energy-needed = Il faut { $cost } d'énergie, tu as { $have }.
+energy-refill-offer = Recharge-la maintenant pour { $price } vults avec !energy refill.
+energy-full = Ton énergie est déjà pleine.
+energy-refilled = Tu as payé { $price } vults, énergie { $energy }.
+explore-found = Tu as trouvé { $vults } !
+explore-nothing = Tu as regardé partout sans rien trouver.
+explore-taxed = { $tax } sont allés au trésor de { $guild }.
+
+## !dex
+
+dex-unknown = Il n'y a pas de { $name } dans le dex.
+dex-header = **Dex**, vus { $seen } ({ $seen_percent } %), capturés { $caught } ({ $caught_percent } %)
+dex-next = Prochain palier : { $goal ->
+        [caught] capturer
+       *[seen] voir
+    } { $percent } % d'entre eux pour { $vults } vults. Vois-en un avec !dex <nom|numéro>.
+dex-unseen = **#{ $number } ???**, tu n'as pas encore croisé celui-ci.
+dex-evolves-at = { $name } (niv. { $level })
+dex-learns-at = { $spell } au niv. { $level }
+dex-species = **#{ $number } { $name }**, { $types }, { $rarity ->
+        [Common] Commun
+        [Uncommon] Peu commun
+        [Rare] Rare
+        [Epic] Épique
+        [Legendary] Légendaire
+       *[other] { $rarity }
+    }, { $state ->
+        [caught] capturé
+       *[seen] vu
+    }
+dex-base-stats = Stats de base : { $stats }, total { $total }
+dex-evolution = Évolution : { $chain }
+dex-spells = Sorts : { $spells }
+dex-learns = Apprend : { $spells }
+dex-sources = Se trouve via : { $sources }
+dex-from-wild = la nature, les pièges et les raids
+dex-from-ladder = les récompenses de saison classée
+dex-from-fusion = la fusion de { $first } et { $second }
+dex-milestone = { $name } a { $goal ->
+        [caught] capturé
+       *[seen] vu
+    } { $percent } % du dex et gagne { $vults } vults !
+
+## !fuse
+
+fuse-usage = Utilisation : !fuse <id deus> <id deus> [confirm], vois ce que donne la fusion de deux de tes Deus, puis confirme pour la lancer.
+fuse-same = Une fusion demande deux Deus différents.
+fuse-not-yours = Ces Deus ne sont pas à toi.
+fuse-done = { $first } et { $second } ont fusionné en { $stars } { $deus } (id { $id }), niv. { $level } !
+fuse-inherited = Il a hérité de { $spells }.
+fuse-preview = **Fusionner { $first } (id { $first_id }) et { $second } (id { $second_id })** coûte { $price } vults et les consomme tous les deux, ce qu'ils portent retourne dans ton inventaire. Ça donne :
+fuse-one-of = un sort parmi { $spells } venant de { $parent }
+fuse-outcome = - { $species } ({ $chance } %), héritant de { $spells }
+fuse-child = { $stars }, niv. { $level }, variance { $variance }
+fuse-how = Réagis avec { $confirm } pour lancer la fusion, ou utilise !fuse { $first } { $second } confirm.
+
+## !inventory, !use et !shop
+
+inventory-header = **{ $name }**, { $vults }
+inventory-empty = Aucun objet, jette un œil au !shop.
+inventory-held = { $deus } ({ $id }) porte : { $item }
+item-unknown = Il n'y a pas d'objet appelé { $name }, voir !shop.
+use-usage = Utilisation : !use <objet> [id deus]
+use-missing = Tu n'as pas de { $item }, voir !shop.
+use-caught = Tu as capturé un { $deus } sauvage (id { $id }) !
+use-evolved = { $from } a évolué en { $into } !
+use-candy-level = { $deus } gagne { $exp } exp et passe au niveau { $level } !
+use-candy = { $deus } gagne { $exp } exp.
+use-scroll = { $deus } a appris { $spell } ! Vois !spells { $id } pour l'emmener au combat.
+use-swapped = { $deus } porte maintenant : { $item }, { $old } retourne dans ton inventaire.
+use-held = { $deus } porte maintenant : { $item }.
+use-nothing-to-catch = Il n'y a rien à capturer dehors.
+use-trap-on-yours = { $item } capture les Deus sauvages, pas les tiens.
+use-energy-on-deus = { $item } est pour toi, pas pour ton Deus.
+use-material = { $item } s'utilise avec !ascend.
+use-which-deus = Quel Deus ? Utilise-le avec !use { $item } <id deus>.
+use-max-level = { $deus } ne peut pas grandir davantage.
+use-no-reaction = { $deus } ne réagit pas à : { $item }.
+use-knows-spell = { $deus } connaît déjà { $spell }.
+use-holds-item = { $deus } porte déjà : { $item }.
+shop-header = **Boutique**
+shop-line = { $item }, { $vults } : { $about }
+shop-how = Achète avec !shop buy <objet> [nombre].
+shop-count = Tu peux en acheter de 1 à { $most } à la fois.
+shop-not-for-sale = { $item } n'est pas à vendre.
+shop-bought = Tu as acheté { $count } { $item } pour { $vults }, tu en as { $have } maintenant.
+
+## !profile, les sections gardent le nom qui sert à les cacher
+
+profile-sections = Sections que tu peux cacher : { $sections }.
+profile-hidden = Ta section { $section } est maintenant { $state ->
+        [hidden] cachée aux
+       *[visible] visible par les
+    } autres joueurs.
+profile-favorite-usage = Utilisation : !profile favorite <id deus>
+profile-favorite = { $deus } est maintenant ton Deus favori.
+profile-field-hidden = { $field } (caché)
+profile-field-vults = Vults
+profile-field-joined = Arrivé
+profile-field-energy = Énergie
+profile-field-collection = Collection
+profile-field-favorite = Favori
+profile-field-pvp = JcJ
+profile-field-streak = Série de victoires
+profile-field-badges = Badges
+profile-lead = { $deus } { $stars } niv. { $level }
+profile-streak = { $streak } (record { $best })
+profile-days-ago = { $days ->
+        [0] aujourd'hui
+        [1] hier
+       *[other] il y a { $days } jours
+    }
+profile-no-deus = Pas encore de Deus
+profile-deus-count = { $count } Deus
+profile-no-duels = Pas encore de duel
+profile-pvp = { $wins }V / { $losses }D ({ $percent } %)
+
+## !quests
+
+quests-header = **Quêtes de { $name }**
+quests-period = __{ $period ->
+        [daily] Quotidiennes
+       *[weekly] Hebdomadaires
+    }__, nouvelles dans { $left }
+quests-done = ✔ { $quest } : { $objective }, terminée
+quests-open =
+    { $quest } : { $objective }, pour { $reward }
+    { $progress }
+
+quest-completed = { $name } a terminé la quête { $period ->
+        [daily] quotidienne
+       *[weekly] hebdomadaire
+    } { $quest } et gagne { $reward } !
+objective-win-battles = Gagner { $count ->
+    [one] un combat JcJ
+   *[other] { $count } combats JcJ
+    }
+objective-play-battles = Jouer { $count ->
+    [one] un combat JcJ
+   *[other] { $count } combats JcJ
+    }
+objective-capture = Capturer { $count } Deus
+objective-capture-kind = Capturer { $count } Deus de type { $kind }
+objective-evolve = Faire évoluer { $count } Deus
+objective-spend-vults = Dépenser { $count } vults
+
+## !raid
+
+raid-boss = Boss de raid { $boss }
+raid-nothing-wild = Il n'y a rien à affronter dehors.
+raid-none = Aucun boss de raid pour l'instant, il en apparaît un toutes les quelques heures.
+raid-header = **Boss de raid { $boss }**, niv. { $level }, part dans { $left }
+raid-life = Vie { $life }
+raid-how = Attaque-le avec !raid attack, ça coûte { $cost } d'énergie.
+raid-gone = { $boss } est déjà parti.
+raid-attack-header = **{ $name }** contre **Boss de raid { $boss }**, { $turns } tours
+raid-dealt = Tu as infligé { $damage } dégâts, il reste { $life } de vie à { $boss }.
+raid-appeared = Un boss de raid est apparu : { $boss }, niv. { $level }, avec { $life } de vie ! Attaquez-le ensemble avec !raid attack avant qu'il parte dans { $left }.
+raid-fallen = Le boss de raid { $boss } est tombé ! Les récompenses sont allées à { $players ->
+        [one] { $players } joueur
+       *[other] { $players } joueurs
+    }.
+raid-escaped = Le boss de raid { $boss } s'est enfui avec { $life } de vie.
+raid-standing = { $place }. { $name }, { $damage } dégâts en { $attacks ->
+        [one] { $attacks } attaque
+       *[other] { $attacks } attaques
+    }, { $tier }
+
+## Guerres de guildes
+
+war-begun = Une guerre de guildes a commencé ! Chaque guilde a misé { $stake } vults de son trésor. Gagnez des combats contre les membres des autres serveurs jusqu'à sa fin dans { $left }, le trésor gagnant remporte les { $prize } vults. Voir !war pour les scores.
+war-over-nobody = La guerre de guildes est finie, personne n'a battu un autre serveur. Chaque guilde récupère sa mise.
+war-over-won = La guerre de guildes est finie, { $guild } gagne { $prize } vults pour son trésor !
+war-over-tied = La guerre de guildes est finie, { $guilds } sont à égalité et reçoivent { $prize } vults chacune !
+war-standing = { $place }. **{ $guild }**, { $wins ->
+        [one] { $wins } victoire
+       *[other] { $wins } victoires
+    }
+
+## !server
+
+server-usage =
+    Utilisation :
+    - !server, la guilde de ce serveur
+    - !server create <nom>, fais de ce serveur une guilde et deviens-en l'admin
+    - !server join|leave
+    - !server contribute <vults>, verse des vults au trésor
+    - !server challenge, affronte les gardiens, ça coûte de l'énergie
+    - !server tax <pourcentage>, pour les admins, la part du trésor sur ce que les membres trouvent en explorant ici
+    - !server admin @user, pour les admins, fait d'un membre un admin
+    - !server summon, pour les admins, un nouveau gardien payé par le trésor
+    - !server channel [off], pour les admins, suis les guerres de guildes dans ce salon, ou restes-en à l'écart
+    - !server language <code>|default, pour les admins, la langue du bot ici pour les joueurs qui n'en ont pas choisi
+server-not-a-server = Les guildes se créent dans les serveurs.
+server-created = { $guild } est maintenant une guilde, avec toi comme admin. Les autres la rejoignent avec !server join.
+server-exists = Ce serveur a déjà une guilde.
+server-managers-only = Seul le propriétaire de ce serveur, ou quelqu'un qui peut le gérer, peut créer sa guilde.
+server-no-guild = Ce serveur n'est pas encore une guilde, voir !server create.
+server-joined = Bienvenue dans { $guild } !
+server-already-in = Tu es déjà dans { $guild }.
+server-last-admin = Tu es le dernier admin, nomme quelqu'un d'autre avant de partir.
+server-left = Tu as quitté { $guild }.
+server-not-member = Tu n'es pas dans { $guild }.
+server-contributed = Tu as versé { $vults } au trésor de { $guild }, merci !
+server-you-have = Tu as { $vults }.
+server-no-guardians = { $guild } n'a pas encore de gardiens.
+server-guardians = Gardiens de { $guild }
+server-tax-range = La taxe va de 0 à { $most } pour cent.
+server-tax-set = { $guild } prend maintenant { $tax } % de ce que ses membres trouvent en explorant ici.
+server-channel-off = { $guild } reste maintenant à l'écart des guerres de guildes.
+server-channel-on = { $guild } suit les guerres de guildes dans ce salon et participe à la prochaine.
+server-language-set = { $guild } parle maintenant { $language } aux joueurs qui n'ont pas choisi de langue avec !language.
+server-made-admin = { $name } est maintenant admin de { $guild }.
+server-not-in = { $name } n'est pas dans { $guild }.
+server-guardians-full = { $guild } a { $count } gardiens, c'est le maximum.
+server-nothing-to-summon = Il n'y a rien à invoquer par ici.
+server-summoned = { $deus } (id { $id }) garde maintenant { $guild } !
+server-guardian-price = Un gardien coûte { $price } vults, le trésor en a { $have }.
+server-join-first = Tu n'es pas dans { $guild }, voir !server join.
+server-admins-only = Seuls les admins de { $guild } peuvent faire ça.
+server-header = **{ $guild }**, { $members ->
+        [one] { $members } membre
+       *[other] { $members } membres
+    }
+server-treasury = Trésor : { $vults }, taxe de { $tax } % sur l'exploration
+server-admins = Admins : { $admins }
+server-wars-in = Guerres de guildes dans { $channel }
+server-out-of-wars = Hors des guerres de guildes, les admins les rejoignent avec !server channel.
+server-language = Langue : { $language }
+server-no-guardians-yet = Pas encore de gardiens, les admins en invoquent un pour { $price } vults.
+server-guardian = Gardien { $deus } (id { $id }), niv. { $level }
+
+## !spells, !learn et !forget
+
+spells-usage = Utilisation : !spells <id deus>
+learn-usage = Utilisation : !learn <id deus> <sort>
+forget-usage = Utilisation : !forget <id deus> <sort>
+spells-equipped = **{ $deus } (id { $id })** emmène { $count }/{ $slots } sorts au combat : { $spells }
+spells-spare = Il connaît aussi : { $spells }
+spells-no-spare = Il ne connaît rien d'autre.
+spells-next = Il apprend { $spell } au niveau { $level }.
+spells-how = Change-les avec !learn { $id } <sort> et !forget { $id } <sort>, les parchemins du !shop en enseignent d'autres.
+spells-changed = { $deus } emmène maintenant { $spells } au combat.
+spells-too-many = { $deus } (id { $id }) emmène { $count } sorts au combat, il n'a de place que pour { $slots }.
+spells-gone = { $deus } (id { $id }) connaît { $spell }, qui n'est plus un sort.
+spells-cant-take = { $deus } (id { $id }) ne peut pas emmener { $spell } au combat, voir !spells { $id }.
+learn-unknown = { $deus } ne connaît pas { $spell }.
+learn-taken = { $deus } emmène déjà { $spell } au combat.
+learn-no-room = { $deus } n'a plus de place pour un sort, !forget d'abord un autre.
+forget-last = { $deus } a besoin d'au moins un sort.
+forget-not-taken = { $deus } n'emmène pas { $spell } au combat.
+
+## !stats et !train
+
+stats-usage = Utilisation : !stats <id deus>
+stats-no-effect = sans effet
+stats-header = **{ $deus }** { $stars } niv. { $level }, nature { $nature } ({ $effect })
+stats-base = Base
+stats-variance = Var
+stats-training = Entr
+stats-nature = Nature
+stats-total = Total
+stats-training-used = Entraînement : { $used } / { $most } utilisés, { $points } points à dépenser avec !train.
+stats-holds = Porte : { $item }, voir !shop.
+train-usage = Utilisation : !train <id deus> <stat> <points>, les stats sont { $stats }.
+train-done = { $deus } a entraîné { $stat } de { $spent } ({ $now } maintenant, { $points } points restants).
+train-too-few = Entraîne d'au moins un point.
+train-points = { $deus } n'a que { $points } points d'entraînement.
+train-maxed = { $deus } ne peut plus entraîner { $stat }.
+
+## !team
+
+team-usage =
+    Utilisation :
+    - !team, affiche ton équipe active
+    - !team list
+    - !team new|use|delete <nom>
+    - !team set <emplacement> <id deus>
+    - !team remove <emplacement>
+    - !team swap <emplacement> <emplacement>
+    - !team lead <emplacement>
+    - !team check [format]
+team-none = Tu n'as pas encore d'équipe, crées-en une avec !team new <nom>.
+team-listed = { $team }{ $active ->
+        [yes] {" "}(active)
+       *[no] {""}
+    } ({ $count } Deus)
+team-created = Équipe { $team } créée.
+team-exists = Tu as déjà une équipe appelée { $team }.
+team-activated = { $team } est maintenant ton équipe active.
+team-deleted = Équipe { $team } supprimée.
+team-set = { $deus } rejoint { $team } à l'emplacement { $slot }.
+team-emptied = L'emplacement { $slot } de { $team } est maintenant vide.
+team-slot-empty = Cet emplacement est vide.
+team-in-already = Ce Deus est déjà dans l'équipe.
+team-swap-empty = Les deux emplacements doivent avoir un Deus pour les échanger.
+team-empty = L'équipe { $team } est vide.
+team-repeats = L'emplacement { $slot } répète un Deus.
+team-not-owned = Le Deus de l'emplacement { $slot } n'est plus à toi.
+team-over-level = { $deus } dépasse le niveau { $level } maximum de { $format }.
+team-one-species = { $format } n'autorise qu'un { $deus } par équipe.
+team-formats = Les formats sont : { $formats }.
+team-ready = { $team } est prête pour les combats { $format }.
+team-unknown = Tu n'as pas d'équipe appelée { $team }.
+team-pick-first = Choisis d'abord une équipe avec !team use <nom>.
+team-slots = Les emplacements vont de 1 à { $most }.
+team-member = { $slot }. { $deus } { $stars } niv. { $level }
+team-lead = { $line } (chef)
+
+## !war
+
+war-none = Pas encore de guerre de guildes. Elles commencent le vendredi à 18:00 UTC entre les guildes qui ont un salon et { $stake } vults dans leur trésor pour la mise, voir !server channel.
+war-running = **Guerre de guildes**, encore { $left }, { $prize } vults pour le trésor gagnant
+war-over = **Guerre de guildes**, terminée
+war-not-in = Ce serveur n'y participe pas, les guildes avec un salon rejoignent la suivante, voir !server channel.
+
+## !tournament
+
+tournament-usage =
+    Utilisation :
+    - !tournament, liste les tournois
+    - !tournament create <single|double|swiss> <frais d'entrée> <nom>
+    - !tournament join|leave [id]
+    - !tournament start|cancel [id], pour l'organisateur
+    - !tournament bracket [id]
+    - !tournament play [id], présente-toi pour ton prochain match
+tournament-created = Le tournoi { $name } ({ $format ->
+        [single] élimination simple
+        [double] double élimination
+       *[swiss] système suisse
+    }) est ouvert, rejoins-le avec !tournament join { $id }. L'entrée coûte { $fee } vults.
+tournament-full = { $name } est complet.
+tournament-fee-too-high = Les frais d'entrée vont jusqu'à { $max } vults.
+tournament-joined = Tu participes à { $name }.
+tournament-already-in = Tu participes déjà à { $name }.
+tournament-closed = { $name } n'accepte plus de participants.
+tournament-left = Tu as quitté { $name }, tes frais d'entrée te sont rendus.
+tournament-cant-leave = Tu ne peux plus quitter { $name }.
+tournament-cancelled = { $name } est annulé, les frais d'entrée sont remboursés.
+tournament-none-start = Aucun tournoi en ce moment, lances-en un avec !tournament create.
+tournament-listed = { $id }. { $name }, { $format ->
+        [single] élimination simple
+        [double] double élimination
+       *[swiss] système suisse
+    } avec { $players } joueurs, { $state ->
+        [open] ouvert
+        [running] en cours
+       *[finished] terminé
+    }
+tournament-unknown = Il n'y a pas de tournoi { $id }.
+tournament-none = Aucun tournoi en ce moment.
+tournament-which = Il y a plusieurs tournois, lequel ? Voir !tournament list.
+tournament-organizer-only = Seul l'organisateur de { $name } peut faire ça.
+tournament-too-few = { $format ->
+        [single] L'élimination simple
+        [double] La double élimination
+       *[swiss] Le système suisse
+    } demande au moins { $players } joueurs.
+tournament-started = { $name } a commencé ! Présente-toi pour ton match avec !tournament play.
+tournament-already-started = { $name } a déjà commencé.
+tournament-walkover = { $name } passe le match { $number }, le temps est écoulé.
+tournament-over = **{ $name }** est terminé !
+tournament-not-running = { $name } n'est pas en cours.
+tournament-not-in = Tu ne participes pas à { $name }.
+tournament-no-match = Tu n'as plus de match à jouer.
+tournament-no-opponent = Ton prochain adversaire n'est pas encore connu.
+tournament-checked-in = Tu es présent pour le match { $number } contre { $foe }. Il reste { $left } à ton adversaire pour se présenter avec !tournament play, sinon tu passes.
+tournament-played = Match { $number } : { $winner } bat { $loser }. Revois-le avec !replay { $replay }.
+tournament-forfeit = Match { $number } : { $name } déclare forfait, { $why }
+tournament-already-played = Le match { $number } a déjà été joué.
+tournament-header = **{ $name }**, { $format ->
+        [single] élimination simple
+        [double] double élimination
+       *[swiss] système suisse
+    } avec { $players } joueurs, { $pool } vults à gagner
+tournament-now = En cours : { $first } contre { $second }, encore { $left }
+tournament-replay = replay { $id }
+tournament-walkover-short = forfait
+tournament-result = { $stage }, match { $number } : { $winner } a battu { $loser } ({ $how })
+tournament-stage = { $stage ->
+        [winners] Tableau des gagnants, tour { $round }
+        [losers] Tableau des perdants, tour { $round }
+        [final] Grande finale
+       *[swiss] Tour { $round }
+    }
+
+## !replay
+
+replay-usage = Utilisation : !replay <id combat> [tour], ou !replay <id combat> json
+replay-unknown = Il n'y a pas de combat { $id }.
+replay-file = Combat { $id }
+replay-turns = Le combat { $id } va du tour 1 au tour { $last }.
+replay-header = **Combat { $id }** : { $first } contre { $second }, tour { $turn } sur { $last }
+replay-next = Suivant : !replay { $id } { $turn }
+replay-spell-gone = Ce combat ne peut plus être rejoué : { $deus } a lancé { $spell }, qui n'est plus un sort.
+
+## Objets, de data/items.json
+
+item-potion = Potion
+item-potion-about = Tenue, soigne 30 % de sa Vie quand elle tombe à la moitié en combat.
+item-super-potion = Super Potion
+item-super-potion-about = Tenue, soigne 60 % de sa Vie quand elle tombe à la moitié en combat.
+item-exp-candy = Bonbon Exp
+item-exp-candy-about = Donne 500 d'exp à un Deus.
+item-rare-candy = Super Bonbon
+item-rare-candy-about = Donne 4000 d'exp à un Deus.
+item-junk-trap = Piège de fortune
+item-junk-trap-about = Attrape un Deus sauvage.
+item-gilded-trap = Piège doré
+item-gilded-trap-about = Attrape un Deus sauvage à deux étoiles.
+item-fire-stone = Pierre Feu
+item-fire-stone-about = Fait évoluer un Deus Feu tout de suite, quel que soit son niveau.
+item-water-stone = Pierre Eau
+item-water-stone-about = Fait évoluer un Deus Eau tout de suite, quel que soit son niveau.
+item-earth-stone = Pierre Terre
+item-earth-stone-about = Fait évoluer un Deus Terre tout de suite, quel que soit son niveau.
+item-wind-stone = Pierre Vent
+item-wind-stone-about = Fait évoluer un Deus Vent tout de suite, quel que soit son niveau.
+item-power-band = Bandeau de force
+item-power-band-about = Tenu, +10 % d'Atk.
+item-iron-plate = Plaque de fer
+item-iron-plate-about = Tenue, +10 % de Def.
+item-swift-feather = Plume rapide
+item-swift-feather-about = Tenue, +10 % de Vitesse.
+item-focus-lens = Lentille de visée
+item-focus-lens-about = Tenue, +10 % d'Attr.
+item-life-charm = Charme de vie
+item-life-charm-about = Tenu, +10 % de Vie.
+item-energy-snack = En-cas énergétique
+item-energy-snack-about = Te donne 30 d'énergie.
+item-energy-drink = Boisson énergétique
+item-energy-drink-about = Te donne 100 d'énergie.
+item-star-shard = Éclat d'étoile
+item-star-shard-about = Monte les étoiles d'un Deus avec !ascend, à la place d'un double.
+item-inferno-scroll = Parchemin Inferno
+item-inferno-scroll-about = Apprend Inferno à un Deus.
+item-tidal-wave-scroll = Parchemin Tidal Wave
+item-tidal-wave-scroll-about = Apprend Tidal Wave à un Deus.
+item-quake-scroll = Parchemin Quake
+item-quake-scroll-about = Apprend Quake à un Deus.
+item-thunderbolt-scroll = Parchemin Thunderbolt
+item-thunderbolt-scroll-about = Apprend Thunderbolt à un Deus.
+item-radiance-scroll = Parchemin Radiance
+item-radiance-scroll-about = Apprend Radiance à un Deus.
+item-barrier-scroll = Parchemin Barrier
+item-barrier-scroll-about = Apprend Barrier à un Deus.
+
+## Quêtes, de data/quests.json
+
+quest-daily-wins = Échauffement
+quest-daily-battles = Entraînement
+quest-daily-catch = Trappeur
+quest-daily-water = Partie de pêche
+quest-daily-evolve = Poussée de croissance
+quest-daily-spend = Lèche-vitrines
+quest-weekly-wins = Champion
+quest-weekly-catch = Collectionneur
+quest-weekly-shadow = Dans le noir
+quest-weekly-evolve = Métamorphose
+quest-weekly-spend = Client fidèle
+
+## Succès, de data/achievements.json
+
+achievement-collector = Collectionneur
+achievement-hoarder = Accumulateur
+achievement-rising-star = Étoile montante
+achievement-superstar = Superstar
+achievement-duelist = Duelliste
+achievement-gladiator = Gladiateur
+achievement-unstoppable = Inarrêtable
+achievement-peak = Au sommet
+achievement-full-circle = La boucle est bouclée
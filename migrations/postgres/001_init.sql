@@ -0,0 +1,29 @@
+CREATE TABLE users (
+    id SERIAL PRIMARY KEY,
+    disc_id BIGINT NOT NULL UNIQUE,
+    name TEXT NOT NULL,
+    vults INT NOT NULL DEFAULT 0 CHECK (vults >= 0),
+    joined BIGINT NOT NULL
+);
+
+CREATE TABLE deus_instances (
+    id SERIAL PRIMARY KEY,
+    owner INT NOT NULL REFERENCES users (id),
+    deus TEXT NOT NULL,
+    exp INT NOT NULL DEFAULT 0,
+    stars INT NOT NULL DEFAULT 1,
+    acquired BIGINT NOT NULL
+);
+
+CREATE INDEX deus_instances_owner ON deus_instances (owner);
+
+CREATE TABLE ledger (
+    id BIGSERIAL PRIMARY KEY,
+    user_id INT NOT NULL REFERENCES users (id),
+    amount INT NOT NULL,
+    balance INT NOT NULL,
+    reason TEXT NOT NULL,
+    at BIGINT NOT NULL
+);
+
+CREATE INDEX ledger_user ON ledger (user_id, id);
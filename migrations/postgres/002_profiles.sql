@@ -0,0 +1,9 @@
+CREATE TABLE profiles (
+    user_id INT PRIMARY KEY REFERENCES users (id),
+    favorite_deus INT REFERENCES deus_instances (id) ON DELETE SET NULL,
+    wins INT NOT NULL DEFAULT 0,
+    losses INT NOT NULL DEFAULT 0,
+    win_streak INT NOT NULL DEFAULT 0,
+    best_streak INT NOT NULL DEFAULT 0,
+    hidden INT NOT NULL DEFAULT 0
+);
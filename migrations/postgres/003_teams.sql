@@ -0,0 +1,16 @@
+CREATE TABLE teams (
+    id SERIAL PRIMARY KEY,
+    owner INT NOT NULL REFERENCES users (id),
+    name TEXT NOT NULL,
+    lead INT REFERENCES deus_instances (id) ON DELETE SET NULL,
+    active BOOL NOT NULL DEFAULT FALSE,
+    UNIQUE (owner, name)
+);
+
+CREATE TABLE team_members (
+    team_id INT NOT NULL REFERENCES teams (id) ON DELETE CASCADE,
+    slot INT NOT NULL,
+    deus_id INT NOT NULL REFERENCES deus_instances (id) ON DELETE CASCADE,
+    PRIMARY KEY (team_id, slot),
+    UNIQUE (team_id, deus_id)
+);
@@ -0,0 +1,12 @@
+ALTER TABLE deus_instances ADD COLUMN var_life INT NOT NULL DEFAULT 0;
+ALTER TABLE deus_instances ADD COLUMN var_atk INT NOT NULL DEFAULT 0;
+ALTER TABLE deus_instances ADD COLUMN var_def INT NOT NULL DEFAULT 0;
+ALTER TABLE deus_instances ADD COLUMN var_speed INT NOT NULL DEFAULT 0;
+ALTER TABLE deus_instances ADD COLUMN var_attr INT NOT NULL DEFAULT 0;
+ALTER TABLE deus_instances ADD COLUMN nature TEXT NOT NULL DEFAULT 'Hardy';
+ALTER TABLE deus_instances ADD COLUMN points INT NOT NULL DEFAULT 0;
+ALTER TABLE deus_instances ADD COLUMN train_life INT NOT NULL DEFAULT 0;
+ALTER TABLE deus_instances ADD COLUMN train_atk INT NOT NULL DEFAULT 0;
+ALTER TABLE deus_instances ADD COLUMN train_def INT NOT NULL DEFAULT 0;
+ALTER TABLE deus_instances ADD COLUMN train_speed INT NOT NULL DEFAULT 0;
+ALTER TABLE deus_instances ADD COLUMN train_attr INT NOT NULL DEFAULT 0;
@@ -0,0 +1,10 @@
+CREATE TABLE replays (
+    id SERIAL PRIMARY KEY,
+    player_a INT NOT NULL REFERENCES users (id),
+    player_b INT NOT NULL REFERENCES users (id),
+    seed BIGINT NOT NULL,
+    teams TEXT NOT NULL,
+    actions BYTEA NOT NULL,
+    winner INT REFERENCES users (id),
+    at BIGINT NOT NULL
+);
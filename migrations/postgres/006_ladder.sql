@@ -0,0 +1,13 @@
+CREATE TABLE ratings (
+    user_id INT NOT NULL REFERENCES users (id),
+    season INT NOT NULL,
+    rating INT NOT NULL,
+    games INT NOT NULL DEFAULT 0,
+    wins INT NOT NULL DEFAULT 0,
+    losses INT NOT NULL DEFAULT 0,
+    peak INT NOT NULL,
+    rewarded BOOL NOT NULL DEFAULT FALSE,
+    PRIMARY KEY (user_id, season)
+);
+
+CREATE INDEX ratings_ladder ON ratings (season, rating DESC);
@@ -0,0 +1,29 @@
+CREATE TABLE tournaments (
+    id SERIAL PRIMARY KEY,
+    name TEXT NOT NULL,
+    format TEXT NOT NULL,
+    organizer INT NOT NULL REFERENCES users (id),
+    fee INT NOT NULL,
+    state TEXT NOT NULL DEFAULT 'open',
+    created BIGINT NOT NULL,
+    started BIGINT NOT NULL DEFAULT 0
+);
+
+CREATE TABLE tournament_entrants (
+    tournament_id INT NOT NULL REFERENCES tournaments (id),
+    user_id INT NOT NULL REFERENCES users (id),
+    seed INT NOT NULL DEFAULT 0,
+    ready INT,
+    joined BIGINT NOT NULL,
+    PRIMARY KEY (tournament_id, user_id)
+);
+
+CREATE TABLE tournament_results (
+    tournament_id INT NOT NULL REFERENCES tournaments (id),
+    number INT NOT NULL,
+    winner INT NOT NULL REFERENCES users (id),
+    walkover BOOL NOT NULL,
+    replay INT REFERENCES replays (id),
+    at BIGINT NOT NULL,
+    PRIMARY KEY (tournament_id, number)
+);
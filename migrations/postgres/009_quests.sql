@@ -0,0 +1,8 @@
+CREATE TABLE quest_progress (
+    user_id INT NOT NULL REFERENCES users (id),
+    quest TEXT NOT NULL,
+    period INT NOT NULL,
+    progress INT NOT NULL DEFAULT 0,
+    completed BOOLEAN NOT NULL DEFAULT FALSE,
+    PRIMARY KEY (user_id, quest, period)
+);
@@ -0,0 +1,7 @@
+CREATE TABLE achievements (
+    id SERIAL PRIMARY KEY,
+    user_id INT NOT NULL REFERENCES users (id),
+    achievement TEXT NOT NULL,
+    at BIGINT NOT NULL,
+    UNIQUE (user_id, achievement)
+);
@@ -0,0 +1,13 @@
+CREATE TABLE outbox (
+    id BIGSERIAL PRIMARY KEY,
+    event TEXT NOT NULL,
+    at BIGINT NOT NULL
+);
+
+CREATE INDEX outbox_at ON outbox (at);
+
+CREATE TABLE outbox_deliveries (
+    event_id BIGINT NOT NULL REFERENCES outbox (id),
+    subscriber TEXT NOT NULL,
+    PRIMARY KEY (event_id, subscriber)
+);
@@ -0,0 +1,2 @@
+ALTER TABLE users ADD COLUMN energy INT NOT NULL DEFAULT 100 CHECK (energy >= 0);
+ALTER TABLE users ADD COLUMN energy_at BIGINT NOT NULL DEFAULT 0;
@@ -0,0 +1,16 @@
+ALTER TABLE users ADD COLUMN kind TEXT NOT NULL DEFAULT 'player' CHECK (kind IN ('player', 'server'));
+
+CREATE TABLE guilds (
+    account INT PRIMARY KEY REFERENCES users (id),
+    tax INT NOT NULL DEFAULT 0
+);
+
+CREATE TABLE guild_members (
+    guild INT NOT NULL REFERENCES guilds (account),
+    user_id INT NOT NULL REFERENCES users (id),
+    admin BOOL NOT NULL DEFAULT FALSE,
+    joined BIGINT NOT NULL,
+    PRIMARY KEY (guild, user_id)
+);
+
+CREATE INDEX guild_members_user ON guild_members (user_id);
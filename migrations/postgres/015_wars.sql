@@ -0,0 +1,16 @@
+ALTER TABLE guilds ADD COLUMN channel BIGINT;
+
+CREATE TABLE wars (
+    id SERIAL PRIMARY KEY,
+    starts BIGINT NOT NULL,
+    ends BIGINT NOT NULL,
+    prize INT NOT NULL,
+    settled BOOL NOT NULL DEFAULT FALSE
+);
+
+CREATE TABLE war_scores (
+    war_id INT NOT NULL REFERENCES wars (id),
+    guild INT NOT NULL REFERENCES guilds (account),
+    score INT NOT NULL DEFAULT 0,
+    PRIMARY KEY (war_id, guild)
+);
@@ -0,0 +1,18 @@
+CREATE TABLE raids (
+    id SERIAL PRIMARY KEY,
+    boss TEXT NOT NULL,
+    level INT NOT NULL,
+    life INT NOT NULL CHECK (life >= 0),
+    max_life INT NOT NULL,
+    starts BIGINT NOT NULL,
+    ends BIGINT NOT NULL,
+    settled BOOL NOT NULL DEFAULT FALSE
+);
+
+CREATE TABLE raid_damage (
+    raid_id INT NOT NULL REFERENCES raids (id),
+    user_id INT NOT NULL REFERENCES users (id),
+    damage INT NOT NULL,
+    attacks INT NOT NULL,
+    PRIMARY KEY (raid_id, user_id)
+);
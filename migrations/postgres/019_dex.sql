@@ -0,0 +1,15 @@
+CREATE TABLE dex (
+    user_id INT NOT NULL REFERENCES users (id),
+    deus TEXT NOT NULL,
+    caught BOOLEAN NOT NULL,
+    PRIMARY KEY (user_id, deus)
+);
+
+CREATE TABLE dex_rewards (
+    user_id INT NOT NULL REFERENCES users (id),
+    milestone TEXT NOT NULL,
+    at BIGINT NOT NULL,
+    PRIMARY KEY (user_id, milestone)
+);
+
+INSERT INTO dex (user_id, deus, caught) SELECT DISTINCT owner, deus, TRUE FROM deus_instances;
@@ -0,0 +1,6 @@
+CREATE TABLE challenges (
+    challenger INT NOT NULL REFERENCES users (id),
+    opponent INT NOT NULL REFERENCES users (id),
+    at BIGINT NOT NULL,
+    PRIMARY KEY (challenger, opponent)
+);
@@ -0,0 +1 @@
+ALTER TABLE deus_instances ADD COLUMN version INT NOT NULL DEFAULT 0;
@@ -0,0 +1,29 @@
+CREATE TABLE users (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    disc_id INTEGER NOT NULL UNIQUE,
+    name TEXT NOT NULL,
+    vults INTEGER NOT NULL DEFAULT 0 CHECK (vults >= 0),
+    joined INTEGER NOT NULL
+);
+
+CREATE TABLE deus_instances (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    owner INTEGER NOT NULL REFERENCES users (id),
+    deus TEXT NOT NULL,
+    exp INTEGER NOT NULL DEFAULT 0,
+    stars INTEGER NOT NULL DEFAULT 1,
+    acquired INTEGER NOT NULL
+);
+
+CREATE INDEX deus_instances_owner ON deus_instances (owner);
+
+CREATE TABLE ledger (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL REFERENCES users (id),
+    amount INTEGER NOT NULL,
+    balance INTEGER NOT NULL,
+    reason TEXT NOT NULL,
+    at INTEGER NOT NULL
+);
+
+CREATE INDEX ledger_user ON ledger (user_id, id);
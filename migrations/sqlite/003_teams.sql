@@ -0,0 +1,16 @@
+CREATE TABLE teams (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    owner INTEGER NOT NULL REFERENCES users (id),
+    name TEXT NOT NULL,
+    lead INTEGER REFERENCES deus_instances (id) ON DELETE SET NULL,
+    active INTEGER NOT NULL DEFAULT 0,
+    UNIQUE (owner, name)
+);
+
+CREATE TABLE team_members (
+    team_id INTEGER NOT NULL REFERENCES teams (id) ON DELETE CASCADE,
+    slot INTEGER NOT NULL,
+    deus_id INTEGER NOT NULL REFERENCES deus_instances (id) ON DELETE CASCADE,
+    PRIMARY KEY (team_id, slot),
+    UNIQUE (team_id, deus_id)
+);
@@ -0,0 +1,12 @@
+ALTER TABLE deus_instances ADD COLUMN var_life INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE deus_instances ADD COLUMN var_atk INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE deus_instances ADD COLUMN var_def INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE deus_instances ADD COLUMN var_speed INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE deus_instances ADD COLUMN var_attr INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE deus_instances ADD COLUMN nature TEXT NOT NULL DEFAULT 'Hardy';
+ALTER TABLE deus_instances ADD COLUMN points INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE deus_instances ADD COLUMN train_life INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE deus_instances ADD COLUMN train_atk INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE deus_instances ADD COLUMN train_def INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE deus_instances ADD COLUMN train_speed INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE deus_instances ADD COLUMN train_attr INTEGER NOT NULL DEFAULT 0;
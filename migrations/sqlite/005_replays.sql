@@ -0,0 +1,10 @@
+CREATE TABLE replays (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    player_a INTEGER NOT NULL REFERENCES users (id),
+    player_b INTEGER NOT NULL REFERENCES users (id),
+    seed INTEGER NOT NULL,
+    teams TEXT NOT NULL,
+    actions BLOB NOT NULL,
+    winner INTEGER REFERENCES users (id),
+    at INTEGER NOT NULL
+);
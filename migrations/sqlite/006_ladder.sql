@@ -0,0 +1,13 @@
+CREATE TABLE ratings (
+    user_id INTEGER NOT NULL REFERENCES users (id),
+    season INTEGER NOT NULL,
+    rating INTEGER NOT NULL,
+    games INTEGER NOT NULL DEFAULT 0,
+    wins INTEGER NOT NULL DEFAULT 0,
+    losses INTEGER NOT NULL DEFAULT 0,
+    peak INTEGER NOT NULL,
+    rewarded INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (user_id, season)
+);
+
+CREATE INDEX ratings_ladder ON ratings (season, rating DESC);
@@ -0,0 +1,29 @@
+CREATE TABLE tournaments (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    name TEXT NOT NULL,
+    format TEXT NOT NULL,
+    organizer INTEGER NOT NULL REFERENCES users (id),
+    fee INTEGER NOT NULL,
+    state TEXT NOT NULL DEFAULT 'open',
+    created INTEGER NOT NULL,
+    started INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE TABLE tournament_entrants (
+    tournament_id INTEGER NOT NULL REFERENCES tournaments (id),
+    user_id INTEGER NOT NULL REFERENCES users (id),
+    seed INTEGER NOT NULL DEFAULT 0,
+    ready INTEGER,
+    joined INTEGER NOT NULL,
+    PRIMARY KEY (tournament_id, user_id)
+);
+
+CREATE TABLE tournament_results (
+    tournament_id INTEGER NOT NULL REFERENCES tournaments (id),
+    number INTEGER NOT NULL,
+    winner INTEGER NOT NULL REFERENCES users (id),
+    walkover INTEGER NOT NULL,
+    replay INTEGER REFERENCES replays (id),
+    at INTEGER NOT NULL,
+    PRIMARY KEY (tournament_id, number)
+);
@@ -0,0 +1,8 @@
+ALTER TABLE deus_instances ADD COLUMN held TEXT;
+
+CREATE TABLE inventory (
+    user_id INTEGER NOT NULL REFERENCES users (id),
+    item TEXT NOT NULL,
+    count INTEGER NOT NULL CHECK (count >= 0),
+    PRIMARY KEY (user_id, item)
+);
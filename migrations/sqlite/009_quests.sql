@@ -0,0 +1,8 @@
+CREATE TABLE quest_progress (
+    user_id INTEGER NOT NULL REFERENCES users (id),
+    quest TEXT NOT NULL,
+    period INTEGER NOT NULL,
+    progress INTEGER NOT NULL DEFAULT 0,
+    completed INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (user_id, quest, period)
+);
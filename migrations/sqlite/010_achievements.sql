@@ -0,0 +1,7 @@
+CREATE TABLE achievements (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    user_id INTEGER NOT NULL REFERENCES users (id),
+    achievement TEXT NOT NULL,
+    at INTEGER NOT NULL,
+    UNIQUE (user_id, achievement)
+);
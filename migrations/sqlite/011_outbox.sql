@@ -0,0 +1,13 @@
+CREATE TABLE outbox (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    event TEXT NOT NULL,
+    at INTEGER NOT NULL
+);
+
+CREATE INDEX outbox_at ON outbox (at);
+
+CREATE TABLE outbox_deliveries (
+    event_id INTEGER NOT NULL REFERENCES outbox (id),
+    subscriber TEXT NOT NULL,
+    PRIMARY KEY (event_id, subscriber)
+);
@@ -0,0 +1,4 @@
+CREATE TABLE jobs (
+    name TEXT PRIMARY KEY,
+    next_run INTEGER NOT NULL
+);
@@ -0,0 +1,2 @@
+ALTER TABLE users ADD COLUMN energy INTEGER NOT NULL DEFAULT 100;
+ALTER TABLE users ADD COLUMN energy_at INTEGER NOT NULL DEFAULT 0;
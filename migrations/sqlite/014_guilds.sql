@@ -0,0 +1,16 @@
+ALTER TABLE users ADD COLUMN kind TEXT NOT NULL DEFAULT 'player' CHECK (kind IN ('player', 'server'));
+
+CREATE TABLE guilds (
+    account INTEGER PRIMARY KEY REFERENCES users (id),
+    tax INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE TABLE guild_members (
+    guild INTEGER NOT NULL REFERENCES guilds (account),
+    user_id INTEGER NOT NULL REFERENCES users (id),
+    admin INTEGER NOT NULL DEFAULT 0,
+    joined INTEGER NOT NULL,
+    PRIMARY KEY (guild, user_id)
+);
+
+CREATE INDEX guild_members_user ON guild_members (user_id);
@@ -0,0 +1,16 @@
+ALTER TABLE guilds ADD COLUMN channel INTEGER;
+
+CREATE TABLE wars (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    starts INTEGER NOT NULL,
+    ends INTEGER NOT NULL,
+    prize INTEGER NOT NULL,
+    settled INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE TABLE war_scores (
+    war_id INTEGER NOT NULL REFERENCES wars (id),
+    guild INTEGER NOT NULL REFERENCES guilds (account),
+    score INTEGER NOT NULL DEFAULT 0,
+    PRIMARY KEY (war_id, guild)
+);
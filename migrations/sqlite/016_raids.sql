@@ -0,0 +1,18 @@
+CREATE TABLE raids (
+    id INTEGER PRIMARY KEY AUTOINCREMENT,
+    boss TEXT NOT NULL,
+    level INTEGER NOT NULL,
+    life INTEGER NOT NULL CHECK (life >= 0),
+    max_life INTEGER NOT NULL,
+    starts INTEGER NOT NULL,
+    ends INTEGER NOT NULL,
+    settled INTEGER NOT NULL DEFAULT 0
+);
+
+CREATE TABLE raid_damage (
+    raid_id INTEGER NOT NULL REFERENCES raids (id),
+    user_id INTEGER NOT NULL REFERENCES users (id),
+    damage INTEGER NOT NULL,
+    attacks INTEGER NOT NULL,
+    PRIMARY KEY (raid_id, user_id)
+);
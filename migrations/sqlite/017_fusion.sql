@@ -0,0 +1 @@
+ALTER TABLE deus_instances ADD COLUMN spells TEXT NOT NULL DEFAULT '';
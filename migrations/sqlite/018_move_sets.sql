@@ -0,0 +1 @@
+ALTER TABLE deus_instances ADD COLUMN equipped TEXT NOT NULL DEFAULT '';
@@ -0,0 +1,15 @@
+CREATE TABLE dex (
+    user_id INTEGER NOT NULL REFERENCES users (id),
+    deus TEXT NOT NULL,
+    caught INTEGER NOT NULL,
+    PRIMARY KEY (user_id, deus)
+);
+
+CREATE TABLE dex_rewards (
+    user_id INTEGER NOT NULL REFERENCES users (id),
+    milestone TEXT NOT NULL,
+    at INTEGER NOT NULL,
+    PRIMARY KEY (user_id, milestone)
+);
+
+INSERT INTO dex (user_id, deus, caught) SELECT DISTINCT owner, deus, 1 FROM deus_instances;
@@ -0,0 +1,6 @@
+CREATE TABLE challenges (
+    challenger INTEGER NOT NULL REFERENCES users (id),
+    opponent INTEGER NOT NULL REFERENCES users (id),
+    at INTEGER NOT NULL,
+    PRIMARY KEY (challenger, opponent)
+);
@@ -0,0 +1,22 @@
+CREATE TABLE outbox_subscribers (
+    name TEXT PRIMARY KEY,
+    since INTEGER NOT NULL
+);
+
+INSERT INTO outbox_subscribers (name, since)
+SELECT subscriber, MIN(event_id) - 1 FROM outbox_deliveries GROUP BY subscriber;
+
+CREATE TABLE outbox_failures (
+    event_id INTEGER NOT NULL REFERENCES outbox (id),
+    subscriber TEXT NOT NULL,
+    attempts INTEGER NOT NULL,
+    PRIMARY KEY (event_id, subscriber)
+);
+
+CREATE TABLE outbox_users (
+    event_id INTEGER NOT NULL REFERENCES outbox (id),
+    user_id INTEGER NOT NULL,
+    PRIMARY KEY (event_id, user_id)
+);
+
+CREATE INDEX outbox_users_user ON outbox_users (user_id, event_id);
@@ -0,0 +1 @@
+ALTER TABLE tournament_entrants ADD COLUMN claimed INTEGER;
@@ -0,0 +1 @@
+ALTER TABLE deus_instances ADD COLUMN version INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,117 @@
+use serde_json;
+
+// What a player has to have done, checked against their stats, see
+// achievements::met.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Criterion {
+    // Deus owned at once.
+    Collection { count: i32 },
+    // A Deus of at least that many stars.
+    Stars { count: i32 },
+    Wins { count: i32 },
+    WinStreak { count: i32 },
+    Level { level: i32 },
+    // An evolved form of every starter.
+    EvolveStarters,
+}
+
+// An achievement as written in data/achievements.json.
+#[derive(Debug, Deserialize)]
+pub struct Achievement {
+    // Its name is in data/locales/, see Achievement::title.
+    pub id: String,
+    pub badge: String,
+    pub criterion: Criterion,
+}
+
+// An achievement a user has, awarded once.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Unlocked {
+    pub user: i32,
+    pub achievement: String,
+    pub at: i64,
+}
+
+impl Achievement {
+    // Its name in `lang`, see data/locales/.
+    pub fn title(&self, lang: &str) -> String {
+        tr!(lang, &format!("achievement-{}", self.id))
+    }
+}
+
+impl Criterion {
+    pub fn describe(&self, lang: &str) -> String {
+        match *self {
+            Criterion::Collection { count } => tr!(lang, "criterion-collection", count = count),
+            Criterion::Stars { count } => tr!(lang, "criterion-stars", count = count),
+            Criterion::Wins { count } => tr!(lang, "criterion-wins", count = count),
+            Criterion::WinStreak { count } => tr!(lang, "criterion-win-streak", count = count),
+            Criterion::Level { level } => tr!(lang, "criterion-level", level = level),
+            Criterion::EvolveStarters => tr!(lang, "criterion-evolve-starters"),
+        }
+    }
+}
+
+lazy_static! {
+    static ref CATALOG: Vec<Achievement> = serde_json::from_str(include_str!("../../data/achievements.json"))
+        .expect("data/achievements.json");
+}
+
+pub fn catalog() -> &'static [Achievement] {
+    &CATALOG
+}
+
+pub fn find(id: &str) -> Option<&'static Achievement> {
+    catalog().iter().find(|a| a.id == id)
+}
+
+// Catalog consistency.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use deus::Instance::MAX_STARS;
+    use i18n;
+
+    #[test]
+    fn achievement_ids_are_unique() -> Result<(), String> {
+        for (i, a) in catalog().iter().enumerate() {
+            if catalog()[..i].iter().any(|o| o.id == a.id) {
+                return Err(format!("{} is in the catalog twice", a.id));
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn achievements_have_badges() -> Result<(), String> {
+        match catalog().iter().find(|a| a.badge.trim().is_empty() || a.title("en").trim().is_empty()) {
+            Some(a) => Err(format!("{} has no name or badge", a.id)),
+            None => Ok(()),
+        }
+    }
+
+    #[test]
+    fn achievements_are_in_the_message_catalogs() -> Result<(), String> {
+        match catalog().iter().find(|a| !i18n::is_key(&format!("achievement-{}", a.id))) {
+            Some(a) => Err(format!("{} has no achievement-{} message", a.id, a.id)),
+            None => Ok(()),
+        }
+    }
+
+    #[test]
+    fn achievements_can_be_reached() -> Result<(), String> {
+        for a in catalog() {
+            let ok = match a.criterion {
+                Criterion::Collection { count } | Criterion::Wins { count } | Criterion::WinStreak { count } => count > 0,
+                Criterion::Stars { count } => count >= 1 && count <= MAX_STARS,
+                Criterion::Level { level } => level >= 1 && level <= 100,
+                Criterion::EvolveStarters => true,
+            };
+            if !ok {
+                return Err(format!("{} asks to {}", a.id, a.criterion.describe("en")));
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,142 @@
+pub mod Achievement;
+
+use deus::Deus::{self, Deus as Species};
+use deus::Instance::DeusInstance;
+use events::Event;
+use storage::Storage;
+use users::Profile::Profile;
+use users::Users::UserType;
+use self::Achievement::{Achievement as CatalogAchievement, Criterion};
+
+// What achievements are checked against.
+pub struct Facts {
+    pub deus: Vec<DeusInstance>,
+    pub profile: Profile,
+}
+
+pub fn facts(store: &Storage, user: i32) -> Result<Facts, String> {
+    Ok(Facts { deus: store.deus_of(user)?, profile: store.profile(user)? })
+}
+
+pub fn met(criterion: &Criterion, facts: &Facts) -> bool {
+    match *criterion {
+        Criterion::Collection { count } => facts.deus.len() as i32 >= count,
+        Criterion::Stars { count } => facts.deus.iter().any(|d| d.stars >= count),
+        Criterion::Wins { count } => facts.profile.wins >= count,
+        Criterion::WinStreak { count } => facts.profile.best_streak >= count,
+        Criterion::Level { level } => facts.deus.iter().any(|d| d.level() >= level),
+        Criterion::EvolveStarters => Deus::starters().iter().all(|s| evolved(s, &facts.deus)),
+    }
+}
+
+// Whether one of `deus` is a later stage of `starter`.
+fn evolved(starter: &Species, deus: &[DeusInstance]) -> bool {
+    let mut stage = starter;
+    while let Some(next) = stage.evolves.as_ref().and_then(|e| Deus::find(&e.into)) {
+        if deus.iter().any(|d| d.deus == next.name) {
+            return true;
+        }
+        stage = next;
+    }
+    false
+}
+
+// Awards what a player has earned and doesn't have yet, returns those.
+// Server accounts don't earn any.
+pub fn award(store: &Storage, user: i32) -> Result<Vec<&'static CatalogAchievement>, String> {
+    if store.user(user)?.kind != UserType::Player {
+        return Ok(Vec::new());
+    }
+    let owned = store.achievements(user)?;
+    let facts = facts(store, user)?;
+    let mut awarded = Vec::new();
+    for a in Achievement::catalog() {
+        if owned.iter().any(|u| u.achievement == a.id) || !met(&a.criterion, &facts) {
+            continue;
+        }
+        if store.award_achievement(user, &a.id)? {
+            awarded.push(a);
+        }
+    }
+    Ok(awarded)
+}
+
+// Subscribed to game events, see events::SUBSCRIBERS.
+pub fn on_event(store: &Storage, lang: &str, event: &Event, _: i64) -> Result<Vec<String>, String> {
+    let mut news = Vec::new();
+    for user in event.users() {
+        for a in award(store, user)? {
+            news.push(tr!(lang, "achievement-unlocked", name = store.user(user)?.name, badge = &a.badge,
+                          achievement = a.title(lang), criterion = a.criterion.describe(lang)));
+        }
+    }
+    Ok(news)
+}
+
+// Awards everyone what they earned before achievements existed, or while
+// one was missing from the catalog. Returns (players, achievements) awarded.
+pub fn backfill(store: &Storage) -> Result<(usize, usize), String> {
+    let mut totals = (0, 0);
+    for user in store.users()? {
+        let awarded = award(store, user.id)?.len();
+        if awarded > 0 {
+            totals.0 += 1;
+            totals.1 += awarded;
+        }
+    }
+    Ok(totals)
+}
+
+// Criteria.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn collections_and_stars_count_owned_deus() -> Result<(), String> {
+        let mut facts = Facts { deus: (0..10).map(|_| DeusInstance::new(1, "Lux", 1)).collect(), profile: Profile::default() };
+        ensure!(met(&Criterion::Collection { count: 10 }, &facts), "10 Deus aren't a collection of 10");
+        ensure!(!met(&Criterion::Collection { count: 11 }, &facts), "10 Deus are a collection of 11");
+        ensure!(!met(&Criterion::Stars { count: 3 }, &facts), "1 star Deus count as 3 stars");
+        facts.deus[4].stars = 4;
+        ensure!(met(&Criterion::Stars { count: 3 }, &facts), "a 4 star Deus isn't 3 stars");
+        ensure!(!met(&Criterion::Level { level: 100 }, &facts), "level 1 is level 100");
+        facts.deus[2].exp = 10 * 100 * 100;
+        ensure!(met(&Criterion::Level { level: 100 }, &facts), "level {} isn't 100", facts.deus[2].level());
+        Ok(())
+    }
+
+    #[test]
+    fn duels_count_from_the_profile() -> Result<(), String> {
+        let profile = Profile { wins: 12, best_streak: 4, win_streak: 1, ..Profile::default() };
+        let facts = Facts { deus: Vec::new(), profile: profile };
+        ensure!(met(&Criterion::Wins { count: 10 }, &facts) && !met(&Criterion::Wins { count: 100 }, &facts), "12 wins");
+        ensure!(met(&Criterion::WinStreak { count: 4 }, &facts), "the best streak didn't count");
+        ensure!(!met(&Criterion::WinStreak { count: 5 }, &facts), "a streak of 4 is 5");
+        Ok(())
+    }
+
+    #[test]
+    fn every_starter_has_to_be_evolved() -> Result<(), String> {
+        let mut facts = Facts { deus: Vec::new(), profile: Profile::default() };
+        for s in Deus::starters() {
+            facts.deus.push(DeusInstance::new(1, &s.name, 1));
+        }
+        ensure!(!met(&Criterion::EvolveStarters, &facts), "owning the starters is enough");
+
+        for (i, s) in Deus::starters().iter().enumerate() {
+            let next = s.evolves.as_ref().ok_or_else(|| format!("{} doesn't evolve", s.name))?;
+            facts.deus[i].deus = next.into.clone();
+        }
+        ensure!(met(&Criterion::EvolveStarters, &facts), "evolving every starter isn't enough");
+
+        // Any later stage counts, from the starter's own line only.
+        let first = facts.deus[0].deus.clone();
+        let last = Deus::find(&first).and_then(|d| d.evolves.as_ref()).ok_or_else(|| format!("{} doesn't evolve", first))?;
+        facts.deus[0].deus = last.into.clone();
+        ensure!(met(&Criterion::EvolveStarters, &facts), "{} doesn't count as evolved", last.into);
+        facts.deus[0].deus = "Tempest".to_owned();
+        ensure!(!met(&Criterion::EvolveStarters, &facts), "a Tempest counts as an evolved starter");
+        Ok(())
+    }
+}
@@ -0,0 +1,222 @@
+// Status effects: what spells leave behind on a fighter, how they stack and
+// how they wear off.
+
+use deus::Stats::Stat;
+use deus::Type::DeusType;
+
+pub const MAX_POISON: i32 = 3;
+pub const MAX_STAGE: i32 = 6;
+// Shields stack up to this percent of max life.
+pub const MAX_SHIELD: i32 = 50;
+
+// An effect as written in data/spells.json.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Effect {
+    // 1/8 of max life per stack at the end of each turn.
+    Poison { turns: i32 },
+    // 1/16 of max life at the end of each turn, halves physical damage.
+    Burn { turns: i32 },
+    // Loses its next turn.
+    Stun,
+    // Loses turns until it wakes up.
+    Sleep { turns: i32 },
+    // Absorbs damage, `amount` is a percent of max life.
+    Shield { amount: i32, turns: i32 },
+    Stage { stat: Stat, amount: i32, turns: i32 },
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Kind {
+    Poison,
+    Burn,
+    Stun,
+    Sleep,
+    Shield,
+    Stage(Stat),
+}
+
+// An effect sitting on a fighter. `value` is the poison stacks, the shield
+// left or the stage.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Status {
+    pub kind: Kind,
+    pub turns: i32,
+    pub value: i32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Applied {
+    New(i32),
+    Stacked(i32),
+    Refreshed,
+    Immune(DeusType),
+    Blocked,
+}
+
+impl Effect {
+    pub fn kind(&self) -> Kind {
+        match *self {
+            Effect::Poison { .. } => Kind::Poison,
+            Effect::Burn { .. } => Kind::Burn,
+            Effect::Stun => Kind::Stun,
+            Effect::Sleep { .. } => Kind::Sleep,
+            Effect::Shield { .. } => Kind::Shield,
+            Effect::Stage { stat, .. } => Kind::Stage(stat),
+        }
+    }
+}
+
+impl Kind {
+    pub fn name(&self) -> String {
+        match *self {
+            Kind::Poison => "poison".to_owned(),
+            Kind::Burn => "burn".to_owned(),
+            Kind::Stun => "stun".to_owned(),
+            Kind::Sleep => "sleep".to_owned(),
+            Kind::Shield => "shield".to_owned(),
+            Kind::Stage(stat) => format!("{} stage", stat.name()),
+        }
+    }
+}
+
+fn find(statuses: &mut Vec<Status>, kind: Kind) -> Option<&mut Status> {
+    statuses.iter_mut().find(|s| s.kind == kind)
+}
+
+pub fn has(statuses: &[Status], kind: Kind) -> bool {
+    statuses.iter().any(|s| s.kind == kind)
+}
+
+pub fn stage(statuses: &[Status], stat: Stat) -> i32 {
+    statuses.iter().find(|s| s.kind == Kind::Stage(stat)).map_or(0, |s| s.value)
+}
+
+// Percent a stage puts on its stat: +1 is 150%, -1 is 67%.
+pub fn stage_percent(stage: i32) -> i32 {
+    if stage >= 0 {
+        100 * (2 + stage) / 2
+    } else {
+        200 / (2 - stage)
+    }
+}
+
+// Stacking rules: poison stacks up to MAX_POISON, burn only refreshes, a
+// fighter can't be stunned or put to sleep while it already is one of them,
+// shields add up to MAX_SHIELD and stages add up to +/- MAX_STAGE. Lasting
+// effects keep the longer of the two durations.
+pub fn apply(statuses: &mut Vec<Status>, types: &[DeusType], effect: &Effect, max_life: i32) -> Applied {
+    let kind = effect.kind();
+    if let Some(&t) = types.iter().find(|t| t.immune_to(kind)) {
+        return Applied::Immune(t);
+    }
+
+    let (turns, value) = match *effect {
+        Effect::Poison { turns } => (turns, 1),
+        Effect::Burn { turns } => (turns, 0),
+        Effect::Stun => (1, 0),
+        Effect::Sleep { turns } => (turns, 0),
+        Effect::Shield { amount, turns } => (turns, (max_life * amount / 100).min(max_life * MAX_SHIELD / 100)),
+        Effect::Stage { amount, turns, .. } => (turns, amount.max(-MAX_STAGE).min(MAX_STAGE)),
+    };
+
+    if (kind == Kind::Stun || kind == Kind::Sleep) && (has(statuses, Kind::Stun) || has(statuses, Kind::Sleep)) {
+        return Applied::Blocked;
+    }
+
+    match find(statuses, kind) {
+        None => {
+            statuses.push(Status { kind: kind, turns: turns, value: value });
+            Applied::New(value)
+        }
+        Some(status) => {
+            let stacked = match kind {
+                Kind::Poison => (status.value + 1).min(MAX_POISON),
+                Kind::Shield => (status.value + value).min(max_life * MAX_SHIELD / 100),
+                Kind::Stage(_) => (status.value + value).max(-MAX_STAGE).min(MAX_STAGE),
+                _ => status.value,
+            };
+            let longer = turns > status.turns;
+            status.turns = status.turns.max(turns);
+
+            if stacked != status.value {
+                status.value = stacked;
+                Applied::Stacked(stacked)
+            } else if longer {
+                Applied::Refreshed
+            } else {
+                Applied::Blocked
+            }
+        }
+    }
+}
+
+// Checked before a fighter acts. A stun is used up by the skipped turn, sleep
+// counts down with each one. Returns what kept it from acting and whether
+// that just wore off.
+pub fn skips_turn(statuses: &mut Vec<Status>) -> Option<(Kind, bool)> {
+    let i = match statuses.iter().position(|s| s.kind == Kind::Stun || s.kind == Kind::Sleep) {
+        Some(i) => i,
+        None => return None,
+    };
+
+    let kind = statuses[i].kind;
+    statuses[i].turns -= 1;
+    let over = statuses[i].turns <= 0;
+    if over {
+        statuses.remove(i);
+    }
+    Some((kind, over))
+}
+
+// Shields take damage first, returns how much they absorbed.
+pub fn absorb(statuses: &mut Vec<Status>, damage: i32) -> i32 {
+    let absorbed = match find(statuses, Kind::Shield) {
+        Some(shield) => {
+            let absorbed = shield.value.min(damage);
+            shield.value -= absorbed;
+            absorbed
+        }
+        None => 0,
+    };
+    statuses.retain(|s| s.kind != Kind::Shield || s.value > 0);
+    absorbed
+}
+
+pub enum Tick {
+    Damage(Kind, i32),
+    Expired(Kind),
+}
+
+// End of turn: damage over time, then durations run down. Stun and sleep
+// run down in `skips_turn` instead.
+pub fn tick(statuses: &mut Vec<Status>, max_life: i32) -> Vec<Tick> {
+    let mut ticks = Vec::new();
+
+    for status in statuses.iter() {
+        let damage = match status.kind {
+            Kind::Poison => (max_life / 8).max(1) * status.value,
+            Kind::Burn => (max_life / 16).max(1),
+            _ => continue,
+        };
+        ticks.push(Tick::Damage(status.kind, damage));
+    }
+
+    for status in statuses.iter_mut().filter(|s| s.kind != Kind::Stun && s.kind != Kind::Sleep) {
+        status.turns -= 1;
+        if status.turns <= 0 {
+            ticks.push(Tick::Expired(status.kind));
+        }
+    }
+    statuses.retain(|s| s.turns > 0);
+
+    ticks
+}
+
+// What a fighter keeps when it's switched out.
+pub fn switch_out(statuses: &mut Vec<Status>) {
+    statuses.retain(|s| match s.kind {
+        Kind::Shield | Kind::Stage(_) => false,
+        _ => true,
+    });
+}
@@ -0,0 +1,95 @@
+use std::fmt;
+
+use battle::effects::{Applied, Kind};
+use i18n;
+use items::Item;
+
+// A fighter in the log, its Deus and whose it is, said as battle-whose.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Who {
+    pub owner: String,
+    pub deus: String,
+}
+
+// One line of the battle log.
+#[derive(Clone, Debug, PartialEq)]
+pub enum Event {
+    Turn(i32),
+    SendOut { who: Who },
+    Cast { who: Who, spell: String },
+    Missed { who: Who },
+    Skipped { who: Who, kind: Kind },
+    Damage { who: Who, amount: i32, percent: i32 },
+    Absorbed { who: Who, amount: i32 },
+    Applied { who: Who, kind: Kind, result: Applied },
+    Tick { who: Who, kind: Kind, amount: i32 },
+    Expired { who: Who, kind: Kind },
+    Fainted { who: Who },
+    Drank { who: Who, item: String, amount: i32 },
+    Won { side: String },
+    Draw,
+}
+
+impl Event {
+    pub fn describe(&self, lang: &str) -> String {
+        match *self {
+            Event::Turn(n) => tr!(lang, "log-turn", turn = n),
+            Event::SendOut { ref who } => tr!(lang, "log-send-out", who = who.describe(lang)),
+            Event::Cast { ref who, ref spell } => tr!(lang, "log-cast", who = who.describe(lang), spell = spell),
+            Event::Missed { ref who } => tr!(lang, "log-missed", who = who.describe(lang)),
+            Event::Skipped { ref who, kind: Kind::Sleep } => tr!(lang, "log-asleep", who = who.describe(lang)),
+            Event::Skipped { ref who, .. } => tr!(lang, "log-stunned", who = who.describe(lang)),
+            Event::Damage { ref who, amount, percent } => {
+                let effect = if percent > 100 { "super" } else if percent < 100 { "weak" } else { "normal" };
+                tr!(lang, "log-damage", who = who.describe(lang), amount = amount, effect = effect)
+            }
+            Event::Absorbed { ref who, amount } => tr!(lang, "log-absorbed", who = who.describe(lang), amount = amount),
+            Event::Applied { ref who, kind, result } => applied(lang, who, kind, result),
+            Event::Tick { ref who, kind, amount } => tr!(lang, "log-tick", who = who.describe(lang), amount = amount, status = status(lang, kind)),
+            Event::Expired { ref who, kind: Kind::Sleep } => tr!(lang, "log-wakes", who = who.describe(lang)),
+            Event::Expired { ref who, kind } => tr!(lang, "log-expired", who = who.describe(lang), status = status(lang, kind)),
+            Event::Fainted { ref who } => tr!(lang, "log-fainted", who = who.describe(lang)),
+            Event::Drank { ref who, ref item, amount } => tr!(lang, "log-drank", who = who.describe(lang), item = Item::title(lang, item), amount = amount),
+            Event::Won { ref side } => tr!(lang, "log-won", side = side),
+            Event::Draw => tr!(lang, "log-draw"),
+        }
+    }
+}
+
+impl Who {
+    pub fn describe(&self, lang: &str) -> String {
+        tr!(lang, "battle-whose", owner = &self.owner, deus = &self.deus)
+    }
+}
+
+// In the default language, as replays are exported.
+impl fmt::Display for Event {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.describe(i18n::LOCALES[0].0))
+    }
+}
+
+fn status(lang: &str, kind: Kind) -> String {
+    match kind {
+        Kind::Stage(stat) => tr!(lang, "status-stage", stat = stat.name()),
+        kind => tr!(lang, "status", kind = kind.name()),
+    }
+}
+
+fn applied(lang: &str, who: &Who, kind: Kind, result: Applied) -> String {
+    match (kind, result) {
+        (_, Applied::Immune(t)) => tr!(lang, "log-immune", who = who.describe(lang), deus_type = t.name(), status = status(lang, kind)),
+        (Kind::Stun, Applied::Blocked) | (Kind::Sleep, Applied::Blocked) => tr!(lang, "log-out-of-action", who = who.describe(lang)),
+        (_, Applied::Blocked) => tr!(lang, "log-capped", who = who.describe(lang), status = status(lang, kind)),
+        (_, Applied::Refreshed) => tr!(lang, "log-refreshed", who = who.describe(lang), status = status(lang, kind)),
+        (Kind::Poison, Applied::New(_)) => tr!(lang, "log-poisoned", who = who.describe(lang)),
+        (Kind::Poison, Applied::Stacked(n)) => tr!(lang, "log-poison-worse", who = who.describe(lang), stacks = n),
+        (Kind::Burn, _) => tr!(lang, "log-burned", who = who.describe(lang)),
+        (Kind::Stun, _) => tr!(lang, "log-stun", who = who.describe(lang)),
+        (Kind::Sleep, _) => tr!(lang, "log-sleep", who = who.describe(lang)),
+        (Kind::Shield, Applied::New(n)) | (Kind::Shield, Applied::Stacked(n)) => tr!(lang, "log-shielded", who = who.describe(lang), amount = n),
+        (Kind::Stage(stat), Applied::New(n)) | (Kind::Stage(stat), Applied::Stacked(n)) => {
+            tr!(lang, "log-stage", who = who.describe(lang), stat = stat.name(), stage = format!("{:+}", n))
+        }
+    }
+}
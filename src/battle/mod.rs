@@ -0,0 +1,591 @@
+// Turn based battles between two teams. Everything random comes from the
+// battle's seed, so the same seed and actions always play out the same way.
+
+pub mod effects;
+pub mod log;
+pub mod replay;
+
+use rand::{Rng, SeedableRng, XorShiftRng};
+
+use deus::Deus;
+use deus::Instance::DeusInstance;
+use deus::Spells::{self, Spell, Target};
+use deus::Stats::{self as stats, Stat, Stats};
+use deus::Type::{self, DeusType};
+use i18n::Message;
+use items;
+use self::effects::{Kind, Tick};
+use self::log::{Event, Who};
+
+// Battles still going after this many turns are a draw.
+pub const MAX_TURNS: i32 = 100;
+// Spells a fighter takes into battle, before stars open more slots.
+pub const MAX_SPELLS: usize = 4;
+
+#[derive(Clone, Debug)]
+pub struct Fighter {
+    pub id: i32,
+    pub name: String,
+    pub level: i32,
+    pub types: Vec<DeusType>,
+    pub stats: Stats,
+    pub life: i32,
+    pub spells: Vec<Spell>,
+    pub statuses: Vec<effects::Status>,
+    // The held item, None once a potion is drunk.
+    pub item: Option<String>,
+}
+
+impl Fighter {
+    pub fn new(deus: &DeusInstance) -> Result<Fighter, Message> {
+        let species = Deus::find(&deus.deus).ok_or_else(|| msg!("not-in-catalog", deus = &deus.deus))?;
+        let mut stats = stats::compute(species, deus);
+        items::boost(&mut stats, deus.held.as_ref().map(|s| s.as_str()));
+        let mut spells = Vec::new();
+        for name in deus.move_set()? {
+            spells.push(Spells::find(&name).cloned().ok_or_else(|| msg!("spells-gone", deus = &deus.deus, id = deus.id, spell = &name))?);
+        }
+        Ok(Fighter {
+            id: deus.id,
+            name: deus.deus.clone(),
+            level: deus.level(),
+            types: species.types.clone(),
+            stats: stats,
+            life: stats.life,
+            spells: spells,
+            statuses: Vec::new(),
+            item: deus.held.clone(),
+        })
+    }
+
+    pub fn alive(&self) -> bool {
+        self.life > 0
+    }
+
+    // A stat with its stage applied.
+    pub fn stat(&self, stat: Stat) -> i32 {
+        self.stats.get(stat) * effects::stage_percent(effects::stage(&self.statuses, stat)) / 100
+    }
+}
+
+pub struct Side {
+    pub name: String,
+    pub fighters: Vec<Fighter>,
+    pub active: usize,
+}
+
+impl Side {
+    pub fn new(name: &str, fighters: Vec<Fighter>) -> Side {
+        Side {
+            name: name.to_owned(),
+            fighters: fighters,
+            active: 0,
+        }
+    }
+
+    fn active(&self) -> &Fighter {
+        &self.fighters[self.active]
+    }
+
+    fn active_mut(&mut self) -> &mut Fighter {
+        &mut self.fighters[self.active]
+    }
+
+    fn defeated(&self) -> bool {
+        self.fighters.iter().all(|f| !f.alive())
+    }
+
+    fn who(&self) -> Who {
+        Who { owner: self.name.clone(), deus: self.active().name.clone() }
+    }
+}
+
+// What a side does in a turn: cast one of the active fighter's spells, or
+// switch to another fighter.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub enum Action {
+    Cast(usize),
+    Switch(usize),
+}
+
+pub struct Battle {
+    pub seed: u64,
+    pub sides: [Side; 2],
+    pub turn: i32,
+    pub log: Vec<Event>,
+    rng: XorShiftRng,
+    // Only used by `choose`, so replaying recorded actions doesn't need it.
+    chooser: XorShiftRng,
+}
+
+impl Battle {
+    pub fn new(seed: u64, a: Side, b: Side) -> Battle {
+        let (lo, hi) = (seed as u32, (seed >> 32) as u32);
+        let mut battle = Battle {
+            seed: seed,
+            sides: [a, b],
+            turn: 0,
+            log: Vec::new(),
+            // XorShift needs a seed that isn't all zeros.
+            rng: XorShiftRng::from_seed([lo, hi, lo ^ 0x9E37_79B9, hi | 1]),
+            chooser: XorShiftRng::from_seed([hi, lo, hi ^ 0x85EB_CA6B, lo | 1]),
+        };
+        for side in 0..2 {
+            let who = battle.sides[side].who();
+            battle.log.push(Event::SendOut { who: who });
+        }
+        battle
+    }
+
+    pub fn is_over(&self) -> bool {
+        self.sides.iter().any(|s| s.defeated()) || self.turn >= MAX_TURNS
+    }
+
+    // The winning side, None while the battle goes on or for a draw.
+    pub fn winner(&self) -> Option<usize> {
+        match (self.sides[0].defeated(), self.sides[1].defeated()) {
+            (false, true) => Some(0),
+            (true, false) => Some(1),
+            _ => None,
+        }
+    }
+
+    // Plays the whole battle with `choose` picking for both sides, returns
+    // the actions taken.
+    pub fn run(&mut self) -> Vec<[Action; 2]> {
+        let mut actions = Vec::new();
+        while !self.is_over() {
+            let turn = [self.choose(0), self.choose(1)];
+            self.play(turn);
+            actions.push(turn);
+        }
+        actions
+    }
+
+    pub fn play(&mut self, actions: [Action; 2]) {
+        if self.is_over() {
+            return;
+        }
+        self.turn += 1;
+        self.log.push(Event::Turn(self.turn));
+
+        for side in 0..2 {
+            if let Action::Switch(to) = actions[side] {
+                self.switch(side, to);
+            }
+        }
+        for &side in self.order().iter() {
+            if let Action::Cast(slot) = actions[side] {
+                self.cast(side, slot);
+            }
+        }
+        for side in 0..2 {
+            self.tick(side);
+        }
+        for side in 0..2 {
+            self.replace_fainted(side);
+        }
+
+        if self.is_over() {
+            match self.winner() {
+                Some(side) => {
+                    let name = self.sides[side].name.clone();
+                    self.log.push(Event::Won { side: name });
+                }
+                None => self.log.push(Event::Draw),
+            }
+        }
+    }
+
+    // A spell picked at random, weighted by the damage it would do. Status
+    // spells weigh in while their effects aren't there yet.
+    pub fn choose(&mut self, side: usize) -> Action {
+        let me = self.sides[side].active();
+        let foe = self.sides[1 - side].active();
+
+        let weight = |spell: &Spell| -> u32 {
+            if spell.power > 0 {
+                let stab = if me.types.contains(&spell.kind) { 150 } else { 100 };
+                let percent = Type::effectiveness(spell.kind, &foe.types);
+                return (spell.power * percent / 100 * stab / 100 * spell.accuracy / 100) as u32;
+            }
+            let fresh = spell.effects.iter().any(|e| {
+                let target = if e.target == Target::Foe { foe } else { me };
+                !effects::has(&target.statuses, e.effect.kind())
+            });
+            if fresh { 60 } else { 0 }
+        };
+
+        let weights: Vec<u32> = me.spells.iter().map(weight).collect();
+        let total: u32 = weights.iter().sum();
+        if total == 0 {
+            return Action::Cast(0);
+        }
+        let mut pick = self.chooser.gen_range(0, total);
+        for (i, &w) in weights.iter().enumerate() {
+            if pick < w {
+                return Action::Cast(i);
+            }
+            pick -= w;
+        }
+        Action::Cast(0)
+    }
+
+    // Faster fighter first, ties broken by the rng.
+    fn order(&mut self) -> [usize; 2] {
+        let (a, b) = (self.sides[0].active().stat(Stat::Speed), self.sides[1].active().stat(Stat::Speed));
+        if a > b || (a == b && self.rng.gen()) { [0, 1] } else { [1, 0] }
+    }
+
+    fn switch(&mut self, side: usize, to: usize) {
+        let s = &mut self.sides[side];
+        if to == s.active || to >= s.fighters.len() || !s.fighters[to].alive() {
+            return;
+        }
+        effects::switch_out(&mut s.active_mut().statuses);
+        s.active = to;
+        self.log.push(Event::SendOut { who: s.who() });
+    }
+
+    fn cast(&mut self, side: usize, slot: usize) {
+        let foe = 1 - side;
+        if !self.sides[side].active().alive() {
+            return;
+        }
+
+        let who = self.sides[side].who();
+        if let Some((kind, over)) = effects::skips_turn(&mut self.sides[side].active_mut().statuses) {
+            self.log.push(Event::Skipped { who: who.clone(), kind: kind });
+            if over && kind == Kind::Sleep {
+                self.log.push(Event::Expired { who: who, kind: kind });
+            }
+            return;
+        }
+
+        let spell = match self.sides[side].active().spells.get(slot) {
+            Some(spell) => spell.clone(),
+            None => return,
+        };
+        self.log.push(Event::Cast { who: who, spell: spell.name.clone() });
+        if self.rng.gen_range(0, 100) >= spell.accuracy {
+            self.log.push(Event::Missed { who: self.sides[side].who() });
+            return;
+        }
+
+        if spell.power > 0 && self.sides[foe].active().alive() {
+            self.hit(side, &spell);
+        }
+
+        for e in &spell.effects {
+            let target = if e.target == Target::Foe { foe } else { side };
+            if self.rng.gen_range(0, 100) >= e.chance || !self.sides[target].active().alive() {
+                continue;
+            }
+            let who = self.sides[target].who();
+            let fighter = self.sides[target].active_mut();
+            let result = effects::apply(&mut fighter.statuses, &fighter.types, &e.effect, fighter.stats.life);
+            self.log.push(Event::Applied { who: who, kind: e.effect.kind(), result: result });
+        }
+    }
+
+    fn hit(&mut self, side: usize, spell: &Spell) {
+        let foe = 1 - side;
+        let (damage, percent) = {
+            let (me, them) = (self.sides[side].active(), self.sides[foe].active());
+            let (mut atk, def) = if spell.magic {
+                (me.stat(Stat::Attr), them.stat(Stat::Attr))
+            } else {
+                (me.stat(Stat::Atk), them.stat(Stat::Def))
+            };
+            if !spell.magic && effects::has(&me.statuses, Kind::Burn) {
+                atk /= 2;
+            }
+
+            let base = (2 * me.level / 5 + 2) * spell.power * atk / def.max(1) / 50 + 2;
+            let percent = Type::effectiveness(spell.kind, &them.types);
+            let stab = if me.types.contains(&spell.kind) { 150 } else { 100 };
+            let roll = self.rng.gen_range(85, 101);
+            ((base * percent / 100 * stab / 100 * roll / 100).max(1), percent)
+        };
+
+        let who = self.sides[foe].who();
+        let absorbed = effects::absorb(&mut self.sides[foe].active_mut().statuses, damage);
+        if absorbed > 0 {
+            self.log.push(Event::Absorbed { who: who.clone(), amount: absorbed });
+        }
+        self.damage(foe, damage - absorbed);
+        self.log.push(Event::Damage { who: who, amount: damage - absorbed, percent: percent });
+        self.check_fainted(foe);
+    }
+
+    fn damage(&mut self, side: usize, amount: i32) {
+        let fighter = self.sides[side].active_mut();
+        fighter.life = (fighter.life - amount).max(0);
+    }
+
+    fn check_fainted(&mut self, side: usize) {
+        if !self.sides[side].active().alive() {
+            let who = self.sides[side].who();
+            self.log.push(Event::Fainted { who: who });
+        }
+    }
+
+    fn tick(&mut self, side: usize) {
+        if !self.sides[side].active().alive() {
+            return;
+        }
+        let who = self.sides[side].who();
+        let max_life = self.sides[side].active().stats.life;
+
+        for tick in effects::tick(&mut self.sides[side].active_mut().statuses, max_life) {
+            match tick {
+                Tick::Damage(kind, amount) => {
+                    if self.sides[side].active().alive() {
+                        self.damage(side, amount);
+                        self.log.push(Event::Tick { who: who.clone(), kind: kind, amount: amount });
+                        self.check_fainted(side);
+                    }
+                }
+                Tick::Expired(kind) => self.log.push(Event::Expired { who: who.clone(), kind: kind }),
+            }
+        }
+        self.drink(side);
+    }
+
+    // A held potion is drunk at the end of the turn its holder is down to
+    // half Life.
+    fn drink(&mut self, side: usize) {
+        let who = self.sides[side].who();
+        let (item, amount) = {
+            let fighter = self.sides[side].active_mut();
+            let heal = match items::potion(fighter.item.as_ref().map(|s| s.as_str())) {
+                Some(heal) if fighter.alive() && fighter.life * 2 <= fighter.stats.life => heal,
+                _ => return,
+            };
+            let amount = (fighter.stats.life * heal / 100).min(fighter.stats.life - fighter.life);
+            fighter.life += amount;
+            (fighter.item.take().unwrap(), amount)
+        };
+        self.log.push(Event::Drank { who: who, item: item, amount: amount });
+    }
+
+    fn replace_fainted(&mut self, side: usize) {
+        let s = &mut self.sides[side];
+        if s.active().alive() {
+            return;
+        }
+        if let Some(next) = s.fighters.iter().position(|f| f.alive()) {
+            s.active = next;
+            self.log.push(Event::SendOut { who: s.who() });
+        }
+    }
+}
+
+// A fresh seed for a new battle.
+pub fn seed() -> u64 {
+    ::rand::random()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample_side(name: &str, species: &[&str]) -> Result<Side, String> {
+        let mut fighters = Vec::new();
+        for (i, s) in species.iter().enumerate() {
+            let mut deus = DeusInstance::new(0, s, 1);
+            deus.id = i as i32 + 1;
+            deus.exp = 10 * 30 * 30;
+            fighters.push(Fighter::new(&deus).map_err(|e| e.tr("en"))?);
+        }
+        Ok(Side::new(name, fighters))
+    }
+
+    fn holding(mut side: Side, item: &str) -> Side {
+        for fighter in &mut side.fighters {
+            fighter.item = Some(item.to_owned());
+        }
+        side
+    }
+
+    fn sample(seed: u64) -> Result<Battle, String> {
+        Ok(Battle::new(seed, sample_side("a", &["Ignis", "Terra", "Lux"])?, sample_side("b", &["Aqua", "Umbra", "Zephyr"])?))
+    }
+
+    #[test]
+    fn the_same_seed_plays_the_same_battle() -> Result<(), String> {
+        let mut first = sample(42)?;
+        let actions = first.run();
+        let mut again = sample(42)?;
+        for turn in actions {
+            again.play(turn);
+        }
+        if first.log != again.log {
+            return Err("replaying the actions gave another log".to_owned());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn battles_end() -> Result<(), String> {
+        for seed in 0..20 {
+            let mut battle = sample(seed)?;
+            battle.run();
+            match battle.log.last() {
+                Some(&Event::Won { .. }) | Some(&Event::Draw) => {}
+                other => return Err(format!("seed {} ended on {:?}", seed, other)),
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn types_are_immune_to_their_statuses() -> Result<(), String> {
+        let mut statuses = Vec::new();
+        let burn = effects::Effect::Burn { turns: 3 };
+        match effects::apply(&mut statuses, &[DeusType::Water, DeusType::Fire], &burn, 100) {
+            effects::Applied::Immune(DeusType::Fire) => Ok(()),
+            other => Err(format!("burning a Fire type gave {:?}", other)),
+        }
+    }
+
+    #[test]
+    fn effects_stack_up_to_their_caps() -> Result<(), String> {
+        let mut statuses = Vec::new();
+        let poison = effects::Effect::Poison { turns: 2 };
+        for _ in 0..5 {
+            effects::apply(&mut statuses, &[], &poison, 100);
+        }
+        if statuses[0].value != effects::MAX_POISON {
+            return Err(format!("poison stacked to {}", statuses[0].value));
+        }
+
+        let sleep = effects::Effect::Sleep { turns: 2 };
+        effects::apply(&mut statuses, &[], &effects::Effect::Stun, 100);
+        if effects::apply(&mut statuses, &[], &sleep, 100) != effects::Applied::Blocked {
+            return Err("a stunned fighter fell asleep".to_owned());
+        }
+
+        let growl = effects::Effect::Stage { stat: Stat::Atk, amount: -4, turns: 3 };
+        effects::apply(&mut statuses, &[], &growl, 100);
+        effects::apply(&mut statuses, &[], &growl, 100);
+        if effects::stage(&statuses, Stat::Atk) != -effects::MAX_STAGE {
+            return Err(format!("Atk stage went to {}", effects::stage(&statuses, Stat::Atk)));
+        }
+
+        effects::tick(&mut statuses, 100);
+        effects::tick(&mut statuses, 100);
+        if effects::has(&statuses, Kind::Poison) {
+            return Err("poison outlived its turns".to_owned());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn potions_are_drunk_once_and_replay() -> Result<(), String> {
+        let mut drunk = 0;
+        for seed in 0..20 {
+            let a = holding(sample_side("a", &["Ignis", "Terra", "Lux"])?, "Potion");
+            let mut battle = Battle::new(seed, a, sample_side("b", &["Aqua", "Umbra", "Zephyr"])?);
+            let teams = replay::encode_teams(&[replay::TeamSnapshot::of(&battle.sides[0]), replay::TeamSnapshot::of(&battle.sides[1])]);
+            let actions = battle.run();
+
+            let mut who: Vec<(&String, &String)> = battle.log.iter()
+                .filter_map(|e| match *e {
+                    Event::Drank { ref who, .. } => Some((&who.owner, &who.deus)),
+                    _ => None,
+                })
+                .collect();
+            let count = who.len();
+            drunk += count;
+            who.sort();
+            who.dedup();
+            if who.len() != count {
+                return Err(format!("seed {} drank a potion twice", seed));
+            }
+            if battle.sides[0].fighters.iter().filter(|f| f.item.is_none()).count() != count {
+                return Err(format!("seed {} lost track of the potions drunk", seed));
+            }
+
+            let teams = replay::decode_teams(&teams)?;
+            let mut again = Battle::new(seed, teams[0].side().map_err(|e| e.tr("en"))?, teams[1].side().map_err(|e| e.tr("en"))?);
+            for turn in actions {
+                again.play(turn);
+            }
+            if again.log != battle.log {
+                return Err(format!("seed {} replayed without its potions", seed));
+            }
+        }
+        if drunk == 0 {
+            return Err("nobody drank a potion in 20 battles".to_owned());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn replays_keep_the_spells_they_were_played_with() -> Result<(), String> {
+        let mut battle = Battle::new(7, sample_side("a", &["Ignis", "Terra"])?, sample_side("b", &["Aqua", "Umbra"])?);
+        let mut teams = [replay::TeamSnapshot::of(&battle.sides[0]), replay::TeamSnapshot::of(&battle.sides[1])];
+        let actions = battle.run();
+
+        // Say Ignis' first spell was taken out of data/spells.json since.
+        teams[0].fighters[0].spells[0] = "Lost Art".to_owned();
+        teams[0].fighters[0].spell_stats[0].name = "Lost Art".to_owned();
+        let kept = replay::decode_teams(&replay::encode_teams(&teams))?;
+        let again = replay::Replay::new([1, 2], 7, kept.clone(), actions.clone(), None).play().map_err(|e| e.tr("en"))?;
+        let renamed = |log: &[Event]| log.iter().map(|e| e.to_string().replace("Lost Art", &battle.sides[0].fighters[0].spells[0].name))
+            .collect::<Vec<_>>();
+        if renamed(&again.log) != renamed(&battle.log) {
+            return Err("replay played out differently with its own spells".to_owned());
+        }
+
+        // Replays saved before the spells were kept only have the names.
+        let mut old = kept;
+        for fighter in old.iter_mut().flat_map(|t| t.fighters.iter_mut()) {
+            fighter.spell_stats.clear();
+        }
+        if replay::Replay::new([1, 2], 7, old, actions, None).play().is_ok() {
+            return Err("an old replay played without a spell it cast".to_owned());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn fighters_take_the_spells_picked_for_them() -> Result<(), String> {
+        let spells = |deus: &DeusInstance| {
+            Fighter::new(deus).map(|f| f.spells.iter().map(|s| s.name.clone()).collect::<Vec<_>>()).map_err(|e| e.tr("en"))
+        };
+        let mut deus = DeusInstance::new(1, "Ignis", 1);
+        if spells(&deus)? != vec!["Ember", "Tackle", "Growl"] {
+            return Err(format!("Ignis took {:?}", spells(&deus)?));
+        }
+        if deus.learn("Flame Lash").is_ok() {
+            return Err("a level 1 Ignis learned Flame Lash".to_owned());
+        }
+
+        deus.exp = 10 * 14 * 14;
+        deus.learn("flame lash").map_err(|e| e.tr("en"))?;
+        deus.forget("Tackle").map_err(|e| e.tr("en"))?;
+        if spells(&deus)? != vec!["Ember", "Growl", "Flame Lash"] {
+            return Err(format!("Ignis took {:?}", spells(&deus)?));
+        }
+        deus.learn("War Cry").map_err(|e| e.tr("en"))?;
+        if deus.learn("Tackle").is_ok() || deus.equipped.len() != MAX_SPELLS {
+            return Err(format!("Ignis took {:?}", deus.equipped));
+        }
+
+        deus.equipped.push("Inferno".to_owned());
+        if Fighter::new(&deus).is_ok() {
+            return Err("Ignis took Inferno into battle without knowing it".to_owned());
+        }
+
+        // Say a spell it learned was taken out of data/spells.json since.
+        let mut stale = DeusInstance::new(1, "Ignis", 1);
+        stale.spells.push("Lost Art".to_owned());
+        stale.equipped = vec!["Ember".to_owned(), "Lost Art".to_owned()];
+        if Fighter::new(&stale).is_ok() {
+            return Err("Ignis went into battle without the spell it lost".to_owned());
+        }
+        Ok(())
+    }
+}
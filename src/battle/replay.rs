@@ -0,0 +1,203 @@
+// What's kept of a battle: the seed, both teams as they were when it started
+// and the actions taken. Playing the actions again gives the same battle.
+
+use serde_json;
+
+use battle::{Action, Battle, Fighter, Side};
+use battle::log::Event;
+use deus::Spells::{self, Spell};
+use deus::Stats::Stats;
+use deus::Type::DeusType;
+use i18n::Message;
+
+// A fighter as it entered the battle.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub id: i32,
+    pub name: String,
+    pub level: i32,
+    pub types: Vec<DeusType>,
+    pub stats: Stats,
+    pub spells: Vec<String>,
+    // The spells as they were when the battle was played, so changes to the
+    // catalog don't change old battles. Empty in replays saved before they
+    // were kept, those look the names up.
+    #[serde(default)]
+    pub spell_stats: Vec<Spell>,
+    // The held item, potions are drunk during the battle.
+    #[serde(default)]
+    pub item: Option<String>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct TeamSnapshot {
+    pub name: String,
+    pub fighters: Vec<Snapshot>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Replay {
+    pub id: i32,
+    pub players: [i32; 2],
+    pub seed: u64,
+    pub teams: [TeamSnapshot; 2],
+    pub actions: Vec<[Action; 2]>,
+    // User id of the winner, None for a draw.
+    pub winner: Option<i32>,
+    pub at: i64,
+}
+
+// The offline export of a replay, the log is included so it can be read
+// without the bot.
+#[derive(Serialize)]
+struct Export<'a> {
+    id: i32,
+    players: [i32; 2],
+    seed: u64,
+    teams: &'a [TeamSnapshot; 2],
+    actions: &'a [[Action; 2]],
+    winner: Option<i32>,
+    at: i64,
+    log: Vec<String>,
+}
+
+impl Snapshot {
+    pub fn of(fighter: &Fighter) -> Snapshot {
+        Snapshot {
+            id: fighter.id,
+            name: fighter.name.clone(),
+            level: fighter.level,
+            types: fighter.types.clone(),
+            stats: fighter.stats,
+            spells: fighter.spells.iter().map(|s| s.name.clone()).collect(),
+            spell_stats: fighter.spells.clone(),
+            item: fighter.item.clone(),
+        }
+    }
+
+    // Fails on an old replay with a spell the catalog doesn't have anymore,
+    // leaving it out would move the slots the actions cast.
+    pub fn fighter(&self) -> Result<Fighter, Message> {
+        let spells = if self.spell_stats.is_empty() {
+            let mut spells = Vec::new();
+            for name in &self.spells {
+                spells.push(Spells::find(name).cloned().ok_or_else(|| msg!("replay-spell-gone", deus = &self.name, spell = name))?);
+            }
+            spells
+        } else {
+            self.spell_stats.clone()
+        };
+        Ok(Fighter {
+            id: self.id,
+            name: self.name.clone(),
+            level: self.level,
+            types: self.types.clone(),
+            stats: self.stats,
+            life: self.stats.life,
+            spells: spells,
+            statuses: Vec::new(),
+            item: self.item.clone(),
+        })
+    }
+}
+
+impl TeamSnapshot {
+    pub fn of(side: &Side) -> TeamSnapshot {
+        TeamSnapshot {
+            name: side.name.clone(),
+            fighters: side.fighters.iter().map(Snapshot::of).collect(),
+        }
+    }
+
+    pub fn side(&self) -> Result<Side, Message> {
+        Ok(Side::new(&self.name, self.fighters.iter().map(Snapshot::fighter).collect::<Result<_, _>>()?))
+    }
+}
+
+impl Replay {
+    // Storage fills in the id and time.
+    pub fn new(players: [i32; 2], seed: u64, teams: [TeamSnapshot; 2], actions: Vec<[Action; 2]>, winner: Option<i32>) -> Replay {
+        Replay {
+            id: 0,
+            players: players,
+            seed: seed,
+            teams: teams,
+            actions: actions,
+            winner: winner,
+            at: 0,
+        }
+    }
+
+    // The battle played again from the start.
+    pub fn play(&self) -> Result<Battle, Message> {
+        self.play_to(self.actions.len())
+    }
+
+    // The battle as it was after its first `turns` turns.
+    pub fn play_to(&self, turns: usize) -> Result<Battle, Message> {
+        let mut battle = Battle::new(self.seed, self.teams[0].side()?, self.teams[1].side()?);
+        for &turn in self.actions.iter().take(turns) {
+            battle.play(turn);
+        }
+        Ok(battle)
+    }
+
+    pub fn to_json(&self) -> Result<String, Message> {
+        let export = Export {
+            id: self.id,
+            players: self.players,
+            seed: self.seed,
+            teams: &self.teams,
+            actions: &self.actions,
+            winner: self.winner,
+            at: self.at,
+            log: self.play()?.log.iter().map(|e| e.to_string()).collect(),
+        };
+        Ok(serde_json::to_string_pretty(&export).unwrap())
+    }
+}
+
+// The log cut in one page per turn, the first page starts with the send
+// outs.
+pub fn pages(log: &[Event]) -> Vec<Vec<&Event>> {
+    let mut pages: Vec<Vec<&Event>> = vec![Vec::new()];
+    for event in log {
+        if let Event::Turn(n) = *event {
+            if n > 1 {
+                pages.push(Vec::new());
+            }
+        }
+        pages.last_mut().unwrap().push(event);
+    }
+    pages
+}
+
+// Storage encodings: teams as JSON, actions as a byte each, switches have the
+// high bit set.
+pub fn encode_teams(teams: &[TeamSnapshot; 2]) -> String {
+    serde_json::to_string(teams).unwrap()
+}
+
+pub fn decode_teams(teams: &str) -> Result<[TeamSnapshot; 2], String> {
+    serde_json::from_str::<[TeamSnapshot; 2]>(teams).map_err(|e| format!("bad replay teams: {}", e))
+}
+
+pub fn encode_actions(actions: &[[Action; 2]]) -> Vec<u8> {
+    let byte = |a: Action| match a {
+        Action::Cast(slot) => slot as u8,
+        Action::Switch(to) => 0x80 | to as u8,
+    };
+    actions.iter().flat_map(|turn| vec![byte(turn[0]), byte(turn[1])]).collect()
+}
+
+pub fn decode_actions(bytes: &[u8]) -> Vec<[Action; 2]> {
+    let action = |b: u8| if b & 0x80 == 0 {
+        Action::Cast(b as usize)
+    } else {
+        Action::Switch((b & 0x7F) as usize)
+    };
+    bytes.chunks(2)
+        .filter(|c| c.len() == 2)
+        .map(|c| [action(c[0]), action(c[1])])
+        .collect()
+}
@@ -0,0 +1,38 @@
+use achievements::{self, Achievement};
+use cmds::{self, registered};
+use storage;
+use users::Profile::Section;
+
+// !achievements [@user], what a player unlocked and what's left. Players who
+// hid their badges on their !profile only show them to themselves.
+command!(achievements(ctx, msg) {
+    let store = storage::get(ctx)?;
+    let lang = cmds::lang(&*store, msg);
+    let target = msg.mentions.get(0).unwrap_or(&msg.author);
+    let user = registered(&*store, target, lang)?;
+    if target.id != msg.author.id && store.profile(user.id)?.is_hidden(Section::Badges) {
+        return Err(tr!(lang, "achievements-hidden", name = &user.name));
+    }
+    let unlocked = store.achievements(user.id)?;
+
+    let mut lines = vec![tr!(lang, "achievements-header", name = &user.name, unlocked = unlocked.len(),
+                             total = Achievement::catalog().len())];
+    for a in Achievement::catalog() {
+        lines.push(if unlocked.iter().any(|u| u.achievement == a.id) {
+            format!("{} **{}**: {}", a.badge, a.title(lang), a.criterion.describe(lang))
+        } else {
+            format!("🔒 {}: {}", a.title(lang), a.criterion.describe(lang))
+        });
+    }
+
+    let _ = msg.reply(&lines.join("\n"));
+});
+
+// !backfill, owner only: awards what players earned before achievements
+// were tracked. Safe to run again.
+command!(backfill(ctx, msg) {
+    let store = storage::get(ctx)?;
+    let lang = cmds::lang(&*store, msg);
+    let (players, awarded) = achievements::backfill(&*store)?;
+    let _ = msg.reply(&tr!(lang, "achievements-backfilled", awarded = awarded, players = players));
+});
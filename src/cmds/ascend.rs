@@ -0,0 +1,88 @@
+use cmds::{self, registered, stars};
+use deus::Ascension::{self, Step, MATERIAL};
+use deus::Instance::{DeusInstance, MAX_STARS};
+use events;
+use items;
+use storage;
+
+// !ascend <deus id> [shards|<copy id>...], raises a Deus by a star.
+command!(ascend(ctx, msg, args) {
+    let store = storage::get(ctx)?;
+    let lang = cmds::lang(&*store, msg);
+    let usage = || tr!(lang, "ascend-usage");
+    let me = registered(&*store, &msg.author, lang)?;
+    let deus = store.deus(args.get(0).and_then(|s| s.parse::<i32>().ok()).ok_or_else(&usage)?)?;
+    if deus.owner != me.id {
+        return Err(tr!(lang, "not-your-deus"));
+    }
+    let step = Ascension::next(deus.stars).ok_or_else(|| tr!(lang, "ascend-maxed", deus = &deus.deus, stars = MAX_STARS))?;
+    let shards = store.inventory(me.id)?.into_iter().find(|i| i.item == MATERIAL).map_or(0, |i| i.count);
+
+    let (copies, material) = match args.get(1).map(|s| s.as_str()) {
+        None => {
+            let spare: Vec<String> = store.deus_of(me.id)?.iter()
+                .filter(|d| d.deus == deus.deus && d.id != deus.id)
+                .map(|d| format!("{} (id {})", stars(d.stars), d.id))
+                .collect();
+            let _ = msg.reply(&preview(lang, &deus, step, &spare, shards).join("\n"));
+            return Ok(());
+        }
+        Some("shards") => {
+            if shards < step.materials {
+                return Err(tr!(lang, "ascend-not-enough-shards", needed = step.materials,
+                               item = items::Item::title(lang, MATERIAL), have = shards));
+            }
+            (Vec::new(), Some((MATERIAL, step.materials)))
+        }
+        Some(_) => {
+            let copies = args[1..].iter().map(|s| s.parse::<i32>()).collect::<Result<Vec<_>, _>>().map_err(|_| usage())?;
+            if copies.len() != step.copies {
+                return Err(tr!(lang, "ascend-wrong-copies", needed = step.copies, deus = &deus.deus, given = copies.len()));
+            }
+            for &id in &copies {
+                let copy = store.deus(id)?;
+                if copy.owner != me.id || copy.deus != deus.deus || copy.id == deus.id {
+                    return Err(tr!(lang, "ascend-not-a-copy", id = id, deus = &deus.deus));
+                }
+            }
+            (copies, None)
+        }
+    };
+
+    let up = DeusInstance { stars: step.stars, ..deus.clone() };
+    let reason = format!("ascended {} to {} stars", deus.deus, step.stars);
+    let ascended = match store.ascend_deus(&up, &copies, material, step.vults, &reason) {
+        Ok(ascended) => ascended,
+        Err(storage::Error::NotEnoughVults) => return Err(tr!(lang, "costs-vults", price = step.vults, have = me.vults)),
+        Err(storage::Error::NotFound) => return Err(tr!(lang, "ascend-changed")),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut lines = vec![tr!(lang, "ascend-done", deus = &ascended.deus, id = ascended.id, stars = stars(ascended.stars),
+                             multiplier = step.multiplier)];
+    if step.spell_slot {
+        lines.push(tr!(lang, "ascend-spell-slot", slots = Ascension::spell_slots(ascended.stars)));
+    }
+    lines.extend(events::flush(&*store, lang, me.id));
+    let _ = msg.reply(&lines.join("\n"));
+});
+
+fn preview(lang: &str, deus: &DeusInstance, step: &Step, spare: &[String], shards: i32) -> Vec<String> {
+    let mut lines = vec![
+        tr!(lang, "ascend-preview", deus = &deus.deus, id = deus.id, stars = stars(step.stars), vults = step.vults,
+            copies = step.copies, materials = step.materials, item = items::Item::title(lang, MATERIAL)),
+        tr!(lang, "ascend-preview-stats", from = Ascension::multiplier(deus.stars), to = step.multiplier),
+    ];
+    if step.spell_slot {
+        lines.push(tr!(lang, "ascend-preview-slots", to = Ascension::spell_slots(step.stars),
+                       from = Ascension::spell_slots(deus.stars)));
+    }
+    lines.push(if spare.is_empty() {
+        tr!(lang, "ascend-no-copies", deus = &deus.deus)
+    } else {
+        tr!(lang, "ascend-copies", deus = &deus.deus, copies = spare.join(", "))
+    });
+    lines.push(tr!(lang, "ascend-shards", count = shards, item = items::Item::title(lang, MATERIAL)));
+    lines.push(tr!(lang, "ascend-how", id = deus.id));
+    lines
+}
@@ -0,0 +1,262 @@
+use rand::{self, Rng};
+
+use battle::{self, Battle, Fighter, Side};
+use battle::replay::{Replay, TeamSnapshot};
+use cmds::{self, registered};
+use cmds::ladder::{settle, standing};
+use deus::Instance::DeusInstance;
+use deus::Team;
+use dex;
+use energy::{self, BATTLE_COST};
+use events;
+use items;
+use render;
+use ladder;
+use storage::{self, now, Storage};
+use users::Rating::Rating;
+use users::Users::User;
+
+// Discord messages are capped at 2000 characters.
+const LOG_LENGTH: usize = 1500;
+// How long a challenge can be accepted for, in seconds.
+const CHALLENGE_TIME: i64 = 10 * 60;
+
+// !battle @user challenges them, the battle is fought and rated once they
+// answer with !battle accept @you: your active team against theirs, choices
+// made by the bot for both sides. !battle wild fights wild Deus instead.
+command!(battle(ctx, msg, args) {
+    let store = storage::get(ctx)?;
+    let lang = cmds::lang(&*store, msg);
+    let me = registered(&*store, &msg.author, lang)?;
+    let accept = match args.get(0).map(|s| s.as_str()) {
+        Some("wild") => {
+            let _ = msg.reply(&pve(&*store, lang, &me, &tr!(lang, "battle-wild-side"), wild)?.join("\n"));
+            return Ok(());
+        }
+        Some("accept") => true,
+        _ => false,
+    };
+    let target = msg.mentions.get(0).ok_or_else(|| tr!(lang, "battle-usage"))?;
+    if target.id == msg.author.id {
+        return Err(tr!(lang, "battle-yourself"));
+    }
+    let foe = registered(&*store, target, lang)?;
+    if !accept {
+        side(&*store, lang, &me)?;
+        side(&*store, lang, &foe)?;
+        store.challenge(me.id, foe.id, now())?;
+        let _ = msg.reply(&tr!(lang, "battle-challenged", mine = &me.name, theirs = &foe.name,
+                               minutes = CHALLENGE_TIME / 60));
+        return Ok(());
+    }
+
+    // Whoever challenged goes first.
+    let (theirs, their_deus) = side(&*store, lang, &foe)?;
+    let (mine, my_deus) = side(&*store, lang, &me)?;
+    let seed = battle::seed();
+    let mut battle = Battle::new(seed, theirs, mine);
+    let teams = [TeamSnapshot::of(&battle.sides[0]), TeamSnapshot::of(&battle.sides[1])];
+    let actions = battle.run();
+    let winner = battle.winner();
+
+    let mut their_team = spent(&battle.sides[0], their_deus.clone());
+    let mut my_team = spent(&battle.sides[1], my_deus.clone());
+    let mut news = reward(lang, &mut my_team, &their_deus, winner == Some(1));
+    news.extend(reward(lang, &mut their_team, &my_deus, winner == Some(0)));
+    let mut team = their_team;
+    team.extend(my_team);
+
+    settle(&*store)?;
+    let season = ladder::current_season();
+    let before = (store.rating(foe.id, season)?, store.rating(me.id, season)?);
+    let players = [foe.id, me.id];
+    let played = Replay::new(players, seed, teams, actions, winner.map(|side| players[side]));
+    let (replay, theirs, mine) = match store.finish_pvp(season, &played, &team, now() - CHALLENGE_TIME) {
+        Ok(finished) => finished,
+        Err(storage::Error::Closed) => {
+            return Err(tr!(lang, "battle-no-challenge", name = &foe.name, minutes = CHALLENGE_TIME / 60));
+        }
+        Err(e) => return Err(e.into()),
+    };
+    news.extend(dex::see(&*store, lang, me.id, &species(&their_deus))?);
+    news.extend(dex::see(&*store, lang, foe.id, &species(&my_deus))?);
+    news.extend(events::flush(&*store, lang, me.id));
+
+    let mut lines = vec![tr!(lang, "battle-header", mine = &foe.name, theirs = &me.name), "```".to_owned()];
+    lines.extend(tail(&battle, lang));
+    lines.push("```".to_owned());
+    lines.push(outcome_line(lang, &battle));
+    lines.push(tr!(lang, "battle-ranked", mine = movement(lang, &foe, &before.0, &theirs),
+                   theirs = movement(lang, &me, &before.1, &mine)));
+    lines.push(tr!(lang, "battle-replay", id = replay.id));
+    lines.extend(news);
+
+    let _ = msg.reply(&lines.join("\n"));
+    let png = render::battle_snapshot(lang, &battle);
+    let _ = ctx.send_file(&png[..], &format!("battle-{}.png", replay.id), |m| m.content(""));
+});
+
+// Your active team against Deus nobody plays, `foes` picks them given your
+// team. It takes energy rather than rating, nothing is recorded but exp and
+// training points.
+pub fn pve<F>(store: &Storage, lang: &str, me: &User, name: &str, foes: F) -> Result<Vec<String>, String>
+    where F: FnOnce(&str, &[DeusInstance]) -> Result<Vec<DeusInstance>, String>
+{
+    let (mine, my_deus) = side(store, lang, me)?;
+    let their_deus = foes(lang, &my_deus)?;
+    let fighters = their_deus.iter().map(Fighter::new).collect::<Result<Vec<_>, _>>().map_err(|e| e.tr(lang))?;
+
+    // Fought before the energy is taken, nothing is kept without it.
+    let mut battle = Battle::new(battle::seed(), mine, Side::new(name, fighters));
+    battle.run();
+    let winner = battle.winner();
+    let mut team = spent(&battle.sides[0], my_deus);
+    let mut news = reward(lang, &mut team, &their_deus, winner == Some(0));
+    let at = now();
+    let tired = match store.finish_pve(me.id, BATTLE_COST, &team, at) {
+        Ok(user) => user,
+        Err(storage::Error::NotEnoughEnergy) => {
+            return Err(tr!(lang, "energy-needed", cost = BATTLE_COST, have = energy::describe(lang, me, at)));
+        }
+        Err(e) => return Err(e.into()),
+    };
+    news.extend(dex::see(store, lang, me.id, &species(&their_deus))?);
+
+    let mut lines = vec![tr!(lang, "battle-header", mine = &me.name, theirs = &battle.sides[1].name), "```".to_owned()];
+    lines.extend(tail(&battle, lang));
+    lines.push("```".to_owned());
+    lines.push(outcome_line(lang, &battle));
+    lines.push(tr!(lang, "energy-left", energy = energy::describe(lang, &tired, at)));
+    lines.extend(news);
+    Ok(lines)
+}
+
+// The species in a team, for the dex.
+pub fn species(team: &[DeusInstance]) -> Vec<String> {
+    team.iter().map(|d| d.deus.clone()).collect()
+}
+
+// As many wild Deus as `team` has, within two levels of its average.
+fn wild(lang: &str, team: &[DeusInstance]) -> Result<Vec<DeusInstance>, String> {
+    let mut rng = rand::thread_rng();
+    let wild = items::wild();
+    let level = team.iter().map(|d| d.level()).sum::<i32>() / team.len().max(1) as i32;
+
+    let mut members = Vec::new();
+    for _ in team {
+        let species = rng.choose(&wild).ok_or_else(|| tr!(lang, "battle-nothing-wild"))?;
+        let mut deus = DeusInstance::roll(0, &species.name, 1);
+        let level = (level + rng.gen_range(-2, 3)).max(1).min(100);
+        deus.exp = 10 * level * level;
+        members.push(deus);
+    }
+    Ok(members)
+}
+
+// A user's active team, lead first, along with the instances it's made of.
+pub fn side(store: &Storage, lang: &str, user: &User) -> Result<(Side, Vec<DeusInstance>), String> {
+    let team = store.teams(user.id)?
+        .into_iter()
+        .find(|t| t.active)
+        .ok_or_else(|| tr!(lang, "battle-no-team", name = &user.name))?;
+    let owned = store.deus_of(user.id)?;
+    Team::validate(&team, &owned, Team::format("open").unwrap()).map_err(|e| e.tr(lang))?;
+
+    let mut members: Vec<DeusInstance> = team.members.iter()
+        .filter_map(|id| owned.iter().find(|d| d.id == *id).cloned())
+        .collect();
+    if let Some(i) = team.lead().and_then(|lead| members.iter().position(|d| d.id == lead)) {
+        let lead = members.remove(i);
+        members.insert(0, lead);
+    }
+
+    let fighters = members.iter().map(Fighter::new).collect::<Result<Vec<_>, _>>().map_err(|e| e.tr(lang))?;
+    Ok((Side::new(&user.name, fighters), members))
+}
+
+// Potions drunk in the battle are gone, returns the team as it is now.
+pub fn spend(store: &Storage, side: &Side, team: Vec<DeusInstance>) -> Result<Vec<DeusInstance>, String> {
+    let after = spent(side, team.clone());
+    for (deus, before) in after.iter().zip(&team) {
+        if deus.held != before.held {
+            store.update_deus(deus)?;
+        }
+    }
+    Ok(after)
+}
+
+// The Deus of `team` that drank their potion in the battle.
+pub fn drank(side: &Side, team: &[DeusInstance]) -> Vec<i32> {
+    spent(side, team.to_vec()).iter().zip(team).filter(|&(after, before)| after.held != before.held).map(|(d, _)| d.id).collect()
+}
+
+// The team without the potions drunk in the battle, nothing is saved.
+fn spent(side: &Side, team: Vec<DeusInstance>) -> Vec<DeusInstance> {
+    let mut after = Vec::new();
+    for mut deus in team {
+        let kept = side.fighters.iter().any(|f| f.id == deus.id && f.item.is_some());
+        if !kept {
+            deus.held = None;
+        }
+        after.push(deus);
+    }
+    after
+}
+
+// Exp and training points for everyone who took part, winners get more,
+// left for the caller to save. Returns a line per level up.
+fn reward(lang: &str, team: &mut [DeusInstance], foes: &[DeusInstance], won: bool) -> Vec<String> {
+    let foe_level = if foes.is_empty() {
+        team.iter().map(|d| d.level()).sum::<i32>() / team.len().max(1) as i32
+    } else {
+        foes.iter().map(|d| d.level()).sum::<i32>() / foes.len() as i32
+    };
+    let (exp, points) = if won { (10 * foe_level, 3) } else { (3 * foe_level, 1) };
+
+    let mut news = Vec::new();
+    for deus in team.iter_mut() {
+        let level = deus.level();
+        let evolved = deus.gain_exp(exp);
+        deus.earn_points(points);
+        if deus.level() > level {
+            news.push(tr!(lang, "battle-level-up", deus = evolved.as_ref().unwrap_or(&deus.deus), level = deus.level()));
+        }
+        if let Some(from) = evolved {
+            news.push(tr!(lang, "battle-evolved", from = from, into = &deus.deus));
+        }
+    }
+    news
+}
+
+// "Ann 1216 (+16)", with the new tier when it changed.
+fn movement(lang: &str, user: &User, before: &Rating, after: &Rating) -> String {
+    let line = format!("{} {} ({:+})", user.name, after.rating, after.rating - before.rating);
+    if ladder::placed(after) && standing(lang, after) != standing(lang, before) {
+        tr!(lang, "battle-new-tier", line = line, tier = standing(lang, after))
+    } else {
+        line
+    }
+}
+
+fn outcome_line(lang: &str, battle: &Battle) -> String {
+    match battle.winner() {
+        Some(side) => tr!(lang, "battle-won", side = &battle.sides[side].name, turns = battle.turn),
+        None => tr!(lang, "battle-draw", turns = battle.turn),
+    }
+}
+
+// The end of the log, as much as fits in a message.
+pub fn tail(battle: &Battle, lang: &str) -> Vec<String> {
+    let mut lines = Vec::new();
+    let mut length = 0;
+    for event in battle.log.iter().rev() {
+        let line = event.describe(lang);
+        length += line.len() + 1;
+        if length > LOG_LENGTH {
+            break;
+        }
+        lines.push(line);
+    }
+    lines.reverse();
+    lines
+}
@@ -0,0 +1,17 @@
+use cmds;
+use render;
+use storage;
+
+// !card <deus id>, a Deus' card as an image, anyone's Deus can be shown.
+command!(card(ctx, msg, args) {
+    let store = storage::get(ctx)?;
+    let lang = cmds::lang(&*store, msg);
+    let id = args.get(0).and_then(|s| s.parse::<i32>().ok()).ok_or_else(|| tr!(lang, "card-usage"))?;
+    let deus = match store.deus(id) {
+        Ok(deus) => deus,
+        Err(storage::Error::NotFound) => return Err(tr!(lang, "no-such-deus", id = id)),
+        Err(e) => return Err(e.into()),
+    };
+    let png = render::deus_card(lang, &deus)?;
+    let _ = ctx.send_file(&png[..], &format!("deus-{}.png", id), |m| m.content(&tr!(lang, "deus-named", deus = &deus.deus, id = id)));
+});
@@ -0,0 +1,90 @@
+use cmds::{self, registered};
+use deus::Deus::{self, Deus as Species};
+use deus::Stats::STATS;
+use dex::{self, Entry::Entry as DexEntry, MILESTONES};
+use menus::{self, Menu};
+use quests::bar;
+use storage;
+
+// !dex [name|number], the species you've come across, or one of them.
+command!(dex(ctx, msg, args) {
+    let store = storage::get(ctx)?;
+    let lang = cmds::lang(&*store, msg);
+    let me = registered(&*store, &msg.author, lang)?;
+    let news = dex::reward(&*store, lang, me.id)?;
+    let entries = store.dex(me.id)?;
+
+    if args.is_empty() {
+        let mut lines = summary(lang, &entries);
+        lines.extend(news);
+        let pages = menus::paginate(&lines[0], &lines[1..]);
+        return menus::open(ctx, msg, Menu::pages(msg.author.id.0, lang, pages));
+    }
+
+    let wanted = args.join(" ");
+    let species = match wanted.trim_left_matches('#').parse::<i32>() {
+        Ok(number) => Deus::catalog().iter().find(|d| d.number == number),
+        Err(_) => Deus::find(&wanted),
+    };
+    let mut lines = page(lang, species.ok_or_else(|| tr!(lang, "dex-unknown", name = &wanted))?, &entries);
+    lines.extend(news);
+    let _ = msg.reply(&lines.join("\n"));
+});
+
+fn summary(lang: &str, entries: &[DexEntry]) -> Vec<String> {
+    let total = Deus::catalog().len() as i32;
+    let (seen, caught) = dex::completion(entries);
+    let mut lines = vec![
+        tr!(lang, "dex-header", seen = bar(seen as i32, total), seen_percent = dex::percent(seen),
+            caught = bar(caught as i32, total), caught_percent = dex::percent(caught)),
+    ];
+    for species in Deus::catalog() {
+        lines.push(match entries.iter().find(|e| e.deus == species.name) {
+            Some(e) if e.caught => format!("#{} {} ●", species.number, species.name),
+            Some(_) => format!("#{} {} ○", species.number, species.name),
+            None => format!("#{} ???", species.number),
+        });
+    }
+    if let Some(next) = MILESTONES.iter().find(|m| !dex::reached(m, entries)) {
+        lines.push(tr!(lang, "dex-next", goal = if next.caught { "caught" } else { "seen" }, percent = next.percent,
+                       vults = next.vults));
+    }
+    lines
+}
+
+fn page(lang: &str, species: &'static Species, entries: &[DexEntry]) -> Vec<String> {
+    let caught = match entries.iter().find(|e| e.deus == species.name) {
+        Some(e) => e.caught,
+        None => return vec![tr!(lang, "dex-unseen", number = species.number)],
+    };
+
+    let types: Vec<&str> = species.types.iter().map(|t| t.name()).collect();
+    let stats: Vec<String> = STATS.iter().map(|&s| format!("{} {}", s.name(), species.base.get(s))).collect();
+    let chain: Vec<String> = dex::chain(species).iter()
+        .map(|d| {
+            let name = if d.name == species.name { format!("**{}**", d.name) } else { d.name.clone() };
+            let at = Deus::catalog().iter().filter_map(|b| b.evolves.as_ref()).find(|e| e.into == d.name).map(|e| e.level);
+            match at {
+                Some(level) => tr!(lang, "dex-evolves-at", name = name, level = level),
+                None => name,
+            }
+        })
+        .collect();
+    let learns: Vec<String> = species.learns.iter().map(|l| tr!(lang, "dex-learns-at", spell = &l.spell, level = l.level)).collect();
+
+    let mut lines = vec![
+        tr!(lang, "dex-species", number = species.number, name = &species.name, types = types.join("/"),
+            rarity = dex::rarity(species), state = if caught { "caught" } else { "seen" }),
+        tr!(lang, "dex-base-stats", stats = stats.join(", "), total = species.base.total()),
+        tr!(lang, "dex-evolution", chain = chain.join(" → ")),
+        tr!(lang, "dex-spells", spells = species.spells.join(", ")),
+    ];
+    if !learns.is_empty() {
+        lines.push(tr!(lang, "dex-learns", spells = learns.join(", ")));
+    }
+    let sources = dex::sources(lang, species);
+    if !sources.is_empty() {
+        lines.push(tr!(lang, "dex-sources", sources = sources.join(", ")));
+    }
+    lines
+}
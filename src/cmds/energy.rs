@@ -0,0 +1,75 @@
+use rand;
+
+use cmds::{self, registered, server};
+use energy::{self, EXPLORE_COST, MAX_ENERGY, POINT_PRICE};
+use events;
+use storage::{self, now};
+
+// !energy, !energy refill
+command!(energy(ctx, msg, args) {
+    let store = storage::get(ctx)?;
+    let lang = cmds::lang(&*store, msg);
+    let me = registered(&*store, &msg.author, lang)?;
+    let at = now();
+    let missing = MAX_ENERGY - energy::current(&me, at);
+
+    if args.get(0).map(|s| s.as_str()) != Some("refill") {
+        let mut line = tr!(lang, "energy-left", energy = energy::describe(lang, &me, at));
+        if missing > 0 {
+            line += &format!(" {}", tr!(lang, "energy-refill-offer", price = missing * POINT_PRICE));
+        }
+        let _ = msg.reply(&line);
+        return Ok(());
+    }
+
+    if missing == 0 {
+        return Err(tr!(lang, "energy-full"));
+    }
+    let refilled = match store.refill_energy(me.id, missing, missing * POINT_PRICE, None, at) {
+        Ok(user) => user,
+        Err(storage::Error::NotEnoughVults) => {
+            return Err(tr!(lang, "costs-vults", price = missing * POINT_PRICE, have = me.vults));
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut lines = vec![tr!(lang, "energy-refilled", price = missing * POINT_PRICE,
+                             energy = energy::describe(lang, &refilled, at))];
+    lines.extend(events::flush(&*store, lang, me.id));
+
+    let _ = msg.reply(&lines.join("\n"));
+});
+
+// !explore, spends energy looking around for vults.
+command!(explore(ctx, msg) {
+    let store = storage::get(ctx)?;
+    let lang = cmds::lang(&*store, msg);
+    let me = registered(&*store, &msg.author, lang)?;
+    let at = now();
+
+    let found = energy::find(&mut rand::thread_rng());
+    let mut line = if found > 0 {
+        tr!(lang, "explore-found", vults = tr!(lang, "vults", count = found))
+    } else {
+        tr!(lang, "explore-nothing")
+    };
+    // Members exploring in their guild's server pay its tax.
+    let mut taxed = None;
+    if let Some((account, guild)) = server::here(&*store, msg)? {
+        let tax = guild.tax_on(found);
+        if tax > 0 && store.guild_members(account.id)?.iter().any(|m| m.user == me.id) {
+            line += &format!(" {}", tr!(lang, "explore-taxed", tax = tax, guild = &account.name));
+            taxed = Some((account.id, tax));
+        }
+    }
+
+    let tired = match store.explore(me.id, EXPLORE_COST, found, taxed, at) {
+        Ok(user) => user,
+        Err(storage::Error::NotEnoughEnergy) => {
+            return Err(tr!(lang, "energy-needed", cost = EXPLORE_COST, have = energy::describe(lang, &me, at)));
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    let _ = msg.reply(&format!("{} {}", line, tr!(lang, "energy-left", energy = energy::describe(lang, &tired, at))));
+});
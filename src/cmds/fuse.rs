@@ -0,0 +1,77 @@
+use rand;
+
+use cmds::{self, registered, stars};
+use deus::Fusion::{self, PRICE};
+use deus::Instance::{level_for_exp, DeusInstance};
+use deus::Stats::STATS;
+use events;
+use menus::{self, Menu};
+use storage::{self, Storage};
+use users::Users::User;
+
+// !fuse <deus id> <deus id> [confirm], turns two Deus into a new one, once
+// confirmed with a reaction or the confirm argument.
+command!(fuse(ctx, msg, args) {
+    let store = storage::get(ctx)?;
+    let lang = cmds::lang(&*store, msg);
+    let me = registered(&*store, &msg.author, lang)?;
+    let id = |i: usize| args.get(i).and_then(|s| s.parse::<i32>().ok()).ok_or_else(|| tr!(lang, "fuse-usage"));
+    let (a, b) = (id(0)?, id(1)?);
+    if a == b {
+        return Err(tr!(lang, "fuse-same"));
+    }
+    let (a, b) = (store.deus(a)?, store.deus(b)?);
+    if a.owner != me.id || b.owner != me.id {
+        return Err(tr!(lang, "fuse-not-yours"));
+    }
+
+    if args.get(2).map(|s| s.as_str()) != Some("confirm") {
+        let question = preview(lang, &a, &b).join("\n");
+        let menu = Menu::confirm(msg.author.id.0, lang, question, move |store| fuse_pair(store, lang, &me, &a, &b));
+        return menus::open(ctx, msg, menu);
+    }
+    let _ = msg.reply(&fuse_pair(&*store, lang, &me, &a, &b)?);
+});
+
+fn fuse_pair(store: &Storage, lang: &str, me: &User, a: &DeusInstance, b: &DeusInstance) -> Result<String, String> {
+    let child = Fusion::fuse(&mut rand::thread_rng(), a, b);
+    let fused = match store.fuse_deus([a.id, b.id], &child, PRICE, &format!("fused {} and {}", a.deus, b.deus)) {
+        Ok(fused) => fused,
+        Err(storage::Error::NotEnoughVults) => return Err(tr!(lang, "costs-vults", price = PRICE, have = me.vults)),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut lines = vec![tr!(lang, "fuse-done", first = &a.deus, second = &b.deus, stars = stars(fused.stars),
+                             deus = &fused.deus, id = fused.id, level = fused.level())];
+    if !fused.spells.is_empty() {
+        lines.push(tr!(lang, "fuse-inherited", spells = fused.spells.join(", ")));
+    }
+    lines.extend(events::flush(store, lang, me.id));
+    Ok(lines.join("\n"))
+}
+
+fn preview(lang: &str, a: &DeusInstance, b: &DeusInstance) -> Vec<String> {
+    let mut lines = vec![tr!(lang, "fuse-preview", first = &a.deus, first_id = a.id, second = &b.deus, second_id = b.id,
+                             price = PRICE)];
+    for (species, chance) in Fusion::outcomes(a, b) {
+        let mut spells = Vec::new();
+        for parent in &[a, b] {
+            let inheritable = Fusion::inheritable(parent, &species);
+            if !inheritable.is_empty() {
+                spells.push(tr!(lang, "fuse-one-of", spells = inheritable.join(", "), parent = &parent.deus));
+            }
+        }
+        let spells = if spells.is_empty() { tr!(lang, "nothing") } else { spells.join(", ") };
+        lines.push(tr!(lang, "fuse-outcome", species = &species, chance = chance, spells = spells));
+    }
+    let variance: Vec<String> = STATS.iter()
+        .map(|&stat| {
+            let (least, most) = Fusion::variance_range(a, b, stat);
+            format!("{} {}-{}", stat.name(), least, most)
+        })
+        .collect();
+    lines.push(tr!(lang, "fuse-child", stars = stars(a.stars.min(b.stars)), level = level_for_exp((a.exp + b.exp) / 2),
+                   variance = variance.join(", ")));
+    lines.push(tr!(lang, "fuse-how", confirm = menus::CONFIRM, first = a.id, second = b.id));
+    lines
+}
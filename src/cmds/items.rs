@@ -0,0 +1,149 @@
+use cmds::{self, registered};
+use energy::{self, MAX_ENERGY};
+use events;
+use items::{self, Item as Catalog};
+use items::Item::{Effect, Item};
+use menus::{self, Menu};
+use storage::{self, now};
+
+// Most of one item bought at once.
+const MAX_BUY: i32 = 99;
+
+// !inventory, your items and what your Deus hold.
+command!(inventory(ctx, msg) {
+    let store = storage::get(ctx)?;
+    let lang = cmds::lang(&*store, msg);
+    let me = registered(&*store, &msg.author, lang)?;
+
+    let mut lines = vec![tr!(lang, "inventory-header", name = &me.name, vults = tr!(lang, "vults", count = me.vults))];
+    let stacks = store.inventory(me.id)?;
+    if stacks.is_empty() {
+        lines.push(tr!(lang, "inventory-empty"));
+    }
+    for stack in stacks {
+        lines.push(format!("{}x {}", stack.count, Catalog::title(lang, &stack.item)));
+    }
+    for deus in store.deus_of(me.id)? {
+        if let Some(ref held) = deus.held {
+            lines.push(tr!(lang, "inventory-held", deus = &deus.deus, id = deus.id, item = Catalog::title(lang, held)));
+        }
+    }
+
+    let _ = msg.reply(&lines.join("\n"));
+});
+
+// !use <item> [deus id]
+command!(use_item(ctx, msg, args) {
+    let store = storage::get(ctx)?;
+    let lang = cmds::lang(&*store, msg);
+    let me = registered(&*store, &msg.author, lang)?;
+    let (name, id) = split_number(&args);
+    if name.is_empty() {
+        return Err(tr!(lang, "use-usage"));
+    }
+    let item = find(lang, &name)?;
+
+    if let Effect::Energy { amount } = item.effect {
+        let at = now();
+        if energy::current(&me, at) >= MAX_ENERGY {
+            return Err(tr!(lang, "energy-full"));
+        }
+        let refilled = match store.refill_energy(me.id, amount, 0, Some(&item.name), at) {
+            Ok(user) => user,
+            Err(storage::Error::NotFound) => return Err(tr!(lang, "use-missing", item = item.title(lang))),
+            Err(e) => return Err(e.into()),
+        };
+        let _ = msg.reply(&tr!(lang, "energy-left", energy = energy::describe(lang, &refilled, at)));
+        return Ok(());
+    }
+
+    let deus = match id {
+        Some(id) => match store.deus(id) {
+            Ok(deus) => Some(deus),
+            Err(storage::Error::NotFound) => return Err(tr!(lang, "not-your-deus")),
+            Err(e) => return Err(e.into()),
+        },
+        None => None,
+    };
+    let before = deus.clone();
+    let after = items::apply(item, me.id, deus).map_err(|e| e.with("item", item.title(lang)).tr(lang))?;
+    let saved = match store.use_item(me.id, &item.name, &after) {
+        Ok(saved) => saved,
+        Err(storage::Error::NotFound) => return Err(tr!(lang, "use-missing", item = item.title(lang))),
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut lines = vec![match (&item.effect, before.as_ref()) {
+        (&Effect::Trap { .. }, _) => tr!(lang, "use-caught", deus = &saved.deus, id = saved.id),
+        (&Effect::Stone { .. }, Some(before)) => tr!(lang, "use-evolved", from = &before.deus, into = &saved.deus),
+        (&Effect::Candy { exp }, Some(before)) if saved.level() > before.level() => {
+            tr!(lang, "use-candy-level", deus = &saved.deus, exp = exp, level = saved.level())
+        }
+        (&Effect::Candy { exp }, _) => tr!(lang, "use-candy", deus = &saved.deus, exp = exp),
+        (&Effect::Scroll { ref spell }, _) => tr!(lang, "use-scroll", deus = &saved.deus, spell = spell, id = saved.id),
+        (_, before) => match before.and_then(|d| d.held.as_ref()) {
+            Some(old) => tr!(lang, "use-swapped", deus = &saved.deus, item = item.title(lang), old = Catalog::title(lang, old)),
+            None => tr!(lang, "use-held", deus = &saved.deus, item = item.title(lang)),
+        },
+    }];
+    lines.extend(events::flush(&*store, lang, me.id));
+
+    let _ = msg.reply(&lines.join("\n"));
+});
+
+// !shop, !shop buy <item> [count]
+command!(shop(ctx, msg, args) {
+    let store = storage::get(ctx)?;
+    let lang = cmds::lang(&*store, msg);
+
+    if args.get(0).map(|s| s.as_str()) != Some("buy") {
+        let mut lines = Vec::new();
+        for item in Catalog::shop() {
+            lines.push(tr!(lang, "shop-line", item = item.title(lang), vults = tr!(lang, "vults", count = item.price),
+                           about = item.describe(lang)));
+        }
+        lines.push(tr!(lang, "shop-how"));
+        let pages = menus::paginate(&tr!(lang, "shop-header"), &lines);
+        return menus::open(ctx, msg, Menu::pages(msg.author.id.0, lang, pages));
+    }
+
+    let me = registered(&*store, &msg.author, lang)?;
+    let (name, count) = split_number(&args[1..]);
+    let item = find(lang, &name)?;
+    let count = count.unwrap_or(1);
+    if count < 1 || count > MAX_BUY {
+        return Err(tr!(lang, "shop-count", most = MAX_BUY));
+    }
+    if item.price == 0 {
+        return Err(tr!(lang, "shop-not-for-sale", item = item.title(lang)));
+    }
+
+    let stack = match store.buy_item(me.id, &item.name, count, item.price) {
+        Ok(stack) => stack,
+        Err(storage::Error::NotEnoughVults) => {
+            return Err(tr!(lang, "costs-vults", price = item.price * count, have = me.vults));
+        }
+        Err(e) => return Err(e.into()),
+    };
+
+    let mut lines = vec![tr!(lang, "shop-bought", count = count, item = item.title(lang),
+                             vults = tr!(lang, "vults", count = item.price * count), have = stack.count)];
+    lines.extend(events::flush(&*store, lang, me.id));
+
+    let _ = msg.reply(&lines.join("\n"));
+});
+
+// Item names have spaces, a number at the end is a count or a Deus id.
+fn split_number(args: &[String]) -> (String, Option<i32>) {
+    match args.split_last() {
+        Some((last, rest)) if !rest.is_empty() => match last.parse::<i32>() {
+            Ok(n) => (rest.join(" "), Some(n)),
+            Err(_) => (args.join(" "), None),
+        },
+        _ => (args.join(" "), None),
+    }
+}
+
+fn find(lang: &str, name: &str) -> Result<&'static Item, String> {
+    Catalog::find_in(lang, name).ok_or_else(|| tr!(lang, "item-unknown", name = name))
+}
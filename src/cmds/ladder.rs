@@ -0,0 +1,95 @@
+use cmds::{self, registered};
+use ladder;
+use menus::{self, Menu};
+use storage::{self, now, Storage};
+use users::Rating::Rating;
+
+const LADDER_LENGTH: i64 = 100;
+
+// !ladder [season], the best rated players of a season, the current one by
+// default.
+command!(ladder(ctx, msg, args) {
+    let store = storage::get(ctx)?;
+    let lang = cmds::lang(&*store, msg);
+    settle(&*store)?;
+    let current = ladder::current_season();
+    let season = match args.get(0) {
+        Some(s) => match s.parse::<i32>() {
+            Ok(season) if season >= 1 && season <= current => season,
+            _ => return Err(tr!(lang, "ladder-seasons", current = current)),
+        },
+        None => current,
+    };
+
+    let top = store.ladder(season, LADDER_LENGTH)?;
+    if top.is_empty() {
+        return Err(tr!(lang, "ladder-empty", season = season));
+    }
+
+    let mut lines = Vec::new();
+    for (i, &(ref rating, ref name)) in top.iter().enumerate() {
+        lines.push(tr!(lang, "ladder-line", place = i + 1, name = name, standing = standing(lang, rating),
+                       rating = rating.rating, record = record(lang, rating)));
+    }
+    if season == current {
+        lines.push(tr!(lang, "ladder-ends", days = days_left(season)));
+    }
+
+    let pages = menus::paginate(&tr!(lang, "ladder-header", season = season), &lines);
+    menus::open(ctx, msg, Menu::pages(msg.author.id.0, lang, pages))?;
+});
+
+// !rank [@user], where a player stands this season.
+command!(rank(ctx, msg) {
+    let store = storage::get(ctx)?;
+    let lang = cmds::lang(&*store, msg);
+    settle(&*store)?;
+    let target = msg.mentions.get(0).unwrap_or(&msg.author);
+    let user = registered(&*store, target, lang)?;
+    let season = ladder::current_season();
+    let rating = store.rating(user.id, season)?;
+
+    let mut lines = vec![tr!(lang, "rank-header", name = &user.name, season = season, days = days_left(season))];
+    if rating.games == 0 {
+        lines.push(tr!(lang, "rank-unplayed", rating = rating.rating));
+    } else {
+        lines.push(tr!(lang, "rank-rating", standing = standing(lang, &rating), rating = rating.rating, peak = rating.peak));
+        lines.push(record(lang, &rating));
+        let ladder = store.ladder(season, i64::max_value())?;
+        if let Some(i) = ladder.iter().position(|&(ref r, _)| r.user == user.id) {
+            lines.push(tr!(lang, "rank-place", place = i + 1, players = ladder.len()));
+        }
+    }
+
+    let last = store.rating(user.id, season - 1)?;
+    if ladder::placed(&last) {
+        lines.push(tr!(lang, "rank-last-season", tier = ladder::rank(last.rating), rating = last.rating));
+    }
+
+    let _ = msg.reply(&lines.join("\n"));
+});
+
+// Seasons end with the calendar. The first ranked command of a season pays
+// the rewards of the one before, later calls find nothing left to pay.
+pub fn settle(store: &Storage) -> Result<(), String> {
+    store.reward_season(ladder::current_season() - 1)?;
+    Ok(())
+}
+
+// A tier once placed, how far along placement before that.
+pub fn standing(lang: &str, rating: &Rating) -> String {
+    if ladder::placed(rating) {
+        ladder::rank(rating.rating)
+    } else {
+        tr!(lang, "rank-placement", games = rating.games, of = ladder::PLACEMENT_GAMES)
+    }
+}
+
+fn record(lang: &str, rating: &Rating) -> String {
+    tr!(lang, "rank-record", wins = rating.wins, losses = rating.losses, draws = rating.draws())
+}
+
+// Whole days, the number alone so the message around it can agree with it.
+fn days_left(season: i32) -> i64 {
+    (ladder::season_end(season) - now() + 86399) / 86400
+}
@@ -0,0 +1,27 @@
+use cmds::{self, registered};
+use i18n;
+use storage;
+
+// !language [code|default], the language the bot answers you in, `default`
+// going back to your server's.
+command!(language(ctx, msg, args) {
+    let store = storage::get(ctx)?;
+    let lang = cmds::lang(&*store, msg);
+    let me = registered(&*store, &msg.author, lang)?;
+    let choices = i18n::choices();
+
+    let reply = match args.get(0).map(|s| s.as_str()) {
+        None => tr!(lang, "language-current", language = i18n::name(lang), choices = choices),
+        Some("default") => {
+            store.set_locale(me.id, None)?;
+            let lang = cmds::lang(&*store, msg);
+            tr!(lang, "language-default", language = i18n::name(lang))
+        }
+        Some(code) => {
+            let picked = i18n::supported(code).ok_or_else(|| tr!(lang, "language-unknown", code = code, choices = &choices))?;
+            store.set_locale(me.id, Some(picked))?;
+            tr!(picked, "language-set", language = i18n::name(picked))
+        }
+    };
+    let _ = msg.reply(&reply);
+});
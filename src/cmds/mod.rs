@@ -1 +1,68 @@
+pub mod achievements;
+pub mod ascend;
+pub mod battle;
+pub mod card;
+pub mod dex;
+pub mod energy;
+pub mod fuse;
+pub mod items;
+pub mod language;
+pub mod ladder;
 pub mod ping;
+pub mod profile;
+pub mod quests;
+pub mod raid;
+pub mod replay;
+pub mod server;
+pub mod spells;
+pub mod start;
+pub mod stats;
+pub mod team;
+pub mod tournament;
+pub mod train;
+pub mod war;
+
+use serenity::client::Context;
+use serenity::model::{Message, User as DiscordUser};
+
+use i18n;
+use storage::{self, Error, Storage};
+use users::Users::{User, UserType};
+
+// Side colour of the bot's embeds.
+pub const COLOUR: u32 = 0xC27C0E;
+
+// The game account of a discord user, pointing people who don't have one yet
+// to !start.
+pub fn registered(store: &Storage, user: &DiscordUser, lang: &str) -> Result<User, String> {
+    match store.user_by_disc_id(user.id.0 as i64) {
+        Ok(user) => Ok(user),
+        Err(Error::NotFound) => Err(tr!(lang, "no-profile", name = &user.name)),
+        Err(e) => Err(e.into()),
+    }
+}
+
+// What to reply when a command fails with `why`. Errors that are message
+// keys, storage errors turned into strings by `?` say, are said in the
+// user's language here.
+pub fn failed(ctx: &Context, msg: &Message, why: &str) -> String {
+    if !i18n::is_key(why) {
+        return why.to_owned();
+    }
+    let lang = storage::get(ctx).map(|store| lang(&*store, msg)).unwrap_or_else(|_| i18n::default());
+    tr!(lang, why)
+}
+
+// The language to answer `msg` in: its author's, else their server's, else
+// the default.
+pub fn lang(store: &Storage, msg: &Message) -> &'static str {
+    let user = store.user_by_disc_id(msg.author.id.0 as i64).ok().and_then(|u| u.locale);
+    let server = msg.guild_id()
+        .and_then(|g| store.user_by_disc_id(g.0 as i64).ok())
+        .and_then(|a| if a.kind == UserType::Server { a.locale } else { None });
+    i18n::resolve(user.as_ref().map(|s| s.as_str()), server.as_ref().map(|s| s.as_str()))
+}
+
+pub fn stars(count: i32) -> String {
+    "★".repeat(count as usize)
+}
@@ -1,4 +1,8 @@
-command!(ping(_context, msg) {
-    let _ = msg.reply("Pong!");
-}   
-);
+use cmds;
+use i18n;
+use storage;
+
+command!(ping(ctx, msg) {
+    let lang = storage::get(ctx).map(|store| cmds::lang(&*store, msg)).unwrap_or_else(|_| i18n::default());
+    let _ = msg.reply(&tr!(lang, "pong"));
+});
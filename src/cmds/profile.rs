@@ -0,0 +1,138 @@
+use achievements::Achievement;
+use cmds::{self, registered, stars, COLOUR};
+use deus::Instance::DeusInstance;
+use energy;
+use storage::{self, now, Storage};
+use users::Profile::{Profile, Section, SECTIONS};
+use users::Users::User;
+
+// !profile [@user], !profile hide|show <section>, !profile favorite <deus id>
+command!(profile(ctx, msg, args) {
+    let store = storage::get(ctx)?;
+    let lang = cmds::lang(&*store, msg);
+
+    match args.get(0).map(|s| s.as_str()) {
+        Some("hide") | Some("show") => {
+            let me = registered(&*store, &msg.author, lang)?;
+            let section = args.get(1)
+                .and_then(|s| Section::from_name(s))
+                .ok_or_else(|| tr!(lang, "profile-sections", sections = section_names()))?;
+            let hide = args[0] == "hide";
+
+            let mut profile = store.profile(me.id)?;
+            profile.set_hidden(section, hide);
+            store.set_profile_settings(me.id, profile.favorite, profile.hidden)?;
+
+            let state = if hide { "hidden" } else { "visible" };
+            let _ = msg.reply(&tr!(lang, "profile-hidden", section = section.name(), state = state));
+        }
+        Some("favorite") => {
+            let me = registered(&*store, &msg.author, lang)?;
+            let id = args.get(1)
+                .and_then(|s| s.parse::<i32>().ok())
+                .ok_or_else(|| tr!(lang, "profile-favorite-usage"))?;
+            let deus = store.deus(id)?;
+            if deus.owner != me.id {
+                return Err(tr!(lang, "not-your-deus"));
+            }
+
+            let profile = store.profile(me.id)?;
+            store.set_profile_settings(me.id, Some(id), profile.hidden)?;
+            let _ = msg.reply(&tr!(lang, "profile-favorite", deus = &deus.deus));
+        }
+        _ => {
+            let target = msg.mentions.get(0).unwrap_or(&msg.author);
+            let user = registered(&*store, target, lang)?;
+            let fields = card(&*store, lang, &user, msg.author.id == target.id)?;
+            let avatar = target.avatar_url();
+
+            let _ = ctx.send_message(|m| m.embed(|e| {
+                let e = e.title(&user.name).colour(COLOUR);
+                let e = match avatar {
+                    Some(ref url) => e.thumbnail(url),
+                    None => e,
+                };
+                fields.iter().fold(e, |e, &(ref name, ref value)| e.field(|f| f.name(name).value(value)))
+            }));
+        }
+    }
+});
+
+fn section_names() -> String {
+    SECTIONS.iter().map(|s| s.name()).collect::<Vec<_>>().join(", ")
+}
+
+// Name and value of every field on the card. Hidden sections are left out
+// for other players, owners see them marked as hidden.
+fn card(store: &Storage, lang: &str, user: &User, own: bool) -> Result<Vec<(String, String)>, String> {
+    let profile = store.profile(user.id)?;
+    let deus = store.deus_of(user.id)?;
+    let lead = store.teams(user.id)?.iter().find(|t| t.active).and_then(|t| t.lead());
+    let badges: Vec<String> = store.achievements(user.id)?.iter()
+        .filter_map(|u| Achievement::find(&u.achievement))
+        .map(|a| format!("{} {}", a.badge, a.title(lang)))
+        .collect();
+    let mut fields = Vec::new();
+
+    {
+        let mut add = |section: Option<Section>, key: &str, value: String| {
+            let hidden = section.map_or(false, |s| profile.is_hidden(s));
+            if !hidden {
+                fields.push((tr!(lang, key), value));
+            } else if own {
+                fields.push((tr!(lang, "profile-field-hidden", field = tr!(lang, key)), value));
+            }
+        };
+
+        add(Some(Section::Vults), "profile-field-vults", user.vults.to_string());
+        add(None, "profile-field-joined", days_ago(lang, user.joined));
+        add(None, "profile-field-energy", energy::describe(lang, user, now()));
+        add(Some(Section::Collection), "profile-field-collection", collection(lang, &deus));
+        if let Some(lead) = favorite(&profile, lead, &deus) {
+            add(Some(Section::Collection), "profile-field-favorite",
+                tr!(lang, "profile-lead", deus = &lead.deus, stars = stars(lead.stars), level = lead.level()));
+        }
+        add(Some(Section::Pvp), "profile-field-pvp", pvp(lang, &profile));
+        add(Some(Section::Streaks), "profile-field-streak",
+            tr!(lang, "profile-streak", streak = profile.win_streak, best = profile.best_streak));
+        if !badges.is_empty() {
+            add(Some(Section::Badges), "profile-field-badges", badges.join("\n"));
+        }
+    }
+
+    Ok(fields)
+}
+
+fn days_ago(lang: &str, at: i64) -> String {
+    tr!(lang, "profile-days-ago", days = (now() - at) / 86400)
+}
+
+fn collection(lang: &str, deus: &[DeusInstance]) -> String {
+    if deus.is_empty() {
+        return tr!(lang, "profile-no-deus");
+    }
+
+    let max = deus.iter().map(|d| d.stars).max().unwrap_or(1);
+    let counts = (1..max + 1)
+        .map(|n| (n, deus.iter().filter(|d| d.stars == n).count()))
+        .filter(|&(_, count)| count > 0)
+        .map(|(n, count)| format!("{} x{}", stars(n), count))
+        .collect::<Vec<_>>();
+    format!("{}\n{}", tr!(lang, "profile-deus-count", count = deus.len()), counts.join("\n"))
+}
+
+// The chosen favorite, else the lead of the active team, else the strongest.
+fn favorite<'a>(profile: &Profile, lead: Option<i32>, deus: &'a [DeusInstance]) -> Option<&'a DeusInstance> {
+    profile.favorite
+        .or(lead)
+        .and_then(|id| deus.iter().find(|d| d.id == id))
+        .or_else(|| deus.iter().max_by_key(|d| (d.stars, d.exp)))
+}
+
+fn pvp(lang: &str, profile: &Profile) -> String {
+    let played = profile.wins + profile.losses;
+    if played == 0 {
+        return tr!(lang, "profile-no-duels");
+    }
+    tr!(lang, "profile-pvp", wins = profile.wins, losses = profile.losses, percent = profile.wins * 100 / played)
+}
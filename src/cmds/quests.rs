@@ -0,0 +1,44 @@
+use cmds::{self, registered};
+use quests;
+use quests::Quest::{Period, PERIODS};
+use storage::{self, now};
+
+// !quests, today's and this week's quests with your progress on them.
+command!(quests(ctx, msg) {
+    let store = storage::get(ctx)?;
+    let lang = cmds::lang(&*store, msg);
+    let me = registered(&*store, &msg.author, lang)?;
+    let at = now();
+
+    let mut lines = vec![tr!(lang, "quests-header", name = &me.name)];
+    for &period in PERIODS {
+        let number = quests::period_of(period, at);
+        lines.push(tr!(lang, "quests-period", period = title(period), left = left(lang, quests::period_end(period, number) - at)));
+        for quest in quests::rotation(period, number) {
+            let progress = store.quest_progress(me.id, &quest.id, number)?;
+            let goal = quest.objective.goal();
+            lines.push(if progress.completed {
+                tr!(lang, "quests-done", quest = quest.title(lang), objective = quest.objective.describe(lang))
+            } else {
+                tr!(lang, "quests-open", quest = quest.title(lang), objective = quest.objective.describe(lang),
+                    reward = quest.reward.describe(lang), progress = quests::bar(progress.progress, goal))
+            });
+        }
+    }
+
+    let _ = msg.reply(&lines.join("\n"));
+});
+
+fn title(period: Period) -> &'static str {
+    match period {
+        Period::Daily => "daily",
+        Period::Weekly => "weekly",
+    }
+}
+
+pub fn left(lang: &str, seconds: i64) -> String {
+    match ((seconds + 3599) / 3600).max(1) {
+        hours if hours < 48 => tr!(lang, "hours", count = hours),
+        hours => tr!(lang, "days", count = (hours + 23) / 24),
+    }
+}
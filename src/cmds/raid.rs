@@ -0,0 +1,58 @@
+use battle;
+use cmds::battle::{side, spend, tail};
+use cmds::quests::left;
+use cmds::{self, registered};
+use dex;
+use energy;
+use quests::bar;
+use raids::{self, ATTACK_COST, TURNS};
+use storage::{self, now};
+
+// !raid, !raid attack
+command!(raid(ctx, msg, args) {
+    let store = storage::get(ctx)?;
+    let lang = cmds::lang(&*store, msg);
+    let at = now();
+    let raid = match raids::current(&*store, at)? {
+        Some(raid) => raid,
+        None => return Err(tr!(lang, "raid-none")),
+    };
+
+    if args.get(0).map(|s| s.as_str()) != Some("attack") {
+        let mut lines = vec![
+            tr!(lang, "raid-header", boss = &raid.boss, level = raid.level, left = left(lang, raid.ends - at)),
+            tr!(lang, "raid-life", life = bar(raid.life, raid.max_life)),
+        ];
+        lines.extend(raids::standings(&*store, lang, &raid, &store.raid_damage(raid.id)?)?);
+        lines.push(tr!(lang, "raid-how", cost = ATTACK_COST));
+        let _ = msg.reply(&lines.join("\n"));
+        return Ok(());
+    }
+
+    let me = registered(&*store, &msg.author, lang)?;
+    let (mine, my_deus) = side(&*store, lang, &me)?;
+    let (damage, fought) = raids::strike(lang, mine, raids::boss(&raid).map_err(|e| e.tr(lang))?, battle::seed());
+    let (dealt, after) = match store.attack_raid(raid.id, me.id, damage, ATTACK_COST, at) {
+        Ok(hit) => hit,
+        Err(storage::Error::NotEnoughEnergy) => {
+            return Err(tr!(lang, "energy-needed", cost = ATTACK_COST, have = energy::describe(lang, &me, at)));
+        }
+        Err(storage::Error::Closed) => return Err(tr!(lang, "raid-gone", boss = &raid.boss)),
+        Err(e) => return Err(e.into()),
+    };
+    spend(&*store, &fought.sides[0], my_deus)?;
+
+    let mut lines = vec![tr!(lang, "raid-attack-header", name = &me.name, boss = &raid.boss, turns = TURNS), "```".to_owned()];
+    lines.extend(tail(&fought, lang));
+    lines.push("```".to_owned());
+    lines.push(tr!(lang, "raid-dealt", damage = dealt, boss = &raid.boss, life = after.life));
+    lines.push(tr!(lang, "energy-left", energy = energy::describe(lang, &store.user(me.id)?, at)));
+    lines.extend(dex::see(&*store, lang, me.id, &[raid.boss.clone()])?);
+    if after.defeated() {
+        if let Some(results) = raids::settle(&*store, lang, &after)? {
+            lines.push(results);
+        }
+    }
+
+    let _ = msg.reply(&lines.join("\n"));
+});
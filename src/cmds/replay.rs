@@ -0,0 +1,52 @@
+use battle::replay;
+use cmds;
+use render;
+use storage;
+
+// !replay <id> [turn], plays a stored battle again and shows one turn of it,
+// with a picture of both teams at the end of the turn.
+// !replay <id> json sends the whole battle as a file.
+command!(replay(ctx, msg, args) {
+    let store = storage::get(ctx)?;
+    let lang = cmds::lang(&*store, msg);
+    let usage = || tr!(lang, "replay-usage");
+    let id = args.get(0).and_then(|s| s.parse::<i32>().ok()).ok_or_else(&usage)?;
+    let stored = match store.replay(id) {
+        Ok(stored) => stored,
+        Err(storage::Error::NotFound) => return Err(tr!(lang, "replay-unknown", id = id)),
+        Err(e) => return Err(e.into()),
+    };
+
+    if args.get(1).map(|s| s.as_str()) == Some("json") {
+        let json = stored.to_json().map_err(|e| e.tr(lang))?;
+        let _ = ctx.send_file(json.as_bytes(), &format!("battle-{}.json", id), |m| m.content(&tr!(lang, "replay-file", id = id)));
+        return Ok(());
+    }
+
+    let turn = match args.get(1) {
+        Some(turn) => turn.parse::<usize>().map_err(|_| usage())?,
+        None => 1,
+    };
+    let battle = stored.play().map_err(|e| e.tr(lang))?;
+    let pages = replay::pages(&battle.log);
+    let last = pages.len();
+    let page = match turn {
+        0 => None,
+        n => pages.get(n - 1),
+    }.ok_or_else(|| tr!(lang, "replay-turns", id = id, last = last))?;
+
+    let mut lines = vec![
+        tr!(lang, "replay-header", id = id, first = &stored.teams[0].name, second = &stored.teams[1].name, turn = turn,
+            last = last),
+        "```".to_owned(),
+    ];
+    lines.extend(page.iter().map(|e| e.describe(lang)));
+    lines.push("```".to_owned());
+    if turn < last {
+        lines.push(tr!(lang, "replay-next", id = id, turn = turn + 1));
+    }
+
+    let _ = msg.reply(&lines.join("\n"));
+    let png = render::battle_snapshot(lang, &stored.play_to(turn).map_err(|e| e.tr(lang))?);
+    let _ = ctx.send_file(&png[..], &format!("battle-{}-{}.png", id, turn), |m| m.content(""));
+});
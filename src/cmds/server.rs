@@ -0,0 +1,251 @@
+use rand::{self, Rng};
+use serenity::client::CACHE;
+use serenity::model::{GuildId, Message};
+use serenity::model::permissions::{self, Permissions};
+
+use cmds::{self, registered};
+use cmds::battle::pve;
+use deus::Instance::DeusInstance;
+use deus::Team::TEAM_SIZE;
+use i18n;
+use items;
+use storage::{self, Storage};
+use users::Guild::{Guild, MAX_TAX};
+use users::Users::{User, UserType};
+
+// Guardians are summoned at this level.
+const GUARDIAN_LEVEL: i32 = 30;
+const GUARDIAN_PRICE: i32 = 1500;
+
+// !server, run the guild of the discord server it's used in.
+command!(server(ctx, msg, args) {
+    let store = storage::get(ctx)?;
+    let lang = cmds::lang(&*store, msg);
+    let usage = || tr!(lang, "server-usage");
+    let arg = |i: usize| args.get(i).map(|s| s.as_str());
+
+    if arg(0) == Some("create") {
+        let me = registered(&*store, &msg.author, lang)?;
+        let server = msg.guild_id().ok_or_else(|| tr!(lang, "server-not-a-server"))?;
+        if !runs(server, msg) {
+            return Err(tr!(lang, "server-managers-only"));
+        }
+        let disc_id = server.0 as i64;
+        let name = args[1..].join(" ");
+        if name.is_empty() {
+            return Err(usage());
+        }
+        return match store.create_guild(disc_id, &name, me.id) {
+            Ok(account) => {
+                let _ = msg.reply(&tr!(lang, "server-created", guild = &account.name));
+                Ok(())
+            }
+            Err(storage::Error::AlreadyExists) => Err(tr!(lang, "server-exists")),
+            Err(e) => Err(e.into()),
+        };
+    }
+
+    let (account, guild) = here(&*store, msg)?.ok_or_else(|| tr!(lang, "server-no-guild"))?;
+    let reply = match arg(0) {
+        None => board(&*store, lang, &account, &guild)?,
+        Some("join") => {
+            let me = registered(&*store, &msg.author, lang)?;
+            match store.join_guild(account.id, me.id) {
+                Ok(_) => tr!(lang, "server-joined", guild = &account.name),
+                Err(storage::Error::AlreadyExists) => return Err(tr!(lang, "server-already-in", guild = &account.name)),
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Some("leave") => {
+            let me = registered(&*store, &msg.author, lang)?;
+            let admins: Vec<i32> = store.guild_members(account.id)?.iter().filter(|m| m.admin).map(|m| m.user).collect();
+            if admins == vec![me.id] {
+                return Err(tr!(lang, "server-last-admin"));
+            }
+            match store.leave_guild(account.id, me.id) {
+                Ok(()) => tr!(lang, "server-left", guild = &account.name),
+                Err(storage::Error::NotFound) => return Err(tr!(lang, "server-not-member", guild = &account.name)),
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Some("contribute") => {
+            let me = member(&*store, lang, msg, &account)?;
+            let amount = match arg(1).and_then(|s| s.parse::<i32>().ok()) {
+                Some(amount) if amount > 0 => amount,
+                _ => return Err(usage()),
+            };
+            match store.transfer_vults(me.id, account.id, amount, &format!("contribution to {}", account.name)) {
+                Ok(()) => tr!(lang, "server-contributed", vults = tr!(lang, "vults", count = amount), guild = &account.name),
+                Err(storage::Error::NotEnoughVults) => return Err(tr!(lang, "server-you-have", vults = tr!(lang, "vults", count = me.vults))),
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Some("challenge") => {
+            let me = registered(&*store, &msg.author, lang)?;
+            let guardians = guardians(&*store, &account)?;
+            if guardians.is_empty() {
+                return Err(tr!(lang, "server-no-guardians", guild = &account.name));
+            }
+            pve(&*store, lang, &me, &tr!(lang, "server-guardians", guild = &account.name), |_, _| Ok(guardians))?.join("\n")
+        }
+        Some("tax") => {
+            admin(&*store, lang, msg, &account)?;
+            let tax = match arg(1).and_then(|s| s.trim_right_matches('%').parse::<i32>().ok()) {
+                Some(tax) if tax >= 0 && tax <= MAX_TAX => tax,
+                _ => return Err(tr!(lang, "server-tax-range", most = MAX_TAX)),
+            };
+            store.set_guild_tax(account.id, tax)?;
+            tr!(lang, "server-tax-set", guild = &account.name, tax = tax)
+        }
+        Some("channel") => {
+            admin(&*store, lang, msg, &account)?;
+            if arg(1) == Some("off") {
+                store.set_guild_channel(account.id, None)?;
+                tr!(lang, "server-channel-off", guild = &account.name)
+            } else {
+                store.set_guild_channel(account.id, Some(msg.channel_id.0 as i64))?;
+                tr!(lang, "server-channel-on", guild = &account.name)
+            }
+        }
+        Some("language") => {
+            admin(&*store, lang, msg, &account)?;
+            let chosen = match arg(1) {
+                Some("default") => None,
+                Some(code) => Some(i18n::supported(code).ok_or_else(|| tr!(lang, "language-unknown", code = code,
+                                                                            choices = i18n::choices()))?),
+                None => return Err(usage()),
+            };
+            store.set_locale(account.id, chosen)?;
+            let now = chosen.unwrap_or_else(i18n::default);
+            tr!(now, "server-language-set", guild = &account.name, language = i18n::name(now))
+        }
+        Some("admin") => {
+            admin(&*store, lang, msg, &account)?;
+            let target = registered(&*store, msg.mentions.get(0).ok_or_else(&usage)?, lang)?;
+            match store.set_guild_admin(account.id, target.id, true) {
+                Ok(()) => tr!(lang, "server-made-admin", name = &target.name, guild = &account.name),
+                Err(storage::Error::NotFound) => {
+                    return Err(tr!(lang, "server-not-in", name = &target.name, guild = &account.name));
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Some("summon") => {
+            admin(&*store, lang, msg, &account)?;
+            if store.deus_of(account.id)?.len() >= TEAM_SIZE {
+                return Err(tr!(lang, "server-guardians-full", guild = &account.name, count = TEAM_SIZE));
+            }
+            let wild = items::wild();
+            let species = rand::thread_rng().choose(&wild).ok_or_else(|| tr!(lang, "server-nothing-to-summon"))?;
+            let mut guardian = DeusInstance::roll(account.id, &species.name, 1);
+            guardian.exp = 10 * GUARDIAN_LEVEL * GUARDIAN_LEVEL;
+            match store.buy_deus(&guardian, GUARDIAN_PRICE, &format!("summoned {}", species.name)) {
+                Ok(guardian) => tr!(lang, "server-summoned", deus = &guardian.deus, id = guardian.id, guild = &account.name),
+                Err(storage::Error::NotEnoughVults) => {
+                    return Err(tr!(lang, "server-guardian-price", price = GUARDIAN_PRICE, have = account.vults));
+                }
+                Err(e) => return Err(e.into()),
+            }
+        }
+        _ => return Err(usage()),
+    };
+
+    let _ = msg.reply(&reply);
+});
+
+// The server account and guild of the discord server `msg` was sent in, if
+// it has one.
+pub fn here(store: &Storage, msg: &Message) -> Result<Option<(User, Guild)>, String> {
+    let disc_id = match msg.guild_id() {
+        Some(id) => id.0 as i64,
+        None => return Ok(None),
+    };
+    match store.user_by_disc_id(disc_id) {
+        Ok(account) if account.kind == UserType::Server => {
+            let guild = store.guild(account.id)?;
+            Ok(Some((account, guild)))
+        }
+        Ok(_) | Err(storage::Error::NotFound) => Ok(None),
+        Err(e) => Err(e.into()),
+    }
+}
+
+// The author, if they're in the guild.
+fn member(store: &Storage, lang: &str, msg: &Message, account: &User) -> Result<User, String> {
+    let me = registered(store, &msg.author, lang)?;
+    if !store.guild_members(account.id)?.iter().any(|m| m.user == me.id) {
+        return Err(tr!(lang, "server-join-first", guild = &account.name));
+    }
+    Ok(me)
+}
+
+// Whether the author of `msg` runs the discord server: its owner, or a
+// member allowed to manage it.
+fn runs(server: GuildId, msg: &Message) -> bool {
+    let cache = CACHE.read().unwrap();
+    cache.get_guild(server).map_or(false, |g| manages(g.permissions_for(msg.channel_id, msg.author.id)))
+}
+
+// Owners have every permission.
+fn manages(permissions: Permissions) -> bool {
+    permissions.intersects(permissions::MANAGE_GUILD | permissions::ADMINISTRATOR)
+}
+
+fn admin(store: &Storage, lang: &str, msg: &Message, account: &User) -> Result<User, String> {
+    let me = registered(store, &msg.author, lang)?;
+    if !store.guild_members(account.id)?.iter().any(|m| m.user == me.id && m.admin) {
+        return Err(tr!(lang, "server-admins-only", guild = &account.name));
+    }
+    Ok(me)
+}
+
+// The strongest of the guild's Deus, as many as make a team.
+fn guardians(store: &Storage, account: &User) -> Result<Vec<DeusInstance>, String> {
+    let mut deus = store.deus_of(account.id)?;
+    deus.sort_by_key(|d| (-d.stars, -d.exp));
+    deus.truncate(TEAM_SIZE);
+    Ok(deus)
+}
+
+fn board(store: &Storage, lang: &str, account: &User, guild: &Guild) -> Result<String, String> {
+    let members = store.guild_members(account.id)?;
+    let mut admins = Vec::new();
+    for m in members.iter().filter(|m| m.admin) {
+        admins.push(store.user(m.user)?.name);
+    }
+
+    let mut lines = vec![
+        tr!(lang, "server-header", guild = &account.name, members = members.len()),
+        tr!(lang, "server-treasury", vults = tr!(lang, "vults", count = account.vults), tax = guild.tax),
+        tr!(lang, "server-admins", admins = admins.join(", ")),
+        match guild.channel {
+            Some(channel) => tr!(lang, "server-wars-in", channel = format!("<#{}>", channel)),
+            None => tr!(lang, "server-out-of-wars"),
+        },
+        tr!(lang, "server-language", language = i18n::name(i18n::resolve(account.locale.as_ref().map(|l| l.as_str()), None))),
+    ];
+    let guardians = guardians(store, account)?;
+    if guardians.is_empty() {
+        lines.push(tr!(lang, "server-no-guardians-yet", price = GUARDIAN_PRICE));
+    }
+    for g in guardians {
+        lines.push(tr!(lang, "server-guardian", deus = &g.deus, id = g.id, level = g.level()));
+    }
+    Ok(lines.join("\n"))
+}
+
+// Who may create the guild.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn only_managers_create_the_guild() -> Result<(), String> {
+        ensure!(!manages(permissions::general()), "a regular member may create the guild");
+        ensure!(!manages(Permissions::empty()), "a member without permissions may create the guild");
+        ensure!(manages(permissions::general() | permissions::MANAGE_GUILD), "a manager may not create the guild");
+        ensure!(manages(permissions::ADMINISTRATOR), "an administrator may not create the guild");
+        ensure!(manages(Permissions::all()), "the owner may not create the guild");
+        Ok(())
+    }
+}
@@ -0,0 +1,70 @@
+use cmds::{self, registered};
+use deus::Ascension;
+use deus::Deus;
+use deus::Instance::DeusInstance;
+use storage::{self, Storage};
+use users::Users::User;
+
+// !spells <deus id>, what a Deus takes into battle and what else it knows.
+command!(spells(ctx, msg, args) {
+    let store = storage::get(ctx)?;
+    let lang = cmds::lang(&*store, msg);
+    let me = registered(&*store, &msg.author, lang)?;
+    let id = args.get(0).and_then(|s| s.parse::<i32>().ok()).ok_or_else(|| tr!(lang, "spells-usage"))?;
+    let deus = owned(&*store, lang, &me, id)?;
+
+    let equipped = deus.move_set().map_err(|e| e.tr(lang))?;
+    let spare: Vec<String> = deus.learned_spells().into_iter().filter(|s| !equipped.contains(s)).collect();
+    let mut lines = vec![
+        tr!(lang, "spells-equipped", deus = &deus.deus, id = deus.id, count = equipped.len(),
+            slots = Ascension::spell_slots(deus.stars), spells = equipped.join(", ")),
+        if spare.is_empty() { tr!(lang, "spells-no-spare") } else { tr!(lang, "spells-spare", spells = spare.join(", ")) },
+    ];
+    if let Some(next) = Deus::find(&deus.deus).and_then(|s| s.learns.iter().find(|l| l.level > deus.level())) {
+        lines.push(tr!(lang, "spells-next", spell = &next.spell, level = next.level));
+    }
+    lines.push(tr!(lang, "spells-how", id = deus.id));
+    let _ = msg.reply(&lines.join("\n"));
+});
+
+// !learn <deus id> <spell>, takes a spell it knows into battle.
+command!(learn(ctx, msg, args) {
+    let store = storage::get(ctx)?;
+    let lang = cmds::lang(&*store, msg);
+    let me = registered(&*store, &msg.author, lang)?;
+    let (id, spell) = parse(&args).ok_or_else(|| tr!(lang, "learn-usage"))?;
+    let mut deus = owned(&*store, lang, &me, id)?;
+    deus.learn(&spell).map_err(|e| e.tr(lang))?;
+    store.update_deus(&deus)?;
+    let _ = msg.reply(&tr!(lang, "spells-changed", deus = &deus.deus, spells = deus.equipped.join(", ")));
+});
+
+// !forget <deus id> <spell>, leaves a spell out of battles.
+command!(forget(ctx, msg, args) {
+    let store = storage::get(ctx)?;
+    let lang = cmds::lang(&*store, msg);
+    let me = registered(&*store, &msg.author, lang)?;
+    let (id, spell) = parse(&args).ok_or_else(|| tr!(lang, "forget-usage"))?;
+    let mut deus = owned(&*store, lang, &me, id)?;
+    deus.forget(&spell).map_err(|e| e.tr(lang))?;
+    store.update_deus(&deus)?;
+    let _ = msg.reply(&tr!(lang, "spells-changed", deus = &deus.deus, spells = deus.equipped.join(", ")));
+});
+
+// "<deus id> <spell>", the spell's name can have spaces.
+fn parse(args: &[String]) -> Option<(i32, String)> {
+    let id = args.get(0).and_then(|s| s.parse::<i32>().ok());
+    let spell = args.get(1..).map(|words| words.join(" "));
+    match (id, spell) {
+        (Some(id), Some(ref spell)) if !spell.is_empty() => Some((id, spell.clone())),
+        _ => None,
+    }
+}
+
+fn owned(store: &Storage, lang: &str, me: &User, id: i32) -> Result<DeusInstance, String> {
+    let deus = store.deus(id)?;
+    if deus.owner != me.id {
+        return Err(tr!(lang, "not-your-deus"));
+    }
+    Ok(deus)
+}
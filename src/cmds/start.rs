@@ -0,0 +1,36 @@
+use rand::{self, Rng};
+
+use cmds;
+use deus::Deus;
+use deus::Instance::DeusInstance;
+use events;
+use storage::{self, Error};
+
+// !start [starter], a random starter when none is picked.
+command!(start(ctx, msg, args) {
+    let store = storage::get(ctx)?;
+    let lang = cmds::lang(&*store, msg);
+    let starters = Deus::starters();
+    let starter = match args.get(0) {
+        Some(name) => match starters.iter().find(|d| d.name.to_lowercase() == name.to_lowercase()) {
+            Some(starter) => *starter,
+            None => {
+                let names = starters.iter().map(|d| d.name.as_str()).collect::<Vec<_>>().join(", ");
+                return Err(tr!(lang, "start-starters", names = names));
+            }
+        },
+        None => *rand::thread_rng().choose(&starters).ok_or_else(|| tr!(lang, "start-no-starters"))?,
+    };
+
+    let user = match store.create_user(msg.author.id.0 as i64, &msg.author.name) {
+        Ok(user) => user,
+        Err(Error::AlreadyExists) => return Err(tr!(lang, "start-exists")),
+        Err(e) => return Err(e.into()),
+    };
+    let deus = store.add_deus(&DeusInstance::roll(user.id, &starter.name, 1))?;
+
+    let mut lines = vec![tr!(lang, "start-welcome", deus = &deus.deus, id = deus.id)];
+    lines.extend(events::flush(&*store, lang, user.id));
+
+    let _ = msg.reply(&lines.join("\n"));
+});
@@ -0,0 +1,49 @@
+use cmds::{self, registered, stars};
+use deus::Deus;
+use deus::Stats;
+use items;
+use storage;
+
+// !stats <deus id>, every part that goes into each stat.
+command!(stats(ctx, msg, args) {
+    let store = storage::get(ctx)?;
+    let lang = cmds::lang(&*store, msg);
+    let me = registered(&*store, &msg.author, lang)?;
+    let id = args.get(0).and_then(|s| s.parse::<i32>().ok()).ok_or_else(|| tr!(lang, "stats-usage"))?;
+    let deus = store.deus(id)?;
+    if deus.owner != me.id {
+        return Err(tr!(lang, "not-your-deus"));
+    }
+    let species = Deus::find(&deus.deus).ok_or_else(|| tr!(lang, "not-in-catalog", deus = &deus.deus))?;
+
+    let nature = deus.nature();
+    let effect = if nature.is_neutral() {
+        tr!(lang, "stats-no-effect")
+    } else {
+        format!("+{} -{}", nature.up.name(), nature.down.name())
+    };
+
+    let mut lines = vec![
+        tr!(lang, "stats-header", deus = &deus.deus, stars = stars(deus.stars), level = deus.level(), nature = nature.name,
+            effect = effect),
+        "```".to_owned(),
+        format!("{:<6}{:>6}{:>6}{:>7}{:>8}{:>7}", "", tr!(lang, "stats-base"), tr!(lang, "stats-variance"),
+                tr!(lang, "stats-training"), tr!(lang, "stats-nature"), tr!(lang, "stats-total")),
+    ];
+    for part in Stats::breakdown(species, &deus) {
+        let nature = match part.nature {
+            100 => "-".to_owned(),
+            n => format!("{:+}%", n - 100),
+        };
+        lines.push(format!("{:<6}{:>6}{:>6}{:>7}{:>8}{:>7}",
+                           part.stat.name(), part.base, part.variance, part.training, nature, part.value));
+    }
+    lines.push("```".to_owned());
+    lines.push(tr!(lang, "stats-training-used", used = deus.training.total(), most = Stats::MAX_TRAINING_TOTAL,
+                   points = deus.points));
+    if let Some(ref held) = deus.held {
+        lines.push(tr!(lang, "stats-holds", item = items::Item::title(lang, held)));
+    }
+
+    let _ = msg.reply(&lines.join("\n"));
+});
@@ -0,0 +1,127 @@
+use cmds::{self, registered, stars};
+use deus::Instance::DeusInstance;
+use deus::Team::{self, Team as DeusTeam, FORMATS};
+use storage;
+
+command!(team(ctx, msg, args) {
+    let store = storage::get(ctx)?;
+    let lang = cmds::lang(&*store, msg);
+    let me = registered(&*store, &msg.author, lang)?;
+    let teams = store.teams(me.id)?;
+    let arg = |i: usize| args.get(i).map(|s| s.as_str());
+
+    let reply = match (arg(0), arg(1), arg(2)) {
+        (None, _, _) => match teams.iter().find(|t| t.active) {
+            Some(team) => show(lang, team, &store.deus_of(me.id)?),
+            None => tr!(lang, "team-none"),
+        },
+        (Some("list"), _, _) => {
+            if teams.is_empty() {
+                tr!(lang, "team-none")
+            } else {
+                teams.iter()
+                    .map(|t| tr!(lang, "team-listed", team = &t.name, active = if t.active { "yes" } else { "no" },
+                                 count = t.members.len()))
+                    .collect::<Vec<_>>()
+                    .join("\n")
+            }
+        }
+        (Some("new"), Some(name), _) => {
+            let mut team = DeusTeam::new(me.id, name);
+            team.active = teams.is_empty();
+            match store.save_team(&team) {
+                Ok(_) => tr!(lang, "team-created", team = name),
+                Err(storage::Error::AlreadyExists) => return Err(tr!(lang, "team-exists", team = name)),
+                Err(e) => return Err(e.into()),
+            }
+        }
+        (Some("use"), Some(name), _) => {
+            let team = by_name(lang, &teams, name)?;
+            store.activate_team(me.id, team.id)?;
+            tr!(lang, "team-activated", team = &team.name)
+        }
+        (Some("delete"), Some(name), _) => {
+            let team = by_name(lang, &teams, name)?;
+            store.delete_team(team.id)?;
+            tr!(lang, "team-deleted", team = &team.name)
+        }
+        (Some("set"), Some(slot), Some(id)) => {
+            let mut team = active(lang, &teams)?;
+            let id = id.parse::<i32>().map_err(|_| tr!(lang, "team-usage"))?;
+            let deus = store.deus(id)?;
+            if deus.owner != me.id {
+                return Err(tr!(lang, "not-your-deus"));
+            }
+            team.set(slot_index(lang, slot)?, deus.id).map_err(|e| e.tr(lang))?;
+            store.save_team(&team)?;
+            tr!(lang, "team-set", deus = &deus.deus, team = &team.name, slot = slot)
+        }
+        (Some("remove"), Some(slot), _) => {
+            let mut team = active(lang, &teams)?;
+            team.remove(slot_index(lang, slot)?).map_err(|e| e.tr(lang))?;
+            store.save_team(&team)?;
+            tr!(lang, "team-emptied", slot = slot, team = &team.name)
+        }
+        (Some("swap"), Some(a), Some(b)) => {
+            let mut team = active(lang, &teams)?;
+            team.swap(slot_index(lang, a)?, slot_index(lang, b)?).map_err(|e| e.tr(lang))?;
+            store.save_team(&team)?;
+            show(lang, &team, &store.deus_of(me.id)?)
+        }
+        (Some("lead"), Some(slot), _) => {
+            let mut team = active(lang, &teams)?;
+            let lead = *team.members.get(slot_index(lang, slot)?).ok_or_else(|| tr!(lang, "team-slot-empty"))?;
+            team.lead = Some(lead);
+            store.save_team(&team)?;
+            show(lang, &team, &store.deus_of(me.id)?)
+        }
+        (Some("check"), format, _) => {
+            let team = active(lang, &teams)?;
+            let format = Team::format(format.unwrap_or("standard")).ok_or_else(|| {
+                tr!(lang, "team-formats", formats = FORMATS.iter().map(|f| f.name).collect::<Vec<_>>().join(", "))
+            })?;
+            Team::validate(&team, &store.deus_of(me.id)?, format).map_err(|e| e.tr(lang))?;
+            tr!(lang, "team-ready", team = &team.name, format = format.name)
+        }
+        _ => tr!(lang, "team-usage"),
+    };
+
+    let _ = msg.reply(&reply);
+});
+
+fn by_name<'a>(lang: &str, teams: &'a [DeusTeam], name: &str) -> Result<&'a DeusTeam, String> {
+    teams.iter().find(|t| t.name == name).ok_or_else(|| tr!(lang, "team-unknown", team = name))
+}
+
+fn active(lang: &str, teams: &[DeusTeam]) -> Result<DeusTeam, String> {
+    teams.iter()
+        .find(|t| t.active)
+        .cloned()
+        .ok_or_else(|| tr!(lang, "team-pick-first"))
+}
+
+// Slots are shown 1 based.
+fn slot_index(lang: &str, slot: &str) -> Result<usize, String> {
+    match slot.parse::<usize>() {
+        Ok(n) if n >= 1 => Ok(n - 1),
+        _ => Err(tr!(lang, "team-slots", most = Team::TEAM_SIZE)),
+    }
+}
+
+fn show(lang: &str, team: &DeusTeam, deus: &[DeusInstance]) -> String {
+    let lead = team.lead();
+    let mut lines = vec![format!("**{}**", team.name)];
+
+    for (i, id) in team.members.iter().enumerate() {
+        let line = match deus.iter().find(|d| d.id == *id) {
+            Some(d) => tr!(lang, "team-member", slot = i + 1, deus = &d.deus, stars = stars(d.stars), level = d.level()),
+            None => format!("{}. ???", i + 1),
+        };
+        lines.push(if lead == Some(*id) { tr!(lang, "team-lead", line = line) } else { line });
+    }
+    for i in team.members.len()..Team::TEAM_SIZE {
+        lines.push(format!("{}. -", i + 1));
+    }
+
+    lines.join("\n")
+}
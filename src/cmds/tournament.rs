@@ -0,0 +1,369 @@
+use std::collections::BTreeMap;
+
+use battle::{self, Battle};
+use battle::replay::{Replay, TeamSnapshot};
+use cmds::battle::{drank, side};
+use cmds::{self, registered};
+use events;
+use ladder;
+use storage::{self, now, Storage};
+use tournament;
+use tournament::bracket::{Bracket, Match, Side, Stage};
+use tournament::Tournament::{Entrant, Format, MatchResult, State, Tournament};
+use users::Users::User;
+
+// Discord messages are capped at 2000 characters.
+const BRACKET_LENGTH: usize = 1500;
+
+command!(tournament(ctx, msg, args) {
+    let store = storage::get(ctx)?;
+    let lang = cmds::lang(&*store, msg);
+    let usage = || tr!(lang, "tournament-usage");
+    let arg = |i: usize| args.get(i).map(|s| s.as_str());
+
+    let reply = match arg(0) {
+        None | Some("list") => list(&*store, lang)?,
+        Some("create") => {
+            let me = registered(&*store, &msg.author, lang)?;
+            let format = arg(1).and_then(Format::from_name).ok_or_else(&usage)?;
+            // Free entry is fine for friendly events.
+            let fee = match arg(2).and_then(|s| s.parse::<i32>().ok()) {
+                Some(fee) if fee > tournament::MAX_FEE => {
+                    return Err(tr!(lang, "tournament-fee-too-high", max = tournament::MAX_FEE));
+                }
+                Some(fee) if fee >= 0 => fee,
+                _ => return Err(usage()),
+            };
+            let name = args.iter().skip(3).cloned().collect::<Vec<_>>().join(" ");
+            if name.is_empty() {
+                return Err(usage());
+            }
+            let t = store.create_tournament(&Tournament::new(&name, format, me.id, fee))?;
+            tr!(lang, "tournament-created", name = &t.name, format = format.name(), id = t.id, fee = t.fee)
+        }
+        Some("join") => {
+            let me = registered(&*store, &msg.author, lang)?;
+            let t = pick(&*store, lang, arg(1))?;
+            match store.join_tournament(t.id, me.id) {
+                Ok(_) => tr!(lang, "tournament-joined", name = &t.name),
+                Err(storage::Error::AlreadyExists) => return Err(tr!(lang, "tournament-already-in", name = &t.name)),
+                Err(storage::Error::Closed) => return Err(tr!(lang, "tournament-full", name = &t.name)),
+                Err(storage::Error::NotEnoughVults) => return Err(tr!(lang, "costs-vults", price = t.fee, have = me.vults)),
+                Err(storage::Error::NotFound) => return Err(tr!(lang, "tournament-closed", name = &t.name)),
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Some("leave") => {
+            let me = registered(&*store, &msg.author, lang)?;
+            let t = pick(&*store, lang, arg(1))?;
+            match store.leave_tournament(t.id, me.id) {
+                Ok(()) => tr!(lang, "tournament-left", name = &t.name),
+                Err(storage::Error::NotFound) => return Err(tr!(lang, "tournament-cant-leave", name = &t.name)),
+                Err(e) => return Err(e.into()),
+            }
+        }
+        Some("start") => {
+            let me = registered(&*store, &msg.author, lang)?;
+            let t = organized(&*store, lang, &me, arg(1))?;
+            start(&*store, lang, &t)?
+        }
+        Some("cancel") => {
+            let me = registered(&*store, &msg.author, lang)?;
+            let t = organized(&*store, lang, &me, arg(1))?;
+            let refunds: Vec<(i32, i32)> = store.entrants(t.id)?.iter().map(|e| (e.user, t.fee)).filter(|&(_, fee)| fee > 0).collect();
+            store.finish_tournament(t.id, &refunds, &format!("tournament {} refund", t.id))?;
+            tr!(lang, "tournament-cancelled", name = &t.name)
+        }
+        Some("bracket") => {
+            let t = pick(&*store, lang, arg(1))?;
+            let mut lines = advance(&*store, lang, &t)?;
+            lines.push(show(&*store, lang, &store.tournament(t.id)?)?);
+            lines.join("\n")
+        }
+        Some("play") => {
+            let me = registered(&*store, &msg.author, lang)?;
+            let t = pick(&*store, lang, arg(1))?;
+            play(&*store, lang, &t, &me)?
+        }
+        Some(_) => return Err(usage()),
+    };
+
+    let _ = msg.reply(&reply);
+});
+
+fn list(store: &Storage, lang: &str) -> Result<String, String> {
+    let tournaments = store.tournaments()?;
+    if tournaments.is_empty() {
+        return Ok(tr!(lang, "tournament-none-start"));
+    }
+
+    let mut lines = Vec::new();
+    for t in tournaments {
+        lines.push(tr!(lang, "tournament-listed", id = t.id, name = &t.name, format = t.format.name(),
+                       players = store.entrants(t.id)?.len(), state = t.state.name()));
+    }
+    Ok(lines.join("\n"))
+}
+
+// The tournament an id points to, or the only one there is.
+fn pick(store: &Storage, lang: &str, id: Option<&str>) -> Result<Tournament, String> {
+    match id {
+        Some(id) => {
+            let id = id.parse::<i32>().map_err(|_| tr!(lang, "tournament-usage"))?;
+            match store.tournament(id) {
+                Ok(t) => Ok(t),
+                Err(storage::Error::NotFound) => Err(tr!(lang, "tournament-unknown", id = id)),
+                Err(e) => Err(e.into()),
+            }
+        }
+        None => {
+            let mut tournaments = store.tournaments()?;
+            match tournaments.len() {
+                1 => Ok(tournaments.remove(0)),
+                0 => Err(tr!(lang, "tournament-none")),
+                _ => Err(tr!(lang, "tournament-which")),
+            }
+        }
+    }
+}
+
+fn organized(store: &Storage, lang: &str, me: &User, id: Option<&str>) -> Result<Tournament, String> {
+    let t = pick(store, lang, id)?;
+    if t.organizer != me.id {
+        return Err(tr!(lang, "tournament-organizer-only", name = &t.name));
+    }
+    Ok(t)
+}
+
+// Seeds the entrants by their rating this season, the earliest to join
+// going first on a tie.
+fn start(store: &Storage, lang: &str, t: &Tournament) -> Result<String, String> {
+    let entrants = store.entrants(t.id)?;
+    if entrants.len() < t.format.min_entrants() {
+        return Err(tr!(lang, "tournament-too-few", format = t.format.name(), players = t.format.min_entrants()));
+    }
+
+    let season = ladder::current_season();
+    let mut rated = Vec::new();
+    for e in &entrants {
+        rated.push((e.user, store.rating(e.user, season)?.rating));
+    }
+    rated.sort_by(|a, b| b.1.cmp(&a.1));
+    let seeds: Vec<i32> = rated.iter().map(|&(user, _)| user).collect();
+
+    match store.start_tournament(t.id, &seeds) {
+        Ok(started) => Ok(format!("{}\n{}", tr!(lang, "tournament-started", name = &t.name), show(store, lang, &started)?)),
+        Err(storage::Error::NotFound) => Err(tr!(lang, "tournament-already-started", name = &t.name)),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn bracket_of(store: &Storage, t: &Tournament) -> Result<(Bracket, Vec<Entrant>), String> {
+    let entrants = store.entrants(t.id)?;
+    let seeds: Vec<i32> = entrants.iter().map(|e| e.user).collect();
+    Ok((Bracket::new(t.format, &seeds, &store.results(t.id)?, t.started), entrants))
+}
+
+// Settles the matches nobody played in time, then pays the prizes once
+// there's nothing left to play. Returns what happened.
+pub fn advance(store: &Storage, lang: &str, t: &Tournament) -> Result<Vec<String>, String> {
+    if t.state != State::Running {
+        return Ok(Vec::new());
+    }
+
+    let mut news = Vec::new();
+    loop {
+        let (bracket, entrants) = bracket_of(store, t)?;
+        // Claimed matches are being played, whoever claimed them records them.
+        let claimed = |m: &Match| entrants.iter().any(|e| e.claimed == Some(m.number));
+        let expired: Vec<&Match> = bracket.playable().into_iter().filter(|m| m.deadline() <= now() && !claimed(m)).collect();
+        if expired.is_empty() {
+            if bracket.finished() {
+                news.extend(finish(store, lang, t, &bracket)?);
+            }
+            return Ok(news);
+        }
+
+        for m in expired {
+            let ready: Vec<i32> = entrants.iter().filter(|e| e.ready == Some(m.number)).map(|e| e.user).collect();
+            let winner = tournament::no_show(&bracket, m, &ready).unwrap();
+            let result = MatchResult { tournament: t.id, number: m.number, winner: winner, walkover: true, replay: None, at: 0 };
+            match store.record_result(&result) {
+                Ok(_) => news.push(tr!(lang, "tournament-walkover", name = store.user(winner)?.name, number = m.number)),
+                Err(storage::Error::AlreadyExists) => {}
+                Err(e) => return Err(e.into()),
+            }
+        }
+    }
+}
+
+fn finish(store: &Storage, lang: &str, t: &Tournament, bracket: &Bracket) -> Result<Vec<String>, String> {
+    let standings = bracket.standings();
+    let prizes = tournament::prizes(tournament::pool(t.fee, bracket.seeds.len()), &standings);
+    match store.finish_tournament(t.id, &prizes, &format!("tournament {} prize", t.id)) {
+        Ok(()) => {}
+        Err(storage::Error::NotFound) => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    }
+
+    let mut news = vec![tr!(lang, "tournament-over", name = &t.name)];
+    for (place, users) in standings.iter().enumerate().take(tournament::PRIZES.len()) {
+        let mut names = Vec::new();
+        for user in users {
+            let won = prizes.iter().find(|p| p.0 == *user).map_or(0, |p| p.1);
+            names.push(format!("{} ({})", store.user(*user)?.name, tr!(lang, "vults", count = won)));
+        }
+        news.push(format!("{}. {}", place + 1, names.join(", ")));
+    }
+    Ok(news)
+}
+
+// Checks the player in for their next match, and plays it when the other
+// side is checked in too.
+fn play(store: &Storage, lang: &str, t: &Tournament, me: &User) -> Result<String, String> {
+    if t.state != State::Running {
+        return Err(tr!(lang, "tournament-not-running", name = &t.name));
+    }
+    let mut news = advance(store, lang, t)?;
+    let (bracket, entrants) = bracket_of(store, t)?;
+    if !entrants.iter().any(|e| e.user == me.id) {
+        return Err(tr!(lang, "tournament-not-in", name = &t.name));
+    }
+    let m = match bracket.match_of(me.id) {
+        Some(m) => m,
+        None => {
+            news.push(if bracket.finished() || !bracket.matches.iter().any(|m| m.has(me.id) && m.winner == Side::Pending) {
+                tr!(lang, "tournament-no-match")
+            } else {
+                tr!(lang, "tournament-no-opponent")
+            });
+            return Ok(news.join("\n"));
+        }
+    };
+    let players = m.players().unwrap();
+    let foe = store.user(if players[0] == me.id { players[1] } else { players[0] })?;
+
+    // Whoever checks in second plays the match, claiming it so nobody else
+    // plays it at the same time.
+    store.set_ready(t.id, me.id, Some(m.number))?;
+    match store.claim_match(t.id, m.number, players) {
+        Ok(()) => {}
+        Err(storage::Error::NotFound) => {
+            news.push(tr!(lang, "tournament-checked-in", number = m.number, foe = &foe.name, left = minutes(lang, m.deadline())));
+            return Ok(news.join("\n"));
+        }
+        Err(e) => return Err(e.into()),
+    }
+
+    // Players who can't field a team lose the match.
+    let (mine, theirs) = (side(store, lang, me), side(store, lang, &foe));
+    let (winner, played, potions, forfeit) = match (mine, theirs) {
+        (Ok((mine, my_deus)), Ok((theirs, their_deus))) => {
+            let seed = battle::seed();
+            let mut battle = Battle::new(seed, mine, theirs);
+            let teams = [TeamSnapshot::of(&battle.sides[0]), TeamSnapshot::of(&battle.sides[1])];
+            let actions = battle.run();
+            let mut potions = drank(&battle.sides[0], &my_deus);
+            potions.extend(drank(&battle.sides[1], &their_deus));
+            let winner = match battle.winner() {
+                Some(0) => me.id,
+                Some(_) => foe.id,
+                None => tournament::better_seed(&bracket, players),
+            };
+            (winner, Some(Replay::new([me.id, foe.id], seed, teams, actions, Some(winner))), potions, None)
+        }
+        (Err(why), Ok(_)) => (foe.id, None, Vec::new(), Some(tr!(lang, "tournament-forfeit", number = m.number, name = &me.name, why = why))),
+        (_, Err(why)) => (me.id, None, Vec::new(), Some(tr!(lang, "tournament-forfeit", number = m.number, name = &foe.name, why = why))),
+    };
+
+    // Nothing is kept from a match somebody else decided first.
+    let result = MatchResult { tournament: t.id, number: m.number, winner: winner, walkover: played.is_none(), replay: None, at: 0 };
+    let recorded = match store.finish_match(&result, played.as_ref(), &potions) {
+        Ok(recorded) => recorded,
+        Err(storage::Error::AlreadyExists) => return Err(tr!(lang, "tournament-already-played", number = m.number)),
+        Err(e) => return Err(e.into()),
+    };
+    news.push(match (forfeit, recorded.replay) {
+        (Some(forfeit), _) => forfeit,
+        (None, replay) => tr!(lang, "tournament-played", number = m.number, winner = name(winner, me, &foe),
+                              loser = name(loser(winner, players), me, &foe), replay = replay.unwrap_or(0)),
+    });
+    news.extend(events::flush(store, lang, me.id));
+    news.extend(advance(store, lang, t)?);
+    Ok(news.join("\n"))
+}
+
+fn loser(winner: i32, players: [i32; 2]) -> i32 {
+    if players[0] == winner { players[1] } else { players[0] }
+}
+
+fn name<'a>(user: i32, me: &'a User, foe: &'a User) -> &'a str {
+    if user == me.id { &me.name } else { &foe.name }
+}
+
+fn minutes(lang: &str, deadline: i64) -> String {
+    tr!(lang, "minutes", count = ((deadline - now() + 59) / 60).max(0))
+}
+
+// The matches being played and the latest results, as many as fit.
+fn show(store: &Storage, lang: &str, t: &Tournament) -> Result<String, String> {
+    let (bracket, entrants) = bracket_of(store, t)?;
+    let mut names = BTreeMap::new();
+    for e in &entrants {
+        names.insert(e.user, store.user(e.user)?.name);
+    }
+    let player = |user: i32| format!("{} ({})", names[&user], bracket.seed_of(user));
+
+    let mut lines = vec![tr!(lang, "tournament-header", name = &t.name, format = t.format.name(), players = entrants.len(),
+                             pool = tournament::pool(t.fee, entrants.len()))];
+    if t.state == State::Open {
+        lines.extend(entrants.iter().map(|e| names[&e.user].clone()));
+        return Ok(lines.join("\n"));
+    }
+    if bracket.finished() {
+        for (place, users) in bracket.standings().iter().enumerate() {
+            let users: Vec<String> = users.iter().map(|&u| player(u)).collect();
+            lines.push(format!("{}. {}", place + 1, users.join(", ")));
+        }
+        return Ok(lines.join("\n"));
+    }
+
+    let playing: Vec<String> = bracket.playable().iter()
+        .map(|m| {
+            let p = m.players().unwrap();
+            tr!(lang, "tournament-now", first = player(p[0]), second = player(p[1]), left = minutes(lang, m.deadline()))
+        })
+        .collect();
+    let mut length = lines[0].len() + playing.iter().map(|l| l.len() + 1).sum::<usize>();
+    lines.extend(playing);
+
+    let mut played = Vec::new();
+    for m in bracket.matches.iter().rev().filter(|m| m.players().is_some() && m.winner != Side::Pending) {
+        let winner = m.winner.player().unwrap();
+        let p = m.players().unwrap();
+        let how = match m.replay {
+            Some(id) => tr!(lang, "tournament-replay", id = id),
+            None => tr!(lang, "tournament-walkover-short"),
+        };
+        let line = tr!(lang, "tournament-result", stage = label(lang, m), number = m.number, winner = player(winner),
+                       loser = player(loser(winner, p)), how = how);
+        length += line.len() + 1;
+        if length > BRACKET_LENGTH {
+            break;
+        }
+        played.push(line);
+    }
+    played.reverse();
+    lines.extend(played);
+    Ok(lines.join("\n"))
+}
+
+fn label(lang: &str, m: &Match) -> String {
+    let stage = match m.stage {
+        Stage::Winners => "winners",
+        Stage::Losers => "losers",
+        Stage::Final => "final",
+        Stage::Swiss => "swiss",
+    };
+    tr!(lang, "tournament-stage", stage = stage, round = m.round)
+}
@@ -0,0 +1,28 @@
+use cmds::{self, registered};
+use deus::Stats::{Stat, STATS};
+use storage;
+
+// !train <deus id> <stat> <points>
+command!(train(ctx, msg, args) {
+    let store = storage::get(ctx)?;
+    let lang = cmds::lang(&*store, msg);
+    let me = registered(&*store, &msg.author, lang)?;
+    let usage = || {
+        let stats = STATS.iter().map(|s| s.name()).collect::<Vec<_>>().join(", ");
+        tr!(lang, "train-usage", stats = stats)
+    };
+
+    let id = args.get(0).and_then(|s| s.parse::<i32>().ok()).ok_or_else(&usage)?;
+    let stat = args.get(1).and_then(|s| Stat::from_name(s)).ok_or_else(&usage)?;
+    let amount = args.get(2).and_then(|s| s.parse::<i32>().ok()).ok_or_else(&usage)?;
+
+    let mut deus = store.deus(id)?;
+    if deus.owner != me.id {
+        return Err(tr!(lang, "not-your-deus"));
+    }
+    let spent = deus.train(stat, amount).map_err(|e| e.tr(lang))?;
+    store.update_deus(&deus)?;
+
+    let _ = msg.reply(&tr!(lang, "train-done", deus = &deus.deus, stat = stat.name(), spent = spent,
+                           now = deus.training.get(stat), points = deus.points));
+});
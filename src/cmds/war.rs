@@ -0,0 +1,43 @@
+use cmds;
+use cmds::quests::left;
+use cmds::server::here;
+use storage::{self, now};
+use wars;
+
+// !war, the scores of the latest guild war this server is in, or of the
+// latest one if it isn't in any.
+command!(war(ctx, msg) {
+    let store = storage::get(ctx)?;
+    let lang = cmds::lang(&*store, msg);
+    let here = here(&*store, msg)?.map(|(account, _)| account.id);
+    let recent = store.wars(wars::RECENT)?;
+
+    let mut shown = None;
+    for war in &recent {
+        let scores = store.war_scores(war.id)?;
+        if here.is_none() || scores.iter().any(|s| Some(s.guild) == here) {
+            shown = Some((war, scores));
+            break;
+        }
+    }
+    let (war, scores) = match shown {
+        Some(shown) => shown,
+        None => match recent.first() {
+            Some(war) => (war, store.war_scores(war.id)?),
+            None => return Err(tr!(lang, "war-none", stake = wars::STAKE)),
+        },
+    };
+
+    let at = now();
+    let mut lines = vec![if war.running(at) && !war.settled {
+        tr!(lang, "war-running", left = left(lang, war.ends - at), prize = war.prize)
+    } else {
+        tr!(lang, "war-over")
+    }];
+    lines.extend(wars::standings(&*store, lang, &scores, here)?);
+    if here.map_or(true, |h| !scores.iter().any(|s| s.guild == h)) {
+        lines.push(tr!(lang, "war-not-in"));
+    }
+
+    let _ = msg.reply(&lines.join("\n"));
+});
@@ -0,0 +1,162 @@
+pub mod tls;
+
+use std::env;
+use std::thread;
+use std::time::Duration;
+
+use postgres::error::{ConnectError, SqlState};
+use r2d2::{self, ManageConnection};
+use r2d2_postgres::{self, PostgresConnectionManager, TlsMode};
+
+pub type Pool = r2d2::Pool<PostgresConnectionManager>;
+pub type Conn = r2d2::PooledConnection<PostgresConnectionManager>;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Tls {
+    Disable,
+    Prefer,
+    Require,
+}
+
+pub struct Settings {
+    pub url: String,
+    pub tls: Tls,
+    pub ca_file: Option<String>,
+    pub pool_size: u32,
+}
+
+impl Settings {
+    // HOLLOW_DB is the connection url, the rest is optional:
+    // HOLLOW_DB_TLS (disable, prefer, require), HOLLOW_DB_CA, HOLLOW_DB_POOL.
+    pub fn from_env() -> Settings {
+        Settings::with_url(env::var("HOLLOW_DB").expect("database"))
+    }
+
+    // The same settings, for the database at `url`.
+    pub fn with_url(url: String) -> Settings {
+        let tls = match env::var("HOLLOW_DB_TLS").ok().as_ref().map(|s| s.as_str()) {
+            None | Some("disable") => Tls::Disable,
+            Some("prefer") => Tls::Prefer,
+            Some("require") => Tls::Require,
+            Some(other) => panic!("HOLLOW_DB_TLS must be disable, prefer or require, got '{}'", other),
+        };
+
+        Settings {
+            url: url,
+            tls: tls,
+            ca_file: env::var("HOLLOW_DB_CA").ok(),
+            pool_size: env::var("HOLLOW_DB_POOL").ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(8),
+        }
+    }
+
+    fn manager(&self) -> Result<PostgresConnectionManager, String> {
+        let mode = match self.tls {
+            Tls::Disable => TlsMode::None,
+            Tls::Prefer => TlsMode::Prefer(Box::new(tls::OpenSsl::new(self.ca_file.as_ref())?)),
+            Tls::Require => TlsMode::Require(Box::new(tls::OpenSsl::new(self.ca_file.as_ref())?)),
+        };
+
+        PostgresConnectionManager::new(self.url.as_str(), mode).map_err(|e| e.to_string())
+    }
+
+    fn config(&self) -> r2d2::Config<::postgres::Connection, ::r2d2_postgres::Error> {
+        r2d2::Config::builder()
+            .pool_size(self.pool_size)
+            .connection_timeout(Duration::from_secs(5))
+            // Broken connections are dropped on checkout and the pool
+            // reconnects in their place.
+            .test_on_check_out(true)
+            .initialization_fail_fast(true)
+            .build()
+    }
+}
+
+// Doubles the wait after every failure, up to `max`.
+pub struct Backoff {
+    next: Duration,
+    max: Duration,
+}
+
+impl Backoff {
+    pub fn new(start: Duration, max: Duration) -> Backoff {
+        Backoff { next: start, max: max }
+    }
+
+    pub fn wait(&mut self) -> Duration {
+        let waited = self.next;
+        thread::sleep(waited);
+        self.next = ::std::cmp::min(self.next * 2, self.max);
+        waited
+    }
+}
+
+// How many times connect tries before giving up, about 5 minutes of waiting.
+const CONNECT_TRIES: u32 = 10;
+
+// Why a connection attempt failed.
+enum Failure {
+    // The database is down or starting up, worth waiting for.
+    Unavailable(String),
+    // A bad url, CA file or password, waiting won't fix it.
+    Settings(String),
+}
+
+// Waits for the database to accept connections, so the bot can start before
+// Postgres does. Settings it can't connect with fail right away.
+pub fn connect(settings: &Settings) -> Result<Pool, String> {
+    let mut backoff = Backoff::new(Duration::from_secs(1), Duration::from_secs(60));
+    let mut tries = 0;
+
+    loop {
+        match attempt(settings) {
+            Ok(pool) => return Ok(pool),
+            Err(Failure::Settings(e)) => return Err(e),
+            Err(Failure::Unavailable(e)) => {
+                tries += 1;
+                if tries == CONNECT_TRIES {
+                    return Err(format!("database unavailable after {} tries: {}", tries, e));
+                }
+                println!("Database unavailable ({}), retrying", e);
+                backoff.wait();
+            }
+        }
+    }
+}
+
+fn attempt(settings: &Settings) -> Result<Pool, Failure> {
+    let manager = settings.manager().map_err(Failure::Settings)?;
+    // One connection first, it tells a server that isn't there yet from one
+    // that turns us away.
+    match manager.connect() {
+        Ok(_) => {}
+        Err(r2d2_postgres::Error::Connect(ConnectError::Io(e))) => return Err(Failure::Unavailable(e.to_string())),
+        Err(r2d2_postgres::Error::Connect(ConnectError::Db(ref e)))
+            if e.code == SqlState::CannotConnectNow || e.code == SqlState::TooManyConnections => {
+            return Err(Failure::Unavailable(e.to_string()));
+        }
+        Err(e) => return Err(Failure::Settings(e.to_string())),
+    }
+    Pool::new(settings.config(), manager).map_err(|e| Failure::Unavailable(e.to_string()))
+}
+
+// Checks a connection out of the pool, retrying a few times so a Postgres
+// restart doesn't fail every command in flight.
+pub fn get(pool: &Pool) -> Result<Conn, String> {
+    let mut backoff = Backoff::new(Duration::from_millis(250), Duration::from_secs(2));
+    let mut tries = 0;
+
+    loop {
+        match pool.get() {
+            Ok(conn) => return Ok(conn),
+            Err(e) => {
+                tries += 1;
+                if tries == 3 {
+                    return Err(format!("database unavailable: {}", e));
+                }
+                backoff.wait();
+            }
+        }
+    }
+}
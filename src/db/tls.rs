@@ -0,0 +1,80 @@
+use std::error::Error;
+use std::fmt;
+use std::io::{self, Read, Write};
+
+use openssl::ssl::{IntoSsl, SslContext, SslMethod, SslStream, SSL_VERIFY_PEER};
+use openssl_verify::verify_callback;
+use postgres::tls::{Stream, TlsHandshake, TlsStream};
+
+// TLS for Postgres on top of the openssl 0.7 hyper already links. postgres'
+// own `with-openssl` feature needs openssl-sys 0.9, and both can't be linked
+// into the same binary.
+pub struct OpenSsl {
+    ctx: SslContext,
+}
+
+impl OpenSsl {
+    pub fn new(ca_file: Option<&String>) -> Result<OpenSsl, String> {
+        let mut ctx = SslContext::new(SslMethod::Sslv23).map_err(|e| e.to_string())?;
+
+        match ca_file {
+            Some(path) => ctx.set_CA_file(path).map_err(|e| e.to_string())?,
+            None => ctx.set_default_verify_paths().map_err(|e| e.to_string())?,
+        }
+
+        Ok(OpenSsl { ctx: ctx })
+    }
+}
+
+impl fmt::Debug for OpenSsl {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("OpenSsl")
+    }
+}
+
+impl TlsHandshake for OpenSsl {
+    fn tls_handshake(&self, host: &str, stream: Stream)
+                     -> Result<Box<TlsStream>, Box<Error + Sync + Send>> {
+        let mut ssl = (&self.ctx).into_ssl()?;
+        ssl.set_hostname(host)?;
+
+        let domain = host.to_owned();
+        ssl.set_verify_callback(SSL_VERIFY_PEER, move |p, x| verify_callback(&domain, p, x));
+
+        let stream = SslStream::connect(ssl, stream)?;
+        Ok(Box::new(PgSslStream(stream)))
+    }
+}
+
+#[derive(Debug)]
+struct PgSslStream(SslStream<Stream>);
+
+// The BIO method behind `SslStream` isn't `Sync`, but the stream is owned by
+// a single connection and never shared between threads.
+unsafe impl Send for PgSslStream {}
+
+impl Read for PgSslStream {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.0.read(buf)
+    }
+}
+
+impl Write for PgSslStream {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.0.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.0.flush()
+    }
+}
+
+impl TlsStream for PgSslStream {
+    fn get_ref(&self) -> &Stream {
+        self.0.get_ref()
+    }
+
+    fn get_mut(&mut self) -> &mut Stream {
+        self.0.get_mut()
+    }
+}
@@ -0,0 +1,87 @@
+use serde_json;
+
+use battle::MAX_SPELLS;
+
+// The item !ascend takes instead of copies.
+pub const MATERIAL: &'static str = "Star Shard";
+
+// What it takes to reach `stars` from the star below, and what that gives.
+// The table lives in data/ascension.json.
+#[derive(Debug, Deserialize)]
+pub struct Step {
+    pub stars: i32,
+    // Copies of the same species used up, or else `materials` of MATERIAL.
+    pub copies: usize,
+    pub materials: i32,
+    pub vults: i32,
+    // Percent all stats are multiplied by, see Stats::value.
+    pub multiplier: i32,
+    // Whether it takes one more spell into battle.
+    #[serde(default)]
+    pub spell_slot: bool,
+}
+
+lazy_static! {
+    static ref CATALOG: Vec<Step> = serde_json::from_str(include_str!("../../data/ascension.json"))
+        .expect("data/ascension.json");
+}
+
+pub fn catalog() -> &'static [Step] {
+    &CATALOG
+}
+
+// The step up from `stars`, None at MAX_STARS.
+pub fn next(stars: i32) -> Option<&'static Step> {
+    catalog().iter().find(|s| s.stars == stars + 1)
+}
+
+// Percent stats are multiplied by at `stars`, 100 for a single star.
+pub fn multiplier(stars: i32) -> i32 {
+    catalog().iter().filter(|s| s.stars <= stars).map(|s| s.multiplier).max().unwrap_or(100)
+}
+
+// Spells a Deus with `stars` takes into battle.
+pub fn spell_slots(stars: i32) -> usize {
+    MAX_SPELLS + catalog().iter().filter(|s| s.stars <= stars && s.spell_slot).count()
+}
+
+// Table consistency.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use deus::Instance::MAX_STARS;
+    use items::Item::{self, Effect};
+
+    #[test]
+    fn every_star_up_to_the_max_has_a_step() -> Result<(), String> {
+        let stars: Vec<i32> = catalog().iter().map(|s| s.stars).collect();
+        ensure!(stars == (2..MAX_STARS + 1).collect::<Vec<_>>(), "steps go to {:?} stars", stars);
+        ensure!(next(MAX_STARS).is_none() && next(1).map(|s| s.stars) == Some(2), "next steps are off");
+        Ok(())
+    }
+
+    #[test]
+    fn stars_cost_more_and_give_more() -> Result<(), String> {
+        ensure!(catalog().iter().all(|s| s.copies > 0 && s.materials > 0 && s.vults >= 0), "a star is free");
+        for pair in catalog().windows(2) {
+            let (low, high) = (&pair[0], &pair[1]);
+            ensure!(high.copies >= low.copies && high.materials >= low.materials && high.vults >= low.vults,
+                    "{} stars cost less than {}", high.stars, low.stars);
+            ensure!(high.multiplier > low.multiplier, "{} stars multiply by {}%, {} by {}%",
+                    high.stars, high.multiplier, low.stars, low.multiplier);
+        }
+        ensure!(multiplier(1) == 100 && catalog().iter().all(|s| s.multiplier > 100), "stars weaken stats");
+        ensure!(multiplier(MAX_STARS) == catalog().last().map_or(100, |s| s.multiplier), "the max multiplier is {}",
+                multiplier(MAX_STARS));
+        ensure!(spell_slots(1) == MAX_SPELLS && spell_slots(MAX_STARS) >= spell_slots(1), "spell slots go down");
+        Ok(())
+    }
+
+    #[test]
+    fn the_ascension_material_is_an_item() -> Result<(), String> {
+        match Item::find(MATERIAL).map(|i| &i.effect) {
+            Some(&Effect::Material) => Ok(()),
+            other => Err(format!("{} is {:?}", MATERIAL, other)),
+        }
+    }
+}
@@ -1,3 +1,142 @@
-struct Deus {
-    name: String
+use serde_json;
+
+use deus::Stats::Stats;
+use deus::Type::DeusType;
+
+// A species, DEUS in the diagram. The catalog lives in data/deus.json.
+#[derive(Debug, Deserialize)]
+pub struct Deus {
+    pub number: i32,
+    pub name: String,
+    pub types: Vec<DeusType>,
+    pub base: Stats,
+    // Spells it knows from the start, the first few are taken into battle
+    // unless its owner picks others, see DeusInstance::move_set.
+    pub spells: Vec<String>,
+    // Spells it picks up as it levels.
+    #[serde(default)]
+    pub learns: Vec<Learn>,
+    #[serde(default)]
+    pub evolves: Option<Evolve>,
+    // Offered by !start.
+    #[serde(default)]
+    pub starter: bool,
+    // Only handed out as a reward, see ladder::Tier::reward.
+    #[serde(default)]
+    pub exclusive: bool,
+}
+
+// Evolve in the diagram: the species this one becomes and the level it
+// needs.
+#[derive(Debug, Deserialize)]
+pub struct Evolve {
+    pub into: String,
+    pub level: i32,
+}
+
+// A spell a species learns once it reaches `level`.
+#[derive(Debug, Deserialize)]
+pub struct Learn {
+    pub level: i32,
+    pub spell: String,
+}
+
+lazy_static! {
+    static ref CATALOG: Vec<Deus> = serde_json::from_str(include_str!("../../data/deus.json"))
+        .expect("data/deus.json");
+}
+
+pub fn catalog() -> &'static [Deus] {
+    &CATALOG
+}
+
+pub fn find(name: &str) -> Option<&'static Deus> {
+    let name = name.to_lowercase();
+    catalog().iter().find(|d| d.name.to_lowercase() == name)
+}
+
+pub fn starters() -> Vec<&'static Deus> {
+    catalog().iter().filter(|d| d.starter).collect()
+}
+
+// Catalog consistency.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use deus::Spells;
+
+    #[test]
+    fn species_names_and_numbers_are_unique() -> Result<(), String> {
+        for (i, d) in catalog().iter().enumerate() {
+            if let Some(other) = catalog()[..i].iter().find(|o| o.number == d.number || o.name == d.name) {
+                return Err(format!("{} and {} clash", other.name, d.name));
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn base_stats_are_positive() -> Result<(), String> {
+        match catalog().iter().find(|d| d.base.life <= 0 || d.base.atk <= 0 || d.base.def <= 0 || d.base.speed <= 0 || d.base.attr <= 0) {
+            Some(d) => Err(format!("{} has {:?}", d.name, d.base)),
+            None => Ok(()),
+        }
+    }
+
+    #[test]
+    fn evolutions_lead_to_known_species() -> Result<(), String> {
+        for d in catalog() {
+            if let Some(ref evolve) = d.evolves {
+                if find(&evolve.into).is_none() {
+                    return Err(format!("{} evolves into unknown {}", d.name, evolve.into));
+                }
+                if evolve.level < 2 || evolve.level > 100 {
+                    return Err(format!("{} evolves at level {}", d.name, evolve.level));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn there_are_starters() -> Result<(), String> {
+        if starters().is_empty() { Err("no species is a starter".to_owned()) } else { Ok(()) }
+    }
+
+    #[test]
+    fn exclusive_species_are_not_starters() -> Result<(), String> {
+        match catalog().iter().find(|d| d.exclusive && d.starter) {
+            Some(d) => Err(format!("{} is both a starter and exclusive", d.name)),
+            None => Ok(()),
+        }
+    }
+
+    #[test]
+    fn species_have_types_and_known_spells() -> Result<(), String> {
+        for d in catalog() {
+            if d.types.is_empty() || d.spells.is_empty() {
+                return Err(format!("{} needs a type and a spell", d.name));
+            }
+            if let Some(spell) = d.spells.iter().find(|s| Spells::find(s).is_none()) {
+                return Err(format!("{} knows unknown spell {}", d.name, spell));
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn spells_are_learned_in_level_order() -> Result<(), String> {
+        for d in catalog() {
+            if let Some(l) = d.learns.iter().find(|l| Spells::find(&l.spell).is_none() || d.spells.contains(&l.spell)) {
+                return Err(format!("{} learns unknown or already known {}", d.name, l.spell));
+            }
+            if let Some(l) = d.learns.windows(2).find(|l| l[0].level >= l[1].level) {
+                return Err(format!("{} learns {} at level {}, after {} at {}", d.name, l[1].spell, l[1].level, l[0].spell, l[0].level));
+            }
+            if let Some(l) = d.learns.iter().find(|l| l.level < 2 || l.level > 100) {
+                return Err(format!("{} learns {} at level {}", d.name, l.spell, l.level));
+            }
+        }
+        Ok(())
+    }
 }
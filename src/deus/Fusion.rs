@@ -0,0 +1,182 @@
+use rand::Rng;
+use serde_json;
+
+use deus::Deus;
+use deus::Instance::DeusInstance;
+use deus::Stats::{Stat, MAX_VARIANCE, STATS};
+
+// Vults per !fuse.
+pub const PRICE: i32 = 300;
+// The child's variance lands this far around the parents' average at most.
+pub const SPREAD: i32 = 3;
+
+// A pair of species that fuses into a third one, the catalog lives in
+// data/fusions.json. Pairs that aren't listed give one of the parents'
+// species.
+#[derive(Debug, Deserialize)]
+pub struct Fusion {
+    pub parents: [String; 2],
+    pub into: String,
+}
+
+lazy_static! {
+    static ref CATALOG: Vec<Fusion> = serde_json::from_str(include_str!("../../data/fusions.json"))
+        .expect("data/fusions.json");
+}
+
+pub fn catalog() -> &'static [Fusion] {
+    &CATALOG
+}
+
+// The fusion of two species, in either order.
+pub fn find(a: &str, b: &str) -> Option<&'static Fusion> {
+    let (a, b) = (a.to_lowercase(), b.to_lowercase());
+    catalog().iter().find(|f| {
+        let (x, y) = (f.parents[0].to_lowercase(), f.parents[1].to_lowercase());
+        (x == a && y == b) || (x == b && y == a)
+    })
+}
+
+// The species fusing `a` and `b` can give, with their chance in percent.
+pub fn outcomes(a: &DeusInstance, b: &DeusInstance) -> Vec<(String, u32)> {
+    match find(&a.deus, &b.deus) {
+        Some(fusion) => vec![(fusion.into.clone(), 100)],
+        None if a.deus == b.deus => vec![(a.deus.clone(), 100)],
+        None => vec![(a.deus.clone(), 50), (b.deus.clone(), 50)],
+    }
+}
+
+// The lowest and highest variance the child can get in `stat`.
+pub fn variance_range(a: &DeusInstance, b: &DeusInstance, stat: Stat) -> (i32, i32) {
+    let average = (a.variance.get(stat) + b.variance.get(stat)) / 2;
+    ((average - SPREAD).max(0), (average + SPREAD).min(MAX_VARIANCE))
+}
+
+// What a parent can pass on to a child of `species`: spells it knows that
+// the species doesn't.
+pub fn inheritable(parent: &DeusInstance, species: &str) -> Vec<String> {
+    let own = Deus::find(species).map_or(&[][..], |s| &s.spells[..]);
+    parent.learned_spells().into_iter().filter(|s| !own.contains(s)).collect()
+}
+
+// Fuses `a` and `b` into a new Deus for their owner. It gets the average of
+// their exp, the fewer of their stars and one of their natures, and a spell
+// from each when they have one to give.
+pub fn fuse<R: Rng>(rng: &mut R, a: &DeusInstance, b: &DeusInstance) -> DeusInstance {
+    let mut roll = rng.gen_range(0, 100);
+    let mut species = a.deus.clone();
+    for (name, chance) in outcomes(a, b) {
+        if roll < chance {
+            species = name;
+            break;
+        }
+        roll -= chance;
+    }
+
+    let mut child = DeusInstance::new(a.owner, &species, a.stars.min(b.stars));
+    child.exp = (a.exp + b.exp) / 2;
+    child.nature = if rng.gen() { a.nature.clone() } else { b.nature.clone() };
+    for &stat in STATS.iter() {
+        let (low, high) = variance_range(a, b, stat);
+        *child.variance.get_mut(stat) = rng.gen_range(low, high + 1);
+    }
+    for parent in &[a, b] {
+        let candidates: Vec<String> = inheritable(parent, &species).into_iter().filter(|s| !child.spells.contains(s)).collect();
+        if let Some(spell) = rng.choose(&candidates) {
+            child.spells.push(spell.clone());
+        }
+    }
+    child
+}
+
+// Catalog consistency and fusion rules.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fusions_use_known_species() -> Result<(), String> {
+        for f in catalog() {
+            for name in f.parents.iter().chain(Some(&f.into)) {
+                ensure!(Deus::find(name).is_some(), "{} + {} uses unknown {}", f.parents[0], f.parents[1], name);
+            }
+            ensure!(!Deus::find(&f.into).map_or(false, |d| d.exclusive), "{} + {} gives exclusive {}",
+                    f.parents[0], f.parents[1], f.into);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn each_pair_fuses_one_way() -> Result<(), String> {
+        for (i, f) in catalog().iter().enumerate() {
+            let again = catalog()[..i].iter().any(|g| {
+                (g.parents[0] == f.parents[0] && g.parents[1] == f.parents[1]) ||
+                (g.parents[0] == f.parents[1] && g.parents[1] == f.parents[0])
+            });
+            ensure!(!again, "{} + {} is listed twice", f.parents[0], f.parents[1]);
+            ensure!(f.parents[0] != f.parents[1], "{} fuses with itself", f.parents[0]);
+        }
+        Ok(())
+    }
+
+    fn parent(deus: &str, variance: i32) -> DeusInstance {
+        let mut parent = DeusInstance::new(1, deus, 2);
+        for &stat in STATS.iter() {
+            *parent.variance.get_mut(stat) = variance;
+        }
+        parent
+    }
+
+    #[test]
+    fn children_come_from_the_table_or_a_parent() -> Result<(), String> {
+        use rand::thread_rng;
+
+        let (ignis, umbra, lux) = (parent("Ignis", 10), parent("Umbra", 20), parent("Lux", 30));
+        ensure!(outcomes(&umbra, &ignis) == vec![("Infernus".to_owned(), 100)], "Umbra + Ignis gives {:?}", outcomes(&umbra, &ignis));
+        ensure!(outcomes(&ignis, &lux) == vec![("Ignis".to_owned(), 50), ("Lux".to_owned(), 50)],
+                "Ignis + Lux gives {:?}", outcomes(&ignis, &lux));
+        ensure!(outcomes(&lux, &lux) == vec![("Lux".to_owned(), 100)], "Lux + Lux gives {:?}", outcomes(&lux, &lux));
+        for _ in 0..50 {
+            let child = fuse(&mut thread_rng(), &ignis, &lux);
+            ensure!(child.deus == "Ignis" || child.deus == "Lux", "Ignis + Lux gave {}", child.deus);
+            ensure!(fuse(&mut thread_rng(), &ignis, &umbra).deus == "Infernus", "Ignis + Umbra isn't Infernus");
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn variance_blends_the_parents() -> Result<(), String> {
+        use rand::thread_rng;
+
+        let (low, high) = (parent("Lux", 0), parent("Lux", MAX_VARIANCE));
+        let (least, most) = variance_range(&low, &high, Stat::Atk);
+        ensure!(least == MAX_VARIANCE / 2 - SPREAD && most == MAX_VARIANCE / 2 + SPREAD, "variance from {} to {}", least, most);
+        ensure!(variance_range(&low, &low, Stat::Atk) == (0, SPREAD), "variance went below 0");
+        for _ in 0..50 {
+            let child = fuse(&mut thread_rng(), &low, &high);
+            ensure!(STATS.iter().all(|&s| child.variance.get(s) >= least && child.variance.get(s) <= most),
+                    "variance {:?} from 0 and {}", child.variance, MAX_VARIANCE);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn children_inherit_a_spell_from_each_parent() -> Result<(), String> {
+        use rand::thread_rng;
+
+        let (ignis, umbra) = (parent("Ignis", 0), parent("Umbra", 0));
+        let own = &Deus::find("Infernus").ok_or("Infernus isn't in the catalog")?.spells;
+        for _ in 0..50 {
+            let child = fuse(&mut thread_rng(), &ignis, &umbra);
+            ensure!(child.spells.len() == 2, "Infernus inherited {:?}", child.spells);
+            ensure!(child.spells.iter().all(|s| !own.contains(s)), "Infernus inherited {:?} it knows", child.spells);
+            ensure!(inheritable(&ignis, "Infernus").contains(&child.spells[0]), "{} isn't Ignis'", child.spells[0]);
+            ensure!(child.spells[0] != child.spells[1], "the same spell came from both");
+        }
+        // Spells that came from a fusion are passed on too.
+        let mut taught = parent("Lux", 0);
+        taught.spells = vec!["Ember".to_owned()];
+        ensure!(inheritable(&taught, "Lux") == vec!["Ember".to_owned()], "Lux passes on {:?}", inheritable(&taught, "Lux"));
+        Ok(())
+    }
+}
@@ -0,0 +1,208 @@
+use rand::{self, Rng};
+
+use deus::Ascension;
+use deus::Deus;
+use deus::Nature::{self, Nature as DeusNature, NATURES};
+use deus::Stats::{Stat, Stats, MAX_TRAINING, MAX_TRAINING_TOTAL, MAX_VARIANCE, STATS};
+use i18n::Message;
+
+// The rarest a Deus gets.
+pub const MAX_STARS: i32 = 5;
+
+// A Deus owned by a user, the `Deus[]` of USERS in the diagram.
+#[derive(Clone, Debug, PartialEq)]
+pub struct DeusInstance {
+    pub id: i32,
+    pub owner: i32,
+    pub deus: String,
+    pub exp: i32,
+    pub stars: i32,
+    pub acquired: i64,
+    // Rolled once at acquisition.
+    pub variance: Stats,
+    pub nature: String,
+    // Points won in battles, spent on `training` with !train.
+    pub points: i32,
+    pub training: Stats,
+    // The item it takes into battle, see items::Item::Item::held.
+    pub held: Option<String>,
+    // Spells it knows on top of its species', from fusions and scrolls.
+    pub spells: Vec<String>,
+    // The spells it takes into battle, picked with !learn and !forget.
+    // Empty until its owner picks, see move_set.
+    pub equipped: Vec<String>,
+    // Goes up with every save. Saving an instance read before the last save
+    // is `storage::Error::Changed`, so changes made meanwhile aren't undone.
+    pub version: i32,
+}
+
+impl DeusInstance {
+    // A plain instance, no variance and a neutral nature. Storage fills in
+    // the id and acquisition time.
+    pub fn new(owner: i32, deus: &str, stars: i32) -> DeusInstance {
+        DeusInstance {
+            id: 0,
+            owner: owner,
+            deus: deus.to_owned(),
+            exp: 0,
+            stars: stars,
+            acquired: 0,
+            variance: Stats::default(),
+            nature: NATURES[0].name.to_owned(),
+            points: 0,
+            training: Stats::default(),
+            held: None,
+            spells: Vec::new(),
+            equipped: Vec::new(),
+            version: 0,
+        }
+    }
+
+    // What a newly acquired Deus gets: random variance and nature.
+    pub fn roll(owner: i32, deus: &str, stars: i32) -> DeusInstance {
+        let mut rng = rand::thread_rng();
+        let mut instance = DeusInstance::new(owner, deus, stars);
+        for &stat in STATS.iter() {
+            *instance.variance.get_mut(stat) = rng.gen_range(0, MAX_VARIANCE + 1);
+        }
+        instance.nature = Nature::random().name.to_owned();
+        instance
+    }
+
+    pub fn level(&self) -> i32 {
+        level_for_exp(self.exp)
+    }
+
+    pub fn nature(&self) -> &'static DeusNature {
+        Nature::named(&self.nature).unwrap_or(&NATURES[0])
+    }
+
+    // Every spell it can take into battle: its species', the ones its
+    // species picked up by its level, then its own.
+    pub fn learned_spells(&self) -> Vec<String> {
+        let mut spells: Vec<String> = Vec::new();
+        if let Some(species) = Deus::find(&self.deus) {
+            spells.extend(species.spells.iter().cloned());
+            spells.extend(species.learns.iter().filter(|l| l.level <= self.level()).map(|l| l.spell.clone()));
+        }
+        for spell in &self.spells {
+            if !spells.contains(spell) {
+                spells.push(spell.clone());
+            }
+        }
+        spells
+    }
+
+    // The spells it takes into battle. Until its owner picks, that's its
+    // species' first, then its own in the last slots. Stars can open more
+    // slots, see Ascension::spell_slots.
+    pub fn move_set(&self) -> Result<Vec<String>, Message> {
+        let slots = Ascension::spell_slots(self.stars);
+        if self.equipped.is_empty() {
+            let own = Deus::find(&self.deus).map_or(&[][..], |s| &s.spells[..]);
+            let mut spells: Vec<String> = own.iter().take(slots.saturating_sub(self.spells.len())).cloned().collect();
+            spells.extend(self.spells.iter().cloned());
+            spells.truncate(slots);
+            return Ok(spells);
+        }
+
+        if self.equipped.len() > slots {
+            return Err(msg!("spells-too-many", deus = &self.deus, id = self.id, count = self.equipped.len(), slots = slots));
+        }
+        let learned = self.learned_spells();
+        for (i, spell) in self.equipped.iter().enumerate() {
+            if !learned.contains(spell) || self.equipped[..i].contains(spell) {
+                return Err(msg!("spells-cant-take", deus = &self.deus, id = self.id, spell = spell));
+            }
+        }
+        Ok(self.equipped.clone())
+    }
+
+    // Adds a learned spell to the ones it takes into battle.
+    pub fn learn(&mut self, spell: &str) -> Result<(), Message> {
+        let mut equipped = self.move_set()?;
+        let spell = match self.learned_spells().into_iter().find(|s| s.to_lowercase() == spell.to_lowercase()) {
+            Some(spell) => spell,
+            None => return Err(msg!("learn-unknown", deus = &self.deus, spell = spell)),
+        };
+        if equipped.contains(&spell) {
+            return Err(msg!("learn-taken", deus = &self.deus, spell = spell));
+        }
+        if equipped.len() >= Ascension::spell_slots(self.stars) {
+            return Err(msg!("learn-no-room", deus = &self.deus));
+        }
+        equipped.push(spell);
+        self.equipped = equipped;
+        Ok(())
+    }
+
+    // Leaves a spell out of battles, it stays learned.
+    pub fn forget(&mut self, spell: &str) -> Result<(), Message> {
+        let mut equipped = self.move_set()?;
+        match equipped.iter().position(|s| s.to_lowercase() == spell.to_lowercase()) {
+            Some(_) if equipped.len() == 1 => return Err(msg!("forget-last", deus = &self.deus)),
+            Some(i) => {
+                equipped.remove(i);
+            }
+            None => return Err(msg!("forget-not-taken", deus = &self.deus, spell = spell)),
+        }
+        self.equipped = equipped;
+        Ok(())
+    }
+
+    // Adds exp, evolving through every stage whose level it reaches. Returns
+    // the species it was, if it evolved.
+    pub fn gain_exp(&mut self, exp: i32) -> Option<String> {
+        let from = self.deus.clone();
+        self.exp += exp;
+        while let Some(evolve) = Deus::find(&self.deus).and_then(|s| s.evolves.as_ref()) {
+            if self.level() < evolve.level {
+                break;
+            }
+            self.evolve(&evolve.into);
+        }
+        if self.deus != from { Some(from) } else { None }
+    }
+
+    // Becomes `into`. Spells of the old species it no longer knows leave its
+    // move set.
+    pub fn evolve(&mut self, into: &str) {
+        self.deus = into.to_owned();
+        let learned = self.learned_spells();
+        self.equipped.retain(|s| learned.contains(s));
+    }
+
+    // Points won in battles, never more than training can still take.
+    pub fn earn_points(&mut self, points: i32) {
+        self.points = (self.points + points).min(MAX_TRAINING_TOTAL - self.training.total());
+    }
+
+    // Moves up to `amount` unspent points into a stat, returns how many fit
+    // under the caps.
+    pub fn train(&mut self, stat: Stat, amount: i32) -> Result<i32, Message> {
+        if amount <= 0 {
+            return Err(msg!("train-too-few"));
+        }
+        if amount > self.points {
+            return Err(msg!("train-points", deus = &self.deus, points = self.points));
+        }
+
+        let room = (MAX_TRAINING - self.training.get(stat)).min(MAX_TRAINING_TOTAL - self.training.total());
+        let spent = amount.min(room);
+        if spent <= 0 {
+            return Err(msg!("train-maxed", deus = &self.deus, stat = stat.name()));
+        }
+        *self.training.get_mut(stat) += spent;
+        self.points -= spent;
+        Ok(spent)
+    }
+}
+
+// Level n needs 10 * n^2 exp in total, capped at 100.
+pub fn level_for_exp(exp: i32) -> i32 {
+    let mut level = 1;
+    while level < 100 && exp >= 10 * (level + 1) * (level + 1) {
+        level += 1;
+    }
+    level
+}
@@ -0,0 +1,59 @@
+use rand::{self, Rng};
+
+use deus::Stats::Stat;
+
+// Personality of an instance, rolled at acquisition. Raises one stat by 10%
+// and lowers another by 10%, the ones with up == down change nothing. Life
+// is never touched.
+#[derive(Debug, PartialEq)]
+pub struct Nature {
+    pub name: &'static str,
+    pub up: Stat,
+    pub down: Stat,
+}
+
+pub const NATURES: &'static [Nature] = &[
+    Nature { name: "Hardy", up: Stat::Atk, down: Stat::Atk },
+    Nature { name: "Reckless", up: Stat::Atk, down: Stat::Def },
+    Nature { name: "Stubborn", up: Stat::Atk, down: Stat::Speed },
+    Nature { name: "Brutish", up: Stat::Atk, down: Stat::Attr },
+    Nature { name: "Gentle", up: Stat::Def, down: Stat::Atk },
+    Nature { name: "Docile", up: Stat::Def, down: Stat::Def },
+    Nature { name: "Stoic", up: Stat::Def, down: Stat::Speed },
+    Nature { name: "Sturdy", up: Stat::Def, down: Stat::Attr },
+    Nature { name: "Timid", up: Stat::Speed, down: Stat::Atk },
+    Nature { name: "Hasty", up: Stat::Speed, down: Stat::Def },
+    Nature { name: "Serious", up: Stat::Speed, down: Stat::Speed },
+    Nature { name: "Jolly", up: Stat::Speed, down: Stat::Attr },
+    Nature { name: "Mystic", up: Stat::Attr, down: Stat::Atk },
+    Nature { name: "Rash", up: Stat::Attr, down: Stat::Def },
+    Nature { name: "Quiet", up: Stat::Attr, down: Stat::Speed },
+    Nature { name: "Bashful", up: Stat::Attr, down: Stat::Attr },
+];
+
+impl Nature {
+    pub fn is_neutral(&self) -> bool {
+        self.up == self.down
+    }
+
+    // Percent applied to a stat.
+    pub fn modifier(&self, stat: Stat) -> i32 {
+        if self.is_neutral() {
+            100
+        } else if stat == self.up {
+            110
+        } else if stat == self.down {
+            90
+        } else {
+            100
+        }
+    }
+}
+
+pub fn named(name: &str) -> Option<&'static Nature> {
+    NATURES.iter().find(|n| n.name == name)
+}
+
+pub fn random() -> &'static Nature {
+    rand::thread_rng().choose(NATURES).unwrap()
+}
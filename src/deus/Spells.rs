@@ -0,0 +1,91 @@
+use serde_json;
+
+use battle::effects::Effect;
+use deus::Type::DeusType;
+
+// Spells in the diagram. The catalog lives in data/spells.json, replays keep
+// a copy of the spells cast in them.
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct Spell {
+    pub name: String,
+    #[serde(rename = "type")]
+    pub kind: DeusType,
+    // 0 for spells that only apply effects.
+    #[serde(default)]
+    pub power: i32,
+    #[serde(default = "always")]
+    pub accuracy: i32,
+    // Uses Attr on both sides instead of Atk against Def.
+    #[serde(default)]
+    pub magic: bool,
+    #[serde(default)]
+    pub effects: Vec<SpellEffect>,
+}
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+pub struct SpellEffect {
+    #[serde(default = "always")]
+    pub chance: i32,
+    pub target: Target,
+    pub effect: Effect,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Target {
+    Foe,
+    Caster,
+}
+
+fn always() -> i32 {
+    100
+}
+
+lazy_static! {
+    static ref CATALOG: Vec<Spell> = serde_json::from_str(include_str!("../../data/spells.json"))
+        .expect("data/spells.json");
+}
+
+pub fn catalog() -> &'static [Spell] {
+    &CATALOG
+}
+
+pub fn find(name: &str) -> Option<&'static Spell> {
+    let name = name.to_lowercase();
+    catalog().iter().find(|s| s.name.to_lowercase() == name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use battle::effects::Kind;
+    use deus::Stats::Stat;
+
+    #[test]
+    fn spell_names_are_unique() -> Result<(), String> {
+        for (i, s) in catalog().iter().enumerate() {
+            if catalog()[..i].iter().any(|o| o.name == s.name) {
+                return Err(format!("{} is listed twice", s.name));
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn spells_hit_or_do_something() -> Result<(), String> {
+        for s in catalog() {
+            if s.power < 0 || s.accuracy <= 0 || s.accuracy > 100 {
+                return Err(format!("{} has power {} and accuracy {}", s.name, s.power, s.accuracy));
+            }
+            if s.power == 0 && s.effects.is_empty() {
+                return Err(format!("{} does nothing", s.name));
+            }
+            if let Some(e) = s.effects.iter().find(|e| e.chance <= 0 || e.chance > 100) {
+                return Err(format!("{} has an effect with a {}% chance", s.name, e.chance));
+            }
+            if s.effects.iter().any(|e| e.effect.kind() == Kind::Stage(Stat::Life)) {
+                return Err(format!("{} changes Life stages, only max life is a stat", s.name));
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,124 @@
+use deus::Ascension;
+use deus::Deus::Deus;
+use deus::Instance::DeusInstance;
+
+// STATS in the diagram. Used for species base stats, an instance's variance
+// and training, and the final computed stats.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Stats {
+    pub life: i32,
+    pub atk: i32,
+    pub def: i32,
+    pub speed: i32,
+    pub attr: i32,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum Stat {
+    Life,
+    Atk,
+    Def,
+    Speed,
+    Attr,
+}
+
+pub const STATS: [Stat; 5] = [Stat::Life, Stat::Atk, Stat::Def, Stat::Speed, Stat::Attr];
+
+// Variance is rolled per stat at acquisition, from 0 to this.
+pub const MAX_VARIANCE: i32 = 31;
+// Training is capped per stat and over all stats.
+pub const MAX_TRAINING: i32 = 252;
+pub const MAX_TRAINING_TOTAL: i32 = 510;
+
+impl Stat {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Stat::Life => "Life",
+            Stat::Atk => "Atk",
+            Stat::Def => "Def",
+            Stat::Speed => "Speed",
+            Stat::Attr => "Attr",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Stat> {
+        let name = name.to_lowercase();
+        STATS.iter().find(|s| s.name().to_lowercase() == name).cloned()
+    }
+}
+
+impl Stats {
+    pub fn get(&self, stat: Stat) -> i32 {
+        match stat {
+            Stat::Life => self.life,
+            Stat::Atk => self.atk,
+            Stat::Def => self.def,
+            Stat::Speed => self.speed,
+            Stat::Attr => self.attr,
+        }
+    }
+
+    pub fn get_mut(&mut self, stat: Stat) -> &mut i32 {
+        match stat {
+            Stat::Life => &mut self.life,
+            Stat::Atk => &mut self.atk,
+            Stat::Def => &mut self.def,
+            Stat::Speed => &mut self.speed,
+            Stat::Attr => &mut self.attr,
+        }
+    }
+
+    pub fn total(&self) -> i32 {
+        STATS.iter().map(|&s| self.get(s)).sum()
+    }
+}
+
+// Every part that goes into one stat, shown by !stats.
+pub struct Part {
+    pub stat: Stat,
+    pub base: i32,
+    pub variance: i32,
+    pub training: i32,
+    // Percent from the nature, 100 when it doesn't touch the stat.
+    pub nature: i32,
+    pub value: i32,
+}
+
+// The one stat formula:
+//   core  = (2 * base + variance + training / 4) * level / 100
+//   Life  = core + level + 10, the others core + 5
+// then the nature (+/-10%) and stars, see Ascension::multiplier.
+pub fn value(stat: Stat, base: i32, variance: i32, training: i32, level: i32, stars: i32, nature: i32) -> i32 {
+    let core = (2 * base + variance + training / 4) * level / 100;
+    let raw = match stat {
+        Stat::Life => core + level + 10,
+        _ => core + 5,
+    };
+    raw * nature * Ascension::multiplier(stars) / 10000
+}
+
+pub fn breakdown(species: &Deus, deus: &DeusInstance) -> Vec<Part> {
+    let nature = deus.nature();
+    STATS.iter()
+        .map(|&stat| {
+            let (base, variance, training) = (species.base.get(stat), deus.variance.get(stat), deus.training.get(stat));
+            let modifier = nature.modifier(stat);
+            Part {
+                stat: stat,
+                base: base,
+                variance: variance,
+                training: training,
+                nature: modifier,
+                value: value(stat, base, variance, training, deus.level(), deus.stars, modifier),
+            }
+        })
+        .collect()
+}
+
+pub fn compute(species: &Deus, deus: &DeusInstance) -> Stats {
+    let mut stats = Stats::default();
+    for part in breakdown(species, deus) {
+        *stats.get_mut(part.stat) = part.value;
+    }
+    stats
+}
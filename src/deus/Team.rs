@@ -0,0 +1,116 @@
+use deus::Instance::DeusInstance;
+use i18n::Message;
+
+pub const TEAM_SIZE: usize = 4;
+
+// A saved lineup. `members` holds Deus instance ids in slot order, the active
+// team is the one taken into battles.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Team {
+    pub id: i32,
+    pub owner: i32,
+    pub name: String,
+    pub members: Vec<i32>,
+    pub lead: Option<i32>,
+    pub active: bool,
+}
+
+impl Team {
+    pub fn new(owner: i32, name: &str) -> Team {
+        Team {
+            id: 0,
+            owner: owner,
+            name: name.to_owned(),
+            members: Vec::new(),
+            lead: None,
+            active: false,
+        }
+    }
+
+    // The marked lead, or the first slot.
+    pub fn lead(&self) -> Option<i32> {
+        match self.lead {
+            Some(lead) if self.members.contains(&lead) => Some(lead),
+            _ => self.members.first().cloned(),
+        }
+    }
+
+    // Puts a Deus in a slot, replacing what was there. Slots are 0 based and
+    // can't leave a gap.
+    pub fn set(&mut self, slot: usize, deus: i32) -> Result<(), Message> {
+        if slot >= TEAM_SIZE {
+            return Err(msg!("team-slots", most = TEAM_SIZE));
+        }
+        if self.members.iter().enumerate().any(|(i, &d)| d == deus && i != slot) {
+            return Err(msg!("team-in-already"));
+        }
+
+        if slot < self.members.len() {
+            self.members[slot] = deus;
+        } else {
+            self.members.push(deus);
+        }
+        Ok(())
+    }
+
+    pub fn remove(&mut self, slot: usize) -> Result<i32, Message> {
+        if slot >= self.members.len() {
+            return Err(msg!("team-slot-empty"));
+        }
+        Ok(self.members.remove(slot))
+    }
+
+    pub fn swap(&mut self, a: usize, b: usize) -> Result<(), Message> {
+        if a >= self.members.len() || b >= self.members.len() {
+            return Err(msg!("team-swap-empty"));
+        }
+        self.members.swap(a, b);
+        Ok(())
+    }
+}
+
+// Battle rules a team is checked against before it's used.
+pub struct Format {
+    pub name: &'static str,
+    pub max_level: i32,
+    pub unique_species: bool,
+}
+
+pub const FORMATS: &'static [Format] = &[
+    Format { name: "open", max_level: 100, unique_species: false },
+    Format { name: "standard", max_level: 50, unique_species: true },
+    Format { name: "little", max_level: 20, unique_species: true },
+];
+
+pub fn format(name: &str) -> Option<&'static Format> {
+    FORMATS.iter().find(|f| f.name == name)
+}
+
+// `deus` are the owner's instances, members missing from it are no longer
+// theirs.
+pub fn validate(team: &Team, deus: &[DeusInstance], format: &Format) -> Result<(), Message> {
+    if team.members.is_empty() {
+        return Err(msg!("team-empty", team = &team.name));
+    }
+
+    let mut species = Vec::new();
+    for (i, id) in team.members.iter().enumerate() {
+        if team.members[..i].contains(id) {
+            return Err(msg!("team-repeats", slot = i + 1));
+        }
+
+        let member = match deus.iter().find(|d| d.id == *id) {
+            Some(member) => member,
+            None => return Err(msg!("team-not-owned", slot = i + 1)),
+        };
+        if member.level() > format.max_level {
+            return Err(msg!("team-over-level", deus = &member.deus, level = format.max_level, format = format.name));
+        }
+        if format.unique_species && species.contains(&member.deus) {
+            return Err(msg!("team-one-species", format = format.name, deus = &member.deus));
+        }
+        species.push(member.deus.clone());
+    }
+
+    Ok(())
+}
@@ -0,0 +1,62 @@
+use battle::effects::Kind;
+
+// The Type of DEUS in the diagram. Types decide spell effectiveness and
+// which status effects a Deus shrugs off.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+pub enum DeusType {
+    Fire,
+    Water,
+    Earth,
+    Wind,
+    Storm,
+    Light,
+    Shadow,
+}
+
+impl DeusType {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            DeusType::Fire => "Fire",
+            DeusType::Water => "Water",
+            DeusType::Earth => "Earth",
+            DeusType::Wind => "Wind",
+            DeusType::Storm => "Storm",
+            DeusType::Light => "Light",
+            DeusType::Shadow => "Shadow",
+        }
+    }
+
+    // Fire > Wind > Earth > Storm > Water > Fire, Light and Shadow beat each
+    // other.
+    fn beats(&self, other: DeusType) -> bool {
+        use self::DeusType::*;
+        match (*self, other) {
+            (Fire, Wind) | (Wind, Earth) | (Earth, Storm) | (Storm, Water) | (Water, Fire) => true,
+            (Light, Shadow) | (Shadow, Light) => true,
+            _ => false,
+        }
+    }
+
+    pub fn immune_to(&self, kind: Kind) -> bool {
+        match (*self, kind) {
+            (DeusType::Fire, Kind::Burn) => true,
+            (DeusType::Earth, Kind::Poison) => true,
+            (DeusType::Storm, Kind::Stun) => true,
+            (DeusType::Light, Kind::Sleep) => true,
+            _ => false,
+        }
+    }
+}
+
+// Percent of damage a spell of type `spell` does to a Deus of `types`.
+pub fn effectiveness(spell: DeusType, types: &[DeusType]) -> i32 {
+    types.iter().fold(100, |percent, &t| {
+        if spell.beats(t) {
+            percent * 2
+        } else if t.beats(spell) && t != spell {
+            percent / 2
+        } else {
+            percent
+        }
+    })
+}
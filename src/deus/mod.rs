@@ -1,2 +1,9 @@
+pub mod Ascension;
 pub mod Deus;
+pub mod Fusion;
+pub mod Instance;
+pub mod Nature;
 pub mod Spells;
+pub mod Stats;
+pub mod Team;
+pub mod Type;
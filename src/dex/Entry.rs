@@ -0,0 +1,8 @@
+// A species a user has come across, in battle or by owning one.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Entry {
+    pub user: i32,
+    pub deus: String,
+    // Set once they've owned one, it stays set when it's gone.
+    pub caught: bool,
+}
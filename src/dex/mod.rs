@@ -0,0 +1,184 @@
+pub mod Entry;
+
+use deus::Deus::{self, Deus as Species};
+use deus::Fusion;
+use events::Event;
+use items;
+use storage::Storage;
+use users::Users::UserType;
+use self::Entry::Entry as DexEntry;
+
+// A share of the catalog to see or catch, paid once.
+pub struct Milestone {
+    // Kept with the user once paid, renaming one pays it again.
+    pub name: &'static str,
+    pub caught: bool,
+    pub percent: i32,
+    pub vults: i32,
+}
+
+pub const MILESTONES: &'static [Milestone] = &[
+    Milestone { name: "seen-50", caught: false, percent: 50, vults: 300 },
+    Milestone { name: "seen-100", caught: false, percent: 100, vults: 1000 },
+    Milestone { name: "caught-25", caught: true, percent: 25, vults: 500 },
+    Milestone { name: "caught-50", caught: true, percent: 50, vults: 1500 },
+    Milestone { name: "caught-75", caught: true, percent: 75, vults: 3000 },
+    Milestone { name: "caught-100", caught: true, percent: 100, vults: 10000 },
+];
+
+// Rarity from the species' base stat total, exclusives are Legendary.
+pub const RARITIES: &'static [(i32, &'static str)] = &[(420, "Epic"), (350, "Rare"), (300, "Uncommon"), (0, "Common")];
+
+pub fn rarity(species: &Species) -> &'static str {
+    if species.exclusive {
+        return "Legendary";
+    }
+    RARITIES.iter().find(|&&(total, _)| species.base.total() >= total).map_or("Common", |r| r.1)
+}
+
+// How many species of the catalog are in `entries` as (seen, caught), the
+// caught ones being seen too.
+pub fn completion(entries: &[DexEntry]) -> (usize, usize) {
+    let known: Vec<&DexEntry> = entries.iter().filter(|e| Deus::find(&e.deus).is_some()).collect();
+    (known.len(), known.iter().filter(|e| e.caught).count())
+}
+
+pub fn percent(count: usize) -> i32 {
+    (count * 100 / Deus::catalog().len().max(1)) as i32
+}
+
+pub fn reached(milestone: &Milestone, entries: &[DexEntry]) -> bool {
+    let (seen, caught) = completion(entries);
+    let count = if milestone.caught { caught } else { seen };
+    count * 100 >= milestone.percent as usize * Deus::catalog().len()
+}
+
+// Pays the milestones a player has reached and wasn't paid for, returns what
+// to tell them. Server accounts don't get any.
+pub fn reward(store: &Storage, lang: &str, user: i32) -> Result<Vec<String>, String> {
+    let player = store.user(user)?;
+    if player.kind != UserType::Player {
+        return Ok(Vec::new());
+    }
+    let entries = store.dex(user)?;
+    let mut news = Vec::new();
+    for m in MILESTONES.iter().filter(|m| reached(m, &entries)) {
+        let what = if m.caught { "caught" } else { "seen" };
+        if store.reward_dex(user, m.name, m.vults, &format!("{}% of the dex {}", m.percent, what))? {
+            news.push(tr!(lang, "dex-milestone", name = &player.name, goal = what, percent = m.percent, vults = m.vults));
+        }
+    }
+    Ok(news)
+}
+
+// Marks `species` as seen by `user` and pays what that reached.
+pub fn see(store: &Storage, lang: &str, user: i32, species: &[String]) -> Result<Vec<String>, String> {
+    store.see_deus(user, species)?;
+    reward(store, lang, user)
+}
+
+// Subscribed to game events, see events::SUBSCRIBERS. Owning a Deus marks it
+// caught as it's stored, this pays for it.
+pub fn on_event(store: &Storage, lang: &str, event: &Event, _: i64) -> Result<Vec<String>, String> {
+    let mut news = Vec::new();
+    for user in event.users() {
+        news.extend(reward(store, lang, user)?);
+    }
+    Ok(news)
+}
+
+// The stages `species` is part of, from the first.
+pub fn chain(species: &'static Species) -> Vec<&'static Species> {
+    let mut first = species;
+    while let Some(before) = Deus::catalog().iter().find(|d| d.evolves.as_ref().map_or(false, |e| e.into == first.name)) {
+        first = before;
+    }
+    let mut chain = vec![first];
+    while let Some(next) = chain[chain.len() - 1].evolves.as_ref().and_then(|e| Deus::find(&e.into)) {
+        chain.push(next);
+    }
+    chain
+}
+
+// Where a species comes from, other than evolving.
+pub fn sources(lang: &str, species: &Species) -> Vec<String> {
+    let mut sources = Vec::new();
+    if species.starter {
+        sources.push("!start".to_owned());
+    }
+    if items::wild().iter().any(|d| d.name == species.name) {
+        sources.push(tr!(lang, "dex-from-wild"));
+    }
+    if species.exclusive {
+        sources.push(tr!(lang, "dex-from-ladder"));
+    }
+    for f in Fusion::catalog().iter().filter(|f| f.into == species.name) {
+        sources.push(tr!(lang, "dex-from-fusion", first = &f.parents[0], second = &f.parents[1]));
+    }
+    sources
+}
+
+// Milestones and species pages.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testing;
+
+    fn entries(count: usize, caught: usize) -> Vec<DexEntry> {
+        Deus::catalog().iter().take(count).enumerate()
+            .map(|(i, d)| DexEntry { user: 1, deus: d.name.clone(), caught: i < caught })
+            .collect()
+    }
+
+    #[test]
+    fn milestones_can_be_reached() -> Result<(), String> {
+        for (i, m) in MILESTONES.iter().enumerate() {
+            ensure!(m.percent > 0 && m.percent <= 100 && m.vults > 0, "{} is out of range", m.name);
+            ensure!(!MILESTONES[..i].iter().any(|o| o.name == m.name), "{} is there twice", m.name);
+        }
+        let all = Deus::catalog().len();
+        ensure!(MILESTONES.iter().all(|m| reached(m, &entries(all, all))), "a full dex misses a milestone");
+        ensure!(!MILESTONES.iter().any(|m| reached(m, &entries(0, 0))), "an empty dex reached a milestone");
+        ensure!(!MILESTONES.iter().any(|m| m.caught && reached(m, &entries(all, 0))), "seeing everything counted as catching");
+        ensure!(completion(&[DexEntry { user: 1, deus: "Missingno".to_owned(), caught: true }]) == (0, 0), "unknown species count");
+        Ok(())
+    }
+
+    #[test]
+    fn every_species_comes_from_somewhere() -> Result<(), String> {
+        for species in Deus::catalog() {
+            let first = chain(species)[0];
+            ensure!(!sources("en", first).is_empty(), "nothing gives {}", first.name);
+        }
+        ensure!(rarity(Deus::find("Eclipse").ok_or("no Eclipse")?) == "Legendary", "Eclipse isn't Legendary");
+        ensure!(rarity(Deus::find("Zephyr").ok_or("no Zephyr")?) == "Common", "Zephyr isn't Common");
+        Ok(())
+    }
+
+    #[test]
+    fn evolution_chains_run_from_the_first_stage() -> Result<(), String> {
+        for name in &["Ignis", "Pyros", "Infernus"] {
+            let stages: Vec<&str> = chain(Deus::find(name).ok_or("no Ignis line")?).iter().map(|d| d.name.as_str()).collect();
+            ensure!(stages == vec!["Ignis", "Pyros", "Infernus"], "{}'s chain is {:?}", name, stages);
+        }
+        ensure!(chain(Deus::find("Lux").ok_or("no Lux")?).len() == 1, "Lux evolves");
+        Ok(())
+    }
+
+    #[test]
+    fn milestones_are_paid_once() -> Result<(), String> {
+        use deus::Instance::DeusInstance;
+
+        let store = testing::store();
+        let user = store.create_user(1, "a")?;
+        let half: Vec<String> = Deus::catalog().iter().take(Deus::catalog().len() / 2).map(|d| d.name.clone()).collect();
+        let news = see(&store, "en", user.id, &half)?;
+        ensure!(news.len() == 1 && store.user(user.id)?.vults == 300, "seeing half paid {:?}", news);
+        ensure!(see(&store, "en", user.id, &half)?.is_empty(), "seeing them again paid again");
+
+        store.add_deus(&DeusInstance::new(user.id, &half[0], 1))?;
+        ensure!(store.dex(user.id)?.iter().filter(|e| e.caught).count() == 1, "owning a Deus didn't catch it");
+        ensure!(store.dex(user.id)?.len() == half.len(), "catching one added an entry");
+        Ok(())
+    }
+}
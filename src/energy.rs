@@ -0,0 +1,156 @@
+// Energy paces exploring and wild battles. Storage keeps the amount a user
+// had when it was last counted and when that was, the points regenerated
+// since are worked out from the clock whenever it's looked at: nothing has to
+// tick, and restarts don't lose any.
+
+use rand::Rng;
+
+use users::Users::User;
+
+pub const MAX_ENERGY: i32 = 100;
+// Seconds per point, a full pool in 10 hours.
+pub const REGEN: i64 = 360;
+pub const EXPLORE_COST: i32 = 10;
+// Battles against Deus nobody plays, see cmds::battle::pve.
+pub const BATTLE_COST: i32 = 15;
+// Vults per point when refilling with !energy refill.
+pub const POINT_PRICE: i32 = 2;
+
+// What !explore turns up, as (chance in percent, fewest vults, most vults).
+pub const FINDS: &'static [(u32, i32, i32)] = &[(20, 0, 0), (70, 5, 20), (10, 40, 80)];
+
+// A user's energy at `at`, along with the time it counts from: points
+// regenerate every REGEN seconds after it.
+pub fn count(energy: i32, since: i64, at: i64) -> (i32, i64) {
+    let gained = (at - since).max(0) / REGEN;
+    if energy as i64 + gained >= MAX_ENERGY as i64 {
+        // Nothing builds up while the pool is full.
+        (MAX_ENERGY, at)
+    } else {
+        (energy + gained as i32, since + gained * REGEN)
+    }
+}
+
+pub fn current(user: &User, at: i64) -> i32 {
+    count(user.energy, user.energy_at, at).0
+}
+
+// What storage keeps after adding `amount` at `at`, negative to spend, as
+// (energy, since). Refills stop at MAX_ENERGY, None when there isn't enough
+// to spend. Spending keeps the time towards the next point.
+pub fn add(energy: i32, since: i64, amount: i32, at: i64) -> Option<(i32, i64)> {
+    let (energy, since) = count(energy, since, at);
+    if energy + amount < 0 {
+        return None;
+    }
+    Some(((energy + amount).min(MAX_ENERGY), since))
+}
+
+// Seconds until the pool is full again.
+pub fn full_in(user: &User, at: i64) -> i64 {
+    let (energy, since) = count(user.energy, user.energy_at, at);
+    ((MAX_ENERGY - energy) as i64 * REGEN - (at - since)).max(0)
+}
+
+// "73/100, full in 2h 42m"
+pub fn describe(lang: &str, user: &User, at: i64) -> String {
+    let energy = current(user, at);
+    if energy >= MAX_ENERGY {
+        return format!("{}/{}", energy, MAX_ENERGY);
+    }
+    let minutes = (full_in(user, at) + 59) / 60;
+    tr!(lang, "energy-filling", energy = energy, max = MAX_ENERGY, hours = minutes / 60,
+        minutes = format!("{:02}", minutes % 60))
+}
+
+// The vults found on one !explore.
+pub fn find<R: Rng>(rng: &mut R) -> i32 {
+    let mut roll = rng.gen_range(0, 100);
+    for &(chance, least, most) in FINDS {
+        if roll < chance {
+            return rng.gen_range(least, most + 1);
+        }
+        roll -= chance;
+    }
+    0
+}
+
+// Regeneration maths.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use items::Item::{self, Effect};
+    use users::Users::UserType;
+
+    fn user(energy: i32, since: i64) -> User {
+        User {
+            id: 1,
+            disc_id: 1,
+            name: "a".to_owned(),
+            kind: UserType::Player,
+            vults: 0,
+            joined: 0,
+            energy: energy,
+            energy_at: since,
+            locale: None,
+        }
+    }
+
+    #[test]
+    fn energy_regenerates_with_time() -> Result<(), String> {
+        ensure!(count(10, 1000, 1000 + REGEN - 1) == (10, 1000), "a point came early");
+        ensure!(count(10, 1000, 1000 + 3 * REGEN + 5) == (13, 1000 + 3 * REGEN), "3 points took {:?}",
+                count(10, 1000, 1000 + 3 * REGEN + 5));
+        // A clock that went back doesn't take any away.
+        ensure!(count(10, 1000, 500) == (10, 1000), "energy went back in time");
+        Ok(())
+    }
+
+    #[test]
+    fn energy_stops_at_the_max() -> Result<(), String> {
+        ensure!(count(95, 0, 100 * REGEN) == (MAX_ENERGY, 100 * REGEN), "got {:?}", count(95, 0, 100 * REGEN));
+        ensure!(add(90, 0, 50, 0) == Some((MAX_ENERGY, 0)), "refilled to {:?}", add(90, 0, 50, 0));
+        // Time spent full doesn't count towards later points.
+        let (energy, since) = add(95, 0, -30, 100 * REGEN).ok_or("couldn't spend a full pool")?;
+        ensure!(count(energy, since, 100 * REGEN + REGEN) == (71, 101 * REGEN), "spent from full to {:?}",
+                count(energy, since, 101 * REGEN));
+        Ok(())
+    }
+
+    #[test]
+    fn spending_keeps_the_time_towards_the_next_point() -> Result<(), String> {
+        ensure!(add(10, 0, -11, REGEN - 1).is_none(), "spent 11 out of 10");
+        ensure!(add(10, 0, -11, REGEN).is_some(), "the regenerated point couldn't be spent");
+        let (energy, since) = add(50, 0, -10, REGEN / 2).ok_or("couldn't spend 10 of 50")?;
+        ensure!(energy == 40 && since == 0, "spending reset the clock to {:?}", (energy, since));
+        ensure!(count(energy, since, REGEN) == (41, REGEN), "the next point came at {:?}", count(energy, since, REGEN));
+        Ok(())
+    }
+
+    #[test]
+    fn time_to_full_counts_down() -> Result<(), String> {
+        let tired = user(MAX_ENERGY - 2, 0);
+        ensure!(full_in(&tired, 60) == 2 * REGEN - 60, "full in {}", full_in(&tired, 60));
+        ensure!(describe("en", &tired, 60) == "98/100, full in 0h 11m", "described as {}", describe("en", &tired, 60));
+        ensure!(full_in(&tired, 2 * REGEN) == 0 && describe("en", &tired, 2 * REGEN) == "100/100", "never filled up");
+        Ok(())
+    }
+
+    #[test]
+    fn exploring_doesn_t_pay_for_its_energy() -> Result<(), String> {
+        ensure!(FINDS.iter().map(|f| f.0).sum::<u32>() == 100, "find chances don't add up to 100");
+        ensure!(FINDS.iter().all(|&(_, least, most)| least >= 0 && least <= most), "finds are {:?}", FINDS);
+        // Refilling to explore more would print vults otherwise.
+        let found = FINDS.iter().map(|&(chance, least, most)| chance as i32 * (least + most)).sum::<i32>();
+        let cheapest = Item::catalog().iter()
+            .filter_map(|i| match i.effect {
+                Effect::Energy { amount } if i.price > 0 => Some((i.price, amount)),
+                _ => None,
+            })
+            .fold((POINT_PRICE, 1), |best, (price, amount)| if price * best.1 < best.0 * amount { (price, amount) } else { best });
+        // found / 200 vults against EXPLORE_COST points at price / amount each.
+        ensure!(found * cheapest.1 < 200 * EXPLORE_COST * cheapest.0, "exploring finds {} vults on average, {} energy costs {}",
+                found as f64 / 200.0, EXPLORE_COST, EXPLORE_COST as f64 * cheapest.0 as f64 / cheapest.1 as f64);
+        Ok(())
+    }
+}
@@ -0,0 +1,297 @@
+// Things that happen in the game. Storage writes them to an outbox in the
+// same transaction as the change they describe, and features that follow what
+// players do, like quests, subscribe here rather than being called from each
+// command.
+
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+use serde_json;
+
+use achievements;
+use dex;
+use i18n;
+use quests;
+use wars;
+use storage::{now, Storage};
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Event {
+    UserRegistered { user: i32 },
+    // `winner` is None for a draw.
+    BattleFinished { players: [i32; 2], winner: Option<i32> },
+    DeusCaptured { user: i32, deus: i32, species: String },
+    DeusEvolved { user: i32, deus: i32, from: String, into: String },
+    VultsTransferred { from: i32, to: i32, amount: i32 },
+    VultsSpent { user: i32, amount: i32 },
+}
+
+// An event in the outbox, `at` being when it happened.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Envelope {
+    pub id: i64,
+    pub event: Event,
+    pub at: i64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Mode {
+    // Run by the command behind the event, what they return goes in its
+    // reply.
+    Sync,
+    // Run in the background by the worker.
+    Queued,
+}
+
+pub struct Subscriber {
+    // Deliveries are tracked under it, renaming one delivers everything again.
+    pub name: &'static str,
+    pub mode: Mode,
+    // Returns lines to tell the players about, if any, in the language given.
+    pub handle: fn(&Storage, &str, &Event, i64) -> Result<Vec<String>, String>,
+}
+
+pub const SUBSCRIBERS: &'static [Subscriber] = &[
+    Subscriber { name: "quests", mode: Mode::Sync, handle: quests::on_event },
+    Subscriber { name: "achievements", mode: Mode::Sync, handle: achievements::on_event },
+    Subscriber { name: "wars", mode: Mode::Sync, handle: wars::on_event },
+    Subscriber { name: "dex", mode: Mode::Sync, handle: dex::on_event },
+    Subscriber { name: "log", mode: Mode::Queued, handle: log },
+];
+
+// Sync deliveries no command made, after a crash say, are left to the worker
+// once they're this old.
+const GRACE: i64 = 60;
+// Failed deliveries are tried again this many times at most, then given up
+// on so a bad event doesn't come back forever.
+const ATTEMPTS: i32 = 5;
+const BATCH: i64 = 200;
+const POLL: u64 = 10;
+
+lazy_static! {
+    // Commands and the worker take turns, so nothing is delivered twice.
+    static ref DELIVERY: Mutex<()> = Mutex::new(());
+}
+
+impl Event {
+    // The players it's about.
+    pub fn users(&self) -> Vec<i32> {
+        match *self {
+            Event::BattleFinished { players, .. } => players.to_vec(),
+            Event::VultsTransferred { from, to, .. } => vec![from, to],
+            Event::UserRegistered { user } |
+            Event::DeusCaptured { user, .. } |
+            Event::DeusEvolved { user, .. } |
+            Event::VultsSpent { user, .. } => vec![user],
+        }
+    }
+}
+
+// How events are kept in the outbox.
+pub fn encode(event: &Event) -> String {
+    serde_json::to_string(event).unwrap()
+}
+
+pub fn decode(event: &str) -> Result<Event, String> {
+    serde_json::from_str(event).map_err(|e| format!("bad event {}: {}", event, e))
+}
+
+// Runs the sync subscribers on what happened to `user`, for the reply of the
+// command that did it. The command went through whatever happens here, what
+// fails is logged and left to the worker.
+pub fn flush(store: &Storage, lang: &str, user: i32) -> Vec<String> {
+    deliver(store, SUBSCRIBERS, lang, Some(user), &|s, _| s.mode == Mode::Sync)
+}
+
+// Runs the queued subscribers, and the sync ones on events no command picked
+// up.
+pub fn drain(store: &Storage, at: i64) -> Vec<String> {
+    deliver(store, SUBSCRIBERS, i18n::default(), None, &|s, e| s.mode == Mode::Queued || e.at + GRACE <= at)
+}
+
+// Drains the outbox every few seconds, for as long as the bot runs. Events
+// stored before a crash are delivered once it's back up, however long that
+// took. Subscribers start where the outbox is the first time they run.
+pub fn worker(store: Arc<Storage>) {
+    for s in SUBSCRIBERS {
+        store.subscribe(s.name).expect("event subscribers");
+    }
+    thread::spawn(move || loop {
+        for line in drain(&*store, now()) {
+            println!("[ events ] - {}", line);
+        }
+        thread::sleep(Duration::from_secs(POLL));
+    });
+}
+
+// Events about `user`, or everyone's, that `pick` takes. An event is marked
+// delivered once its subscriber is done with it: one that fails, or a crash in
+// between, gets it again. Failures are logged and don't hold up the rest.
+fn deliver(store: &Storage, subscribers: &[Subscriber], lang: &str, user: Option<i32>,
+           pick: &Fn(&Subscriber, &Envelope) -> bool) -> Vec<String> {
+    let _turn = DELIVERY.lock().unwrap();
+    let mut news = Vec::new();
+    for s in subscribers {
+        let pending = match user {
+            Some(user) => store.pending_events_for(s.name, user, BATCH),
+            None => store.pending_events(s.name, BATCH),
+        };
+        let pending = match pending {
+            Ok(pending) => pending,
+            Err(why) => {
+                println!("[ events ] - {} can't list its events: {}", s.name, why);
+                continue;
+            }
+        };
+        for envelope in pending.iter().filter(|e| pick(s, e)) {
+            match handle(store, s, lang, envelope) {
+                Ok(lines) => news.extend(lines),
+                Err(why) => println!("[ events ] - {} failed on event {}: {}", s.name, envelope.id, why),
+            }
+        }
+    }
+    news
+}
+
+// Hands one event to a subscriber, marking it delivered after the last
+// attempt whether it worked or not.
+fn handle(store: &Storage, s: &Subscriber, lang: &str, envelope: &Envelope) -> Result<Vec<String>, String> {
+    match (s.handle)(store, lang, &envelope.event, envelope.at) {
+        Ok(lines) => {
+            store.mark_delivered(envelope.id, s.name)?;
+            Ok(lines)
+        }
+        Err(why) => {
+            if store.fail_delivery(envelope.id, s.name)? < ATTEMPTS {
+                return Err(why);
+            }
+            store.mark_delivered(envelope.id, s.name)?;
+            Err(format!("{}, given up after {} attempts", why, ATTEMPTS))
+        }
+    }
+}
+
+fn log(_: &Storage, _: &str, event: &Event, at: i64) -> Result<Vec<String>, String> {
+    println!("[ events ] - {} at {}", encode(event), at);
+    Ok(Vec::new())
+}
+
+// Delivery.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testing;
+
+    fn seen(_: &Storage, _: &str, event: &Event, _: i64) -> Result<Vec<String>, String> {
+        Ok(vec![format!("{:?}", event.users())])
+    }
+
+    fn broken(_: &Storage, _: &str, _: &Event, _: i64) -> Result<Vec<String>, String> {
+        Err("broken".to_owned())
+    }
+
+    const CHECKED: &'static [Subscriber] = &[
+        Subscriber { name: "sync", mode: Mode::Sync, handle: seen },
+        Subscriber { name: "queued", mode: Mode::Queued, handle: seen },
+    ];
+
+    // A memory store with CHECKED subscribed.
+    fn subscribed_store() -> Result<::storage::memory::MemoryStorage, String> {
+        let store = testing::store();
+        for s in CHECKED {
+            store.subscribe(s.name)?;
+        }
+        Ok(store)
+    }
+
+    #[test]
+    fn events_survive_the_outbox() -> Result<(), String> {
+        let events = vec![
+            Event::UserRegistered { user: 1 },
+            Event::BattleFinished { players: [1, 2], winner: None },
+            Event::DeusCaptured { user: 1, deus: 4, species: "Lux".to_owned() },
+            Event::DeusEvolved { user: 1, deus: 4, from: "Ignis".to_owned(), into: "Pyros".to_owned() },
+            Event::VultsTransferred { from: 1, to: 2, amount: 30 },
+            Event::VultsSpent { user: 2, amount: 30 },
+        ];
+        for event in events {
+            let back = decode(&encode(&event))?;
+            ensure!(back == event, "{} came back as {:?}", encode(&event), back);
+        }
+        ensure!(decode("{\"kind\": \"unheard_of\"}").is_err(), "decoded an unknown event");
+        Ok(())
+    }
+
+    #[test]
+    fn commands_run_the_sync_subscribers_on_their_user_s_events() -> Result<(), String> {
+        let store = subscribed_store()?;
+        let a = store.create_user(1, "a")?;
+        let b = store.create_user(2, "b")?;
+        let sync = |s: &Subscriber, _: &Envelope| s.mode == Mode::Sync;
+
+        let news = deliver(&store, CHECKED, "en", Some(a.id), &sync);
+        ensure!(news == vec![format!("[{}]", a.id)], "a's flush saw {:?}", news);
+        ensure!(deliver(&store, CHECKED, "en", Some(a.id), &sync).is_empty(), "a's event was delivered twice");
+        ensure!(store.pending_events("sync", BATCH)?.len() == 1, "b's event went with a's");
+        ensure!(store.pending_events("queued", BATCH)?.len() == 2, "a flush ran a queued subscriber");
+        ensure!(deliver(&store, CHECKED, "en", Some(b.id), &sync) == vec![format!("[{}]", b.id)], "b's flush missed b");
+        Ok(())
+    }
+
+    #[test]
+    fn the_worker_runs_queued_subscribers_and_leftovers() -> Result<(), String> {
+        let store = subscribed_store()?;
+        let a = store.create_user(1, "a")?;
+        let at = now();
+        let worker = |at: i64| move |s: &Subscriber, e: &Envelope| s.mode == Mode::Queued || e.at + GRACE <= at;
+
+        let news = deliver(&store, CHECKED, "en", None, &worker(at));
+        ensure!(news == vec![format!("[{}]", a.id)], "the worker saw {:?}", news);
+        ensure!(store.pending_events("sync", BATCH)?.len() == 1, "the worker didn't leave the sync event to its command");
+
+        // Nobody flushed it, say the bot went down for days in between.
+        let later = at + 7 * 86400;
+        ensure!(deliver(&store, CHECKED, "en", None, &worker(later)).len() == 1, "the leftover wasn't delivered");
+        ensure!(store.pending_events("sync", BATCH)?.is_empty() && store.pending_events("queued", BATCH)?.is_empty(),
+                "events are still pending");
+        Ok(())
+    }
+
+    #[test]
+    fn failed_deliveries_are_retried_then_given_up_on() -> Result<(), String> {
+        let store = subscribed_store()?;
+        let subscribers = &[
+            Subscriber { name: "broken", mode: Mode::Sync, handle: broken },
+            Subscriber { name: "sync", mode: Mode::Sync, handle: seen },
+        ];
+        store.subscribe("broken")?;
+        let a = store.create_user(1, "a")?;
+        let sync = |s: &Subscriber, _: &Envelope| s.mode == Mode::Sync;
+
+        let news = deliver(&store, subscribers, "en", Some(a.id), &sync);
+        ensure!(news == vec![format!("[{}]", a.id)], "a failing subscriber held up the next one, got {:?}", news);
+        for _ in 1..ATTEMPTS {
+            ensure!(store.pending_events("broken", BATCH)?.len() == 1, "a failed delivery isn't tried again");
+            deliver(&store, subscribers, "en", Some(a.id), &sync);
+        }
+        ensure!(store.pending_events("broken", BATCH)?.is_empty(), "a failing delivery is still tried after {} attempts", ATTEMPTS);
+        Ok(())
+    }
+
+    #[test]
+    fn new_subscribers_start_at_the_end_of_the_outbox() -> Result<(), String> {
+        let store = subscribed_store()?;
+        store.create_user(1, "a")?;
+        store.subscribe("late")?;
+        ensure!(store.pending_events("late", BATCH)?.is_empty(), "a new subscriber got the history");
+        let b = store.create_user(2, "b")?;
+        let pending: Vec<Event> = store.pending_events("late", BATCH)?.into_iter().map(|e| e.event).collect();
+        ensure!(pending == vec![Event::UserRegistered { user: b.id }], "a new subscriber has {:?} pending", pending);
+        store.subscribe("late")?;
+        ensure!(store.pending_events("late", BATCH)?.len() == 1, "subscribing again started over");
+        ensure!(store.pending_events("unknown", BATCH)?.is_empty(), "a subscriber that never started has events");
+        Ok(())
+    }
+}
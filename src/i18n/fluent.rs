@@ -0,0 +1,321 @@
+// The part of Fluent (projectfluent.org) the catalogs in data/locales/ use:
+// messages, variables and selectors on plural categories or exact values.
+//
+//   key = Text with { $name } in it
+//   other-key = { $count ->
+//       [one] one Deus
+//      *[other] { $count } Deus
+//   }
+//
+// Lines starting with # are comments, indented lines carry on the message
+// above, and { "{" } writes a literal brace.
+
+use std::collections::BTreeMap;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Part {
+    Text(String),
+    Var(String),
+    Select {
+        var: String,
+        variants: Vec<(String, Vec<Part>)>,
+        default: usize,
+    },
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Value {
+    Str(String),
+    Num(i64),
+}
+
+pub type Catalog = BTreeMap<String, Vec<Part>>;
+
+impl<'a> From<&'a str> for Value {
+    fn from(s: &'a str) -> Value {
+        Value::Str(s.to_owned())
+    }
+}
+
+impl<'a> From<&'a String> for Value {
+    fn from(s: &'a String) -> Value {
+        Value::Str(s.clone())
+    }
+}
+
+impl From<String> for Value {
+    fn from(s: String) -> Value {
+        Value::Str(s)
+    }
+}
+
+impl From<i32> for Value {
+    fn from(n: i32) -> Value {
+        Value::Num(n as i64)
+    }
+}
+
+impl From<i64> for Value {
+    fn from(n: i64) -> Value {
+        Value::Num(n)
+    }
+}
+
+impl From<usize> for Value {
+    fn from(n: usize) -> Value {
+        Value::Num(n as i64)
+    }
+}
+
+impl From<u32> for Value {
+    fn from(n: u32) -> Value {
+        Value::Num(n as i64)
+    }
+}
+
+pub fn parse(text: &str) -> Result<Catalog, String> {
+    let mut catalog = Catalog::new();
+    let mut current: Option<(String, Vec<String>)> = None;
+    for (number, line) in text.lines().enumerate() {
+        if line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with(' ') || line.starts_with('\t') {
+            match current {
+                Some((_, ref mut lines)) => lines.push(line.trim().to_owned()),
+                None if line.trim().is_empty() => {}
+                None => return Err(format!("line {}: indented text before any message", number + 1)),
+            }
+            continue;
+        }
+        if let Some((key, lines)) = current.take() {
+            add(&mut catalog, key, lines)?;
+        }
+        if line.trim().is_empty() {
+            continue;
+        }
+        let mut split = line.splitn(2, '=');
+        let key = split.next().unwrap().trim().to_owned();
+        let first = split.next().ok_or_else(|| format!("line {}: no = after {}", number + 1, key))?.trim();
+        if key.is_empty() || !key.chars().all(|c| c.is_alphanumeric() || c == '-' || c == '_') {
+            return Err(format!("line {}: bad key {:?}", number + 1, key));
+        }
+        current = Some((key, if first.is_empty() { Vec::new() } else { vec![first.to_owned()] }));
+    }
+    if let Some((key, lines)) = current.take() {
+        add(&mut catalog, key, lines)?;
+    }
+    Ok(catalog)
+}
+
+fn add(catalog: &mut Catalog, key: String, lines: Vec<String>) -> Result<(), String> {
+    // Blank lines within a message are kept, the ones closing it aren't.
+    let end = lines.iter().rposition(|l| !l.is_empty()).map_or(0, |i| i + 1);
+    let text = lines[..end].join("\n");
+    if text.is_empty() {
+        return Err(format!("{} is empty", key));
+    }
+    let pattern = Parser { chars: text.chars().collect(), at: 0 }.pattern(false).map_err(|e| format!("{}: {}", key, e))?;
+    if catalog.insert(key.clone(), pattern).is_some() {
+        return Err(format!("{} is there twice", key));
+    }
+    Ok(())
+}
+
+struct Parser {
+    chars: Vec<char>,
+    at: usize,
+}
+
+impl Parser {
+    fn peek(&self) -> Option<char> {
+        self.chars.get(self.at).cloned()
+    }
+
+    fn expect(&mut self, c: char) -> Result<(), String> {
+        if self.peek() != Some(c) {
+            return Err(format!("expected {:?} at {}, found {:?}", c, self.at, self.peek()));
+        }
+        self.at += 1;
+        Ok(())
+    }
+
+    fn spaces(&mut self, newlines: bool) {
+        while let Some(c) = self.peek() {
+            if c == ' ' || (newlines && c == '\n') {
+                self.at += 1;
+            } else {
+                break;
+            }
+        }
+    }
+
+    // Text and placeables, a variant's ends with its line or its selector.
+    fn pattern(&mut self, variant: bool) -> Result<Vec<Part>, String> {
+        let mut parts = Vec::new();
+        let mut text = String::new();
+        while let Some(c) = self.peek() {
+            match c {
+                '\n' | '}' if variant => break,
+                '}' => return Err(format!("unmatched }} at {}", self.at)),
+                '{' => {
+                    match self.placeable()? {
+                        Part::Text(literal) => text.push_str(&literal),
+                        part => {
+                            if !text.is_empty() {
+                                parts.push(Part::Text(text.clone()));
+                                text.clear();
+                            }
+                            parts.push(part);
+                        }
+                    }
+                }
+                c => {
+                    text.push(c);
+                    self.at += 1;
+                }
+            }
+        }
+        if variant {
+            let trimmed = text.trim_right().len();
+            text.truncate(trimmed);
+        }
+        if !text.is_empty() {
+            parts.push(Part::Text(text));
+        }
+        Ok(parts)
+    }
+
+    fn placeable(&mut self) -> Result<Part, String> {
+        self.expect('{')?;
+        self.spaces(false);
+        if self.peek() == Some('"') {
+            self.at += 1;
+            let mut literal = String::new();
+            while let Some(c) = self.peek() {
+                self.at += 1;
+                if c == '"' {
+                    self.spaces(false);
+                    self.expect('}')?;
+                    return Ok(Part::Text(literal));
+                }
+                literal.push(c);
+            }
+            return Err("unterminated string literal".to_owned());
+        }
+        self.expect('$')?;
+        let mut var = String::new();
+        while let Some(c) = self.peek() {
+            if c.is_alphanumeric() || c == '_' || c == '-' {
+                var.push(c);
+                self.at += 1;
+            } else {
+                break;
+            }
+        }
+        if var.is_empty() {
+            return Err(format!("no variable name at {}", self.at));
+        }
+        self.spaces(false);
+        if self.peek() == Some('}') {
+            self.at += 1;
+            return Ok(Part::Var(var));
+        }
+        self.expect('-')?;
+        self.expect('>')?;
+
+        let mut variants = Vec::new();
+        let mut default = None;
+        loop {
+            self.spaces(true);
+            match self.peek() {
+                Some('}') => {
+                    self.at += 1;
+                    break;
+                }
+                Some('*') => {
+                    if default.is_some() {
+                        return Err(format!("${} has two defaults", var));
+                    }
+                    default = Some(variants.len());
+                    self.at += 1;
+                }
+                _ => {}
+            }
+            self.expect('[')?;
+            let mut key = String::new();
+            while let Some(c) = self.peek() {
+                self.at += 1;
+                if c == ']' {
+                    break;
+                }
+                key.push(c);
+            }
+            self.spaces(false);
+            let value = self.pattern(true)?;
+            variants.push((key.trim().to_owned(), value));
+        }
+        let default = default.ok_or_else(|| format!("${} has no default variant", var))?;
+        Ok(Part::Select { var: var, variants: variants, default: default })
+    }
+}
+
+// Plural category of `n`, as CLDR has it for the languages we speak.
+pub fn plural(locale: &str, n: i64) -> &'static str {
+    match locale {
+        "fr" if n == 0 || n == 1 => "one",
+        "fr" => "other",
+        _ if n == 1 => "one",
+        _ => "other",
+    }
+}
+
+pub fn format(locale: &str, pattern: &[Part], args: &[(&str, Value)], out: &mut String) {
+    for part in pattern {
+        match *part {
+            Part::Text(ref text) => out.push_str(text),
+            Part::Var(ref var) => match args.iter().find(|&&(name, _)| name == var) {
+                Some(&(_, Value::Str(ref s))) => out.push_str(s),
+                Some(&(_, Value::Num(n))) => out.push_str(&n.to_string()),
+                // Left as it's written so the gap shows.
+                None => out.push_str(&format!("{{${}}}", var)),
+            },
+            Part::Select { ref var, ref variants, default } => {
+                let value = args.iter().find(|&&(name, _)| name == var).map(|&(_, ref v)| v);
+                let chosen = variants.iter()
+                    .position(|&(ref key, _)| match value {
+                        Some(&Value::Num(n)) => *key == n.to_string(),
+                        Some(&Value::Str(ref s)) => key == s,
+                        None => false,
+                    })
+                    .or_else(|| match value {
+                        Some(&Value::Num(n)) => variants.iter().position(|&(ref key, _)| key == plural(locale, n)),
+                        _ => None,
+                    })
+                    .unwrap_or(default);
+                format(locale, &variants[chosen].1, args, out);
+            }
+        }
+    }
+}
+
+// The variables `pattern` uses, for comparing translations.
+#[cfg(test)]
+pub fn variables(pattern: &[Part]) -> Vec<String> {
+    let mut vars = Vec::new();
+    for part in pattern {
+        match *part {
+            Part::Text(_) => {}
+            Part::Var(ref var) => vars.push(var.clone()),
+            Part::Select { ref var, ref variants, .. } => {
+                vars.push(var.clone());
+                for &(_, ref value) in variants {
+                    vars.extend(variables(value));
+                }
+            }
+        }
+    }
+    vars.sort();
+    vars.dedup();
+    vars
+}
@@ -0,0 +1,188 @@
+// What the bot says, in the catalogs of data/locales/, one per language. A
+// reply is in the language its user picked with !language, or else the one
+// their server's admins picked, or else HOLLOW_LOCALE.
+
+// tr!(lang, "key", name = value, ...), the message `key` in `lang`.
+macro_rules! tr {
+    ($lang:expr, $key:expr) => {
+        ::i18n::tr($lang, $key, &[])
+    };
+    ($lang:expr, $key:expr, $($name:ident = $value:expr),+ $(,)*) => {
+        ::i18n::tr($lang, $key, &[$((stringify!($name), ::i18n::fluent::Value::from($value))),+])
+    };
+}
+
+// msg!("key", name = value, ...), the message `key` to say once the language
+// is known, see Message.
+macro_rules! msg {
+    ($key:expr) => {
+        ::i18n::Message { key: $key, args: Vec::new() }
+    };
+    ($key:expr, $($name:ident = $value:expr),+ $(,)*) => {
+        ::i18n::Message { key: $key, args: vec![$((stringify!($name), ::i18n::fluent::Value::from($value))),+] }
+    };
+}
+
+pub mod fluent;
+
+use std::collections::BTreeMap;
+use std::env;
+
+use self::fluent::{Catalog, Value};
+
+// ASKED, the keys the sources pass to tr! and msg! as literals, each with its
+// file, listed by build.rs.
+#[cfg(test)]
+include!(concat!(env!("OUT_DIR"), "/keys.rs"));
+
+// Code and name of each language there's a catalog for, the first being the
+// fallback for messages a catalog lacks.
+pub const LOCALES: &'static [(&'static str, &'static str)] = &[("en", "English"), ("fr", "Français")];
+
+lazy_static! {
+    static ref CATALOGS: BTreeMap<&'static str, Catalog> = {
+        let mut catalogs = BTreeMap::new();
+        catalogs.insert("en", fluent::parse(include_str!("../../data/locales/en.ftl")).expect("data/locales/en.ftl"));
+        catalogs.insert("fr", fluent::parse(include_str!("../../data/locales/fr.ftl")).expect("data/locales/fr.ftl"));
+        catalogs
+    };
+}
+
+// The language of `code`, if there's a catalog for it.
+pub fn supported(code: &str) -> Option<&'static str> {
+    let code = code.trim().to_lowercase();
+    LOCALES.iter().map(|&(c, _)| c).find(|&c| c == code)
+}
+
+// "en (English), fr (Français)", for picking one.
+pub fn choices() -> String {
+    LOCALES.iter().map(|&(code, name)| format!("{} ({})", code, name)).collect::<Vec<_>>().join(", ")
+}
+
+pub fn name(code: &str) -> &'static str {
+    LOCALES.iter().find(|&&(c, _)| c == code).map_or(LOCALES[0].1, |&(_, name)| name)
+}
+
+// The language used when neither the user nor their server picked one, from
+// HOLLOW_LOCALE.
+pub fn default() -> &'static str {
+    env::var("HOLLOW_LOCALE").ok().and_then(|l| supported(&l)).unwrap_or(LOCALES[0].0)
+}
+
+// The user's language, then the server's, then the default.
+pub fn resolve(user: Option<&str>, server: Option<&str>) -> &'static str {
+    user.and_then(supported).or_else(|| server.and_then(supported)).unwrap_or_else(default)
+}
+
+// What game logic says without knowing who it's talking to, errors mostly.
+// Commands say it in their user's language.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Message {
+    pub key: &'static str,
+    pub args: Vec<(&'static str, Value)>,
+}
+
+impl Message {
+    pub fn tr(&self, lang: &str) -> String {
+        tr(lang, self.key, &self.args)
+    }
+
+    // With `name` set to `value`, for what only the command can say in its
+    // user's language, like an item's name.
+    pub fn with<V: Into<Value>>(mut self, name: &'static str, value: V) -> Message {
+        self.args.retain(|&(n, _)| n != name);
+        self.args.push((name, value.into()));
+        self
+    }
+}
+
+// Whether `key` is a message, rather than something already said.
+pub fn is_key(key: &str) -> bool {
+    CATALOGS[LOCALES[0].0].contains_key(key)
+}
+
+// Use tr! rather than this.
+pub fn tr(lang: &str, key: &str, args: &[(&str, Value)]) -> String {
+    let pattern = CATALOGS.get(lang).and_then(|c| c.get(key)).or_else(|| CATALOGS[LOCALES[0].0].get(key));
+    let mut out = String::new();
+    match pattern {
+        Some(pattern) => fluent::format(lang, pattern, args, &mut out),
+        None => out.push_str(key),
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use storage;
+
+    #[test]
+    fn every_catalog_has_every_message() -> Result<(), String> {
+        let reference = &CATALOGS[LOCALES[0].0];
+        for &(code, _) in LOCALES {
+            let catalog = CATALOGS.get(code).ok_or_else(|| format!("there's no {} catalog", code))?;
+            let missing: Vec<&String> = reference.keys().filter(|k| !catalog.contains_key(*k)).collect();
+            ensure!(missing.is_empty(), "{} lacks {:?}", code, missing);
+            let unknown: Vec<&String> = catalog.keys().filter(|k| !reference.contains_key(*k)).collect();
+            ensure!(unknown.is_empty(), "{} has {:?}, which {} doesn't", code, unknown, LOCALES[0].0);
+        }
+        Ok(())
+    }
+
+    // What the sources ask for by name, and the storage errors, see
+    // storage::Error::key.
+    #[test]
+    fn every_message_the_code_asks_for_is_in_the_catalogs() -> Result<(), String> {
+        ensure!(ASKED.len() > 100, "only found {} messages in the sources", ASKED.len());
+        let errors = [storage::Error::NotFound, storage::Error::AlreadyExists, storage::Error::NotEnoughVults,
+                      storage::Error::NotEnoughEnergy, storage::Error::Closed, storage::Error::Backend(String::new())];
+        let missing: Vec<String> = ASKED.iter().cloned()
+            .chain(errors.iter().map(|e| (e.key(), "storage::Error")))
+            .filter(|&(key, _)| !is_key(key))
+            .map(|(key, file)| format!("{} ({})", key, file)).collect();
+        ensure!(missing.is_empty(), "{} lacks {:?}", LOCALES[0].0, missing);
+        Ok(())
+    }
+
+    #[test]
+    fn translations_use_the_same_variables() -> Result<(), String> {
+        let reference = &CATALOGS[LOCALES[0].0];
+        for &(code, _) in &LOCALES[1..] {
+            for (key, pattern) in &CATALOGS[code] {
+                if let Some(expected) = reference.get(key) {
+                    let (theirs, ours) = (fluent::variables(pattern), fluent::variables(expected));
+                    ensure!(theirs == ours, "{} in {} uses {:?} rather than {:?}", key, code, theirs, ours);
+                }
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn plurals_follow_each_language() -> Result<(), String> {
+        let catalog = fluent::parse("deus = { $count ->\n    [0] no Deus\n    [one] { $count } Deus\n   *[other] { $count } Deus at { \"{\" }{ $where }{ \"}\" }\n    }")?;
+        let say = |lang: &str, count: i64| {
+            let mut out = String::new();
+            fluent::format(lang, &catalog["deus"], &[("count", Value::Num(count)), ("where", Value::from("home"))], &mut out);
+            out
+        };
+        ensure!(say("en", 0) == "no Deus", "en 0 said {:?}", say("en", 0));
+        ensure!(say("en", 1) == "1 Deus", "en 1 said {:?}", say("en", 1));
+        ensure!(say("en", 2) == "2 Deus at {home}", "en 2 said {:?}", say("en", 2));
+        ensure!(fluent::plural("fr", 0) == "one" && fluent::plural("fr", 1) == "one", "0 and 1 aren't singular in fr");
+        ensure!(fluent::plural("en", 0) == "other" && fluent::plural("fr", 2) == "other", "0 is singular in en");
+        ensure!(fluent::parse("a = { $n ->\n [one] x\n }").is_err(), "a selector without a default parsed");
+        ensure!(fluent::parse("a = b\na = c").is_err(), "a message defined twice parsed");
+        Ok(())
+    }
+
+    #[test]
+    fn locales_resolve_user_server_default() -> Result<(), String> {
+        ensure!(resolve(Some("fr"), Some("en")) == "fr", "the user's language didn't come first");
+        ensure!(resolve(None, Some("FR")) == "fr", "the server's language wasn't used");
+        ensure!(resolve(Some("xx"), None) == default(), "an unknown language wasn't skipped");
+        ensure!(tr("fr", "no-such-message", &[]) == "no-such-message", "a missing message isn't its key");
+        Ok(())
+    }
+}
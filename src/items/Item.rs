@@ -0,0 +1,177 @@
+use serde_json;
+
+use deus::Stats::Stat;
+use deus::Type::DeusType;
+
+// An item as written in data/items.json, see items::apply for what using one
+// does.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Effect {
+    // Held, heals `heal` percent of max life at the end of the turn its
+    // holder is down to half, then it's gone.
+    Potion { heal: i32 },
+    Candy { exp: i32 },
+    // Catches a random wild Deus.
+    Trap { stars: i32 },
+    // Evolves a Deus of its type, whatever its level.
+    Stone {
+        #[serde(rename = "type")]
+        kind: DeusType,
+    },
+    // Held, adds `percent` to a stat in battle.
+    Held { stat: Stat, percent: i32 },
+    // Gives its user energy, see energy::MAX_ENERGY.
+    Energy { amount: i32 },
+    // Used up by !ascend, see deus::Ascension.
+    Material,
+    // Teaches a Deus a spell, whatever its species.
+    Scroll { spell: String },
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Item {
+    // Also how it's stored, its name and what it does in each language are in
+    // data/locales/, see Item::title.
+    pub name: String,
+    // In vults, 0 keeps it out of the shop.
+    #[serde(default)]
+    pub price: i32,
+    pub effect: Effect,
+}
+
+// Trap stars, the most a caught Deus can start with.
+#[cfg(test)]
+pub const MAX_TRAP_STARS: i32 = 3;
+// Held items add at most this percent to a stat.
+#[cfg(test)]
+pub const MAX_HELD_PERCENT: i32 = 25;
+
+impl Item {
+    // Given to a Deus to hold rather than used up at once.
+    pub fn held(&self) -> bool {
+        match self.effect {
+            Effect::Potion { .. } | Effect::Held { .. } => true,
+            _ => false,
+        }
+    }
+
+    // Its name in `lang`.
+    pub fn title(&self, lang: &str) -> String {
+        tr!(lang, &self.key())
+    }
+
+    // What it does, in `lang`.
+    pub fn describe(&self, lang: &str) -> String {
+        tr!(lang, &format!("{}-about", self.key()))
+    }
+
+    // "item-exp-candy" for Exp Candy.
+    fn key(&self) -> String {
+        format!("item-{}", self.name.to_lowercase().replace(' ', "-"))
+    }
+}
+
+lazy_static! {
+    static ref CATALOG: Vec<Item> = serde_json::from_str(include_str!("../../data/items.json"))
+        .expect("data/items.json");
+}
+
+pub fn catalog() -> &'static [Item] {
+    &CATALOG
+}
+
+pub fn find(name: &str) -> Option<&'static Item> {
+    let name = name.to_lowercase();
+    catalog().iter().find(|i| i.name.to_lowercase() == name)
+}
+
+// By its name in `lang` or its catalog name.
+pub fn find_in(lang: &str, name: &str) -> Option<&'static Item> {
+    let lower = name.to_lowercase();
+    find(name).or_else(|| catalog().iter().find(|i| i.title(lang).to_lowercase() == lower))
+}
+
+// A stored item name in `lang`, as stored if it left the catalog.
+pub fn title(lang: &str, name: &str) -> String {
+    find(name).map_or_else(|| name.to_owned(), |i| i.title(lang))
+}
+
+// What !shop sells, in catalog order.
+pub fn shop() -> Vec<&'static Item> {
+    catalog().iter().filter(|i| i.price > 0).collect()
+}
+
+// Catalog consistency.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use deus::{Deus, Spells};
+    use energy::MAX_ENERGY;
+    use i18n;
+
+    #[test]
+    fn item_names_are_unique() -> Result<(), String> {
+        for (i, item) in catalog().iter().enumerate() {
+            if catalog()[..i].iter().any(|o| o.name.to_lowercase() == item.name.to_lowercase()) {
+                return Err(format!("{} is in the catalog twice", item.name));
+            }
+        }
+        for &(lang, _) in i18n::LOCALES {
+            for item in catalog() {
+                let found = find_in(lang, &item.title(lang)).map(|i| &i.name);
+                ensure!(found == Some(&item.name), "{} in {} is {:?}", item.title(lang), lang, found);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn items_are_in_the_message_catalogs() -> Result<(), String> {
+        for item in catalog() {
+            let about = format!("{}-about", item.key());
+            ensure!(i18n::is_key(&item.key()) && i18n::is_key(&about), "{} lacks {} or {}", item.name, item.key(), about);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn prices_are_not_negative() -> Result<(), String> {
+        match catalog().iter().find(|i| i.price < 0) {
+            Some(i) => Err(format!("{} costs {}", i.name, i.price)),
+            None => Ok(()),
+        }
+    }
+
+    #[test]
+    fn item_effects_are_in_range() -> Result<(), String> {
+        for item in catalog() {
+            let fine = match item.effect {
+                Effect::Potion { heal } => heal > 0 && heal <= 100,
+                Effect::Candy { exp } => exp > 0,
+                Effect::Trap { stars } => stars >= 1 && stars <= MAX_TRAP_STARS,
+                Effect::Stone { .. } => true,
+                Effect::Held { percent, .. } => percent > 0 && percent <= MAX_HELD_PERCENT,
+                Effect::Energy { amount } => amount > 0 && amount <= MAX_ENERGY,
+                Effect::Material => true,
+                Effect::Scroll { ref spell } => Spells::find(spell).is_some(),
+            };
+            if !fine {
+                return Err(format!("{} has {:?}", item.name, item.effect));
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn stones_evolve_something() -> Result<(), String> {
+        for item in catalog() {
+            if let Effect::Stone { kind } = item.effect {
+                if !Deus::catalog().iter().any(|d| d.evolves.is_some() && d.types.contains(&kind)) {
+                    return Err(format!("no {} species evolves, {} is useless", kind.name(), item.name));
+                }
+            }
+        }
+        Ok(())
+    }
+}
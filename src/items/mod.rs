@@ -0,0 +1,172 @@
+pub mod Item;
+
+use rand::{self, Rng};
+
+use deus::Deus::{self, Deus as Species};
+use deus::Instance::DeusInstance;
+use deus::Stats::Stats;
+use i18n::Message;
+use self::Item::{Effect, Item as CatalogItem};
+
+// What a trap can catch: anything that isn't a starter or exclusive.
+pub fn wild() -> Vec<&'static Species> {
+    Deus::catalog().iter().filter(|d| !d.starter && !d.exclusive).collect()
+}
+
+// The Deus using `item` leaves: `deus` changed, or a new one (id 0) for a
+// trap. Storage takes the item and saves it in one go, see
+// Storage::use_item.
+pub fn apply(item: &CatalogItem, owner: i32, deus: Option<DeusInstance>) -> Result<DeusInstance, Message> {
+    let mut deus = match (&item.effect, deus) {
+        (&Effect::Trap { stars }, None) => {
+            let wild = wild();
+            let species = rand::thread_rng().choose(&wild).ok_or_else(|| msg!("use-nothing-to-catch"))?;
+            return Ok(DeusInstance::roll(owner, &species.name, stars));
+        }
+        (&Effect::Trap { .. }, Some(_)) => return Err(msg!("use-trap-on-yours", item = &item.name)),
+        (&Effect::Energy { .. }, _) => return Err(msg!("use-energy-on-deus", item = &item.name)),
+        (&Effect::Material, _) => return Err(msg!("use-material", item = &item.name)),
+        (_, None) => return Err(msg!("use-which-deus", item = &item.name)),
+        (_, Some(deus)) => deus,
+    };
+    if deus.owner != owner {
+        return Err(msg!("not-your-deus"));
+    }
+
+    match item.effect {
+        Effect::Candy { exp } => {
+            if deus.level() >= 100 {
+                return Err(msg!("use-max-level", deus = &deus.deus));
+            }
+            deus.gain_exp(exp);
+        }
+        Effect::Stone { kind } => {
+            let species = Deus::find(&deus.deus).ok_or_else(|| msg!("not-in-catalog", deus = &deus.deus))?;
+            match species.evolves {
+                Some(ref evolve) if species.types.contains(&kind) => deus.evolve(&evolve.into),
+                _ => return Err(msg!("use-no-reaction", deus = &deus.deus, item = &item.name)),
+            }
+        }
+        Effect::Scroll { ref spell } => {
+            if deus.learned_spells().contains(spell) {
+                return Err(msg!("use-knows-spell", deus = &deus.deus, spell = spell));
+            }
+            deus.spells.push(spell.clone());
+        }
+        _ => {
+            if deus.held.as_ref() == Some(&item.name) {
+                return Err(msg!("use-holds-item", deus = &deus.deus, item = &item.name));
+            }
+            deus.held = Some(item.name.clone());
+        }
+    }
+    Ok(deus)
+}
+
+// Battle stats with the held item's bonus.
+pub fn boost(stats: &mut Stats, held: Option<&str>) {
+    if let Some(&Effect::Held { stat, percent }) = held.and_then(Item::find).map(|i| &i.effect) {
+        *stats.get_mut(stat) = stats.get(stat) * (100 + percent) / 100;
+    }
+}
+
+// The percent of max life a held potion heals, if it is one.
+pub fn potion(held: Option<&str>) -> Option<i32> {
+    match held.and_then(Item::find).map(|i| &i.effect) {
+        Some(&Effect::Potion { heal }) => Some(heal),
+        _ => None,
+    }
+}
+
+// Item logic.
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // apply, saying why not in English.
+    fn applied(item: &CatalogItem, owner: i32, deus: Option<DeusInstance>) -> Result<DeusInstance, String> {
+        apply(item, owner, deus).map_err(|e| e.tr("en"))
+    }
+
+    fn item(name: &str) -> Result<&'static CatalogItem, String> {
+        Item::find(name).ok_or_else(|| format!("{} is not in the catalog", name))
+    }
+
+    #[test]
+    fn candies_give_exp() -> Result<(), String> {
+        let deus = applied(item("Exp Candy")?, 1, Some(DeusInstance::new(1, "Ignis", 1)))?;
+        ensure!(deus.exp == 500, "a candy gave {} exp", deus.exp);
+
+        let mut maxed = DeusInstance::new(1, "Ignis", 1);
+        maxed.exp = 10 * 100 * 100;
+        ensure!(applied(item("Exp Candy")?, 1, Some(maxed)).is_err(), "a level 100 Deus ate a candy");
+        ensure!(applied(item("Exp Candy")?, 2, Some(DeusInstance::new(1, "Ignis", 1))).is_err(), "fed someone else's Deus");
+        Ok(())
+    }
+
+    #[test]
+    fn reaching_the_evolution_level_evolves() -> Result<(), String> {
+        let mut ignis = DeusInstance::new(1, "Ignis", 1);
+        ignis.exp = 10 * 15 * 15;
+        ignis.equipped = vec!["Growl".to_owned(), "Ember".to_owned()];
+        let deus = applied(item("Exp Candy")?, 1, Some(ignis.clone()))?;
+        ensure!(deus.deus == "Pyros" && deus.level() == 16, "a level 16 Ignis is a {} at level {}", deus.deus, deus.level());
+        ensure!(deus.equipped == ignis.equipped, "evolving changed its move set to {:?}", deus.equipped);
+
+        ignis.exp = 0;
+        ensure!(ignis.gain_exp(10 * 15 * 15) == None && ignis.deus == "Ignis", "Ignis evolved at level 15");
+        ensure!(ignis.gain_exp(10 * 100 * 100) == Some("Ignis".to_owned()) && Deus::find(&ignis.deus).map_or(false, |s| s.evolves.is_none()),
+                "a level 100 Ignis stopped at {}", ignis.deus);
+        Ok(())
+    }
+
+    #[test]
+    fn stones_evolve_their_type_only() -> Result<(), String> {
+        let deus = applied(item("Fire Stone")?, 1, Some(DeusInstance::new(1, "Ignis", 1)))?;
+        ensure!(deus.deus == "Pyros" && deus.exp == 0, "Ignis became {} at {} exp", deus.deus, deus.exp);
+        ensure!(applied(item("Water Stone")?, 1, Some(DeusInstance::new(1, "Ignis", 1))).is_err(), "Ignis took a Water Stone");
+        ensure!(applied(item("Fire Stone")?, 1, Some(DeusInstance::new(1, "Infernus", 1))).is_err(), "Infernus evolved");
+        Ok(())
+    }
+
+    #[test]
+    fn traps_catch_wild_deus() -> Result<(), String> {
+        for _ in 0..20 {
+            let caught = applied(item("Gilded Trap")?, 7, None)?;
+            let species = Deus::find(&caught.deus).ok_or("caught an unknown species")?;
+            ensure!(!species.starter && !species.exclusive, "caught a {}", species.name);
+            ensure!(caught.owner == 7 && caught.stars == 2 && caught.id == 0, "caught {:?}", caught);
+        }
+        ensure!(applied(item("Junk Trap")?, 1, Some(DeusInstance::new(1, "Ignis", 1))).is_err(), "trapped an owned Deus");
+        Ok(())
+    }
+
+    #[test]
+    fn held_items_boost_their_stat() -> Result<(), String> {
+        let deus = applied(item("Power Band")?, 1, Some(DeusInstance::new(1, "Ignis", 1)))?;
+        ensure!(deus.held == Some("Power Band".to_owned()), "holds {:?}", deus.held);
+        ensure!(applied(item("Power Band")?, 1, Some(deus.clone())).is_err(), "held the same item twice");
+
+        let mut stats = Stats { life: 100, atk: 100, def: 100, speed: 100, attr: 100 };
+        boost(&mut stats, deus.held.as_ref().map(|s| s.as_str()));
+        ensure!(stats.atk == 110 && stats.total() == 510, "boosted to {:?}", stats);
+        boost(&mut stats, Some("Potion"));
+        ensure!(stats.atk == 110 && potion(Some("Potion")) == Some(30), "potions aren't stat boosts");
+        Ok(())
+    }
+
+    #[test]
+    fn scrolls_teach_spells_off_the_species_list() -> Result<(), String> {
+        let deus = applied(item("Thunderbolt Scroll")?, 1, Some(DeusInstance::new(1, "Ignis", 1)))?;
+        ensure!(deus.spells == vec!["Thunderbolt".to_owned()], "Ignis learned {:?}", deus.spells);
+        ensure!(deus.move_set().map_err(|e| e.tr("en"))?.contains(&"Thunderbolt".to_owned()), "Thunderbolt isn't in the move set");
+        ensure!(applied(item("Thunderbolt Scroll")?, 1, Some(deus)).is_err(), "learned Thunderbolt twice");
+        ensure!(applied(item("Inferno Scroll")?, 1, Some(DeusInstance::new(1, "Infernus", 1))).is_err(), "Infernus learned Inferno");
+
+        let mut ignis = DeusInstance::new(1, "Ignis", 1);
+        ignis.equipped = vec!["Ember".to_owned(), "Growl".to_owned()];
+        let pyros = applied(item("Fire Stone")?, 1, Some(ignis))?;
+        ensure!(pyros.equipped == vec!["Ember".to_owned(), "Growl".to_owned()], "Pyros takes {:?}", pyros.equipped);
+        Ok(())
+    }
+}
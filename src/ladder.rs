@@ -0,0 +1,325 @@
+// Ranked PvP: Elo ratings, tiers and seasons. Everything here is worked out
+// from ratings alone, storage only keeps the numbers.
+
+use storage;
+use users::Rating::Rating;
+
+pub const START: i32 = 1200;
+// Nobody drops below this, however long the losing streak.
+pub const FLOOR: i32 = 100;
+// Ratings move faster until a player is placed, and only placed players get
+// a tier and a season reward.
+pub const PLACEMENT_GAMES: i32 = 10;
+const PLACEMENT_K: f64 = 40.0;
+const K: f64 = 20.0;
+
+// Seasons last four weeks, the first one started on 2017-06-01 UTC.
+pub const SEASON_EPOCH: i64 = 1496275200;
+pub const SEASON_LENGTH: i64 = 28 * 24 * 60 * 60;
+
+// How a game went, seen from one of the players.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Outcome {
+    Win,
+    Loss,
+    Draw,
+}
+
+impl Outcome {
+    pub fn flip(&self) -> Outcome {
+        match *self {
+            Outcome::Win => Outcome::Loss,
+            Outcome::Loss => Outcome::Win,
+            Outcome::Draw => Outcome::Draw,
+        }
+    }
+
+    fn score(&self) -> f64 {
+        match *self {
+            Outcome::Win => 1.0,
+            Outcome::Loss => 0.0,
+            Outcome::Draw => 0.5,
+        }
+    }
+}
+
+// A rating nobody has played yet.
+pub fn fresh(user: i32, season: i32, rating: i32) -> Rating {
+    Rating {
+        user: user,
+        season: season,
+        rating: rating,
+        games: 0,
+        wins: 0,
+        losses: 0,
+        peak: rating,
+        rewarded: false,
+    }
+}
+
+// Where a player starts a season: halfway back to START from the last rating
+// they had, if any.
+pub fn carry(user: i32, season: i32, last: Option<&Rating>) -> Rating {
+    match last {
+        Some(last) if last.season == season => last.clone(),
+        Some(last) => fresh(user, season, soft_reset(last.rating)),
+        None => fresh(user, season, START),
+    }
+}
+
+pub fn soft_reset(rating: i32) -> i32 {
+    START + (rating - START) / 2
+}
+
+pub fn placed(rating: &Rating) -> bool {
+    rating.games >= PLACEMENT_GAMES
+}
+
+// The chance of `a` beating `b`, draws counting as half.
+pub fn expected(a: i32, b: i32) -> f64 {
+    1.0 / (1.0 + 10f64.powf((b - a) as f64 / 400.0))
+}
+
+// How many points `a` wins or loses from one game against `b`.
+pub fn change(a: &Rating, b: &Rating, outcome: Outcome) -> i32 {
+    let k = if placed(a) { K } else { PLACEMENT_K };
+    (k * (outcome.score() - expected(a.rating, b.rating))).round() as i32
+}
+
+// One game between `a` and `b`, `outcome` being a's.
+pub fn record(a: &mut Rating, b: &mut Rating, outcome: Outcome) {
+    let (for_a, for_b) = (change(a, b, outcome), change(b, a, outcome.flip()));
+    apply(a, for_a, outcome);
+    apply(b, for_b, outcome.flip());
+}
+
+fn apply(rating: &mut Rating, change: i32, outcome: Outcome) {
+    rating.rating = (rating.rating + change).max(FLOOR);
+    rating.peak = rating.peak.max(rating.rating);
+    rating.games += 1;
+    match outcome {
+        Outcome::Win => rating.wins += 1,
+        Outcome::Loss => rating.losses += 1,
+        Outcome::Draw => {}
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Tier {
+    Bronze,
+    Silver,
+    Gold,
+    Platinum,
+    Diamond,
+    Master,
+}
+
+// Lowest rating of each tier. Bronze goes all the way down, its floor is
+// only used to split it into divisions.
+pub const TIERS: &'static [(Tier, i32)] = &[
+    (Tier::Bronze, 900),
+    (Tier::Silver, 1100),
+    (Tier::Gold, 1300),
+    (Tier::Platinum, 1500),
+    (Tier::Diamond, 1700),
+    (Tier::Master, 1900),
+];
+
+const DIVISIONS: i32 = 3;
+const TIER_WIDTH: i32 = 200;
+
+// What a placed player gets when a season ends, by final tier.
+pub struct Reward {
+    pub vults: i32,
+    // An exclusive species, only handed out as a reward.
+    pub deus: Option<&'static str>,
+}
+
+impl Tier {
+    pub fn of(rating: i32) -> Tier {
+        TIERS.iter().rev().find(|&&(_, floor)| rating >= floor).map_or(Tier::Bronze, |&(tier, _)| tier)
+    }
+
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Tier::Bronze => "Bronze",
+            Tier::Silver => "Silver",
+            Tier::Gold => "Gold",
+            Tier::Platinum => "Platinum",
+            Tier::Diamond => "Diamond",
+            Tier::Master => "Master",
+        }
+    }
+
+    fn floor(&self) -> i32 {
+        TIERS.iter().find(|&&(tier, _)| tier == *self).unwrap().1
+    }
+
+    pub fn reward(&self) -> Reward {
+        let (vults, deus) = match *self {
+            Tier::Bronze => (50, None),
+            Tier::Silver => (100, None),
+            Tier::Gold => (200, None),
+            Tier::Platinum => (350, None),
+            Tier::Diamond => (500, Some("Aurora")),
+            Tier::Master => (800, Some("Eclipse")),
+        };
+        Reward { vults: vults, deus: deus }
+    }
+}
+
+// The ledger reason of a season reward.
+pub fn reward_reason(rating: &Rating) -> String {
+    format!("season {} reward, {}", rating.season, rank(rating.rating))
+}
+
+// III at the bottom of a tier up to I at the top. Master has no divisions.
+pub fn division(rating: i32) -> Option<i32> {
+    let tier = Tier::of(rating);
+    if tier == Tier::Master {
+        return None;
+    }
+    let into = (rating - tier.floor()).max(0);
+    Some(DIVISIONS - (into * DIVISIONS / TIER_WIDTH).min(DIVISIONS - 1))
+}
+
+// "Gold II", or "Master".
+pub fn rank(rating: i32) -> String {
+    let tier = Tier::of(rating).name();
+    match division(rating) {
+        Some(1) => format!("{} I", tier),
+        Some(2) => format!("{} II", tier),
+        Some(_) => format!("{} III", tier),
+        None => tier.to_owned(),
+    }
+}
+
+pub fn season_at(at: i64) -> i32 {
+    ((at - SEASON_EPOCH) / SEASON_LENGTH) as i32 + 1
+}
+
+pub fn current_season() -> i32 {
+    season_at(storage::now())
+}
+
+// When a season is over, and the next one starts.
+pub fn season_end(season: i32) -> i64 {
+    SEASON_EPOCH + season as i64 * SEASON_LENGTH
+}
+
+// Rating maths.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use deus::Deus;
+
+    fn placed_at(user: i32, rating: i32) -> Rating {
+        Rating { games: PLACEMENT_GAMES, ..fresh(user, 1, rating) }
+    }
+
+    // Plays `outcomes`, each one from a's side, and returns both ratings.
+    fn play(mut a: Rating, mut b: Rating, outcomes: &[Outcome]) -> (Rating, Rating) {
+        for &outcome in outcomes {
+            record(&mut a, &mut b, outcome);
+        }
+        (a, b)
+    }
+
+    #[test]
+    fn equal_players_trade_the_same_points() -> Result<(), String> {
+        let (a, b) = play(placed_at(1, START), placed_at(2, START), &[Outcome::Win]);
+        ensure!(a.rating == START + 10 && b.rating == START - 10, "got {} and {}", a.rating, b.rating);
+        let (a, b) = play(placed_at(1, START), placed_at(2, START), &[Outcome::Draw]);
+        ensure!(a.rating == START && b.rating == START, "a draw moved them to {} and {}", a.rating, b.rating);
+        Ok(())
+    }
+
+    #[test]
+    fn upsets_move_ratings_more() -> Result<(), String> {
+        let (low, _) = play(placed_at(1, 1000), placed_at(2, 1400), &[Outcome::Win]);
+        let (high, _) = play(placed_at(1, 1400), placed_at(2, 1000), &[Outcome::Win]);
+        ensure!(low.rating - 1000 == 18 && high.rating - 1400 == 2,
+                "upset won {}, expected win won {}", low.rating - 1000, high.rating - 1400);
+        Ok(())
+    }
+
+    #[test]
+    fn a_run_of_games_gives_known_ratings() -> Result<(), String> {
+        use self::Outcome::*;
+
+        // Placement games count double, the last two are played once placed.
+        let games = [Win, Win, Loss, Win, Draw, Win, Win, Loss, Win, Win, Loss, Win];
+        let (a, b) = play(fresh(1, 1, START), fresh(2, 1, START), &games);
+        ensure!((a.rating, a.games, a.wins, a.losses, a.peak) == (1257, 12, 8, 3, 1264), "a ended at {:?}", a);
+        ensure!((b.rating, b.games, b.wins, b.losses, b.peak) == (1143, 12, 3, 8, 1200), "b ended at {:?}", b);
+        ensure!(a.draws() == 1 && b.draws() == 1, "the draw went missing");
+        Ok(())
+    }
+
+    #[test]
+    fn ratings_are_zero_sum_once_placed() -> Result<(), String> {
+        let mut a = placed_at(1, 1350);
+        let mut b = placed_at(2, 1080);
+        for i in 0..50 {
+            let outcome = [Outcome::Win, Outcome::Loss, Outcome::Draw, Outcome::Loss][i % 4];
+            record(&mut a, &mut b, outcome);
+            ensure!(a.rating + b.rating == 1350 + 1080, "game {} left {} and {}", i, a.rating, b.rating);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn ratings_never_drop_below_the_floor() -> Result<(), String> {
+        let (a, _) = play(placed_at(1, FLOOR + 5), placed_at(2, FLOOR + 5), &[Outcome::Loss]);
+        ensure!(a.rating == FLOOR, "ended at {}", a.rating);
+        Ok(())
+    }
+
+    #[test]
+    fn tiers_and_divisions_follow_the_rating() -> Result<(), String> {
+        let expected = [
+            (0, "Bronze III"), (1099, "Bronze I"), (1100, "Silver III"), (1166, "Silver III"),
+            (1167, "Silver II"), (1233, "Silver II"), (1234, "Silver I"), (1299, "Silver I"), (1750, "Diamond III"),
+            (1899, "Diamond I"), (1900, "Master"), (2400, "Master"),
+        ];
+        for &(rating, name) in expected.iter() {
+            ensure!(rank(rating) == name, "{} is {}, not {}", rating, rank(rating), name);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn soft_resets_halve_the_way_back_to_the_start() -> Result<(), String> {
+        let pairs = [(1600, 1400), (1000, 1100), (START, START), (1201, 1200)];
+        for &(rating, reset) in pairs.iter() {
+            ensure!(soft_reset(rating) == reset, "{} reset to {}", rating, soft_reset(rating));
+        }
+        let last = placed_at(1, 1600);
+        let next = carry(1, 2, Some(&last));
+        ensure!(next.rating == 1400 && next.games == 0 && next.peak == 1400, "carried {:?}", next);
+        ensure!(carry(1, 1, Some(&last)) == last, "carrying within a season changed the rating");
+        Ok(())
+    }
+
+    #[test]
+    fn seasons_follow_the_calendar() -> Result<(), String> {
+        ensure!(season_at(SEASON_EPOCH) == 1, "the epoch is in season {}", season_at(SEASON_EPOCH));
+        ensure!(season_at(season_end(1) - 1) == 1 && season_at(season_end(1)) == 2, "season 1 ends at the wrong time");
+        ensure!(season_end(3) - season_end(2) == SEASON_LENGTH, "seasons have different lengths");
+        Ok(())
+    }
+
+    #[test]
+    fn reward_species_are_exclusive() -> Result<(), String> {
+        for &(tier, _) in TIERS {
+            if let Some(name) = tier.reward().deus {
+                match Deus::find(name) {
+                    Some(species) if species.exclusive => {}
+                    Some(_) => return Err(format!("{} can be had outside of ranked", name)),
+                    None => return Err(format!("{} rewards unknown {}", tier.name(), name)),
+                }
+            }
+        }
+        Ok(())
+    }
+}
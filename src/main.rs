@@ -1,21 +1,62 @@
 #[macro_use] extern crate serenity;
 extern crate time;
 extern crate postgres;
+extern crate r2d2;
+extern crate r2d2_postgres;
+extern crate openssl;
+extern crate openssl_verify;
+extern crate typemap;
+extern crate rand;
+#[macro_use] extern crate lazy_static;
+extern crate serde;
+#[macro_use] extern crate serde_derive;
+extern crate serde_json;
+#[cfg(feature = "sqlite")]
+extern crate rusqlite;
 
 use std::env;
-use postgres::{Connection, TlsMode};
+use std::sync::Arc;
 use serenity::client::{Client, Context};
 use serenity::model::Message;
+#[cfg(test)]
+#[macro_use]
+mod testing;
+#[macro_use]
+mod i18n;
+mod achievements;
+mod battle;
 mod cmds;
+mod db;
+mod deus;
+mod dex;
+mod energy;
+mod events;
+mod items;
+mod ladder;
+mod menus;
+mod quests;
+mod raids;
+mod render;
+mod scheduler;
+mod storage;
+mod tournament;
+mod users;
+mod wars;
 
 fn main() {
-
     let mut client = Client::login_bot(&env::var("TROT_TOKEN").expect("token"));
-    let conn = Connection::connect(env::var("HOLLOW_DB").expect("database"), TlsMode::None).unwrap();
+    let store = storage::from_env();
+    events::worker(store.clone());
+    scheduler::Scheduler::new(Arc::new(scheduler::SystemClock), scheduler::JOBS)
+        .expect("job schedules")
+        .start(store.clone());
+    client.data.lock().unwrap().insert::<storage::Store>(store);
 
     client.on_ready(|_ctx, ready| {
         println!("{} is connected!", ready.user.name);
     });
+    client.on_reaction_add(|ctx, reaction| menus::on_reaction(&ctx, &reaction));
+    client.on_reaction_remove(|ctx, reaction| menus::on_reaction(&ctx, &reaction));
 
     client.with_framework(|f| f
         .configure(|c| c.prefix("!")) // set the bot's prefix to "!" 
@@ -24,18 +65,50 @@ fn main() {
             println!("[ {} ] - Got command '{}' by user '{}'", now, command_name, msg.author.name);
             true 
         })
+        .after(|ctx, msg, _command_name, result| {
+            if let Err(why) = result {
+                let _ = msg.reply(&cmds::failed(ctx, msg, &why));
+            }
+        })
         .on("help", help)
         .command("ping", |c| c.exec(cmds::ping::ping))
+        .command("start", |c| c.exec(cmds::start::start))
+        .command("language", |c| c.exec(cmds::language::language))
+        .command("profile", |c| c.exec(cmds::profile::profile))
+        .command("team", |c| c.exec(cmds::team::team))
+        .command("stats", |c| c.exec(cmds::stats::stats))
+        .command("card", |c| c.exec(cmds::card::card))
+        .command("battle", |c| c.exec(cmds::battle::battle))
+        .command("replay", |c| c.exec(cmds::replay::replay))
+        .command("ladder", |c| c.exec(cmds::ladder::ladder))
+        .command("rank", |c| c.exec(cmds::ladder::rank))
+        .command("tournament", |c| c.exec(cmds::tournament::tournament))
+        .command("inventory", |c| c.exec(cmds::items::inventory))
+        .command("dex", |c| c.exec(cmds::dex::dex))
+        .command("use", |c| c.exec(cmds::items::use_item))
+        .command("shop", |c| c.exec(cmds::items::shop))
+        .command("quests", |c| c.exec(cmds::quests::quests))
+        .command("achievements", |c| c.exec(cmds::achievements::achievements))
+        .command("backfill", |c| c.check(owner_check).exec(cmds::achievements::backfill))
+        .command("train", |c| c.exec(cmds::train::train))
+        .command("spells", |c| c.exec(cmds::spells::spells))
+        .command("learn", |c| c.exec(cmds::spells::learn))
+        .command("forget", |c| c.exec(cmds::spells::forget))
+        .command("fuse", |c| c.exec(cmds::fuse::fuse))
+        .command("ascend", |c| c.exec(cmds::ascend::ascend))
+        .command("energy", |c| c.exec(cmds::energy::energy))
+        .command("server", |c| c.exec(cmds::server::server))
+        .command("explore", |c| c.exec(cmds::energy::explore))
+        .command("war", |c| c.exec(cmds::war::war))
+        .command("raid", |c| c.exec(cmds::raid::raid))
     );
 
     let _ = client.start();
 }
 
-command!(help(_context, msg) {
-    msg.reply(
-        "Hey bro, here are the commands:
-        - !start, create your profil if you doesn't have one."
-        ); 
+command!(help(ctx, msg) {
+    let lang = storage::get(ctx).map(|store| cmds::lang(&*store, msg)).unwrap_or_else(|_| i18n::default());
+    let _ = msg.reply(&tr!(lang, "help"));
 });
 
 fn owner_check(_: &mut Context, msg: &Message) ->  bool {
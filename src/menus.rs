@@ -0,0 +1,282 @@
+// Replies driven by reactions: ◀ ▶ turn the pages of a long list and ✅ ❌
+// answer a question, the message being edited in place. Only the user who
+// ran the command drives it, and it stops listening after a while without
+// reactions. Any command opens one with `open`.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+use serenity::client::Context;
+use serenity::model::{Message, Reaction, ReactionType};
+
+use storage::{self, now, Storage};
+
+pub const PREVIOUS: &'static str = "◀";
+pub const NEXT: &'static str = "▶";
+pub const CONFIRM: &'static str = "✅";
+pub const CANCEL: &'static str = "❌";
+// Seconds a menu waits for a reaction before it stops listening.
+pub const TIMEOUT: i64 = 120;
+// Lines on a page, and characters, under the 2000 discord allows.
+pub const PAGE_LINES: usize = 15;
+const PAGE_LENGTH: usize = 1800;
+
+// What ✅ does, its result replaces the message.
+pub type Action = Box<Fn(&Storage) -> Result<String, String> + Send>;
+
+pub struct Menu {
+    // Discord id of the user driving it.
+    pub owner: u64,
+    pub pages: Vec<String>,
+    pub page: usize,
+    // What the menu says around the pages is in this language.
+    pub lang: &'static str,
+    action: Option<Action>,
+    // Last reaction, or when it was opened.
+    pub touched: i64,
+}
+
+pub enum Outcome {
+    Ignored,
+    Show(String),
+    Confirm(Action),
+    // Closed, with what the message says now if it changes.
+    Close(Option<String>),
+}
+
+lazy_static! {
+    // Open menus by message id.
+    static ref OPEN: Mutex<HashMap<u64, Menu>> = Mutex::new(HashMap::new());
+}
+
+impl Menu {
+    pub fn pages(owner: u64, lang: &'static str, pages: Vec<String>) -> Menu {
+        Menu {
+            owner: owner,
+            pages: if pages.is_empty() { vec![String::new()] } else { pages },
+            page: 0,
+            lang: lang,
+            action: None,
+            touched: now(),
+        }
+    }
+
+    // `question` with ✅ doing `action` and ❌ calling it off.
+    pub fn confirm<F>(owner: u64, lang: &'static str, question: String, action: F) -> Menu
+        where F: Fn(&Storage) -> Result<String, String> + Send + 'static
+    {
+        let mut menu = Menu::pages(owner, lang, vec![question]);
+        menu.action = Some(Box::new(action));
+        menu
+    }
+
+    pub fn text(&self) -> String {
+        if self.pages.len() > 1 {
+            format!("{}\n{}", self.pages[self.page], tr!(self.lang, "menu-page", page = self.page + 1, pages = self.pages.len()))
+        } else {
+            self.pages[self.page].clone()
+        }
+    }
+
+    // The reactions it's driven with, none for a single page.
+    pub fn reactions(&self) -> Vec<&'static str> {
+        let mut reactions = Vec::new();
+        if self.pages.len() > 1 {
+            reactions.extend_from_slice(&[PREVIOUS, NEXT]);
+        }
+        if self.action.is_some() {
+            reactions.extend_from_slice(&[CONFIRM, CANCEL]);
+        }
+        reactions
+    }
+
+    pub fn expired(&self, at: i64) -> bool {
+        at >= self.touched + TIMEOUT
+    }
+
+    // `user` reacted with `emoji` at `at`.
+    pub fn react(&mut self, user: u64, emoji: &str, at: i64) -> Outcome {
+        if user != self.owner || self.expired(at) {
+            return Outcome::Ignored;
+        }
+        let outcome = match emoji {
+            PREVIOUS if self.page > 0 => {
+                self.page -= 1;
+                Outcome::Show(self.text())
+            }
+            NEXT if self.page + 1 < self.pages.len() => {
+                self.page += 1;
+                Outcome::Show(self.text())
+            }
+            CONFIRM => match self.action.take() {
+                Some(action) => Outcome::Confirm(action),
+                None => Outcome::Ignored,
+            },
+            CANCEL if self.action.is_some() => Outcome::Close(Some(tr!(self.lang, "menu-cancelled"))),
+            CANCEL => Outcome::Close(None),
+            _ => Outcome::Ignored,
+        };
+        match outcome {
+            Outcome::Ignored => {}
+            _ => self.touched = at,
+        }
+        outcome
+    }
+}
+
+// `lines` cut in pages, each starting with `header`.
+pub fn paginate(header: &str, lines: &[String]) -> Vec<String> {
+    let mut pages = Vec::new();
+    let mut page = header.to_owned();
+    let mut count = 0;
+    for line in lines {
+        if count == PAGE_LINES || (count > 0 && page.len() + line.len() + 1 > PAGE_LENGTH) {
+            pages.push(page);
+            page = header.to_owned();
+            count = 0;
+        }
+        if !page.is_empty() {
+            page.push('\n');
+        }
+        page.push_str(line);
+        count += 1;
+    }
+    pages.push(page);
+    pages
+}
+
+// Sends `menu` in the channel of `msg`, then listens for its reactions.
+pub fn open(ctx: &Context, msg: &Message, menu: Menu) -> Result<(), String> {
+    let sent = msg.channel_id.say(&menu.text()).map_err(|e| format!("{:?}", e))?;
+    let reactions = menu.reactions();
+    if reactions.is_empty() {
+        return Ok(());
+    }
+    {
+        let mut open = OPEN.lock().unwrap();
+        let at = now();
+        open.retain(|_, m| !m.expired(at));
+        open.insert(sent.id.0, menu);
+    }
+    for reaction in reactions {
+        let _ = ctx.create_reaction(sent.id, reaction.to_owned());
+    }
+    Ok(())
+}
+
+// Handles a reaction added or removed, removing one counts too so a menu can
+// be driven without the bot managing messages.
+pub fn on_reaction(ctx: &Context, reaction: &Reaction) {
+    let emoji = match reaction.emoji {
+        ReactionType::Unicode(ref emoji) => emoji.clone(),
+        _ => return,
+    };
+    let at = now();
+    let outcome = {
+        let mut open = OPEN.lock().unwrap();
+        open.retain(|_, m| !m.expired(at));
+        let outcome = match open.get_mut(&reaction.message_id.0) {
+            Some(menu) => menu.react(reaction.user_id.0, &emoji, at),
+            None => return,
+        };
+        match outcome {
+            Outcome::Confirm(_) | Outcome::Close(_) => {
+                open.remove(&reaction.message_id.0);
+            }
+            _ => {}
+        }
+        outcome
+    };
+
+    let text = match outcome {
+        Outcome::Ignored | Outcome::Close(None) => return,
+        Outcome::Show(text) | Outcome::Close(Some(text)) => text,
+        Outcome::Confirm(action) => match storage::get(ctx).and_then(|store| action(&*store)) {
+            Ok(text) | Err(text) => text,
+        },
+    };
+    let _ = ctx.edit_message(reaction.message_id, &text, |e| e);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testing;
+
+    fn sample() -> Menu {
+        let mut menu = Menu::pages(1, "en", vec!["a".to_owned(), "b".to_owned(), "c".to_owned()]);
+        menu.touched = 1000;
+        menu
+    }
+
+    #[test]
+    fn only_the_owner_drives_a_menu() -> Result<(), String> {
+        let mut menu = sample();
+        ensure!(match menu.react(2, NEXT, 1001) { Outcome::Ignored => true, _ => false }, "someone else turned the page");
+        ensure!(menu.page == 0 && menu.touched == 1000, "someone else's reaction counted");
+        ensure!(match menu.react(1, NEXT, 1001) { Outcome::Show(_) => true, _ => false }, "the owner couldn't turn the page");
+        Ok(())
+    }
+
+    #[test]
+    fn pages_turn_and_stop_at_the_ends() -> Result<(), String> {
+        let mut menu = sample();
+        ensure!(menu.reactions() == vec![PREVIOUS, NEXT], "a list has {:?}", menu.reactions());
+        ensure!(match menu.react(1, PREVIOUS, 1001) { Outcome::Ignored => true, _ => false }, "went before the first page");
+        for _ in 0..5 {
+            menu.react(1, NEXT, 1002);
+        }
+        ensure!(menu.page == 2, "on page {} of 3", menu.page + 1);
+        ensure!(menu.text() == "c\nPage 3/3", "the last page reads {:?}", menu.text());
+        ensure!(match menu.react(1, PREVIOUS, 1003) { Outcome::Show(ref t) => t == "b\nPage 2/3", _ => false }, "didn't go back");
+        ensure!(Menu::pages(1, "en", vec!["a".to_owned()]).reactions().is_empty(), "a single page has reactions");
+        menu.lang = "fr";
+        ensure!(menu.text() == "b\nPage 2 sur 3", "the French page reads {:?}", menu.text());
+        Ok(())
+    }
+
+    #[test]
+    fn menus_expire_without_reactions() -> Result<(), String> {
+        let mut menu = sample();
+        menu.react(1, NEXT, 1000 + TIMEOUT - 1);
+        ensure!(!menu.expired(1000 + TIMEOUT), "a reaction didn't keep the menu open");
+        ensure!(match menu.react(1, NEXT, 1000 + 2 * TIMEOUT) { Outcome::Ignored => true, _ => false }, "an expired menu turned");
+        ensure!(menu.page == 1, "an expired menu is on page {}", menu.page + 1);
+        Ok(())
+    }
+
+    #[test]
+    fn confirming_runs_the_action_once() -> Result<(), String> {
+        let store = testing::store();
+        let mut menu = Menu::confirm(1, "en", "Sure?".to_owned(), |_| Ok("Done.".to_owned()));
+        menu.touched = 1000;
+        ensure!(menu.reactions() == vec![CONFIRM, CANCEL], "a question has {:?}", menu.reactions());
+        let done = match menu.react(1, CONFIRM, 1001) {
+            Outcome::Confirm(action) => action(&store)?,
+            _ => return Err("✅ didn't confirm".to_owned()),
+        };
+        ensure!(done == "Done.", "the action said {:?}", done);
+        ensure!(match menu.react(1, CONFIRM, 1002) { Outcome::Ignored => true, _ => false }, "confirmed twice");
+
+        let mut menu = Menu::confirm(1, "en", "Sure?".to_owned(), |_| Err("ran".to_owned()));
+        menu.touched = 1000;
+        ensure!(match menu.react(1, CANCEL, 1001) { Outcome::Close(Some(ref t)) => t == "Never mind then.", _ => false },
+                "❌ didn't call it off");
+        Ok(())
+    }
+
+    #[test]
+    fn long_lists_are_cut_in_pages() -> Result<(), String> {
+        let lines: Vec<String> = (0..40).map(|i| format!("line {}", i)).collect();
+        let pages = paginate("**List**", &lines);
+        ensure!(pages.len() == 3, "40 lines gave {} pages", pages.len());
+        ensure!(pages.iter().all(|p| p.starts_with("**List**\n")), "a page doesn't start with the header");
+        ensure!(pages[2].lines().count() == 11, "the last page has {} lines", pages[2].lines().count());
+
+        let long: Vec<String> = (0..10).map(|_| ::std::iter::repeat('x').take(500).collect()).collect();
+        let pages = paginate("", &long);
+        ensure!(pages.iter().all(|p| p.len() <= PAGE_LENGTH), "a page is over {} characters", PAGE_LENGTH);
+        ensure!(pages.len() == 4 && !pages[0].starts_with('\n'), "long lines gave {} pages", pages.len());
+        Ok(())
+    }
+}
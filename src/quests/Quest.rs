@@ -0,0 +1,213 @@
+use serde_json;
+
+use deus::Type::DeusType;
+use items::Item;
+
+#[derive(Clone, Copy, Debug, PartialEq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Period {
+    Daily,
+    Weekly,
+}
+
+pub const PERIODS: &'static [Period] = &[Period::Daily, Period::Weekly];
+
+// What a quest asks for, counted from game events, see quests::credits.
+#[derive(Clone, Debug, PartialEq, Deserialize)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum Objective {
+    // Battles against other players, rated or in tournaments. Wild, guardian
+    // and raid battles don't count.
+    WinBattles { count: i32 },
+    PlayBattles { count: i32 },
+    // Deus caught with a trap, of one type when `kind` is set.
+    Capture {
+        count: i32,
+        #[serde(default, rename = "type")]
+        kind: Option<DeusType>,
+    },
+    Evolve { count: i32 },
+    SpendVults { count: i32 },
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ItemReward {
+    pub item: String,
+    pub count: i32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct Reward {
+    #[serde(default)]
+    pub vults: i32,
+    #[serde(default)]
+    pub items: Vec<ItemReward>,
+}
+
+// A quest as written in data/quests.json.
+#[derive(Debug, Deserialize)]
+pub struct Quest {
+    pub id: String,
+    pub name: String,
+    pub period: Period,
+    pub objective: Objective,
+    pub reward: Reward,
+}
+
+// Where a user is on one quest in one period, see quests::period_of.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Progress {
+    pub user: i32,
+    pub quest: String,
+    pub period: i32,
+    pub progress: i32,
+    // Set with the reward, once progress reaches the goal.
+    pub completed: bool,
+}
+
+impl Period {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Period::Daily => "daily",
+            Period::Weekly => "weekly",
+        }
+    }
+}
+
+impl Objective {
+    pub fn goal(&self) -> i32 {
+        match *self {
+            Objective::WinBattles { count } |
+            Objective::PlayBattles { count } |
+            Objective::Capture { count, .. } |
+            Objective::Evolve { count } |
+            Objective::SpendVults { count } => count,
+        }
+    }
+
+    pub fn describe(&self, lang: &str) -> String {
+        match *self {
+            Objective::WinBattles { count } => tr!(lang, "objective-win-battles", count = count),
+            Objective::PlayBattles { count } => tr!(lang, "objective-play-battles", count = count),
+            Objective::Capture { count, kind: Some(kind) } => tr!(lang, "objective-capture-kind", count = count, kind = kind.name()),
+            Objective::Capture { count, kind: None } => tr!(lang, "objective-capture", count = count),
+            Objective::Evolve { count } => tr!(lang, "objective-evolve", count = count),
+            Objective::SpendVults { count } => tr!(lang, "objective-spend-vults", count = count),
+        }
+    }
+}
+
+impl Quest {
+    // Its name in `lang`, see data/locales/.
+    pub fn title(&self, lang: &str) -> String {
+        tr!(lang, &format!("quest-{}", self.id))
+    }
+
+    // The ledger reason of its vults.
+    pub fn reward_reason(&self) -> String {
+        format!("{} quest {}", self.period.name(), self.name)
+    }
+}
+
+impl Reward {
+    // "60 vults and 1 Potion"
+    pub fn describe(&self, lang: &str) -> String {
+        let mut parts = Vec::new();
+        if self.vults > 0 {
+            parts.push(tr!(lang, "vults", count = self.vults));
+        }
+        parts.extend(self.items.iter().map(|i| format!("{} {}", i.count, Item::title(lang, &i.item))));
+        match parts.pop() {
+            Some(last) if !parts.is_empty() => format!("{} {} {}", parts.join(", "), tr!(lang, "and"), last),
+            Some(last) => last,
+            None => tr!(lang, "nothing"),
+        }
+    }
+}
+
+impl Progress {
+    pub fn new(user: i32, quest: &str, period: i32) -> Progress {
+        Progress {
+            user: user,
+            quest: quest.to_owned(),
+            period: period,
+            progress: 0,
+            completed: false,
+        }
+    }
+}
+
+lazy_static! {
+    static ref CATALOG: Vec<Quest> = serde_json::from_str(include_str!("../../data/quests.json"))
+        .expect("data/quests.json");
+}
+
+pub fn catalog() -> &'static [Quest] {
+    &CATALOG
+}
+
+#[cfg(test)]
+pub fn find(id: &str) -> Option<&'static Quest> {
+    catalog().iter().find(|q| q.id == id)
+}
+
+// Catalog consistency.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use i18n;
+    use items;
+
+    #[test]
+    fn quest_ids_are_unique() -> Result<(), String> {
+        for (i, q) in catalog().iter().enumerate() {
+            if catalog()[..i].iter().any(|o| o.id == q.id) {
+                return Err(format!("{} is in the catalog twice", q.id));
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn quests_are_in_the_message_catalogs() -> Result<(), String> {
+        match catalog().iter().find(|q| !i18n::is_key(&format!("quest-{}", q.id))) {
+            Some(q) => Err(format!("{} has no quest-{} message", q.id, q.id)),
+            None => Ok(()),
+        }
+    }
+
+    #[test]
+    fn quests_have_goals_and_rewards() -> Result<(), String> {
+        for q in catalog() {
+            if q.objective.goal() <= 0 {
+                return Err(format!("{} has a goal of {}", q.id, q.objective.goal()));
+            }
+            if q.reward.vults < 0 || (q.reward.vults == 0 && q.reward.items.is_empty()) {
+                return Err(format!("{} rewards {}", q.id, q.reward.describe("en")));
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn rewards_are_known_items() -> Result<(), String> {
+        for q in catalog() {
+            if let Some(i) = q.reward.items.iter().find(|i| Item::find(&i.item).is_none() || i.count <= 0) {
+                return Err(format!("{} rewards {} {}", q.id, i.count, i.item));
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn captures_ask_for_a_type_that_can_be_caught() -> Result<(), String> {
+        for q in catalog() {
+            if let Objective::Capture { kind: Some(kind), .. } = q.objective {
+                if !items::wild().iter().any(|d| d.types.contains(&kind)) {
+                    return Err(format!("{} asks for {} Deus, no trap catches one", q.id, kind.name()));
+                }
+            }
+        }
+        Ok(())
+    }
+}
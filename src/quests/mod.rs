@@ -0,0 +1,172 @@
+pub mod Quest;
+
+use rand::{Rng, SeedableRng, XorShiftRng};
+
+use deus::Deus;
+use events::Event;
+use storage::Storage;
+use self::Quest::{Objective, Period, Quest as CatalogQuest, PERIODS};
+
+const DAY: i64 = 86400;
+// Cells of a progress bar.
+const BAR_LENGTH: i32 = 10;
+
+// Quests up at once.
+pub fn count(period: Period) -> usize {
+    match period {
+        Period::Daily => 3,
+        Period::Weekly => 2,
+    }
+}
+
+// Days since the epoch, or weeks starting on Mondays: 1970-01-01 was a
+// Thursday.
+pub fn period_of(period: Period, at: i64) -> i32 {
+    match period {
+        Period::Daily => (at / DAY) as i32,
+        Period::Weekly => ((at / DAY + 3) / 7) as i32,
+    }
+}
+
+// When the quests of a period rotate out.
+pub fn period_end(period: Period, number: i32) -> i64 {
+    match period {
+        Period::Daily => (number as i64 + 1) * DAY,
+        Period::Weekly => ((number as i64 + 1) * 7 - 3) * DAY,
+    }
+}
+
+// The quests of one period, the same for everyone, in catalog order.
+pub fn rotation(period: Period, number: i32) -> Vec<&'static CatalogQuest> {
+    let mut pool: Vec<&CatalogQuest> = Quest::catalog().iter().filter(|q| q.period == period).collect();
+    let salt = match period {
+        Period::Daily => 0x9E37_79B9,
+        Period::Weekly => 0x85EB_CA6B,
+    };
+    let mut rng = XorShiftRng::from_seed([number as u32, salt, number as u32 ^ salt, 1]);
+    rng.shuffle(&mut pool);
+    pool.truncate(count(period));
+    pool.sort_by_key(|q| Quest::catalog().iter().position(|c| c.id == q.id));
+    pool
+}
+
+// How much an event counts towards an objective, as (user, amount).
+pub fn credits(objective: &Objective, event: &Event) -> Vec<(i32, i32)> {
+    match (objective, event) {
+        (&Objective::WinBattles { .. }, &Event::BattleFinished { winner: Some(winner), .. }) => vec![(winner, 1)],
+        (&Objective::PlayBattles { .. }, &Event::BattleFinished { players, .. }) => vec![(players[0], 1), (players[1], 1)],
+        (&Objective::Capture { kind, .. }, &Event::DeusCaptured { user, ref species, .. }) => {
+            let fits = match kind {
+                Some(kind) => Deus::find(species).map_or(false, |d| d.types.contains(&kind)),
+                None => true,
+            };
+            if fits { vec![(user, 1)] } else { Vec::new() }
+        }
+        (&Objective::Evolve { .. }, &Event::DeusEvolved { user, .. }) => vec![(user, 1)],
+        (&Objective::SpendVults { .. }, &Event::VultsSpent { user, amount }) => vec![(user, amount)],
+        _ => Vec::new(),
+    }
+}
+
+// Subscribed to game events, see events::SUBSCRIBERS. Counts an event that
+// happened at `at` towards the quests up then, returns a line per quest it
+// completed.
+pub fn on_event(store: &Storage, lang: &str, event: &Event, at: i64) -> Result<Vec<String>, String> {
+    let mut news = Vec::new();
+    for &period in PERIODS {
+        let number = period_of(period, at);
+        for quest in rotation(period, number) {
+            for (user, amount) in credits(&quest.objective, event) {
+                if store.progress_quest(user, quest, number, amount)? {
+                    news.push(tr!(lang, "quest-completed", name = store.user(user)?.name, period = period.name(),
+                                  quest = quest.title(lang), reward = quest.reward.describe(lang)));
+                }
+            }
+        }
+    }
+    Ok(news)
+}
+
+// "▰▰▰▱▱▱▱▱▱▱ 1/3"
+pub fn bar(progress: i32, goal: i32) -> String {
+    let filled = (BAR_LENGTH * progress / goal.max(1)).max(0).min(BAR_LENGTH) as usize;
+    format!("{}{} {}/{}", "▰".repeat(filled), "▱".repeat(BAR_LENGTH as usize - filled), progress, goal)
+}
+
+// Rotation and objective logic.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use deus::Type::DeusType;
+
+    fn ids(quests: &[&CatalogQuest]) -> Vec<String> {
+        quests.iter().map(|q| q.id.clone()).collect()
+    }
+
+    #[test]
+    fn rotations_hold_for_a_whole_period() -> Result<(), String> {
+        for &period in PERIODS {
+            let number = period_of(period, 1496275200);
+            let start = period_end(period, number - 1);
+            ensure!(period_of(period, start) == number && period_of(period, period_end(period, number) - 1) == number,
+                    "{} period {} runs from {} to {}", period.name(), number, start, period_end(period, number));
+            let quests = rotation(period, number);
+            ensure!(ids(&quests) == ids(&rotation(period, number)), "{} rotation moved", period.name());
+            ensure!(quests.len() == count(period), "{} {} quests up", quests.len(), period.name());
+            ensure!(quests.iter().all(|q| q.period == period), "{} rotation has {:?}", period.name(), ids(&quests));
+            let mut unique = ids(&quests);
+            unique.dedup();
+            ensure!(unique.len() == quests.len(), "{} rotation repeats {:?}", period.name(), ids(&quests));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn rotations_change_from_one_period_to_the_next() -> Result<(), String> {
+        for &period in PERIODS {
+            let mut seen: Vec<Vec<String>> = (17000..17014).map(|n| ids(&rotation(period, n))).collect();
+            seen.sort();
+            seen.dedup();
+            ensure!(seen.len() >= 4, "{} quests only had {} rotations in 14 periods", period.name(), seen.len());
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn weeks_start_on_mondays() -> Result<(), String> {
+        // Monday 2017-06-05, 00:00 UTC.
+        let monday = 1496620800;
+        let week = period_of(Period::Weekly, monday);
+        ensure!(period_of(Period::Weekly, monday - 1) == week - 1, "the week didn't change on Monday");
+        ensure!(period_end(Period::Weekly, week) == monday + 7 * DAY, "week {} ends at {}", week, period_end(Period::Weekly, week));
+        ensure!(period_end(Period::Daily, period_of(Period::Daily, monday)) == monday + DAY, "days don't end at midnight");
+        Ok(())
+    }
+
+    #[test]
+    fn events_count_for_their_objectives() -> Result<(), String> {
+        let battle = Event::BattleFinished { players: [1, 2], winner: Some(2) };
+        let draw = Event::BattleFinished { players: [1, 2], winner: None };
+        let caught = Event::DeusCaptured { user: 3, deus: 9, species: "Maris".to_owned() };
+        let water = Objective::Capture { count: 1, kind: Some(DeusType::Water) };
+        let shadow = Objective::Capture { count: 1, kind: Some(DeusType::Shadow) };
+
+        ensure!(credits(&Objective::WinBattles { count: 3 }, &battle) == vec![(2, 1)], "the loser won");
+        ensure!(credits(&Objective::WinBattles { count: 3 }, &draw).is_empty(), "a draw counted as a win");
+        ensure!(credits(&Objective::PlayBattles { count: 3 }, &draw) == vec![(1, 1), (2, 1)], "a draw wasn't played");
+        ensure!(credits(&water, &caught) == vec![(3, 1)], "Maris isn't Water");
+        ensure!(credits(&shadow, &caught).is_empty(), "Maris is Shadow");
+        ensure!(credits(&Objective::SpendVults { count: 200 }, &Event::VultsSpent { user: 4, amount: 150 }) == vec![(4, 150)],
+                "spending didn't count");
+        ensure!(credits(&Objective::Evolve { count: 1 }, &battle).is_empty(), "a battle evolved something");
+        Ok(())
+    }
+
+    #[test]
+    fn progress_bars_fill_up() -> Result<(), String> {
+        ensure!(bar(0, 3) == "▱▱▱▱▱▱▱▱▱▱ 0/3", "empty bar is {}", bar(0, 3));
+        ensure!(bar(1, 3) == "▰▰▰▱▱▱▱▱▱▱ 1/3", "a third is {}", bar(1, 3));
+        ensure!(bar(200, 200) == "▰▰▰▰▰▰▰▰▰▰ 200/200", "full bar is {}", bar(200, 200));
+        Ok(())
+    }
+}
@@ -0,0 +1,50 @@
+// A boss players attack together between `starts` and `ends`, its life shared
+// by everyone. See raids::TIERS for what they get once it falls.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Raid {
+    pub id: i32,
+    // The boss' species.
+    pub boss: String,
+    pub level: i32,
+    // What's left of it.
+    pub life: i32,
+    pub max_life: i32,
+    pub starts: i64,
+    pub ends: i64,
+    // Set once the rewards are paid, or the boss got away.
+    pub settled: bool,
+}
+
+// What one player did to a raid boss.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Damage {
+    pub raid: i32,
+    pub user: i32,
+    pub damage: i32,
+    pub attacks: i32,
+}
+
+impl Raid {
+    // Storage fills in the id.
+    pub fn new(boss: &str, level: i32, life: i32, starts: i64, ends: i64) -> Raid {
+        Raid {
+            id: 0,
+            boss: boss.to_owned(),
+            level: level,
+            life: life,
+            max_life: life,
+            starts: starts,
+            ends: ends,
+            settled: false,
+        }
+    }
+
+    pub fn defeated(&self) -> bool {
+        self.life <= 0
+    }
+
+    // Whether it can be attacked at `at`.
+    pub fn open(&self, at: i64) -> bool {
+        !self.settled && !self.defeated() && self.starts <= at && at < self.ends
+    }
+}
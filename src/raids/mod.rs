@@ -0,0 +1,188 @@
+pub mod Raid;
+
+use std::env;
+
+use rand::Rng;
+use serenity::model::ChannelId;
+
+use battle::{Battle, Fighter, Side};
+use deus::Instance::DeusInstance;
+use i18n::Message;
+use items;
+use storage::Storage;
+use self::Raid::{Damage, Raid as Boss};
+
+// Bosses show up every few hours, see scheduler::JOBS, and stay this long.
+pub const LENGTH: i64 = 2 * 3600;
+pub const BOSS_LEVEL: i32 = 50;
+pub const BOSS_STARS: i32 = 3;
+// Times the life the boss would have in a duel.
+pub const LIFE: i32 = 80;
+// Turns an attack lasts, unless the team falls first.
+pub const TURNS: i32 = 10;
+// Energy per attack.
+pub const ATTACK_COST: i32 = 20;
+// How much of the boss' life a player took, in percent, what that makes them
+// and the vults they get once it falls. Anyone who hit it is a Helper.
+pub const TIERS: &'static [(i32, &'static str, i32)] = &[(10, "Vanguard", 800), (3, "Striker", 400), (0, "Helper", 150)];
+// How far back open and ended raids are looked for.
+pub const RECENT: i64 = 10;
+
+// Where bosses are announced, from HOLLOW_RAID_CHANNEL. No bosses show up
+// without one.
+pub fn channel() -> Option<u64> {
+    env::var("HOLLOW_RAID_CHANNEL").ok().and_then(|c| c.parse().ok())
+}
+
+// The raid that can be attacked at `at`, if there's one.
+pub fn current(store: &Storage, at: i64) -> Result<Option<Boss>, String> {
+    Ok(store.raids(RECENT)?.into_iter().find(|r| r.open(at)))
+}
+
+// The boss as it fights, with what's left of its life.
+pub fn boss(raid: &Boss) -> Result<Fighter, Message> {
+    let mut deus = DeusInstance::new(0, &raid.boss, BOSS_STARS);
+    deus.exp = 10 * raid.level * raid.level;
+    let mut fighter = Fighter::new(&deus)?;
+    fighter.life = raid.life;
+    Ok(fighter)
+}
+
+// Spawns a wild boss at `at`, unless one is still up. Errors are in `lang`.
+pub fn spawn<R: Rng>(store: &Storage, lang: &str, rng: &mut R, at: i64) -> Result<Option<Boss>, String> {
+    if current(store, at)?.is_some() {
+        return Ok(None);
+    }
+    let wild = items::wild();
+    let species = rng.choose(&wild).ok_or_else(|| tr!(lang, "raid-nothing-wild"))?;
+    let mut deus = DeusInstance::new(0, &species.name, BOSS_STARS);
+    deus.exp = 10 * BOSS_LEVEL * BOSS_LEVEL;
+    let life = Fighter::new(&deus).map_err(|e| e.tr(lang))?.stats.life * LIFE;
+    Ok(Some(store.create_raid(&Boss::new(&species.name, BOSS_LEVEL, life, at, at + LENGTH))?))
+}
+
+// One attack by `team` on `boss`: the damage it did, and the battle for its
+// log and the potions drunk, told in `lang`.
+pub fn strike(lang: &str, team: Side, boss: Fighter, seed: u64) -> (i32, Battle) {
+    let life = boss.life;
+    let name = tr!(lang, "raid-boss", boss = &boss.name);
+    let mut battle = Battle::new(seed, team, Side::new(&name, vec![boss]));
+    while !battle.is_over() && battle.turn < TURNS {
+        let turn = [battle.choose(0), battle.choose(1)];
+        battle.play(turn);
+    }
+    ((life - battle.sides[1].fighters[0].life).max(0), battle)
+}
+
+// The tier `damage` makes, None for no damage at all.
+pub fn tier(damage: i32, max_life: i32) -> Option<&'static (i32, &'static str, i32)> {
+    if damage <= 0 {
+        return None;
+    }
+    TIERS.iter().find(|&&(percent, _, _)| damage as i64 * 100 >= percent as i64 * max_life as i64)
+}
+
+// What each player gets out of a raid as (user, vults), nothing when the boss
+// got away.
+pub fn payouts(raid: &Boss, damage: &[Damage]) -> Vec<(i32, i32)> {
+    if !raid.defeated() {
+        return Vec::new();
+    }
+    damage.iter().filter_map(|d| tier(d.damage, raid.max_life).map(|t| (d.user, t.2))).collect()
+}
+
+// Pays out a raid that's over and returns its results, None when someone
+// settled it first.
+pub fn settle(store: &Storage, lang: &str, raid: &Boss) -> Result<Option<String>, String> {
+    let damage = store.raid_damage(raid.id)?;
+    let paid = payouts(raid, &damage);
+    if !store.settle_raid(raid.id, &paid, &format!("raid on {}", raid.boss))? {
+        return Ok(None);
+    }
+
+    let mut lines = vec![if raid.defeated() {
+        tr!(lang, "raid-fallen", boss = &raid.boss, players = paid.len())
+    } else {
+        tr!(lang, "raid-escaped", boss = &raid.boss, life = raid.life)
+    }];
+    lines.extend(standings(store, lang, raid, &damage)?);
+    Ok(Some(lines.join("\n")))
+}
+
+// "1. Ann, 1520 damage in 3 attacks, Vanguard"
+pub fn standings(store: &Storage, lang: &str, raid: &Boss, damage: &[Damage]) -> Result<Vec<String>, String> {
+    let mut lines = Vec::new();
+    for (i, d) in damage.iter().enumerate() {
+        let tier = tier(d.damage, raid.max_life).map_or("", |t| t.1);
+        lines.push(tr!(lang, "raid-standing", place = i + 1, name = store.user(d.user)?.name, damage = d.damage,
+                       attacks = d.attacks, tier = tier));
+    }
+    Ok(lines)
+}
+
+// Posts `text` in the raid channel, if there's one.
+pub fn announce(text: &str) -> Result<(), String> {
+    match channel() {
+        Some(channel) => ChannelId(channel).say(text).map(|_| ()).map_err(|e| format!("couldn't announce the raid: {:?}", e)),
+        None => Ok(()),
+    }
+}
+
+// Bosses and rewards.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testing::{self, NOON};
+
+    fn damage(user: i32, damage: i32) -> Damage {
+        Damage { raid: 1, user: user, damage: damage, attacks: 1 }
+    }
+
+    #[test]
+    fn tiers_follow_the_share_of_the_boss_life() -> Result<(), String> {
+        ensure!(TIERS.windows(2).all(|w| w[0].0 > w[1].0 && w[0].2 > w[1].2), "tiers aren't in order: {:?}", TIERS);
+        ensure!(TIERS.last().map(|t| t.0) == Some(0), "some hits don't make a tier");
+        ensure!(tier(100, 1000).map(|t| t.1) == Some("Vanguard"), "a tenth isn't Vanguard");
+        ensure!(tier(99, 1000).map(|t| t.1) == Some("Striker"), "just under a tenth is {:?}", tier(99, 1000));
+        ensure!(tier(1, 1000).map(|t| t.1) == Some("Helper"), "a scratch is {:?}", tier(1, 1000));
+        ensure!(tier(0, 1000).is_none(), "no damage made a tier");
+        Ok(())
+    }
+
+    #[test]
+    fn rewards_only_go_out_once_the_boss_falls() -> Result<(), String> {
+        let mut raid = Boss::new("Tempest", BOSS_LEVEL, 1000, 0, LENGTH);
+        let hits = [damage(1, 600), damage(2, 50), damage(3, 0)];
+        ensure!(payouts(&raid, &hits).is_empty(), "a boss that's still up paid out");
+        raid.life = 0;
+        ensure!(payouts(&raid, &hits) == vec![(1, 800), (2, 400)], "paid {:?}", payouts(&raid, &hits));
+        Ok(())
+    }
+
+    #[test]
+    fn attacks_last_a_few_turns() -> Result<(), String> {
+        let raid = Boss::new("Tempest", BOSS_LEVEL, 1000000, 0, LENGTH);
+        let team = Fighter::new(&DeusInstance::new(1, "Lux", 1)).map_err(|e| e.tr("en"))?;
+        let (dealt, battle) = strike("en", Side::new("a", vec![team]), boss(&raid).map_err(|e| e.tr("en"))?, 7);
+        ensure!(battle.turn <= TURNS, "the attack lasted {} turns", battle.turn);
+        ensure!(dealt >= 0 && dealt < raid.life, "the attack dealt {}", dealt);
+
+        let strong = Fighter::new(&DeusInstance { exp: 10 * 60 * 60, ..DeusInstance::new(1, "Lux", 5) }).map_err(|e| e.tr("en"))?;
+        let (dealt, _) = strike("en", Side::new("a", vec![strong]), boss(&raid).map_err(|e| e.tr("en"))?, 7);
+        ensure!(dealt > 0, "a level 60 Lux didn't scratch the boss");
+        Ok(())
+    }
+
+    #[test]
+    fn one_boss_is_up_at_a_time() -> Result<(), String> {
+        use rand::thread_rng;
+
+        let store = testing::store();
+        let raid = spawn(&store, "en", &mut thread_rng(), NOON)?.ok_or("no boss showed up")?;
+        ensure!(raid.level == BOSS_LEVEL && raid.life == raid.max_life && raid.life > 0, "spawned {:?}", raid);
+        ensure!(items::wild().iter().any(|d| d.name == raid.boss), "{} isn't wild", raid.boss);
+        ensure!(spawn(&store, "en", &mut thread_rng(), NOON + 60)?.is_none(), "two bosses were up at once");
+        ensure!(spawn(&store, "en", &mut thread_rng(), NOON + LENGTH)?.is_some(), "no boss showed up after the last one left");
+        Ok(())
+    }
+}
@@ -0,0 +1,120 @@
+use render::font;
+use render::png;
+use render::sprite::Sprite;
+
+pub type Rgb = [u8; 3];
+
+// An RGB image drawn into in memory, then written as a PNG. Drawing outside
+// of it is clipped.
+pub struct Canvas {
+    pub width: i32,
+    pub height: i32,
+    pixels: Vec<u8>,
+}
+
+// A five pointed star, 7x7.
+const STAR: [&'static str; 7] = [
+    "...#...",
+    "...#...",
+    "#######",
+    ".#####.",
+    "..###..",
+    ".##.##.",
+    "#.....#",
+];
+
+impl Canvas {
+    pub fn new(width: i32, height: i32, background: Rgb) -> Canvas {
+        let mut pixels = Vec::with_capacity((width * height * 3) as usize);
+        for _ in 0..width * height {
+            pixels.extend_from_slice(&background);
+        }
+        Canvas {
+            width: width,
+            height: height,
+            pixels: pixels,
+        }
+    }
+
+    pub fn set(&mut self, x: i32, y: i32, color: Rgb) {
+        if x < 0 || y < 0 || x >= self.width || y >= self.height {
+            return;
+        }
+        let at = ((y * self.width + x) * 3) as usize;
+        self.pixels[at..at + 3].copy_from_slice(&color);
+    }
+
+    pub fn fill(&mut self, x: i32, y: i32, width: i32, height: i32, color: Rgb) {
+        for row in y..y + height {
+            for column in x..x + width {
+                self.set(column, row, color);
+            }
+        }
+    }
+
+    // A frame `thickness` pixels wide, inside the rectangle.
+    pub fn outline(&mut self, x: i32, y: i32, width: i32, height: i32, thickness: i32, color: Rgb) {
+        self.fill(x, y, width, thickness, color);
+        self.fill(x, y + height - thickness, width, thickness, color);
+        self.fill(x, y, thickness, height, color);
+        self.fill(x + width - thickness, y, thickness, height, color);
+    }
+
+    // `text` with its top left corner at x, y, each font pixel drawn as a
+    // `scale` square. Returns where the text ends.
+    pub fn text(&mut self, x: i32, y: i32, text: &str, scale: i32, color: Rgb) -> i32 {
+        let mut left = x;
+        for c in text.chars() {
+            let columns = font::glyph(c);
+            for (i, column) in columns.iter().enumerate() {
+                for row in 0..font::HEIGHT {
+                    if column >> row & 1 == 1 {
+                        self.fill(left + i as i32 * scale, y + row * scale, scale, scale, color);
+                    }
+                }
+            }
+            left += (font::WIDTH + font::SPACING) * scale;
+        }
+        left - font::SPACING * scale
+    }
+
+    // A bar filled in proportion to `value` out of `max`.
+    pub fn bar(&mut self, x: i32, y: i32, width: i32, height: i32, value: i32, max: i32, color: Rgb, empty: Rgb) {
+        self.fill(x, y, width, height, empty);
+        let filled = if max <= 0 { 0 } else { (value.max(0).min(max) as i64 * width as i64 / max as i64) as i32 };
+        // Anything left shows, however little.
+        let filled = if value > 0 && filled == 0 { 1 } else { filled };
+        self.fill(x, y, filled, height, color);
+    }
+
+    pub fn star(&mut self, x: i32, y: i32, color: Rgb) {
+        for (row, line) in STAR.iter().enumerate() {
+            for (column, c) in line.chars().enumerate() {
+                if c == '#' {
+                    self.set(x + column as i32, y + row as i32, color);
+                }
+            }
+        }
+    }
+
+    // `sprite` with each of its pixels drawn as a `scale` square, `palette`
+    // giving the colours of its two type slots, outline and eyes.
+    pub fn sprite(&mut self, x: i32, y: i32, sprite: &Sprite, scale: i32, palette: [Rgb; 4]) {
+        for (row, line) in sprite.rows.iter().enumerate() {
+            for (column, c) in line.chars().enumerate() {
+                let color = match c {
+                    '1' => palette[0],
+                    '2' => palette[1],
+                    '#' => palette[2],
+                    'o' => palette[3],
+                    _ => continue,
+                };
+                self.fill(x + column as i32 * scale, y + row as i32 * scale, scale, scale, color);
+            }
+        }
+    }
+
+    pub fn png(&self) -> Vec<u8> {
+        png::encode(self.width as u32, self.height as u32, &self.pixels)
+    }
+}
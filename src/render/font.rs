@@ -0,0 +1,42 @@
+// The 5x7 bitmap font in data/render/font.txt, the only one cards use so
+// rendering doesn't depend on what's installed.
+
+use std::collections::BTreeMap;
+
+pub const WIDTH: i32 = 5;
+pub const HEIGHT: i32 = 7;
+// Between two characters.
+pub const SPACING: i32 = 1;
+
+lazy_static! {
+    static ref GLYPHS: BTreeMap<char, [u8; 5]> = parse(include_str!("../../data/render/font.txt"));
+}
+
+fn parse(text: &str) -> BTreeMap<char, [u8; 5]> {
+    let mut glyphs = BTreeMap::new();
+    for line in text.lines().filter(|l| !l.is_empty() && !l.starts_with('#')) {
+        let mut fields = line.split_whitespace();
+        // The code point can be wider than the column bytes, é or œ.
+        let code = fields.next().and_then(|f| u32::from_str_radix(f, 16).ok()).expect("data/render/font.txt");
+        let c = ::std::char::from_u32(code).expect("data/render/font.txt");
+        let bytes: Vec<u8> = fields.map(|b| u8::from_str_radix(b, 16).expect("data/render/font.txt")).collect();
+        glyphs.insert(c, [bytes[0], bytes[1], bytes[2], bytes[3], bytes[4]]);
+    }
+    glyphs
+}
+
+// The columns of `c`, a question mark for characters the font doesn't have.
+pub fn glyph(c: char) -> [u8; 5] {
+    GLYPHS.get(&c).or_else(|| GLYPHS.get(&'?')).cloned().unwrap_or([0; 5])
+}
+
+#[cfg(test)]
+pub fn has(c: char) -> bool {
+    GLYPHS.contains_key(&c)
+}
+
+// Width of `text` in pixels at `scale`.
+pub fn width(text: &str, scale: i32) -> i32 {
+    let count = text.chars().count() as i32;
+    if count == 0 { 0 } else { (count * (WIDTH + SPACING) - SPACING) * scale }
+}
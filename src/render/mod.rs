@@ -0,0 +1,325 @@
+pub mod Canvas;
+pub mod font;
+pub mod png;
+pub mod sprite;
+
+use std::env;
+use std::fs::{self, File};
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+
+use battle::{Battle, Side};
+use deus::Deus;
+use deus::Instance::DeusInstance;
+use deus::Stats::{self, MAX_TRAINING, MAX_VARIANCE, STATS};
+use deus::Type::DeusType;
+use self::Canvas::{Canvas as Image, Rgb};
+
+// Part of every cache key, bump it when the layout changes so cards drawn
+// the old way aren't served again.
+pub const VERSION: u32 = 1;
+
+pub const CARD_WIDTH: i32 = 320;
+pub const CARD_HEIGHT: i32 = 180;
+pub const SNAPSHOT_WIDTH: i32 = 320;
+const ROW: i32 = 34;
+
+const BACKGROUND: Rgb = [0x1E, 0x1E, 0x28];
+const PANEL: Rgb = [0x2A, 0x2A, 0x38];
+const INK: Rgb = [0xF0, 0xF0, 0xF0];
+const MUTED: Rgb = [0x9A, 0x9A, 0xA8];
+const DARK: Rgb = [0x14, 0x14, 0x1A];
+const GOLD: Rgb = [0xFF, 0xC8, 0x3C];
+const EMPTY: Rgb = [0x44, 0x44, 0x50];
+const HEALTHY: Rgb = [0x4C, 0xC3, 0x5A];
+const HURT: Rgb = [0xF0, 0xC0, 0x30];
+const CRITICAL: Rgb = [0xE0, 0x40, 0x40];
+const FAINTED: Rgb = [0x60, 0x60, 0x68];
+
+pub fn color(t: DeusType) -> Rgb {
+    match t {
+        DeusType::Fire => [0xE2, 0x58, 0x22],
+        DeusType::Water => [0x3B, 0x82, 0xF6],
+        DeusType::Earth => [0xA0, 0x52, 0x2D],
+        DeusType::Wind => [0x7F, 0xD1, 0xAE],
+        DeusType::Storm => [0xF4, 0xC4, 0x30],
+        DeusType::Light => [0xFF, 0xE6, 0x80],
+        DeusType::Shadow => [0x6B, 0x3F, 0xA0],
+    }
+}
+
+fn darker(c: Rgb) -> Rgb {
+    [(c[0] as u32 * 3 / 4) as u8, (c[1] as u32 * 3 / 4) as u8, (c[2] as u32 * 3 / 4) as u8]
+}
+
+// The sprite colours of a species, a single type is shaded with a darker
+// tone of its colour.
+fn palette(types: &[DeusType]) -> [Rgb; 4] {
+    let first = types.get(0).map(|&t| color(t)).unwrap_or(MUTED);
+    let second = types.get(1).map(|&t| color(t)).unwrap_or(darker(first));
+    [first, second, DARK, INK]
+}
+
+// Where rendered images are kept, from HOLLOW_CARD_CACHE.
+pub fn cache_dir() -> PathBuf {
+    PathBuf::from(env::var("HOLLOW_CARD_CACHE").unwrap_or_else(|_| "cards".to_owned()))
+}
+
+// FNV-1a, 64 bits.
+pub fn hash(bytes: &[u8]) -> u64 {
+    bytes.iter().fold(0xCBF2_9CE4_8422_2325, |h, &b| (h ^ b as u64).wrapping_mul(0x0000_0100_0000_01B3))
+}
+
+// The PNG of what `key` describes, read from the cache in `dir` when it was
+// drawn before. `key` holds everything the drawing depends on, so the same
+// content is never drawn twice. A cache that can't be written to only means
+// drawing again next time.
+pub fn cached<F>(dir: &Path, kind: &str, key: &str, draw: F) -> Vec<u8>
+    where F: FnOnce() -> Image
+{
+    let path = dir.join(format!("{}-{:016x}.png", kind, hash(format!("{}:{}", VERSION, key).as_bytes())));
+    let mut png = Vec::new();
+    if File::open(&path).and_then(|mut f| f.read_to_end(&mut png)).is_ok() && !png.is_empty() {
+        return png;
+    }
+    let png = draw().png();
+    // Written aside then renamed, so a half written file is never read.
+    let partial = path.with_extension("part");
+    let _ = fs::create_dir_all(dir)
+        .and_then(|_| File::create(&partial))
+        .and_then(|mut f| f.write_all(&png))
+        .and_then(|_| fs::rename(&partial, &path));
+    png
+}
+
+// A card for `deus`: its artwork, name, level, stars, types and stats,
+// labelled in `lang`.
+pub fn deus_card(lang: &str, deus: &DeusInstance) -> Result<Vec<u8>, String> {
+    let species = Deus::find(&deus.deus).ok_or_else(|| tr!(lang, "not-in-catalog", deus = &deus.deus))?;
+    let stats = Stats::compute(species, deus);
+    let key = format!("{}|{}|{}|{}|{}|{}|{:?}", lang, species.name, deus.id, deus.level(), deus.stars, deus.nature, stats);
+    Ok(cached(&cache_dir(), "deus", &key, || draw_card(lang, species, deus, &stats)))
+}
+
+pub fn draw_card(lang: &str, species: &Deus::Deus, deus: &DeusInstance, stats: &Stats::Stats) -> Image {
+    let mut card = Image::new(CARD_WIDTH, CARD_HEIGHT, BACKGROUND);
+    let colors = palette(&species.types);
+    card.outline(0, 0, CARD_WIDTH, CARD_HEIGHT, 3, colors[0]);
+
+    card.fill(12, 12, 156, 156, PANEL);
+    if let Some(sprite) = sprite::find(&species.name) {
+        card.sprite(18, 18, sprite, 12, colors);
+    }
+
+    let x = 180;
+    let scale = if font::width(&species.name, 2) <= CARD_WIDTH - x - 8 { 2 } else { 1 };
+    card.text(x, 14, &species.name, scale, INK);
+    card.text(x, 34, &format!("{}  #{}", tr!(lang, "render-level", level = deus.level()), deus.id), 1, MUTED);
+    for i in 0..5 {
+        card.star(x + i * 9, 46, if i < deus.stars { GOLD } else { EMPTY });
+    }
+
+    let mut left = x;
+    for &t in &species.types {
+        let width = font::width(t.name(), 1) + 6;
+        card.fill(left, 60, width, 11, color(t));
+        card.text(left + 3, 62, t.name(), 1, DARK);
+        left += width + 4;
+    }
+
+    let nature = deus.nature();
+    for (i, &stat) in STATS.iter().enumerate() {
+        let y = 80 + i as i32 * 16;
+        let value = stats.get(stat);
+        // Bars are out of what a Deus built for the stat would have at this
+        // level and stars.
+        let best = Stats::value(stat, 150, MAX_VARIANCE, MAX_TRAINING, deus.level(), deus.stars, 110);
+        card.text(x, y, stat.name(), 1, MUTED);
+        card.bar(x + 34, y, 64, 7, value, best, colors[0], EMPTY);
+        card.text(x + 102, y, &value.to_string(), 1, INK);
+    }
+    card.text(x, 162, nature.name, 1, MUTED);
+    card
+}
+
+// Both teams of `battle` as they are now, with their Life and the fighters
+// out marked, labelled in `lang`.
+pub fn battle_snapshot(lang: &str, battle: &Battle) -> Vec<u8> {
+    let mut key = format!("{}|{}", lang, battle.turn);
+    for side in &battle.sides {
+        key.push_str(&format!("|{}:{}", side.name, side.active));
+        for f in &side.fighters {
+            key.push_str(&format!(";{}:{}:{}:{}:{:?}", f.name, f.level, f.life, f.stats.life, f.types));
+        }
+    }
+    cached(&cache_dir(), "battle", &key, || draw_battle(lang, battle))
+}
+
+pub fn draw_battle(lang: &str, battle: &Battle) -> Image {
+    let rows = battle.sides.iter().map(|s| s.fighters.len()).max().unwrap_or(0) as i32;
+    let mut image = Image::new(SNAPSHOT_WIDTH, 34 + rows * ROW + 4, BACKGROUND);
+    let title = tr!(lang, "render-turn", turn = battle.turn);
+    image.text((SNAPSHOT_WIDTH - font::width(&title, 1)) / 2, 6, &title, 1, INK);
+    for (i, side) in battle.sides.iter().enumerate() {
+        draw_side(lang, &mut image, 4 + i as i32 * 160, side);
+    }
+    image
+}
+
+fn draw_side(lang: &str, image: &mut Image, x: i32, side: &Side) {
+    image.text(x, 20, &side.name, 1, MUTED);
+    for (i, f) in side.fighters.iter().enumerate() {
+        let y = 34 + i as i32 * ROW;
+        image.fill(x, y, 152, ROW - 2, PANEL);
+        if i == side.active && f.alive() {
+            image.outline(x, y, 152, ROW - 2, 1, GOLD);
+        }
+        let colors = if f.alive() { palette(&f.types) } else { [FAINTED, FAINTED, DARK, DARK] };
+        if let Some(sprite) = sprite::find(&f.name) {
+            image.sprite(x + 2, y + 4, sprite, 2, colors);
+        }
+        let ink = if f.alive() { INK } else { FAINTED };
+        image.text(x + 30, y + 3, &f.name, 1, ink);
+        let level = tr!(lang, "render-level", level = f.level);
+        image.text(x + 148 - font::width(&level, 1), y + 3, &level, 1, MUTED);
+        let percent = if f.stats.life > 0 { f.life * 100 / f.stats.life } else { 0 };
+        let health = if percent > 50 { HEALTHY } else if percent > 20 { HURT } else { CRITICAL };
+        image.bar(x + 30, y + 13, 116, 5, f.life, f.stats.life, health, EMPTY);
+        image.text(x + 30, y + 21, &format!("{}/{}", f.life.max(0), f.stats.life), 1, ink);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use deus::Nature::NATURES;
+    use i18n;
+
+    #[test]
+    fn png_checksums() -> Result<(), String> {
+        ensure!(png::crc32(b"IEND") == 0xAE42_6082, "crc32 of IEND is {:08x}", png::crc32(b"IEND"));
+        ensure!(png::adler32(b"Wikipedia") == 0x11E6_0398, "adler32 of Wikipedia is {:08x}", png::adler32(b"Wikipedia"));
+        let image = Image::new(3, 2, INK).png();
+        ensure!(image.starts_with(&[0x89, b'P', b'N', b'G']), "no PNG signature");
+        ensure!(image.ends_with(&[0xAE, 0x42, 0x60, 0x82]), "no IEND chunk at the end");
+        Ok(())
+    }
+
+    #[test]
+    fn the_font_covers_every_name() -> Result<(), String> {
+        // Printable ASCII and Latin-1, side names are Discord names.
+        for c in (0x20u32..0x7F).chain(0xA0..0x100).filter_map(::std::char::from_u32) {
+            ensure!(font::has(c), "{:?} has no glyph", c);
+        }
+        for species in Deus::catalog() {
+            for c in species.name.chars().chain(species.types.iter().flat_map(|t| t.name().chars())) {
+                ensure!(font::has(c), "{} can't be written, {:?} has no glyph", species.name, c);
+            }
+        }
+        for label in STATS.iter().map(|s| s.name()).chain(NATURES.iter().map(|n| n.name)) {
+            ensure!(label.chars().all(font::has), "{:?} can't be written", label);
+        }
+        for &(lang, _) in i18n::LOCALES {
+            let labels = [
+                tr!(lang, "render-level", level = 100),
+                tr!(lang, "render-turn", turn = 100),
+                tr!(lang, "battle-wild-side"),
+                tr!(lang, "server-guardians", guild = "Les Œuvres d’Élodie"),
+                tr!(lang, "raid-boss", boss = "Ignis"),
+            ];
+            for label in &labels {
+                ensure!(label.chars().all(font::has), "{:?} in {} can't be written", label, lang);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn every_species_has_a_sprite() -> Result<(), String> {
+        for species in Deus::catalog() {
+            let sprite = sprite::find(&species.name).ok_or_else(|| format!("{} has no sprite", species.name))?;
+            ensure!(sprite.rows.len() == sprite::SIZE, "{} has {} rows", species.name, sprite.rows.len());
+            for row in &sprite.rows {
+                ensure!(row.chars().count() == sprite::SIZE, "{} has a row of {}", species.name, row.chars().count());
+                ensure!(row.chars().all(|c| sprite::PIXELS.contains(c)), "{} has a row {:?}", species.name, row);
+            }
+        }
+        for (name, _) in sprite::all() {
+            ensure!(Deus::find(name).is_some(), "the sprite {} is for no species", name);
+        }
+        Ok(())
+    }
+
+    fn sample_deus() -> DeusInstance {
+        let mut deus = DeusInstance::new(0, "Ignis", 3);
+        deus.id = 7;
+        deus.exp = 10 * 25 * 25;
+        deus
+    }
+
+    fn sample_battle() -> Result<Battle, String> {
+        use battle::Fighter;
+        let side = |name: &str, species: &[&str]| -> Result<Side, String> {
+            let mut fighters = Vec::new();
+            for (i, s) in species.iter().enumerate() {
+                let mut deus = DeusInstance::new(0, s, 1);
+                deus.id = i as i32 + 1;
+                deus.exp = 10 * 30 * 30;
+                fighters.push(Fighter::new(&deus).map_err(|e| e.tr("en"))?);
+            }
+            Ok(Side::new(name, fighters))
+        };
+        let mut battle = Battle::new(42, side("Ash", &["Ignis", "Terra", "Lux"])?, side("Wild Deus", &["Aqua", "Umbra"])?);
+        while battle.turn < 4 && !battle.is_over() {
+            let actions = [battle.choose(0), battle.choose(1)];
+            battle.play(actions);
+        }
+        Ok(battle)
+    }
+
+    // Compares `actual` with the golden image `name`. HOLLOW_BLESS=1 writes it
+    // instead, after a deliberate change to how things are drawn.
+    fn golden(name: &str, expected: &[u8], actual: &[u8]) -> Result<(), String> {
+        let source = Path::new(env!("CARGO_MANIFEST_DIR")).join(file!());
+        let path = source.parent().unwrap().join("golden").join(name);
+        if env::var("HOLLOW_BLESS").ok().map_or(false, |b| b == "1") {
+            return File::create(&path).and_then(|mut f| f.write_all(actual)).map_err(|e| format!("writing {}: {}", path.display(), e));
+        }
+        if expected != actual {
+            let out = env::temp_dir().join(name);
+            let _ = File::create(&out).and_then(|mut f| f.write_all(actual));
+            return Err(format!("{} differs, it was drawn to {}; run with HOLLOW_BLESS=1 if that's intended", name, out.display()));
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn deus_card_matches_its_golden_image() -> Result<(), String> {
+        let deus = sample_deus();
+        let species = Deus::find(&deus.deus).ok_or("Ignis is not in the catalog")?;
+        let card = draw_card("en", species, &deus, &Stats::compute(species, &deus));
+        golden("card.png", include_bytes!("golden/card.png"), &card.png())
+    }
+
+    #[test]
+    fn battle_snapshot_matches_its_golden_image() -> Result<(), String> {
+        golden("battle.png", include_bytes!("golden/battle.png"), &draw_battle("en", &sample_battle()?).png())
+    }
+
+    #[test]
+    fn cached_images_are_reused() -> Result<(), String> {
+        let dir = env::temp_dir().join(format!("junkbot-cards-{}", ::storage::now()));
+        let deus = sample_deus();
+        let species = Deus::find(&deus.deus).ok_or("Ignis is not in the catalog")?;
+        let stats = Stats::compute(species, &deus);
+        let mut draws = 0;
+        let first = cached(&dir, "deus", "sample", || { draws += 1; draw_card("en", species, &deus, &stats) });
+        let again = cached(&dir, "deus", "sample", || { draws += 1; draw_card("en", species, &deus, &stats) });
+        let other = cached(&dir, "deus", "other", || { draws += 1; Image::new(1, 1, INK) });
+        let _ = fs::remove_dir_all(&dir);
+        ensure!(first == again, "the cached card differs from the drawn one");
+        ensure!(first != other, "two keys gave the same image");
+        ensure!(draws == 2, "drew {} times for two keys", draws);
+        Ok(())
+    }
+}
@@ -0,0 +1,37 @@
+// The species artwork in data/render/sprites.txt, one small sprite each.
+
+use std::collections::BTreeMap;
+
+#[cfg(test)]
+pub const SIZE: usize = 12;
+// What a sprite row may hold, see Canvas::sprite.
+#[cfg(test)]
+pub const PIXELS: &'static str = ".12#o";
+
+pub struct Sprite {
+    pub rows: Vec<String>,
+}
+
+lazy_static! {
+    static ref SPRITES: BTreeMap<String, Sprite> = parse(include_str!("../../data/render/sprites.txt"));
+}
+
+// Sprites are a species name then its rows, separated by blank lines.
+// Comments start with "# ", rows can start with an outline.
+fn parse(text: &str) -> BTreeMap<String, Sprite> {
+    let mut sprites = BTreeMap::new();
+    let lines: Vec<&str> = text.lines().filter(|l| !l.starts_with("# ")).collect();
+    for block in lines.split(|l| l.trim().is_empty()).filter(|b| !b.is_empty()) {
+        sprites.insert(block[0].trim().to_lowercase(), Sprite { rows: block[1..].iter().map(|r| r.trim().to_owned()).collect() });
+    }
+    sprites
+}
+
+pub fn find(species: &str) -> Option<&'static Sprite> {
+    SPRITES.get(&species.to_lowercase())
+}
+
+#[cfg(test)]
+pub fn all() -> Vec<(&'static str, &'static Sprite)> {
+    SPRITES.iter().map(|(name, sprite)| (name.as_str(), sprite)).collect()
+}
@@ -0,0 +1,176 @@
+use time::{self, Timespec};
+
+// A cron expression: minute, hour, day of month, month and day of week, in
+// UTC. Fields take `*`, numbers, ranges, lists and steps, like `*/15` or
+// `1-5`. Sunday is 0 or 7. When both days are set either one matching is
+// enough, as in cron.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Schedule {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    any_day: bool,
+    any_weekday: bool,
+}
+
+// How far ahead next_after looks, schedules that don't run within it are
+// rejected.
+const HORIZON: i64 = 5 * 366 * 86400;
+
+impl Schedule {
+    pub fn parse(expression: &str) -> Result<Schedule, String> {
+        let expression = match expression {
+            "@hourly" => "0 * * * *",
+            "@daily" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            other => other,
+        };
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(format!("'{}' doesn't have 5 fields", expression));
+        }
+
+        let weekdays = field(fields[4], 0, 7)?;
+        let schedule = Schedule {
+            minutes: field(fields[0], 0, 59)?,
+            hours: field(fields[1], 0, 23)?,
+            days: field(fields[2], 1, 31)?,
+            months: field(fields[3], 1, 12)?,
+            // 7 is Sunday too.
+            weekdays: (weekdays | weekdays >> 7) & 0x7F,
+            any_day: fields[2] == "*",
+            any_weekday: fields[4] == "*",
+        };
+        match schedule.next_after(0) {
+            Some(_) => Ok(schedule),
+            None => Err(format!("'{}' never runs", expression)),
+        }
+    }
+
+    // The first minute strictly after `at` the schedule matches, as a unix
+    // time.
+    pub fn next_after(&self, at: i64) -> Option<i64> {
+        let mut t = at - at % 60 + 60;
+        while t <= at + HORIZON {
+            let tm = time::at_utc(Timespec::new(t, 0));
+            if !has(self.months, tm.tm_mon + 1) || !self.day_matches(tm.tm_mday, tm.tm_wday) {
+                t += 86400 - t % 86400;
+            } else if !has(self.hours, tm.tm_hour) {
+                t += 3600 - t % 3600;
+            } else if !has(self.minutes, tm.tm_min) {
+                t += 60;
+            } else {
+                return Some(t);
+            }
+        }
+        None
+    }
+
+    fn day_matches(&self, day: i32, weekday: i32) -> bool {
+        match (self.any_day, self.any_weekday) {
+            (true, true) => true,
+            (false, true) => has(self.days, day),
+            (true, false) => has(self.weekdays, weekday),
+            (false, false) => has(self.days, day) || has(self.weekdays, weekday),
+        }
+    }
+}
+
+fn has(set: u64, value: i32) -> bool {
+    set & (1 << value) != 0
+}
+
+// The values a field allows, as a bit set.
+fn field(text: &str, min: i32, max: i32) -> Result<u64, String> {
+    let mut set = 0;
+    for part in text.split(',') {
+        let (range, step) = match part.find('/') {
+            Some(i) => (&part[..i], number(&part[i + 1..], 1, max)?),
+            None => (part, 1),
+        };
+        let (from, to) = if range == "*" {
+            (min, max)
+        } else {
+            match range.find('-') {
+                Some(i) => (number(&range[..i], min, max)?, number(&range[i + 1..], min, max)?),
+                None => {
+                    let n = number(range, min, max)?;
+                    // `5/15` runs from 5 on.
+                    (n, if part.contains('/') { max } else { n })
+                }
+            }
+        };
+        if from > to {
+            return Err(format!("'{}' runs backwards", part));
+        }
+        let mut value = from;
+        while value <= to {
+            set |= 1 << value;
+            value += step;
+        }
+    }
+    Ok(set)
+}
+
+fn number(text: &str, min: i32, max: i32) -> Result<i32, String> {
+    match text.parse::<i32>() {
+        Ok(n) if n >= min && n <= max => Ok(n),
+        _ => Err(format!("'{}' isn't between {} and {}", text, min, max)),
+    }
+}
+
+// Parsing and next runs.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testing::NOON;
+
+    fn next(expression: &str, at: i64) -> Result<i64, String> {
+        Schedule::parse(expression)?.next_after(at).ok_or_else(|| format!("'{}' has no next run", expression))
+    }
+
+    #[test]
+    fn cron_expressions_parse() -> Result<(), String> {
+        for e in &["* * * * *", "*/15 0-6,18 1 */2 1-5", "5/10 * * * 7", "0 0 29 2 *", "@daily", "@weekly"] {
+            Schedule::parse(e)?;
+        }
+        ensure!(Schedule::parse("0 0 * * 7")? == Schedule::parse("0 0 * * 0")?, "7 isn't Sunday");
+        ensure!(Schedule::parse("@hourly")? == Schedule::parse("0 * * * *")?, "@hourly isn't every hour");
+        Ok(())
+    }
+
+    #[test]
+    fn bad_cron_expressions_are_rejected() -> Result<(), String> {
+        for e in &["", "* * * *", "* * * * * *", "60 * * * *", "* 24 * * *", "* * 0 * *", "* * * 13 *",
+                   "* * * * 8", "5-1 * * * *", "*/0 * * * *", "a * * * *", "0 0 30 2 *"] {
+            ensure!(Schedule::parse(e).is_err(), "'{}' parsed", e);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn next_runs_fall_on_the_schedule() -> Result<(), String> {
+        ensure!(next("* * * * *", NOON)? == NOON - 30 + 60, "every minute runs at {}", next("* * * * *", NOON)?);
+        ensure!(next("*/15 * * * *", NOON)? == NOON - 450 + 900, "quarter hours run at {}", next("*/15 * * * *", NOON)?);
+        // Midnight, Monday 2017-06-05.
+        ensure!(next("0 0 * * 1", NOON)? == 1496620800, "Mondays run at {}", next("0 0 * * 1", NOON)?);
+        // 2020-02-29, the next leap day.
+        ensure!(next("0 0 29 2 *", NOON)? == 1582934400, "leap days run at {}", next("0 0 29 2 *", NOON)?);
+        // A run isn't due again the minute it ran.
+        let at = next("30 12 * * *", NOON)?;
+        ensure!(next("30 12 * * *", at)? == at + 86400, "ran again at {} after {}", next("30 12 * * *", at)?, at);
+        Ok(())
+    }
+
+    #[test]
+    fn either_day_field_can_match() -> Result<(), String> {
+        // The 1st of the month, or a Saturday: Saturday 2017-06-03 comes first.
+        ensure!(next("0 0 1 * 6", NOON)? == 1496448000, "day or weekday runs at {}", next("0 0 1 * 6", NOON)?);
+        // With a wildcard the other field decides on its own.
+        ensure!(next("0 0 1 * *", NOON)? == 1498867200, "the 1st runs at {}", next("0 0 1 * *", NOON)?);
+        Ok(())
+    }
+}
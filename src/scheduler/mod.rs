@@ -0,0 +1,277 @@
+pub mod cron;
+
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use rand;
+
+use cmds;
+use i18n;
+use ladder;
+use raids;
+use storage::{self, Storage};
+use wars;
+use self::cron::Schedule;
+
+// Where the scheduler gets the time from, so checks can move it along.
+pub trait Clock: Send + Sync {
+    fn now(&self) -> i64;
+}
+
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> i64 {
+        storage::now()
+    }
+}
+
+pub struct Job {
+    // Next runs are stored under it.
+    pub name: &'static str,
+    // See cron::Schedule.
+    pub schedule: &'static str,
+    // Given the time it runs at, returns lines for the log.
+    pub run: fn(&Storage, i64) -> Result<Vec<String>, String>,
+}
+
+pub const JOBS: &'static [Job] = &[
+    Job { name: "season rewards", schedule: "5 * * * *", run: season_rewards },
+    Job { name: "tournament deadlines", schedule: "*/5 * * * *", run: tournament_deadlines },
+    Job { name: "guild war", schedule: "0 18 * * 5", run: guild_war },
+    Job { name: "guild war results", schedule: "*/5 * * * *", run: guild_war_results },
+    Job { name: "raid", schedule: "0 */6 * * *", run: raid },
+    Job { name: "raid results", schedule: "*/5 * * * *", run: raid_results },
+];
+
+// How often the scheduler looks for due jobs.
+const POLL: u64 = 20;
+
+pub struct Scheduler {
+    clock: Arc<Clock>,
+    jobs: Vec<(&'static Job, Schedule)>,
+}
+
+impl Scheduler {
+    pub fn new(clock: Arc<Clock>, jobs: &'static [Job]) -> Result<Scheduler, String> {
+        let mut parsed = Vec::new();
+        for job in jobs {
+            let schedule = Schedule::parse(job.schedule).map_err(|e| format!("job {}: {}", job.name, e))?;
+            parsed.push((job, schedule));
+        }
+        Ok(Scheduler { clock: clock, jobs: parsed })
+    }
+
+    // Runs the jobs that are due and returns how each went. A job is claimed
+    // before it runs, so it runs at most once per due time whichever process
+    // gets to it: one that fails or dies halfway waits for its next run. New
+    // jobs wait for their first run, and runs missed while the bot was down
+    // are made up for with a single one.
+    pub fn tick(&self, store: &Storage) -> Result<Vec<(&'static str, Result<Vec<String>, String>)>, String> {
+        let mut ran = Vec::new();
+        for &(job, ref schedule) in &self.jobs {
+            let at = self.clock.now();
+            let next = schedule.next_after(at).ok_or_else(|| format!("job {} has no next run", job.name))?;
+            match store.job_next_run(job.name)? {
+                None => {
+                    store.claim_job(job.name, None, next)?;
+                }
+                Some(due) if due <= at => {
+                    if store.claim_job(job.name, Some(due), next)? {
+                        ran.push((job.name, (job.run)(store, at)));
+                    }
+                }
+                Some(_) => {}
+            }
+        }
+        Ok(ran)
+    }
+
+    // Ticks in the background for as long as the bot runs.
+    pub fn start(self, store: Arc<Storage>) {
+        thread::spawn(move || loop {
+            match self.tick(&*store) {
+                Ok(ran) => for (name, result) in ran {
+                    match result {
+                        Ok(lines) => for line in lines {
+                            println!("[ jobs ] - {}: {}", name, line);
+                        },
+                        Err(why) => println!("[ jobs ] - {} failed: {}", name, why),
+                    }
+                },
+                Err(why) => println!("[ jobs ] - scheduling failed: {}", why),
+            }
+            thread::sleep(Duration::from_secs(POLL));
+        });
+    }
+}
+
+// Pays the rewards of the season that ended, the ladder commands do it too
+// when someone gets there first.
+fn season_rewards(store: &Storage, at: i64) -> Result<Vec<String>, String> {
+    let season = ladder::season_at(at) - 1;
+    let paid = store.reward_season(season)?;
+    Ok(if paid.is_empty() { Vec::new() } else { vec![format!("paid {} players for season {}", paid.len(), season)] })
+}
+
+// Settles the tournament matches nobody played in time.
+fn tournament_deadlines(store: &Storage, _: i64) -> Result<Vec<String>, String> {
+    let mut news = Vec::new();
+    for t in store.tournaments()? {
+        news.extend(cmds::tournament::advance(store, i18n::default(), &t)?);
+    }
+    Ok(news)
+}
+
+// Opens the week's guild war and tells the guilds in it.
+fn guild_war(store: &Storage, at: i64) -> Result<Vec<String>, String> {
+    let war = match wars::open(store, at)? {
+        Some(war) => war,
+        None => return Ok(Vec::new()),
+    };
+    let lang = i18n::default();
+    let text = tr!(lang, "war-begun", left = cmds::quests::left(lang, war.ends - at), stake = wars::STAKE, prize = war.prize);
+    let mut lines = vec![format!("opened war {}", war.id)];
+    lines.extend(wars::announce(store, &war, &text)?);
+    Ok(lines)
+}
+
+// Pays out the guild wars that ended and tells the guilds in them.
+fn guild_war_results(store: &Storage, at: i64) -> Result<Vec<String>, String> {
+    let mut lines = Vec::new();
+    for war in store.wars(wars::RECENT)? {
+        if war.settled || war.ends > at {
+            continue;
+        }
+        if let Some(results) = wars::settle(store, i18n::default(), &war)? {
+            lines.push(format!("settled war {}", war.id));
+            lines.extend(wars::announce(store, &war, &results)?);
+        }
+    }
+    Ok(lines)
+}
+
+// Spawns a raid boss in the raid channel, when there's one.
+fn raid(store: &Storage, at: i64) -> Result<Vec<String>, String> {
+    if raids::channel().is_none() {
+        return Ok(Vec::new());
+    }
+    let lang = i18n::default();
+    let raid = match raids::spawn(store, lang, &mut rand::thread_rng(), at)? {
+        Some(raid) => raid,
+        None => return Ok(Vec::new()),
+    };
+    raids::announce(&tr!(lang, "raid-appeared", boss = &raid.boss, level = raid.level, life = raid.max_life,
+                         left = cmds::quests::left(lang, raid.ends - at)))?;
+    Ok(vec![format!("spawned raid {} on {}", raid.id, raid.boss)])
+}
+
+// Pays out the raids that are over, the attack that brings a boss down does
+// it too.
+fn raid_results(store: &Storage, at: i64) -> Result<Vec<String>, String> {
+    let mut lines = Vec::new();
+    for raid in store.raids(raids::RECENT)? {
+        if raid.settled || (!raid.defeated() && raid.ends > at) {
+            continue;
+        }
+        if let Some(results) = raids::settle(store, i18n::default(), &raid)? {
+            lines.push(format!("settled raid {}", raid.id));
+            raids::announce(&results)?;
+        }
+    }
+    Ok(lines)
+}
+
+// Claims and catch-up.
+#[cfg(test)]
+mod tests {
+    use std::sync::Mutex;
+
+    use super::*;
+    use testing::{self, NOON};
+
+    // Stands still until told otherwise.
+    pub struct ManualClock {
+        at: Mutex<i64>,
+    }
+
+    impl ManualClock {
+        pub fn new(at: i64) -> ManualClock {
+            ManualClock { at: Mutex::new(at) }
+        }
+
+        pub fn advance(&self, seconds: i64) {
+            *self.at.lock().unwrap() += seconds;
+        }
+    }
+
+    impl Clock for ManualClock {
+        fn now(&self) -> i64 {
+            *self.at.lock().unwrap()
+        }
+    }
+
+    fn stamp(_: &Storage, at: i64) -> Result<Vec<String>, String> {
+        Ok(vec![at.to_string()])
+    }
+
+    const HOURLY: &'static [Job] = &[Job { name: "hourly", schedule: "0 * * * *", run: stamp }];
+
+    fn runs(scheduler: &Scheduler, store: &Storage) -> Result<Vec<String>, String> {
+        let mut lines = Vec::new();
+        for (_, result) in scheduler.tick(store)? {
+            lines.extend(result?);
+        }
+        Ok(lines)
+    }
+
+    #[test]
+    fn jobs_run_once_they_re_due() -> Result<(), String> {
+        let store = testing::store();
+        let clock = Arc::new(ManualClock::new(NOON));
+        let scheduler = Scheduler::new(clock.clone(), HOURLY)?;
+
+        ensure!(runs(&scheduler, &store)?.is_empty(), "a new job ran right away");
+        ensure!(store.job_next_run("hourly")? == Some(NOON - 450 + 3600), "next run at {:?}", store.job_next_run("hourly")?);
+        clock.advance(3600 - 451);
+        ensure!(runs(&scheduler, &store)?.is_empty(), "the job ran a second early");
+        clock.advance(1);
+        ensure!(runs(&scheduler, &store)? == vec![(NOON - 450 + 3600).to_string()], "the job didn't run on time");
+        ensure!(runs(&scheduler, &store)?.is_empty(), "the job ran twice in a row");
+        clock.advance(3600);
+        ensure!(runs(&scheduler, &store)?.len() == 1, "the job didn't run the next hour");
+        Ok(())
+    }
+
+    #[test]
+    fn a_run_is_claimed_once_across_processes() -> Result<(), String> {
+        // Two bots on one database, or one before and after a restart.
+        let store = testing::store();
+        let clock = Arc::new(ManualClock::new(NOON));
+        let first = Scheduler::new(clock.clone(), HOURLY)?;
+        let second = Scheduler::new(clock.clone(), HOURLY)?;
+        runs(&first, &store)?;
+        runs(&second, &store)?;
+
+        clock.advance(3600);
+        let ran = runs(&first, &store)?.len() + runs(&second, &store)?.len();
+        ensure!(ran == 1, "the run happened {} times", ran);
+        Ok(())
+    }
+
+    #[test]
+    fn missed_runs_are_made_up_with_one() -> Result<(), String> {
+        let store = testing::store();
+        let clock = Arc::new(ManualClock::new(NOON));
+        let scheduler = Scheduler::new(clock.clone(), HOURLY)?;
+        runs(&scheduler, &store)?;
+
+        // Down for ten hours.
+        clock.advance(10 * 3600);
+        ensure!(runs(&scheduler, &store)?.len() == 1, "missed runs weren't made up with one");
+        ensure!(store.job_next_run("hourly")? == Some(NOON - 450 + 11 * 3600), "next run at {:?}", store.job_next_run("hourly")?);
+        ensure!(runs(&scheduler, &store)?.is_empty(), "the missed runs ran again");
+        Ok(())
+    }
+}
@@ -0,0 +1,1297 @@
+// Behaviour every backend has to agree on, run against each of them by
+// `cargo test`. Checks leave what they create behind, players would see it, so
+// Postgres is only checked on the database in HOLLOW_TEST_DB.
+
+use std::env;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering, ATOMIC_USIZE_INIT};
+use std::thread;
+
+use battle::{self, Battle, Fighter, Side};
+use battle::replay::{Replay, TeamSnapshot};
+use deus::Instance::DeusInstance;
+use deus::Stats::{Stat, Stats};
+use deus::Team::Team;
+use energy::{self, MAX_ENERGY, REGEN};
+use events::{Envelope, Event};
+use ladder::{self, Outcome, Tier};
+use quests::Quest;
+use raids::Raid::Raid;
+use tournament;
+use tournament::Tournament::{Format, MatchResult, State as TournamentState, Tournament};
+use users::Users::UserType;
+use wars::War::War;
+use super::{now, Error, Storage};
+use super::memory::MemoryStorage;
+use super::postgres::PgStorage;
+
+type Check = fn(&Storage) -> Result<(), String>;
+
+const CHECKS: &'static [(&'static str, Check)] = &[
+    ("users are created once per discord id", users_are_unique),
+    ("users are found by id and discord id", users_lookup),
+    ("missing users are not found", missing_users),
+    ("users keep the language they picked", users_locale),
+    ("deus belong to their owner", deus_ownership),
+    ("deus can be updated and removed, from their latest copy", deus_update_remove),
+    ("deus keep their variance and training", deus_stats),
+    ("deus keep their learned spells and move set", deus_move_set),
+    ("vults never go below zero", vults_not_negative),
+    ("transfers are all or nothing", transfers_atomic),
+    ("ledger lists newest entries first", ledger_order),
+    ("new users get a default profile", default_profile),
+    ("pvp results keep win streaks", pvp_streaks),
+    ("removing the favorite deus clears it", favorite_removed),
+    ("teams keep their slot order", team_order),
+    ("team names are unique per owner", team_names),
+    ("one team is active at a time", team_active),
+    ("removed deus leave their teams", team_member_removed),
+    ("replays play back the same battle", replays),
+    ("ratings carry over into the next season", ratings_carry),
+    ("rated games take an accepted challenge and are saved at once", pvp_finished),
+    ("the ladder lists a season by rating", ladder_order),
+    ("season rewards are paid once", season_rewards),
+    ("tournament entry fees are taken and refunded", tournament_fees),
+    ("started tournaments take no entrants", tournament_start),
+    ("full tournaments take no entrants", tournament_full),
+    ("tournament matches are decided once", tournament_results),
+    ("played tournament matches are saved at once", tournament_matches_finished),
+    ("items are bought with vults", items_bought),
+    ("using an item takes it", items_used),
+    ("held items come back when swapped", items_swapped),
+    ("quest progress is capped and paid once", quests_paid_once),
+    ("quest periods are tracked apart", quest_periods),
+    ("users are listed oldest first", users_listed),
+    ("achievements are awarded once, in order", achievements_once),
+    ("events are written along with their change", events_written),
+    ("deliveries are tracked per subscriber", events_delivered),
+    ("pending events are found per user", events_per_user),
+    ("job runs are claimed once", jobs_claimed),
+    ("energy is spent as regenerated", energy_spent),
+    ("energy refills are paid for", energy_refilled),
+    ("exploring pays what it found with the energy", energy_explored),
+    ("wild battles are saved with the energy they took", energy_pve),
+    ("guilds are server accounts", guilds_created),
+    ("players join and leave guilds", guild_members),
+    ("bought deus are paid for", deus_bought),
+    ("fused deus replace their parents", deus_fused),
+    ("ascending uses up copies or materials", deus_ascended),
+    ("the dex keeps what was seen and caught", dex_tracked),
+    ("dex milestones are paid once", dex_rewarded),
+    ("guild wars are scored per guild", wars_scored),
+    ("guild wars are settled once", wars_settled_once),
+    ("raid attacks take what's left", raids_attacked),
+    ("raids are settled once", raids_settled_once),
+];
+
+// Checks that need the storage shared between threads.
+type SharedCheck = fn(Arc<Storage>) -> Result<(), String>;
+
+const SHARED_CHECKS: &'static [(&'static str, SharedCheck)] = &[
+    ("concurrent raid attacks add up", raids_concurrent),
+    ("concurrent joins stop at a full tournament", tournament_joins_concurrent),
+];
+
+#[test]
+fn memory() -> Result<(), String> {
+    run(Arc::new(MemoryStorage::new()))
+}
+
+#[cfg(feature = "sqlite")]
+#[test]
+fn sqlite() -> Result<(), String> {
+    let path = env::temp_dir().join(format!("junkbot-check-{}.sqlite", now()));
+    let sqlite = super::sqlite::SqliteStorage::open(path.to_str().unwrap()).map_err(|e| e.to_string())?;
+    let result = run(Arc::new(sqlite));
+    let _ = ::std::fs::remove_file(&path);
+    result
+}
+
+#[test]
+fn postgres() -> Result<(), String> {
+    let url = match env::var("HOLLOW_TEST_DB") {
+        Ok(url) => url,
+        Err(_) => {
+            println!("skipping postgres, HOLLOW_TEST_DB is not set");
+            return Ok(());
+        }
+    };
+    ensure!(env::var("HOLLOW_DB").ok() != Some(url.clone()), "refusing to run on HOLLOW_TEST_DB, it's the bot's HOLLOW_DB");
+    let pool = ::db::connect(&::db::Settings::with_url(url)).map_err(|e| format!("database: {}", e))?;
+    run(Arc::new(PgStorage::new(pool).map_err(|e| e.to_string())?))
+}
+
+// Every check on `store`, failing with all of those that failed.
+fn run(store: Arc<Storage>) -> Result<(), String> {
+    let mut failed = Vec::new();
+    for &(check, f) in CHECKS {
+        if let Err(e) = f(&*store) {
+            failed.push(format!("{}: {}", check, e));
+        }
+    }
+    for &(check, f) in SHARED_CHECKS {
+        if let Err(e) = f(store.clone()) {
+            failed.push(format!("{}: {}", check, e));
+        }
+    }
+    ensure!(failed.is_empty(), "{} checks failed:\n{}", failed.len(), failed.join("\n"));
+    Ok(())
+}
+
+static NEXT_ID: AtomicUsize = ATOMIC_USIZE_INIT;
+
+// Discord ids are made up from the clock so runs don't collide.
+fn fresh_disc_id() -> i64 {
+    -(now() * 1000 + NEXT_ID.fetch_add(1, Ordering::SeqCst) as i64 % 1000)
+}
+
+// Seasons far past any real one, so checks get a ladder of their own.
+fn fresh_season() -> i32 {
+    (-fresh_disc_id() % 1_000_000_000) as i32
+}
+
+fn users_are_unique(s: &Storage) -> Result<(), String> {
+    let disc_id = fresh_disc_id();
+    s.create_user(disc_id, "first")?;
+    let again = s.create_user(disc_id, "second");
+    ensure!(again == Err(Error::AlreadyExists), "second create gave {:?}", again);
+    Ok(())
+}
+
+fn users_lookup(s: &Storage) -> Result<(), String> {
+    let created = s.create_user(fresh_disc_id(), "lookup")?;
+    ensure!(created.vults == 0, "new user has {} vults", created.vults);
+    ensure!(s.user(created.id)? == created, "lookup by id differs");
+    ensure!(s.user_by_disc_id(created.disc_id)? == created, "lookup by discord id differs");
+    Ok(())
+}
+
+fn missing_users(s: &Storage) -> Result<(), String> {
+    let missing = s.user_by_disc_id(fresh_disc_id());
+    ensure!(missing == Err(Error::NotFound), "got {:?}", missing);
+    let deus = s.add_deus(&DeusInstance::new(-1, "Nobody", 1));
+    ensure!(deus == Err(Error::NotFound), "deus for a missing user gave {:?}", deus);
+    Ok(())
+}
+
+fn users_locale(s: &Storage) -> Result<(), String> {
+    let created = s.create_user(fresh_disc_id(), "locale")?;
+    ensure!(created.locale == None, "new user speaks {:?}", created.locale);
+    s.set_locale(created.id, Some("fr"))?;
+    ensure!(s.user(created.id)?.locale == Some("fr".to_owned()), "the language wasn't kept");
+    s.set_locale(created.id, None)?;
+    ensure!(s.user(created.id)?.locale == None, "the language wasn't cleared");
+    let missing = s.set_locale(-1, Some("fr"));
+    ensure!(missing == Err(Error::NotFound), "a missing user gave {:?}", missing);
+    Ok(())
+}
+
+fn deus_ownership(s: &Storage) -> Result<(), String> {
+    let a = s.create_user(fresh_disc_id(), "a")?;
+    let b = s.create_user(fresh_disc_id(), "b")?;
+    let first = s.add_deus(&DeusInstance::new(a.id, "Ignis", 1))?;
+    let second = s.add_deus(&DeusInstance::new(a.id, "Aqua", 3))?;
+    s.add_deus(&DeusInstance::new(b.id, "Terra", 1))?;
+
+    ensure!(s.deus_of(a.id)? == vec![first.clone(), second], "deus of a differ");
+    ensure!(s.deus(first.id)? == first, "lookup by id differs");
+    Ok(())
+}
+
+fn deus_update_remove(s: &Storage) -> Result<(), String> {
+    let owner = s.create_user(fresh_disc_id(), "owner")?;
+    let mut deus = s.add_deus(&DeusInstance::new(owner.id, "Ignis", 1))?;
+    deus.exp = 250;
+    deus.stars = 2;
+    s.update_deus(&deus)?;
+    ensure!(s.deus(deus.id)? == DeusInstance { version: deus.version + 1, ..deus.clone() }, "update was not saved");
+    let stale = s.update_deus(&DeusInstance { exp: 0, ..deus.clone() });
+    ensure!(stale == Err(Error::Changed), "saving a stale copy gave {:?}", stale);
+    ensure!(s.deus(deus.id)?.exp == 250, "a stale copy undid the update");
+
+    s.remove_deus(deus.id)?;
+    ensure!(s.deus(deus.id) == Err(Error::NotFound), "removed deus still found");
+    ensure!(s.remove_deus(deus.id) == Err(Error::NotFound), "removed twice");
+    Ok(())
+}
+
+fn deus_stats(s: &Storage) -> Result<(), String> {
+    let owner = s.create_user(fresh_disc_id(), "trainer")?;
+    let mut rolled = DeusInstance::new(owner.id, "Ignis", 1);
+    rolled.variance = Stats { life: 1, atk: 2, def: 3, speed: 4, attr: 31 };
+    rolled.nature = "Timid".to_owned();
+    rolled.points = 10;
+    let mut deus = s.add_deus(&rolled)?;
+    ensure!(deus.variance == rolled.variance && deus.nature == "Timid", "rolls were not saved: {:?}", deus);
+
+    deus.train(Stat::Speed, 6).map_err(|e| e.tr("en"))?;
+    let mut changed = deus.clone();
+    changed.variance.atk = 30;
+    changed.nature = "Hardy".to_owned();
+    s.update_deus(&changed)?;
+    ensure!(s.deus(deus.id)? == DeusInstance { version: deus.version + 1, ..deus }, "update changed the rolls or lost the training");
+    Ok(())
+}
+
+fn deus_move_set(s: &Storage) -> Result<(), String> {
+    let owner = s.create_user(fresh_disc_id(), "teacher")?;
+    let mut taught = DeusInstance::new(owner.id, "Ignis", 1);
+    taught.spells = vec!["Thunderbolt".to_owned()];
+    taught.equipped = vec!["Thunderbolt".to_owned(), "Ember".to_owned()];
+    let mut deus = s.add_deus(&taught)?;
+    ensure!(deus.spells == taught.spells && deus.equipped == taught.equipped, "added {:?} and {:?}", deus.spells, deus.equipped);
+
+    deus.forget("Thunderbolt").map_err(|e| e.tr("en"))?;
+    s.update_deus(&deus)?;
+    ensure!(s.deus(deus.id)?.equipped == vec!["Ember".to_owned()], "saved {:?}", s.deus(deus.id)?.equipped);
+    ensure!(s.deus(deus.id)?.spells == taught.spells, "forgetting it unlearned Thunderbolt");
+    Ok(())
+}
+
+fn vults_not_negative(s: &Storage) -> Result<(), String> {
+    let user = s.create_user(fresh_disc_id(), "spender")?;
+    let entry = s.add_vults(user.id, 50, "gift")?;
+    ensure!(entry.balance == 50, "balance is {}", entry.balance);
+
+    let spend = s.add_vults(user.id, -80, "too much");
+    ensure!(spend == Err(Error::NotEnoughVults), "overspend gave {:?}", spend);
+    ensure!(s.user(user.id)?.vults == 50, "failed spend changed the balance");
+    Ok(())
+}
+
+fn transfers_atomic(s: &Storage) -> Result<(), String> {
+    let from = s.create_user(fresh_disc_id(), "from")?;
+    let to = s.create_user(fresh_disc_id(), "to")?;
+    s.add_vults(from.id, 30, "gift")?;
+
+    s.transfer_vults(from.id, to.id, 20, "trade")?;
+    ensure!(s.user(from.id)?.vults == 10 && s.user(to.id)?.vults == 20, "transfer not applied");
+
+    let failed = s.transfer_vults(from.id, to.id, 20, "trade");
+    ensure!(failed == Err(Error::NotEnoughVults), "overdraft gave {:?}", failed);
+    let failed = s.transfer_vults(from.id, -1, 5, "trade");
+    ensure!(failed == Err(Error::NotFound), "transfer to nobody gave {:?}", failed);
+    ensure!(s.user(from.id)?.vults == 10 && s.user(to.id)?.vults == 20, "failed transfer left changes");
+    Ok(())
+}
+
+fn ledger_order(s: &Storage) -> Result<(), String> {
+    let user = s.create_user(fresh_disc_id(), "ledger")?;
+    s.add_vults(user.id, 10, "first")?;
+    s.add_vults(user.id, 5, "second")?;
+    s.add_vults(user.id, -3, "third")?;
+
+    let reasons: Vec<_> = s.ledger(user.id, 2)?.into_iter().map(|e| e.reason).collect();
+    ensure!(reasons == vec!["third", "second"], "ledger gave {:?}", reasons);
+    Ok(())
+}
+
+fn default_profile(s: &Storage) -> Result<(), String> {
+    let user = s.create_user(fresh_disc_id(), "profile")?;
+    let profile = s.profile(user.id)?;
+    ensure!(profile.user == user.id && profile.wins == 0 && profile.hidden == 0, "got {:?}", profile);
+
+    s.set_profile_settings(user.id, None, 5)?;
+    ensure!(s.profile(user.id)?.hidden == 5, "settings were not saved");
+    ensure!(s.profile(-1) == Err(Error::NotFound), "profile of a missing user");
+    Ok(())
+}
+
+fn pvp_streaks(s: &Storage) -> Result<(), String> {
+    let a = s.create_user(fresh_disc_id(), "a")?;
+    let b = s.create_user(fresh_disc_id(), "b")?;
+    s.record_pvp(a.id, b.id)?;
+    s.record_pvp(a.id, b.id)?;
+    s.record_pvp(b.id, a.id)?;
+
+    let (a, b) = (s.profile(a.id)?, s.profile(b.id)?);
+    ensure!((a.wins, a.losses, a.win_streak, a.best_streak) == (2, 1, 0, 2), "a is {:?}", a);
+    ensure!((b.wins, b.losses, b.win_streak, b.best_streak) == (1, 2, 1, 1), "b is {:?}", b);
+    Ok(())
+}
+
+fn favorite_removed(s: &Storage) -> Result<(), String> {
+    let user = s.create_user(fresh_disc_id(), "fan")?;
+    let deus = s.add_deus(&DeusInstance::new(user.id, "Ignis", 1))?;
+    s.set_profile_settings(user.id, Some(deus.id), 0)?;
+    ensure!(s.profile(user.id)?.favorite == Some(deus.id), "favorite was not saved");
+
+    s.remove_deus(deus.id)?;
+    ensure!(s.profile(user.id)?.favorite == None, "favorite still set");
+    Ok(())
+}
+
+fn team_order(s: &Storage) -> Result<(), String> {
+    let user = s.create_user(fresh_disc_id(), "coach")?;
+    let a = s.add_deus(&DeusInstance::new(user.id, "Ignis", 1))?;
+    let b = s.add_deus(&DeusInstance::new(user.id, "Aqua", 1))?;
+
+    let mut team = Team::new(user.id, "main");
+    team.members = vec![b.id, a.id];
+    team.lead = Some(a.id);
+    let saved = s.save_team(&team)?;
+    ensure!(saved.id != 0, "saved team has no id");
+    ensure!(s.teams(user.id)? == vec![saved.clone()], "teams differ");
+
+    let mut swapped = saved.clone();
+    swapped.members.reverse();
+    s.save_team(&swapped)?;
+    ensure!(s.teams(user.id)?[0].members == vec![a.id, b.id], "order was not saved");
+    Ok(())
+}
+
+fn team_names(s: &Storage) -> Result<(), String> {
+    let a = s.create_user(fresh_disc_id(), "a")?;
+    let b = s.create_user(fresh_disc_id(), "b")?;
+    s.save_team(&Team::new(a.id, "main"))?;
+    s.save_team(&Team::new(b.id, "main"))?;
+
+    let again = s.save_team(&Team::new(a.id, "main"));
+    ensure!(again == Err(Error::AlreadyExists), "duplicate name gave {:?}", again);
+    let mut renamed = s.save_team(&Team::new(a.id, "side"))?;
+    renamed.name = "main".to_owned();
+    let renamed = s.save_team(&renamed);
+    ensure!(renamed == Err(Error::AlreadyExists), "rename onto a taken name gave {:?}", renamed);
+    Ok(())
+}
+
+fn team_active(s: &Storage) -> Result<(), String> {
+    let user = s.create_user(fresh_disc_id(), "coach")?;
+    let first = s.save_team(&Team::new(user.id, "first"))?;
+    let second = s.save_team(&Team::new(user.id, "second"))?;
+
+    s.activate_team(user.id, first.id)?;
+    s.activate_team(user.id, second.id)?;
+    let active: Vec<_> = s.teams(user.id)?.into_iter().filter(|t| t.active).map(|t| t.id).collect();
+    ensure!(active == vec![second.id], "active teams are {:?}", active);
+
+    let other = s.create_user(fresh_disc_id(), "other")?;
+    let stolen = s.activate_team(other.id, first.id);
+    ensure!(stolen == Err(Error::NotFound), "activating someone else's team gave {:?}", stolen);
+    Ok(())
+}
+
+fn team_member_removed(s: &Storage) -> Result<(), String> {
+    let user = s.create_user(fresh_disc_id(), "coach")?;
+    let a = s.add_deus(&DeusInstance::new(user.id, "Ignis", 1))?;
+    let b = s.add_deus(&DeusInstance::new(user.id, "Aqua", 1))?;
+    let mut team = Team::new(user.id, "main");
+    team.members = vec![a.id, b.id];
+    s.save_team(&team)?;
+
+    s.remove_deus(a.id)?;
+    ensure!(s.teams(user.id)?[0].members == vec![b.id], "removed deus is still in the team");
+    Ok(())
+}
+
+fn replays(s: &Storage) -> Result<(), String> {
+    let a = s.create_user(fresh_disc_id(), "a")?;
+    let b = s.create_user(fresh_disc_id(), "b")?;
+    let side = |name: &str, species: &[&str]| -> Result<Side, String> {
+        let fighters = species.iter()
+            .map(|s| Fighter::new(&DeusInstance::new(0, s, 1)))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|e| e.tr("en"))?;
+        Ok(Side::new(name, fighters))
+    };
+
+    let seed = battle::seed();
+    let mut played = Battle::new(seed, side("a", &["Ignis", "Lux"])?, side("b", &["Aqua"])?);
+    let teams = [TeamSnapshot::of(&played.sides[0]), TeamSnapshot::of(&played.sides[1])];
+    let actions = played.run();
+    let saved = s.save_replay(&Replay::new([a.id, b.id], seed, teams, actions, Some(a.id)))?;
+
+    let loaded = s.replay(saved.id)?;
+    ensure!(loaded == saved, "loaded replay differs");
+    ensure!(loaded.play().map_err(|e| e.tr("en"))?.log == played.log, "replay played out differently");
+    let orphan = s.save_replay(&Replay { players: [a.id, -1], ..saved });
+    ensure!(orphan == Err(Error::NotFound), "replay with a missing player gave {:?}", orphan.map(|r| r.id));
+    Ok(())
+}
+
+fn ratings_carry(s: &Storage) -> Result<(), String> {
+    let season = fresh_season();
+    let a = s.create_user(fresh_disc_id(), "a")?;
+    let b = s.create_user(fresh_disc_id(), "b")?;
+    let unplayed = s.rating(a.id, season)?;
+    ensure!(unplayed == ladder::fresh(a.id, season, ladder::START), "unplayed rating is {:?}", unplayed);
+
+    let (won, lost) = s.record_match(season, a.id, b.id, Outcome::Win)?;
+    ensure!(won.rating > ladder::START && lost.rating < ladder::START, "got {} and {}", won.rating, lost.rating);
+    ensure!(won.wins == 1 && lost.losses == 1, "results not counted");
+    ensure!(s.rating(a.id, season)? == won, "stored rating differs");
+
+    let next = s.rating(a.id, season + 1)?;
+    ensure!(next == ladder::carry(a.id, season + 1, Some(&won)), "next season starts at {:?}", next);
+    ensure!(s.rating(-1, season) == Err(Error::NotFound), "missing user has a rating");
+    let orphan = s.record_match(season, a.id, -1, Outcome::Win);
+    ensure!(orphan.is_err(), "game against a missing user was rated");
+    ensure!(s.rating(a.id, season)? == won, "failed game changed the rating");
+    Ok(())
+}
+
+fn pvp_finished(s: &Storage) -> Result<(), String> {
+    let season = fresh_season();
+    let a = s.create_user(fresh_disc_id(), "a")?;
+    let b = s.create_user(fresh_disc_id(), "b")?;
+    let ignis = s.add_deus(&DeusInstance::new(a.id, "Ignis", 1))?;
+    let side = |species: &str| {
+        Fighter::new(&DeusInstance::new(0, species, 1)).map(|f| Side::new(species, vec![f])).map_err(|e| e.tr("en"))
+    };
+    let battle = Battle::new(1, side("Ignis")?, side("Aqua")?);
+    let teams = [TeamSnapshot::of(&battle.sides[0]), TeamSnapshot::of(&battle.sides[1])];
+    let played = Replay::new([a.id, b.id], 1, teams, Vec::new(), Some(a.id));
+    let trained = vec![DeusInstance { exp: 90, ..ignis.clone() }];
+
+    let unasked = s.finish_pvp(season, &played, &trained, now() - 60);
+    ensure!(unasked == Err(Error::Closed), "game nobody challenged gave {:?}", unasked.map(|r| r.0.id));
+    s.challenge(a.id, b.id, now() - 120)?;
+    let late = s.finish_pvp(season, &played, &trained, now() - 60);
+    ensure!(late == Err(Error::Closed), "expired challenge gave {:?}", late.map(|r| r.0.id));
+    ensure!(s.challenge(a.id, -1, now()) == Err(Error::NotFound), "challenged a missing user");
+
+    s.challenge(a.id, b.id, now())?;
+    let missing = vec![DeusInstance { id: -1, ..ignis.clone() }];
+    let failed = s.finish_pvp(season, &played, &missing, now() - 60);
+    ensure!(failed == Err(Error::NotFound), "game with a missing deus gave {:?}", failed.map(|r| r.0.id));
+    ensure!(s.profile(a.id)?.wins == 0 && s.rating(a.id, season)?.games == 0, "failed game was recorded");
+
+    let (replay, won, lost) = s.finish_pvp(season, &played, &trained, now() - 60)?;
+    ensure!(s.replay(replay.id)? == replay, "replay was not saved");
+    ensure!(won.wins == 1 && lost.losses == 1 && s.rating(b.id, season)? == lost, "ratings are {:?} and {:?}", won, lost);
+    ensure!(s.profile(a.id)?.wins == 1 && s.profile(b.id)?.losses == 1, "results were not recorded");
+    ensure!(s.deus(ignis.id)?.exp == 90, "exp was not saved");
+    let again = s.finish_pvp(season, &played, &trained, now() - 60);
+    ensure!(again == Err(Error::Closed), "challenge was used twice, gave {:?}", again.map(|r| r.0.id));
+    Ok(())
+}
+
+fn ladder_order(s: &Storage) -> Result<(), String> {
+    let season = fresh_season();
+    let a = s.create_user(fresh_disc_id(), "a")?;
+    let b = s.create_user(fresh_disc_id(), "b")?;
+    let c = s.create_user(fresh_disc_id(), "c")?;
+    s.record_match(season, a.id, b.id, Outcome::Win)?;
+    s.record_match(season, c.id, b.id, Outcome::Draw)?;
+    s.record_match(season + 1, b.id, a.id, Outcome::Win)?;
+
+    let ladder: Vec<(i32, String)> = s.ladder(season, 10)?.into_iter().map(|(r, name)| (r.user, name)).collect();
+    ensure!(ladder == vec![(a.id, a.name), (c.id, c.name), (b.id, b.name)], "ladder is {:?}", ladder);
+    ensure!(s.ladder(season, 2)?.len() == 2, "limit ignored");
+    ensure!(s.ladder(season + 2, 10)?.is_empty(), "a season nobody played has players");
+    Ok(())
+}
+
+fn season_rewards(s: &Storage) -> Result<(), String> {
+    let season = fresh_season();
+    let a = s.create_user(fresh_disc_id(), "a")?;
+    let b = s.create_user(fresh_disc_id(), "b")?;
+    let c = s.create_user(fresh_disc_id(), "c")?;
+    for _ in 0..ladder::PLACEMENT_GAMES {
+        s.record_match(season, a.id, b.id, Outcome::Win)?;
+    }
+    s.record_match(season, c.id, b.id, Outcome::Loss)?;
+
+    let mut paid: Vec<i32> = s.reward_season(season)?.iter().map(|r| r.user).collect();
+    paid.sort();
+    ensure!(paid == vec![a.id, b.id], "paid {:?}", paid);
+    for user in &[a.id, b.id] {
+        let rating = s.rating(*user, season)?;
+        let vults = Tier::of(rating.rating).reward().vults;
+        ensure!(rating.rewarded, "{} not marked as rewarded", user);
+        ensure!(s.user(*user)?.vults == vults, "{} got {} vults instead of {}", user, s.user(*user)?.vults, vults);
+        ensure!(s.ledger(*user, 1)?[0].reason == ladder::reward_reason(&rating), "reward not in the ledger");
+    }
+    ensure!(s.user(c.id)?.vults == 0, "unplaced player was paid");
+    ensure!(s.reward_season(season)?.is_empty(), "rewards paid twice");
+    Ok(())
+}
+
+fn tournament_fees(s: &Storage) -> Result<(), String> {
+    let watching = subscriber(s)?;
+    let organizer = s.create_user(fresh_disc_id(), "organizer")?;
+    let a = s.create_user(fresh_disc_id(), "a")?;
+    let t = s.create_tournament(&Tournament::new("weekly", Format::Single, organizer.id, 50))?;
+    ensure!(s.tournament(t.id)? == t, "stored tournament differs");
+
+    s.add_vults(a.id, 30, "test")?;
+    let poor = s.join_tournament(t.id, a.id);
+    ensure!(poor == Err(Error::NotEnoughVults), "joining without the fee gave {:?}", poor);
+    ensure!(s.entrants(t.id)?.is_empty(), "failed join left an entrant");
+
+    s.add_vults(a.id, 70, "test")?;
+    s.join_tournament(t.id, a.id)?;
+    ensure!(s.user(a.id)?.vults == 50, "fee not taken, {} left", s.user(a.id)?.vults);
+    let spent: Vec<Event> = events_of(s, &watching, &[a.id])?.into_iter().map(|e| e.event)
+        .filter(|e| match *e { Event::VultsSpent { .. } => true, _ => false })
+        .collect();
+    ensure!(spent == vec![Event::VultsSpent { user: a.id, amount: 50 }], "the fee was spent as {:?}", spent);
+    let again = s.join_tournament(t.id, a.id);
+    ensure!(again == Err(Error::AlreadyExists), "second join gave {:?}", again);
+    ensure!(s.join_tournament(t.id, -1).is_err(), "missing user joined");
+
+    s.leave_tournament(t.id, a.id)?;
+    ensure!(s.user(a.id)?.vults == 100, "fee not refunded, {} left", s.user(a.id)?.vults);
+    ensure!(s.leave_tournament(t.id, a.id) == Err(Error::NotFound), "left twice");
+    s.finish_tournament(t.id, &[], "check")?;
+    Ok(())
+}
+
+fn tournament_start(s: &Storage) -> Result<(), String> {
+    let a = s.create_user(fresh_disc_id(), "a")?;
+    let b = s.create_user(fresh_disc_id(), "b")?;
+    let c = s.create_user(fresh_disc_id(), "c")?;
+    let t = s.create_tournament(&Tournament::new("weekly", Format::Swiss, a.id, 0))?;
+    s.join_tournament(t.id, a.id)?;
+    s.join_tournament(t.id, b.id)?;
+    let joined: Vec<i32> = s.entrants(t.id)?.iter().map(|e| e.user).collect();
+    ensure!(joined == vec![a.id, b.id], "entrants before the start are {:?}", joined);
+
+    ensure!(s.start_tournament(t.id, &[b.id, c.id]) == Err(Error::NotFound), "started with a stranger seeded");
+    let started = s.start_tournament(t.id, &[b.id, a.id])?;
+    ensure!(started.state == TournamentState::Running && started.started > 0, "started as {:?}", started);
+    let seeded: Vec<(i32, i32)> = s.entrants(t.id)?.iter().map(|e| (e.user, e.seed)).collect();
+    ensure!(seeded == vec![(b.id, 1), (a.id, 2)], "seeded as {:?}", seeded);
+    ensure!(s.tournaments()?.iter().any(|o| o.id == t.id), "running tournament not listed");
+
+    ensure!(s.join_tournament(t.id, c.id) == Err(Error::NotFound), "joined a running tournament");
+    ensure!(s.leave_tournament(t.id, a.id) == Err(Error::NotFound), "left a running tournament");
+    ensure!(s.start_tournament(t.id, &[a.id, b.id]) == Err(Error::NotFound), "started twice");
+    s.finish_tournament(t.id, &[], "check")?;
+    Ok(())
+}
+
+fn tournament_full(s: &Storage) -> Result<(), String> {
+    let a = s.create_user(fresh_disc_id(), "a")?;
+    let t = s.create_tournament(&Tournament::new("weekly", Format::Single, a.id, 0))?;
+    s.join_tournament(t.id, a.id)?;
+    for i in 1..tournament::MAX_ENTRANTS {
+        s.join_tournament(t.id, s.create_user(fresh_disc_id(), &format!("entrant {}", i))?.id)?;
+    }
+
+    let late = s.create_user(fresh_disc_id(), "late")?;
+    let full = s.join_tournament(t.id, late.id);
+    ensure!(full == Err(Error::Closed), "joining a full tournament gave {:?}", full);
+    let again = s.join_tournament(t.id, a.id);
+    ensure!(again == Err(Error::AlreadyExists), "joining a full tournament twice gave {:?}", again);
+    ensure!(s.entrants(t.id)?.len() == tournament::MAX_ENTRANTS as usize, "entrants went past the limit");
+    s.finish_tournament(t.id, &[], "check")?;
+    Ok(())
+}
+
+fn tournament_results(s: &Storage) -> Result<(), String> {
+    let a = s.create_user(fresh_disc_id(), "a")?;
+    let b = s.create_user(fresh_disc_id(), "b")?;
+    let t = s.create_tournament(&Tournament::new("weekly", Format::Single, a.id, 0))?;
+    s.join_tournament(t.id, a.id)?;
+    s.join_tournament(t.id, b.id)?;
+    let result = MatchResult { tournament: t.id, number: 1, winner: b.id, walkover: false, replay: None, at: 0 };
+    ensure!(s.record_result(&result) == Err(Error::NotFound), "result recorded before the start");
+
+    s.start_tournament(t.id, &[a.id, b.id])?;
+    s.set_ready(t.id, a.id, Some(1))?;
+    ensure!(s.entrants(t.id)?[0].ready == Some(1), "check in not kept");
+    ensure!(s.claim_match(t.id, 1, [a.id, b.id]) == Err(Error::NotFound), "claimed with one side checked in");
+    ensure!(s.entrants(t.id)?[0].ready == Some(1), "a failed claim checked a player out");
+    s.set_ready(t.id, b.id, Some(1))?;
+    s.claim_match(t.id, 1, [a.id, b.id])?;
+    ensure!(s.claim_match(t.id, 1, [a.id, b.id]) == Err(Error::NotFound), "match claimed twice");
+    ensure!(s.entrants(t.id)?.iter().all(|e| e.ready.is_none()), "claimed players are still checked in");
+    ensure!(s.entrants(t.id)?.iter().all(|e| e.claimed == Some(1)), "claimed match not marked");
+    let recorded = s.record_result(&result)?;
+    ensure!(recorded.at > 0, "result time not filled in");
+    let again = s.record_result(&MatchResult { winner: a.id, ..result.clone() });
+    ensure!(again == Err(Error::AlreadyExists), "second result gave {:?}", again);
+    ensure!(s.results(t.id)? == vec![recorded], "results differ");
+
+    s.finish_tournament(t.id, &[(b.id, 70), (a.id, 30)], "prize")?;
+    ensure!(s.user(b.id)?.vults == 70 && s.user(a.id)?.vults == 30, "prizes not paid");
+    ensure!(s.tournament(t.id)?.state == TournamentState::Finished, "not finished");
+    ensure!(!s.tournaments()?.iter().any(|o| o.id == t.id), "finished tournament still listed");
+    ensure!(s.finish_tournament(t.id, &[(b.id, 70)], "prize") == Err(Error::NotFound), "paid out twice");
+    ensure!(s.user(b.id)?.vults == 70, "second payout went through");
+    let late = s.record_result(&MatchResult { number: 2, ..result });
+    ensure!(late == Err(Error::NotFound), "result recorded after the end gave {:?}", late);
+    Ok(())
+}
+
+fn tournament_matches_finished(s: &Storage) -> Result<(), String> {
+    let watching = subscriber(s)?;
+    let a = s.create_user(fresh_disc_id(), "a")?;
+    let b = s.create_user(fresh_disc_id(), "b")?;
+    let held = s.add_deus(&DeusInstance { held: Some("Potion".to_owned()), ..DeusInstance::new(a.id, "Ignis", 1) })?;
+    let t = s.create_tournament(&Tournament::new("weekly", Format::Single, a.id, 0))?;
+    s.join_tournament(t.id, a.id)?;
+    s.join_tournament(t.id, b.id)?;
+    s.start_tournament(t.id, &[a.id, b.id])?;
+    let side = |species: &str| {
+        Fighter::new(&DeusInstance::new(0, species, 1)).map(|f| Side::new(species, vec![f])).map_err(|e| e.tr("en"))
+    };
+    let battle = Battle::new(1, side("Ignis")?, side("Aqua")?);
+    let teams = [TeamSnapshot::of(&battle.sides[0]), TeamSnapshot::of(&battle.sides[1])];
+    let played = Replay::new([a.id, b.id], 1, teams, Vec::new(), Some(a.id));
+    let result = MatchResult { tournament: t.id, number: 1, winner: a.id, walkover: false, replay: None, at: 0 };
+
+    let recorded = s.finish_match(&result, Some(&played), &[held.id])?;
+    let replay = recorded.replay.ok_or("result points to no replay")?;
+    ensure!(s.replay(replay)?.players == [a.id, b.id], "replay was not saved");
+    ensure!(s.results(t.id)? == vec![recorded], "result was not saved");
+    ensure!(s.deus(held.id)?.held.is_none(), "potion drunk in the match is still held");
+
+    s.update_deus(&DeusInstance { held: Some("Potion".to_owned()), ..s.deus(held.id)? })?;
+    let again = s.finish_match(&MatchResult { winner: b.id, ..result }, Some(&played), &[held.id]);
+    ensure!(again == Err(Error::AlreadyExists), "second match gave {:?}", again);
+    ensure!(s.deus(held.id)?.held.is_some(), "a match decided before drank the potion");
+    let battles = events_of(s, &watching, &[a.id, b.id])?.into_iter()
+        .filter(|e| match e.event { Event::BattleFinished { .. } => true, _ => false })
+        .count();
+    ensure!(battles == 1, "{} battles finished for one match", battles);
+    s.finish_tournament(t.id, &[], "check")?;
+    Ok(())
+}
+
+fn items_bought(s: &Storage) -> Result<(), String> {
+    let user = s.create_user(fresh_disc_id(), "shopper")?;
+    s.add_vults(user.id, 100, "check")?;
+    let broke = s.buy_item(user.id, "Potion", 3, 40);
+    ensure!(broke == Err(Error::NotEnoughVults), "buying too much gave {:?}", broke);
+    ensure!(s.inventory(user.id)?.is_empty() && s.user(user.id)?.vults == 100, "a failed buy left something");
+
+    s.buy_item(user.id, "Potion", 2, 40)?;
+    let stack = s.buy_item(user.id, "Potion", 1, 15)?;
+    ensure!(stack.count == 3, "bought 3, have {}", stack.count);
+    ensure!(s.user(user.id)?.vults == 5, "{} vults left", s.user(user.id)?.vults);
+    let last = s.ledger(user.id, 1)?;
+    ensure!(last[0].amount == -15 && last[0].reason == "bought 1 Potion", "ledger has {:?}", last[0]);
+
+    s.buy_item(user.id, "Exp Candy", 1, 0)?;
+    let items: Vec<(String, i32)> = s.inventory(user.id)?.into_iter().map(|i| (i.item, i.count)).collect();
+    ensure!(items == vec![("Exp Candy".to_owned(), 1), ("Potion".to_owned(), 3)], "inventory is {:?}", items);
+    Ok(())
+}
+
+fn items_used(s: &Storage) -> Result<(), String> {
+    let user = s.create_user(fresh_disc_id(), "user")?;
+    let other = s.create_user(fresh_disc_id(), "other")?;
+    let mut deus = s.add_deus(&DeusInstance::new(user.id, "Ignis", 1))?;
+    deus.exp = 500;
+    let none = s.use_item(user.id, "Exp Candy", &deus);
+    ensure!(none == Err(Error::NotFound), "using a missing item gave {:?}", none);
+    ensure!(s.deus(deus.id)?.exp == 0, "a missing item was used anyway");
+
+    s.buy_item(user.id, "Exp Candy", 2, 0)?;
+    let fed = s.use_item(user.id, "Exp Candy", &deus)?;
+    ensure!(fed.exp == 500 && s.deus(deus.id)? == fed, "used on {:?}", fed);
+    ensure!(s.inventory(user.id)?[0].count == 1, "the candy wasn't taken");
+
+    let theirs = s.add_deus(&DeusInstance::new(other.id, "Aqua", 1))?;
+    let stolen = s.use_item(user.id, "Exp Candy", &DeusInstance { owner: user.id, exp: 500, ..theirs.clone() });
+    ensure!(stolen == Err(Error::NotFound), "using on someone else's Deus gave {:?}", stolen);
+    ensure!(s.deus(theirs.id)? == theirs && s.inventory(user.id)?[0].count == 1, "a refused use changed something");
+
+    s.buy_item(user.id, "Junk Trap", 1, 0)?;
+    let caught = s.use_item(user.id, "Junk Trap", &DeusInstance::new(user.id, "Lux", 1))?;
+    ensure!(caught.id != 0 && s.deus(caught.id)?.deus == "Lux", "caught {:?}", caught);
+    s.use_item(user.id, "Exp Candy", &fed)?;
+    ensure!(s.inventory(user.id)?.is_empty(), "used stacks are left: {:?}", s.inventory(user.id)?);
+    Ok(())
+}
+
+fn items_swapped(s: &Storage) -> Result<(), String> {
+    let user = s.create_user(fresh_disc_id(), "holder")?;
+    let deus = s.add_deus(&DeusInstance::new(user.id, "Terra", 1))?;
+    s.buy_item(user.id, "Power Band", 1, 0)?;
+    s.buy_item(user.id, "Potion", 1, 0)?;
+
+    let banded = s.use_item(user.id, "Power Band", &DeusInstance { held: Some("Power Band".to_owned()), ..deus })?;
+    ensure!(s.deus(banded.id)?.held == Some("Power Band".to_owned()), "holds {:?}", s.deus(banded.id)?.held);
+    let potion = s.use_item(user.id, "Potion", &DeusInstance { held: Some("Potion".to_owned()), ..banded })?;
+    let items: Vec<(String, i32)> = s.inventory(user.id)?.into_iter().map(|i| (i.item, i.count)).collect();
+    ensure!(items == vec![("Power Band".to_owned(), 1)], "after the swap the inventory is {:?}", items);
+
+    // A potion drunk in battle is just gone.
+    s.update_deus(&DeusInstance { held: None, ..potion.clone() })?;
+    ensure!(s.deus(potion.id)?.held.is_none(), "the drunk potion is still held");
+    ensure!(s.inventory(user.id)?.len() == 1, "the drunk potion went back to the inventory");
+    Ok(())
+}
+
+fn quest(id: &str) -> Result<&'static Quest::Quest, String> {
+    Quest::find(id).ok_or_else(|| format!("{} is not in the catalog", id))
+}
+
+fn quests_paid_once(s: &Storage) -> Result<(), String> {
+    let user = s.create_user(fresh_disc_id(), "quester")?;
+    let sparring = quest("daily-battles")?;
+    let fresh = s.quest_progress(user.id, &sparring.id, 100)?;
+    ensure!(fresh == Quest::Progress::new(user.id, &sparring.id, 100), "untouched quest is {:?}", fresh);
+
+    ensure!(!s.progress_quest(user.id, sparring, 100, 3)?, "3 of 5 battles completed the quest");
+    ensure!(s.progress_quest(user.id, sparring, 100, 4)?, "7 of 5 battles didn't complete the quest");
+    ensure!(!s.progress_quest(user.id, sparring, 100, 1)?, "the quest completed twice");
+    let done = s.quest_progress(user.id, &sparring.id, 100)?;
+    ensure!(done.progress == 5 && done.completed, "finished quest is {:?}", done);
+
+    ensure!(s.user(user.id)?.vults == 40, "paid {} vults", s.user(user.id)?.vults);
+    let last = s.ledger(user.id, 1)?;
+    ensure!(last[0].reason == sparring.reward_reason(), "ledger has {:?}", last[0]);
+    let items: Vec<(String, i32)> = s.inventory(user.id)?.into_iter().map(|i| (i.item, i.count)).collect();
+    ensure!(items == vec![("Potion".to_owned(), 1)], "inventory is {:?}", items);
+
+    let nobody = s.progress_quest(-1, sparring, 100, 1);
+    ensure!(nobody == Err(Error::NotFound), "progress for nobody gave {:?}", nobody);
+    Ok(())
+}
+
+fn quest_periods(s: &Storage) -> Result<(), String> {
+    let user = s.create_user(fresh_disc_id(), "quester")?;
+    let shopping = quest("daily-spend")?;
+    ensure!(s.progress_quest(user.id, shopping, 7, 250)?, "spending 250 didn't complete the quest");
+    ensure!(!s.progress_quest(user.id, shopping, 8, 150)?, "150 completed the next day's quest");
+    ensure!(s.quest_progress(user.id, &shopping.id, 8)?.progress == 150, "the next day started from {}",
+            s.quest_progress(user.id, &shopping.id, 8)?.progress);
+    ensure!(s.quest_progress(user.id, &shopping.id, 7)?.progress == 200, "the first day moved");
+
+    // Item only rewards leave no ledger line.
+    ensure!(s.ledger(user.id, 10)?.is_empty(), "ledger has {:?}", s.ledger(user.id, 10)?);
+    let items: Vec<(String, i32)> = s.inventory(user.id)?.into_iter().map(|i| (i.item, i.count)).collect();
+    ensure!(items == vec![("Exp Candy".to_owned(), 2)], "inventory is {:?}", items);
+    Ok(())
+}
+
+fn users_listed(s: &Storage) -> Result<(), String> {
+    let first = s.create_user(fresh_disc_id(), "first")?;
+    let second = s.create_user(fresh_disc_id(), "second")?;
+    let users = s.users()?;
+    let at = |id: i32| users.iter().position(|u| u.id == id);
+    ensure!(at(first.id).is_some() && at(first.id) < at(second.id), "listed at {:?} and {:?}", at(first.id), at(second.id));
+    ensure!(users.windows(2).all(|w| w[0].id < w[1].id), "users aren't in id order");
+    Ok(())
+}
+
+fn achievements_once(s: &Storage) -> Result<(), String> {
+    let user = s.create_user(fresh_disc_id(), "achiever")?;
+    ensure!(s.achievements(user.id)?.is_empty(), "a new user has {:?}", s.achievements(user.id)?);
+    ensure!(s.award_achievement(user.id, "gladiator")?, "the first award didn't count");
+    ensure!(s.award_achievement(user.id, "collector")?, "a second achievement didn't count");
+    ensure!(!s.award_achievement(user.id, "gladiator")?, "gladiator was awarded twice");
+
+    let unlocked: Vec<String> = s.achievements(user.id)?.into_iter().map(|u| u.achievement).collect();
+    ensure!(unlocked == vec!["gladiator".to_owned(), "collector".to_owned()], "unlocked {:?}", unlocked);
+    ensure!(s.achievements(user.id)?[0].at >= user.joined, "unlocked before joining");
+
+    let nobody = s.award_achievement(-1, "gladiator");
+    ensure!(nobody == Err(Error::NotFound), "awarding nobody gave {:?}", nobody);
+    Ok(())
+}
+
+// A subscriber of its own, starting now.
+fn subscriber(s: &Storage) -> Result<String, String> {
+    let name = format!("check {}", fresh_disc_id());
+    s.subscribe(&name)?;
+    Ok(name)
+}
+
+// Outbox events about `users`, for a subscriber that never handles any.
+fn events_of(s: &Storage, subscriber: &str, users: &[i32]) -> Result<Vec<Envelope>, String> {
+    let pending = s.pending_events(subscriber, i64::max_value())?;
+    Ok(pending.into_iter().filter(|e| e.event.users().iter().any(|u| users.contains(u))).collect())
+}
+
+fn events_written(s: &Storage) -> Result<(), String> {
+    let watching = subscriber(s)?;
+    let a = s.create_user(fresh_disc_id(), "a")?;
+    let b = s.create_user(fresh_disc_id(), "b")?;
+    s.add_vults(a.id, 100, "check")?;
+    let broke = s.transfer_vults(b.id, a.id, 50, "check");
+    ensure!(broke == Err(Error::NotEnoughVults), "overdrawn transfer gave {:?}", broke);
+    s.transfer_vults(a.id, b.id, 30, "check")?;
+    s.buy_item(a.id, "Junk Trap", 1, 0)?;
+    s.buy_item(a.id, "Fire Stone", 1, 20)?;
+
+    let caught = s.use_item(a.id, "Junk Trap", &DeusInstance::new(a.id, "Lux", 1))?;
+    let ignis = s.add_deus(&DeusInstance::new(a.id, "Ignis", 1))?;
+    s.use_item(a.id, "Fire Stone", &DeusInstance { deus: "Pyros".to_owned(), ..ignis.clone() })?;
+    let grown = s.add_deus(&DeusInstance::new(a.id, "Ignis", 1))?;
+    s.update_deus(&DeusInstance { deus: "Pyros".to_owned(), exp: 2560, ..grown.clone() })?;
+    let side = |species: &str| {
+        Fighter::new(&DeusInstance::new(0, species, 1)).map(|f| Side::new(species, vec![f])).map_err(|e| e.tr("en"))
+    };
+    let battle = Battle::new(1, side("Ignis")?, side("Aqua")?);
+    let teams = [TeamSnapshot::of(&battle.sides[0]), TeamSnapshot::of(&battle.sides[1])];
+    s.save_replay(&Replay::new([a.id, b.id], 1, teams, Vec::new(), Some(b.id)))?;
+
+    let events: Vec<Event> = events_of(s, &watching, &[a.id, b.id])?.into_iter().map(|e| e.event).collect();
+    let expected = vec![
+        Event::UserRegistered { user: a.id },
+        Event::UserRegistered { user: b.id },
+        Event::VultsTransferred { from: a.id, to: b.id, amount: 30 },
+        Event::VultsSpent { user: a.id, amount: 20 },
+        Event::DeusCaptured { user: a.id, deus: caught.id, species: "Lux".to_owned() },
+        Event::DeusEvolved { user: a.id, deus: ignis.id, from: "Ignis".to_owned(), into: "Pyros".to_owned() },
+        Event::DeusEvolved { user: a.id, deus: grown.id, from: "Ignis".to_owned(), into: "Pyros".to_owned() },
+        Event::BattleFinished { players: [a.id, b.id], winner: Some(b.id) },
+    ];
+    ensure!(events == expected, "outbox has {:?}", events);
+    Ok(())
+}
+
+fn events_delivered(s: &Storage) -> Result<(), String> {
+    let (first, second) = (subscriber(s)?, subscriber(s)?);
+    let user = s.create_user(fresh_disc_id(), "subscribed")?;
+    let event = events_of(s, &first, &[user.id])?.pop().ok_or("registering left no event")?;
+    let pending = |name: &str| -> Result<bool, String> {
+        Ok(s.pending_events(name, i64::max_value())?.iter().any(|e| e.id == event.id))
+    };
+
+    ensure!(pending(&first)? && pending(&second)?, "a new event isn't pending");
+    s.mark_delivered(event.id, &first)?;
+    s.mark_delivered(event.id, &first)?;
+    ensure!(!pending(&first)?, "a delivered event is still pending");
+    ensure!(pending(&second)?, "delivering to one subscriber delivered to all");
+    let late = subscriber(s)?;
+    ensure!(!pending(&late)?, "an event from before the subscriber started is pending");
+    ensure!(s.pending_events("never subscribed", i64::max_value())?.is_empty(), "a subscriber that never started has events");
+    let missing = s.mark_delivered(-1, &first);
+    ensure!(missing == Err(Error::NotFound), "delivering a missing event gave {:?}", missing);
+
+    ensure!(s.fail_delivery(event.id, &second)? == 1 && s.fail_delivery(event.id, &second)? == 2, "failures not counted");
+    ensure!(pending(&second)?, "a failed event isn't pending anymore");
+    ensure!(s.fail_delivery(-1, &second) == Err(Error::NotFound), "failed a missing event");
+    Ok(())
+}
+
+fn events_per_user(s: &Storage) -> Result<(), String> {
+    let watching = subscriber(s)?;
+    let a = s.create_user(fresh_disc_id(), "a")?;
+    let b = s.create_user(fresh_disc_id(), "b")?;
+    s.add_vults(a.id, 100, "check")?;
+    s.transfer_vults(a.id, b.id, 30, "check")?;
+
+    let of = |user: i32, limit: i64| -> Result<Vec<Event>, String> {
+        Ok(s.pending_events_for(&watching, user, limit)?.into_iter().map(|e| e.event).collect())
+    };
+    let transfer = Event::VultsTransferred { from: a.id, to: b.id, amount: 30 };
+    ensure!(of(b.id, 10)? == vec![Event::UserRegistered { user: b.id }, transfer.clone()], "b has {:?}", of(b.id, 10)?);
+    ensure!(of(a.id, 1)? == vec![Event::UserRegistered { user: a.id }], "limit ignored, a has {:?}", of(a.id, 1)?);
+    let first = s.pending_events_for(&watching, b.id, 1)?[0].id;
+    s.mark_delivered(first, &watching)?;
+    ensure!(of(b.id, 10)? == vec![transfer], "delivered events are pending, b has {:?}", of(b.id, 10)?);
+    Ok(())
+}
+
+fn jobs_claimed(s: &Storage) -> Result<(), String> {
+    let job = format!("check {}", fresh_disc_id());
+    ensure!(s.job_next_run(&job)?.is_none(), "a new job has a next run");
+    ensure!(s.claim_job(&job, None, 100)?, "scheduling a new job failed");
+    ensure!(!s.claim_job(&job, None, 200)?, "a job was scheduled twice");
+    ensure!(s.job_next_run(&job)? == Some(100), "next run is {:?}", s.job_next_run(&job)?);
+
+    ensure!(!s.claim_job(&job, Some(50), 300)?, "claimed a run that wasn't due");
+    ensure!(s.claim_job(&job, Some(100), 300)?, "claiming the due run failed");
+    ensure!(!s.claim_job(&job, Some(100), 400)?, "the same run was claimed twice");
+    ensure!(s.job_next_run(&job)? == Some(300), "next run is {:?}", s.job_next_run(&job)?);
+    Ok(())
+}
+
+fn energy_spent(s: &Storage) -> Result<(), String> {
+    let user = s.create_user(fresh_disc_id(), "explorer")?;
+    let at = now();
+    ensure!(energy::current(&user, at) == MAX_ENERGY, "new users start with {}", energy::current(&user, at));
+
+    let spent = s.spend_energy(user.id, 30, at)?;
+    ensure!((spent.energy, spent.energy_at) == (MAX_ENERGY - 30, at), "spent down to {:?}", (spent.energy, spent.energy_at));
+    ensure!(s.user(user.id)? == spent, "stored user is {:?}", s.user(user.id)?);
+    // Points came back in between, whatever the bot did meanwhile.
+    let later = s.spend_energy(user.id, MAX_ENERGY - 28, at + 2 * REGEN + 1)?;
+    ensure!((later.energy, later.energy_at) == (0, at + 2 * REGEN), "spent down to {:?}", (later.energy, later.energy_at));
+
+    let broke = s.spend_energy(user.id, 1, at + 2 * REGEN + 2);
+    ensure!(broke == Err(Error::NotEnoughEnergy), "spending more than there is gave {:?}", broke);
+    ensure!(s.user(user.id)? == later, "a failed spend changed the user");
+    ensure!(s.spend_energy(-1, 1, at) == Err(Error::NotFound), "a missing user spent energy");
+    Ok(())
+}
+
+fn energy_refilled(s: &Storage) -> Result<(), String> {
+    let user = s.create_user(fresh_disc_id(), "tired")?;
+    let at = now();
+    s.spend_energy(user.id, 60, at)?;
+    s.add_vults(user.id, 50, "check")?;
+
+    let broke = s.refill_energy(user.id, 40, 80, None, at);
+    ensure!(broke == Err(Error::NotEnoughVults), "refilling without the vults gave {:?}", broke);
+    let refilled = s.refill_energy(user.id, 20, 40, None, at)?;
+    ensure!(refilled.energy == MAX_ENERGY - 40 && refilled.vults == 10, "refilled to {:?}", refilled);
+    let last = s.ledger(user.id, 1)?;
+    ensure!(last[0].amount == -40 && last[0].reason == "bought 20 energy", "ledger has {:?}", last[0]);
+
+    let missing = s.refill_energy(user.id, 30, 0, Some("Energy Drink"), at);
+    ensure!(missing == Err(Error::NotFound), "refilling with a missing item gave {:?}", missing);
+    ensure!(s.user(user.id)?.energy == MAX_ENERGY - 40, "a failed refill added energy");
+    s.buy_item(user.id, "Energy Drink", 1, 0)?;
+    let drunk = s.refill_energy(user.id, 50, 0, Some("Energy Drink"), at)?;
+    ensure!(drunk.energy == MAX_ENERGY, "refills go over the max, got {}", drunk.energy);
+    ensure!(s.inventory(user.id)?.is_empty(), "the item wasn't taken");
+    Ok(())
+}
+
+fn energy_explored(s: &Storage) -> Result<(), String> {
+    let user = s.create_user(fresh_disc_id(), "explorer")?;
+    let account = s.create_guild(fresh_disc_id(), "Guild", user.id)?;
+    let at = now();
+
+    let explored = s.explore(user.id, 30, 20, Some((account.id, 5)), at)?;
+    ensure!(explored.energy == MAX_ENERGY - 30 && explored.vults == 15, "explored into {:?}", explored);
+    ensure!(s.user(user.id)? == explored, "stored user is {:?}", s.user(user.id)?);
+    ensure!(s.user(account.id)?.vults == 5, "the guild got {} in tax", s.user(account.id)?.vults);
+    let taxed = s.ledger(account.id, 1)?;
+    ensure!(taxed[0].reason == "tax on explorer's exploring", "ledger has {:?}", taxed[0]);
+
+    let tired = s.explore(user.id, MAX_ENERGY, 20, Some((account.id, 5)), at);
+    ensure!(tired == Err(Error::NotEnoughEnergy), "exploring without the energy gave {:?}", tired);
+    let missing = s.explore(user.id, 30, 20, Some((-1, 5)), at);
+    ensure!(missing == Err(Error::NotFound), "taxing a missing guild gave {:?}", missing);
+    ensure!(s.user(user.id)? == explored && s.user(account.id)?.vults == 5, "a failed exploration paid something");
+    Ok(())
+}
+
+fn energy_pve(s: &Storage) -> Result<(), String> {
+    let user = s.create_user(fresh_disc_id(), "fighter")?;
+    let deus = s.add_deus(&DeusInstance { held: Some("Potion".to_owned()), ..DeusInstance::new(user.id, "Ignis", 1) })?;
+    let at = now();
+    let fought = vec![DeusInstance { exp: 90, held: None, ..deus.clone() }];
+
+    let spent = s.finish_pve(user.id, 30, &fought, at)?;
+    ensure!(spent.energy == MAX_ENERGY - 30 && s.user(user.id)? == spent, "spent down to {:?}", spent);
+    let saved = s.deus(deus.id)?;
+    ensure!(saved.exp == 90 && saved.held.is_none(), "the battle left {:?}", saved);
+
+    let tired = s.finish_pve(user.id, MAX_ENERGY, &[s.deus(deus.id)?], at);
+    ensure!(tired == Err(Error::NotEnoughEnergy), "fighting without the energy gave {:?}", tired);
+    let stale = s.finish_pve(user.id, 30, &fought, at);
+    ensure!(stale == Err(Error::Changed), "saving a team read before gave {:?}", stale);
+    ensure!(s.user(user.id)? == spent && s.deus(deus.id)? == saved, "a failed battle saved something");
+    Ok(())
+}
+
+fn guilds_created(s: &Storage) -> Result<(), String> {
+    let admin = s.create_user(fresh_disc_id(), "admin")?;
+    let disc_id = fresh_disc_id();
+    let account = s.create_guild(disc_id, "The Hollow", admin.id)?;
+    ensure!(account.kind == UserType::Server && account.vults == 0, "created {:?}", account);
+    ensure!(s.user_by_disc_id(disc_id)? == account, "the server account isn't found by its server");
+    ensure!(s.guild(account.id)?.tax == 0, "new guilds are taxed {}", s.guild(account.id)?.tax);
+    let members: Vec<(i32, bool)> = s.guild_members(account.id)?.iter().map(|m| (m.user, m.admin)).collect();
+    ensure!(members == vec![(admin.id, true)], "members are {:?}", members);
+
+    let again = s.create_guild(disc_id, "Again", admin.id);
+    ensure!(again == Err(Error::AlreadyExists), "creating a guild twice gave {:?}", again);
+    let run_by_guild = s.create_guild(fresh_disc_id(), "Nested", account.id);
+    ensure!(run_by_guild == Err(Error::NotFound), "a guild ran a guild: {:?}", run_by_guild);
+    ensure!(s.guild(admin.id) == Err(Error::NotFound), "a player has guild settings");
+
+    s.set_guild_tax(account.id, 10)?;
+    ensure!(s.guild(account.id)?.tax == 10, "the tax wasn't saved");
+    ensure!(s.set_guild_tax(admin.id, 10) == Err(Error::NotFound), "taxed a player");
+    Ok(())
+}
+
+fn guild_members(s: &Storage) -> Result<(), String> {
+    let admin = s.create_user(fresh_disc_id(), "admin")?;
+    let member = s.create_user(fresh_disc_id(), "member")?;
+    let account = s.create_guild(fresh_disc_id(), "Guild", admin.id)?;
+    let other = s.create_guild(fresh_disc_id(), "Other", admin.id)?;
+
+    let joined = s.join_guild(account.id, member.id)?;
+    ensure!(!joined.admin && joined.user == member.id, "joined as {:?}", joined);
+    ensure!(s.join_guild(account.id, member.id) == Err(Error::AlreadyExists), "joined twice");
+    ensure!(s.join_guild(account.id, other.id) == Err(Error::NotFound), "a guild joined a guild");
+    ensure!(s.join_guild(admin.id, member.id) == Err(Error::NotFound), "joined a player");
+
+    s.set_guild_admin(account.id, member.id, true)?;
+    let admins = s.guild_members(account.id)?.iter().filter(|m| m.admin).count();
+    ensure!(admins == 2, "{} admins", admins);
+    ensure!(s.set_guild_admin(other.id, member.id, true) == Err(Error::NotFound), "made an outsider admin");
+
+    s.leave_guild(account.id, member.id)?;
+    ensure!(s.leave_guild(account.id, member.id) == Err(Error::NotFound), "left twice");
+    let members: Vec<i32> = s.guild_members(account.id)?.iter().map(|m| m.user).collect();
+    ensure!(members == vec![admin.id], "members are {:?}", members);
+    Ok(())
+}
+
+fn deus_bought(s: &Storage) -> Result<(), String> {
+    let user = s.create_user(fresh_disc_id(), "buyer")?;
+    s.add_vults(user.id, 100, "check")?;
+    let broke = s.buy_deus(&DeusInstance::new(user.id, "Lux", 1), 150, "bought Lux");
+    ensure!(broke == Err(Error::NotEnoughVults), "buying too dear gave {:?}", broke.map(|d| d.id));
+    ensure!(s.deus_of(user.id)?.is_empty(), "a failed buy left a Deus");
+
+    let bought = s.buy_deus(&DeusInstance::new(user.id, "Lux", 1), 60, "bought Lux")?;
+    ensure!(s.deus_of(user.id)? == vec![bought], "owned {:?}", s.deus_of(user.id)?);
+    let last = s.ledger(user.id, 1)?;
+    ensure!(last[0].amount == -60 && last[0].reason == "bought Lux" && s.user(user.id)?.vults == 40,
+            "ledger has {:?}", last[0]);
+    Ok(())
+}
+
+fn deus_fused(s: &Storage) -> Result<(), String> {
+    let user = s.create_user(fresh_disc_id(), "fuser")?;
+    let other = s.create_user(fresh_disc_id(), "other")?;
+    let a = s.add_deus(&DeusInstance { held: Some("Potion".to_owned()), ..DeusInstance::new(user.id, "Ignis", 1) })?;
+    let b = s.add_deus(&DeusInstance::new(user.id, "Umbra", 1))?;
+    let theirs = s.add_deus(&DeusInstance::new(other.id, "Lux", 1))?;
+    let mut team = Team::new(user.id, "main");
+    team.members = vec![a.id, b.id];
+    s.save_team(&team)?;
+    let mut child = DeusInstance::new(user.id, "Infernus", 1);
+    child.spells = vec!["Ember".to_owned(), "Toxic Mist".to_owned()];
+
+    let broke = s.fuse_deus([a.id, b.id], &child, 300, "fused Infernus");
+    ensure!(broke.as_ref().err() == Some(&Error::NotEnoughVults), "fusing without the vults gave {:?}", broke);
+    ensure!(s.deus_of(user.id)?.len() == 2, "a failed fusion took the parents");
+    s.add_vults(user.id, 300, "check")?;
+    ensure!(s.fuse_deus([a.id, theirs.id], &child, 300, "fused Infernus").err() == Some(Error::NotFound), "fused someone else's");
+    ensure!(s.fuse_deus([a.id, a.id], &child, 300, "fused Infernus").err() == Some(Error::NotFound), "fused a Deus with itself");
+    ensure!(s.deus_of(user.id)?.len() == 2 && s.user(user.id)?.vults == 300, "a refused fusion left {} Deus and {} vults",
+            s.deus_of(user.id)?.len(), s.user(user.id)?.vults);
+
+    let fused = s.fuse_deus([a.id, b.id], &child, 300, "fused Infernus")?;
+    ensure!(s.deus_of(user.id)? == vec![fused.clone()], "owned {:?}", s.deus_of(user.id)?);
+    ensure!(s.deus(fused.id)?.spells == child.spells, "the child knows {:?}", s.deus(fused.id)?.spells);
+    ensure!(s.teams(user.id)?[0].members.is_empty(), "the parents are still in the team");
+    let items: Vec<(String, i32)> = s.inventory(user.id)?.into_iter().map(|i| (i.item, i.count)).collect();
+    ensure!(items == vec![("Potion".to_owned(), 1)], "the held potion didn't come back: {:?}", items);
+    ensure!(s.user(user.id)?.vults == 0 && s.ledger(user.id, 1)?[0].reason == "fused Infernus", "the fusion wasn't paid");
+    Ok(())
+}
+
+fn deus_ascended(s: &Storage) -> Result<(), String> {
+    let user = s.create_user(fresh_disc_id(), "ascender")?;
+    let lux = s.add_deus(&DeusInstance::new(user.id, "Lux", 1))?;
+    let copy = s.add_deus(&DeusInstance { held: Some("Potion".to_owned()), ..DeusInstance::new(user.id, "Lux", 1) })?;
+    let other = s.add_deus(&DeusInstance::new(user.id, "Umbra", 1))?;
+    let up = DeusInstance { stars: 2, ..lux.clone() };
+
+    ensure!(s.ascend_deus(&up, &[other.id], None, 0, "ascended Lux").err() == Some(Error::NotFound), "used up another species");
+    ensure!(s.ascend_deus(&up, &[lux.id], None, 0, "ascended Lux").err() == Some(Error::NotFound), "used up the Deus itself");
+    ensure!(s.ascend_deus(&DeusInstance { stars: 3, ..lux.clone() }, &[copy.id], None, 0, "ascended Lux").err() == Some(Error::NotFound),
+            "skipped a star");
+    let broke = s.ascend_deus(&up, &[copy.id], None, 200, "ascended Lux");
+    ensure!(broke.as_ref().err() == Some(&Error::NotEnoughVults), "ascending without the vults gave {:?}", broke);
+    ensure!(s.deus_of(user.id)?.len() == 3 && s.deus(lux.id)?.stars == 1, "a refused ascension went through");
+
+    s.add_vults(user.id, 200, "check")?;
+    let ascended = s.ascend_deus(&up, &[copy.id], None, 200, "ascended Lux")?;
+    ensure!(ascended.stars == 2 && s.deus(lux.id)?.stars == 2, "Lux has {} stars", s.deus(lux.id)?.stars);
+    ensure!(s.deus(copy.id).err() == Some(Error::NotFound), "the copy is still there");
+    ensure!(s.user(user.id)?.vults == 0 && s.ledger(user.id, 1)?[0].reason == "ascended Lux", "the ascension wasn't paid");
+    ensure!(s.ascend_deus(&up, &[], None, 0, "ascended Lux").err() == Some(Error::NotFound), "ascended to the same star twice");
+
+    let three = DeusInstance { stars: 3, ..lux.clone() };
+    s.buy_item(user.id, "Star Shard", 3, 0)?;
+    ensure!(s.ascend_deus(&three, &[], Some(("Star Shard", 4)), 0, "ascended Lux").err() == Some(Error::NotFound),
+            "ascended without enough shards");
+    s.ascend_deus(&three, &[], Some(("Star Shard", 3)), 0, "ascended Lux")?;
+    let items: Vec<(String, i32)> = s.inventory(user.id)?.into_iter().map(|i| (i.item, i.count)).collect();
+    ensure!(items == vec![("Potion".to_owned(), 1)], "the inventory holds {:?}", items);
+    ensure!(s.deus(lux.id)?.stars == 3, "the shards didn't raise Lux");
+    Ok(())
+}
+
+fn dex_tracked(s: &Storage) -> Result<(), String> {
+    let user = s.create_user(fresh_disc_id(), "collector")?;
+    let entries = |s: &Storage| -> Result<Vec<(String, bool)>, String> {
+        Ok(s.dex(user.id)?.into_iter().map(|e| (e.deus, e.caught)).collect())
+    };
+    s.see_deus(user.id, &["Lux".to_owned(), "Umbra".to_owned()])?;
+    let mut ignis = s.add_deus(&DeusInstance::new(user.id, "Ignis", 1))?;
+    s.add_deus(&DeusInstance::new(user.id, "Lux", 1))?;
+    ensure!(entries(s)? == vec![("Ignis".to_owned(), true), ("Lux".to_owned(), true), ("Umbra".to_owned(), false)],
+            "the dex holds {:?}", entries(s)?);
+
+    ignis.deus = "Pyros".to_owned();
+    s.update_deus(&ignis)?;
+    s.remove_deus(ignis.id)?;
+    s.see_deus(user.id, &["Ignis".to_owned(), "Umbra".to_owned()])?;
+    ensure!(entries(s)?.len() == 4 && entries(s)?.iter().filter(|e| e.1).count() == 3, "the dex holds {:?}", entries(s)?);
+    ensure!(s.see_deus(-1, &["Lux".to_owned()]) == Err(Error::NotFound), "a missing user saw Lux");
+    Ok(())
+}
+
+fn dex_rewarded(s: &Storage) -> Result<(), String> {
+    let user = s.create_user(fresh_disc_id(), "completionist")?;
+    ensure!(s.reward_dex(user.id, "caught-25", 500, "25% of the dex caught")?, "the milestone wasn't paid");
+    ensure!(!s.reward_dex(user.id, "caught-25", 500, "25% of the dex caught")?, "the milestone was paid twice");
+    ensure!(s.user(user.id)?.vults == 500 && s.ledger(user.id, 1)?[0].reason == "25% of the dex caught", "the milestone paid {}",
+            s.user(user.id)?.vults);
+    ensure!(s.reward_dex(-1, "caught-25", 500, "nobody") == Err(Error::NotFound), "paid a missing user");
+    Ok(())
+}
+
+fn wars_scored(s: &Storage) -> Result<(), String> {
+    let admin = s.create_user(fresh_disc_id(), "admin")?;
+    let first = s.create_guild(fresh_disc_id(), "First", admin.id)?;
+    let second = s.create_guild(fresh_disc_id(), "Second", admin.id)?;
+    let left_out = s.create_guild(fresh_disc_id(), "Left out", admin.id)?;
+    s.set_guild_channel(first.id, Some(42))?;
+    ensure!(s.guilds()?.iter().any(|g| g.account == first.id && g.channel == Some(42)), "the channel wasn't saved");
+    ensure!(s.set_guild_channel(admin.id, Some(42)) == Err(Error::NotFound), "gave a player a channel");
+    let not_a_guild = s.create_war(&War::new(0, 10, 100), &[first.id, admin.id], 0);
+    ensure!(not_a_guild.as_ref().err() == Some(&Error::NotFound), "a player went to war: {:?}", not_a_guild);
+    s.add_vults(first.id, 250, "check")?;
+    s.add_vults(second.id, 200, "check")?;
+    let short = s.create_war(&War::new(0, 10, 500), &[first.id, second.id], 250);
+    ensure!(short.as_ref().err() == Some(&Error::NotEnoughVults), "a guild staked more than it had: {:?}", short);
+    ensure!(s.user(first.id)?.vults == 250, "a war that didn't open kept {} vults", 250 - s.user(first.id)?.vults);
+    s.add_vults(second.id, 50, "check")?;
+
+    // Long over, so nothing else takes it for a running war.
+    let war = s.create_war(&War::new(0, 10, 500), &[first.id, second.id], 250)?;
+    ensure!(war.id != 0 && war.prize == 500 && !war.settled, "created {:?}", war);
+    ensure!(s.user(first.id)?.vults == 0 && s.user(second.id)?.vults == 0, "treasuries hold {} and {}",
+            s.user(first.id)?.vults, s.user(second.id)?.vults);
+    ensure!(s.ledger(second.id, 1)?[0].reason == "stake in a guild war", "the stake isn't in the ledger");
+    ensure!(s.wars(1)? == vec![war.clone()], "the newest war is {:?}", s.wars(1)?);
+    s.score_war(war.id, second.id, 1)?;
+    s.score_war(war.id, second.id, 2)?;
+    s.score_war(war.id, first.id, 1)?;
+    ensure!(s.score_war(war.id, left_out.id, 1) == Err(Error::NotFound), "scored for a guild outside the war");
+    let scores: Vec<(i32, i32)> = s.war_scores(war.id)?.iter().map(|sc| (sc.guild, sc.score)).collect();
+    ensure!(scores == vec![(second.id, 3), (first.id, 1)], "scores are {:?}", scores);
+    s.settle_war(war.id, &[], "check")?;
+    Ok(())
+}
+
+fn wars_settled_once(s: &Storage) -> Result<(), String> {
+    let admin = s.create_user(fresh_disc_id(), "admin")?;
+    let first = s.create_guild(fresh_disc_id(), "First", admin.id)?;
+    let second = s.create_guild(fresh_disc_id(), "Second", admin.id)?;
+    let war = s.create_war(&War::new(0, 10, 300), &[first.id, second.id], 0)?;
+
+    ensure!(s.settle_war(war.id, &[(first.id, 150), (second.id, 150)], "won a guild war")?, "the war didn't settle");
+    ensure!(!s.settle_war(war.id, &[(first.id, 150)], "won a guild war")?, "the war settled twice");
+    ensure!(s.user(first.id)?.vults == 150 && s.user(second.id)?.vults == 150, "treasuries hold {} and {}",
+            s.user(first.id)?.vults, s.user(second.id)?.vults);
+    ensure!(s.ledger(first.id, 1)?[0].reason == "won a guild war", "the prize isn't in the ledger");
+    ensure!(s.wars(10)?.iter().any(|w| w.id == war.id && w.settled), "the war isn't marked settled");
+    ensure!(s.settle_war(-1, &[], "won a guild war") == Err(Error::NotFound), "settled a war that doesn't exist");
+    Ok(())
+}
+
+fn raids_attacked(s: &Storage) -> Result<(), String> {
+    let a = s.create_user(fresh_disc_id(), "a")?;
+    let b = s.create_user(fresh_disc_id(), "b")?;
+    // Over by now, so nothing else takes it for the running raid, attacked as
+    // of when it ran.
+    let at = now() - 600;
+    let raid = s.create_raid(&Raid::new("Tempest", 50, 100, at, at + 60))?;
+    ensure!(raid.id != 0 && raid.life == 100 && raid.max_life == 100, "created {:?}", raid);
+    let stored = |id: i32| -> Result<Raid, String> {
+        s.raids(10)?.into_iter().find(|r| r.id == id).ok_or_else(|| format!("raid {} isn't stored", id))
+    };
+    ensure!(stored(raid.id)? == raid, "raid {} is stored as {:?}", raid.id, stored(raid.id)?);
+
+    let (dealt, after) = s.attack_raid(raid.id, a.id, 30, 10, at)?;
+    ensure!(dealt == 30 && after.life == 70, "dealt {} leaving {}", dealt, after.life);
+    ensure!(s.user(a.id)?.energy == MAX_ENERGY - 10, "the attack cost {} energy", MAX_ENERGY - s.user(a.id)?.energy);
+    s.attack_raid(raid.id, a.id, 20, 10, at)?;
+    let tired = s.attack_raid(raid.id, b.id, 30, MAX_ENERGY + 1, at);
+    ensure!(tired.as_ref().err() == Some(&Error::NotEnoughEnergy), "attacked without the energy: {:?}", tired);
+    ensure!(stored(raid.id)?.life == 50, "a failed attack left the boss at {}", stored(raid.id)?.life);
+
+    let (dealt, after) = s.attack_raid(raid.id, b.id, 80, 0, at)?;
+    ensure!(dealt == 50 && after.life == 0, "the last hit dealt {} leaving {}", dealt, after.life);
+    let late = s.attack_raid(raid.id, a.id, 10, 0, at);
+    ensure!(late.as_ref().err() == Some(&Error::Closed), "attacked a fallen boss: {:?}", late);
+    let damage: Vec<(i32, i32, i32)> = s.raid_damage(raid.id)?.iter().map(|d| (d.user, d.damage, d.attacks)).collect();
+    ensure!(damage == vec![(a.id, 50, 2), (b.id, 50, 1)], "damage is {:?}", damage);
+
+    let over = s.create_raid(&Raid::new("Tempest", 50, 100, at - 120, at - 60))?;
+    ensure!(s.attack_raid(over.id, a.id, 10, 0, at).err() == Some(Error::Closed), "attacked a raid that ended");
+    ensure!(s.attack_raid(-1, a.id, 10, 0, at).err() == Some(Error::NotFound), "attacked a raid that doesn't exist");
+    s.settle_raid(raid.id, &[], "check")?;
+    s.settle_raid(over.id, &[], "check")?;
+    Ok(())
+}
+
+fn raids_settled_once(s: &Storage) -> Result<(), String> {
+    let a = s.create_user(fresh_disc_id(), "a")?;
+    let raid = s.create_raid(&Raid::new("Tempest", 50, 100, 0, 10))?;
+    ensure!(s.settle_raid(raid.id, &[(a.id, 400)], "raid on Tempest")?, "the raid didn't settle");
+    ensure!(!s.settle_raid(raid.id, &[(a.id, 400)], "raid on Tempest")?, "the raid settled twice");
+    ensure!(s.user(a.id)?.vults == 400 && s.ledger(a.id, 1)?[0].reason == "raid on Tempest", "paid {}", s.user(a.id)?.vults);
+    ensure!(s.raids(10)?.iter().any(|r| r.id == raid.id && r.settled), "the raid isn't marked settled");
+    ensure!(s.settle_raid(-1, &[], "check") == Err(Error::NotFound), "settled a raid that doesn't exist");
+    Ok(())
+}
+
+fn tournament_joins_concurrent(s: Arc<Storage>) -> Result<(), String> {
+    let organizer = s.create_user(fresh_disc_id(), "organizer")?;
+    let t = s.create_tournament(&Tournament::new("weekly", Format::Single, organizer.id, 0))?.id;
+    for i in 3..tournament::MAX_ENTRANTS {
+        s.join_tournament(t, s.create_user(fresh_disc_id(), &format!("entrant {}", i))?.id)?;
+    }
+    let mut players = Vec::new();
+    for i in 0..8 {
+        players.push(s.create_user(fresh_disc_id(), &format!("late {}", i))?.id);
+    }
+
+    // 8 players after the last 3 places.
+    let joins: Vec<_> = players.iter().map(|&user| {
+        let s = s.clone();
+        thread::spawn(move || match s.join_tournament(t, user) {
+            Ok(_) => Ok(true),
+            Err(Error::Closed) => Ok(false),
+            Err(e) => Err(e.to_string()),
+        })
+    }).collect();
+    let mut joined = 0;
+    for join in joins {
+        if join.join().map_err(|_| "a join panicked".to_owned())?? {
+            joined += 1;
+        }
+    }
+
+    let entrants = s.entrants(t)?.len();
+    ensure!(joined == 3 && entrants == tournament::MAX_ENTRANTS as usize, "{} joined, {} entrants", joined, entrants);
+    s.finish_tournament(t, &[], "check")?;
+    Ok(())
+}
+
+fn raids_concurrent(s: Arc<Storage>) -> Result<(), String> {
+    // Over by now, see raids_attacked.
+    let at = now() - 600;
+    let raid = s.create_raid(&Raid::new("Tempest", 50, 200, at, at + 60))?.id;
+    let mut players = Vec::new();
+    for i in 0..8 {
+        players.push(s.create_user(fresh_disc_id(), &format!("raider {}", i))?.id);
+    }
+
+    // 8 players hitting for 7, 5 times each: 280 damage on 200 life.
+    let attacks: Vec<_> = players.iter().map(|&user| {
+        let s = s.clone();
+        thread::spawn(move || {
+            let mut dealt = 0;
+            for _ in 0..5 {
+                match s.attack_raid(raid, user, 7, 0, at) {
+                    Ok((hit, _)) => dealt += hit,
+                    Err(Error::Closed) => break,
+                    Err(e) => return Err(e.to_string()),
+                }
+            }
+            Ok(dealt)
+        })
+    }).collect();
+    let mut dealt = 0;
+    for attack in attacks {
+        dealt += attack.join().map_err(|_| "an attack panicked".to_owned())??;
+    }
+
+    let recorded: i32 = s.raid_damage(raid)?.iter().map(|d| d.damage).sum();
+    let life = s.raids(10)?.iter().find(|r| r.id == raid).map(|r| r.life);
+    ensure!(dealt == 200 && recorded == 200 && life == Some(0), "dealt {}, recorded {}, {:?} life left", dealt, recorded, life);
+    s.settle_raid(raid, &[], "check")?;
+    Ok(())
+}
@@ -0,0 +1,1173 @@
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::Mutex;
+
+use achievements::Achievement::Unlocked;
+use battle::replay::Replay;
+use deus::Instance::DeusInstance;
+use deus::Team::Team;
+use dex::Entry::Entry as DexEntry;
+use energy::{self, MAX_ENERGY};
+use events::{Envelope, Event};
+use ladder::{self, Outcome, Tier};
+use quests::Quest::{Progress, Quest};
+use tournament;
+use tournament::Tournament::{Entrant, MatchResult, State as TournamentState, Tournament};
+use users::Guild::{Guild, Member};
+use users::Inventory::ItemStack;
+use users::Ledger::LedgerEntry;
+use users::Profile::Profile;
+use users::Rating::Rating;
+use users::Users::{User, UserType};
+use raids::Raid::{Damage, Raid};
+use wars::War::{Score, War};
+use super::{now, Error, Result, Storage};
+
+// Keeps everything in process, for unit tests and throwaway runs.
+pub struct MemoryStorage {
+    state: Mutex<State>,
+}
+
+#[derive(Default)]
+struct State {
+    users: BTreeMap<i32, User>,
+    deus: BTreeMap<i32, DeusInstance>,
+    ledger: Vec<LedgerEntry>,
+    // Keyed by user, then item.
+    inventory: BTreeMap<(i32, String), i32>,
+    profiles: BTreeMap<i32, Profile>,
+    teams: BTreeMap<i32, Team>,
+    replays: Vec<Replay>,
+    // Keyed by season, then user.
+    ratings: BTreeMap<(i32, i32), Rating>,
+    tournaments: BTreeMap<i32, Tournament>,
+    entrants: Vec<Entrant>,
+    results: Vec<MatchResult>,
+    // Keyed by user, quest and period.
+    quests: BTreeMap<(i32, String, i32), Progress>,
+    achievements: Vec<Unlocked>,
+    outbox: Vec<Envelope>,
+    // Event ids and the subscribers done with them.
+    deliveries: BTreeSet<(i64, String)>,
+    // The last event before each subscriber started.
+    subscribers: BTreeMap<String, i64>,
+    // Failed deliveries, keyed by event and subscriber.
+    failures: BTreeMap<(i64, String), i32>,
+    // Next runs by job name.
+    jobs: BTreeMap<String, i64>,
+    guilds: BTreeMap<i32, Guild>,
+    members: Vec<Member>,
+    wars: Vec<War>,
+    war_scores: Vec<Score>,
+    raids: Vec<Raid>,
+    raid_damage: Vec<Damage>,
+    // When each challenge was made, keyed by challenger, then opponent.
+    challenges: BTreeMap<(i32, i32), i64>,
+    // Keyed by user, then species, whether it's caught.
+    dex: BTreeMap<(i32, String), bool>,
+    dex_rewards: BTreeSet<(i32, String)>,
+    next_user: i32,
+    next_deus: i32,
+    next_team: i32,
+}
+
+impl MemoryStorage {
+    pub fn new() -> MemoryStorage {
+        MemoryStorage { state: Mutex::new(State::default()) }
+    }
+}
+
+impl State {
+    fn publish(&mut self, event: Event) {
+        let envelope = Envelope { id: self.outbox.len() as i64 + 1, event: event, at: now() };
+        self.outbox.push(envelope);
+    }
+
+    fn credit(&mut self, user: i32, amount: i32, reason: &str) -> Result<LedgerEntry> {
+        let balance = {
+            let user = self.users.get_mut(&user).ok_or(Error::NotFound)?;
+            if user.vults + amount < 0 {
+                return Err(Error::NotEnoughVults);
+            }
+            user.vults += amount;
+            user.vults
+        };
+
+        let entry = LedgerEntry {
+            id: self.ledger.len() as i64 + 1,
+            user: user,
+            amount: amount,
+            balance: balance,
+            reason: reason.to_owned(),
+            at: now(),
+        };
+        self.ledger.push(entry.clone());
+        Ok(entry)
+    }
+
+    fn charge_energy(&mut self, user: i32, amount: i32, at: i64) -> Result<User> {
+        let user = self.users.get_mut(&user).ok_or(Error::NotFound)?;
+        let (energy, since) = energy::add(user.energy, user.energy_at, amount, at).ok_or(Error::NotEnoughEnergy)?;
+        user.energy = energy;
+        user.energy_at = since;
+        Ok(user.clone())
+    }
+
+    fn insert_deus(&mut self, deus: &DeusInstance) -> Result<DeusInstance> {
+        if !self.users.contains_key(&deus.owner) {
+            return Err(Error::NotFound);
+        }
+
+        self.next_deus += 1;
+        let instance = DeusInstance {
+            id: self.next_deus,
+            acquired: now(),
+            ..deus.clone()
+        };
+        self.deus.insert(instance.id, instance.clone());
+        self.dex.insert((instance.owner, instance.deus.clone()), true);
+        Ok(instance)
+    }
+
+    // Whether `deus` can be saved: `NotFound` once gone, `Changed` when saved
+    // since it was read.
+    fn saveable(&self, deus: &DeusInstance) -> Result<()> {
+        match self.deus.get(&deus.id) {
+            Some(d) if d.version != deus.version => Err(Error::Changed),
+            Some(_) => Ok(()),
+            None => Err(Error::NotFound),
+        }
+    }
+
+    // A new species is an evolution, it goes in the outbox along with it.
+    fn update_deus(&mut self, deus: &DeusInstance) -> Result<()> {
+        let from = match self.deus.get_mut(&deus.id) {
+            Some(ref d) if d.version != deus.version => return Err(Error::Changed),
+            Some(d) => {
+                let from = d.deus.clone();
+                *d = DeusInstance {
+                    acquired: d.acquired,
+                    variance: d.variance,
+                    nature: d.nature.clone(),
+                    version: d.version + 1,
+                    ..deus.clone()
+                };
+                from
+            }
+            None => return Err(Error::NotFound),
+        };
+        self.dex.insert((deus.owner, deus.deus.clone()), true);
+        if from != deus.deus {
+            self.publish(Event::DeusEvolved { user: deus.owner, deus: deus.id, from: from, into: deus.deus.clone() });
+        }
+        Ok(())
+    }
+
+    // Takes a Deus out of the profiles and teams it's in, along with it.
+    fn remove_deus(&mut self, id: i32) -> Result<DeusInstance> {
+        let removed = self.deus.remove(&id).ok_or(Error::NotFound)?;
+        for profile in self.profiles.values_mut().filter(|p| p.favorite == Some(id)) {
+            profile.favorite = None;
+        }
+        for team in self.teams.values_mut() {
+            team.members.retain(|&d| d != id);
+            if team.lead == Some(id) {
+                team.lead = None;
+            }
+        }
+        Ok(removed)
+    }
+
+    fn give_item(&mut self, user: i32, item: &str, count: i32) -> ItemStack {
+        let stack = self.inventory.entry((user, item.to_owned())).or_insert(0);
+        *stack += count;
+        ItemStack { user: user, item: item.to_owned(), count: *stack }
+    }
+
+    fn take_item(&mut self, user: i32, item: &str) -> Result<()> {
+        let key = (user, item.to_owned());
+        match self.inventory.get(&key).cloned() {
+            Some(1) => {
+                self.inventory.remove(&key);
+            }
+            Some(count) => {
+                self.inventory.insert(key, count - 1);
+            }
+            None => return Err(Error::NotFound),
+        }
+        Ok(())
+    }
+
+    fn rating(&self, user: i32, season: i32) -> Result<Rating> {
+        if !self.users.contains_key(&user) {
+            return Err(Error::NotFound);
+        }
+        let last = self.ratings.values()
+            .filter(|r| r.user == user && r.season <= season)
+            .max_by_key(|r| r.season);
+        Ok(ladder::carry(user, season, last))
+    }
+
+    fn record_match(&mut self, season: i32, a: i32, b: i32, outcome: Outcome) -> Result<(Rating, Rating)> {
+        let mut first = self.rating(a, season)?;
+        let mut second = self.rating(b, season)?;
+        ladder::record(&mut first, &mut second, outcome);
+        self.ratings.insert((season, a), first.clone());
+        self.ratings.insert((season, b), second.clone());
+        Ok((first, second))
+    }
+
+    fn record_pvp(&mut self, winner: i32, loser: i32) -> Result<()> {
+        self.profile_mut(loser)?;
+        {
+            let winner = self.profile_mut(winner)?;
+            winner.wins += 1;
+            winner.win_streak += 1;
+            winner.best_streak = ::std::cmp::max(winner.best_streak, winner.win_streak);
+        }
+        let loser = self.profile_mut(loser)?;
+        loser.losses += 1;
+        loser.win_streak = 0;
+        Ok(())
+    }
+
+    // Whether `result` can be recorded, see Storage::record_result.
+    fn decidable(&self, result: &MatchResult) -> Result<()> {
+        match self.tournaments.get(&result.tournament) {
+            Some(t) if t.state == TournamentState::Running => {}
+            _ => return Err(Error::NotFound),
+        }
+        if !self.users.contains_key(&result.winner) {
+            return Err(Error::NotFound);
+        }
+        if self.results.iter().any(|r| r.tournament == result.tournament && r.number == result.number) {
+            return Err(Error::AlreadyExists);
+        }
+        Ok(())
+    }
+
+    fn insert_replay(&mut self, replay: &Replay) -> Result<Replay> {
+        if replay.players.iter().any(|p| !self.users.contains_key(p)) {
+            return Err(Error::NotFound);
+        }
+
+        let replay = Replay {
+            id: self.replays.len() as i32 + 1,
+            at: now(),
+            ..replay.clone()
+        };
+        self.replays.push(replay.clone());
+        self.publish(Event::BattleFinished { players: replay.players, winner: replay.winner });
+        Ok(replay)
+    }
+
+    // See Storage::pending_events, only the events about `user` if given.
+    fn pending(&self, subscriber: &str, user: Option<i32>, limit: i64) -> Result<Vec<Envelope>> {
+        let since = match self.subscribers.get(subscriber) {
+            Some(&since) => since,
+            None => return Ok(Vec::new()),
+        };
+        Ok(self.outbox.iter()
+            .filter(|e| e.id > since && !self.deliveries.contains(&(e.id, subscriber.to_owned())))
+            .filter(|e| user.map_or(true, |user| e.event.users().contains(&user)))
+            .take(limit as usize)
+            .cloned()
+            .collect())
+    }
+
+    fn open_tournament(&self, id: i32) -> Result<Tournament> {
+        match self.tournaments.get(&id) {
+            Some(t) if t.state == TournamentState::Open => Ok(t.clone()),
+            _ => Err(Error::NotFound),
+        }
+    }
+
+    fn profile_mut(&mut self, user: i32) -> Result<&mut Profile> {
+        if !self.users.contains_key(&user) {
+            return Err(Error::NotFound);
+        }
+        Ok(self.profiles.entry(user).or_insert_with(|| Profile { user: user, ..Profile::default() }))
+    }
+}
+
+impl Storage for MemoryStorage {
+    fn create_user(&self, disc_id: i64, name: &str) -> Result<User> {
+        let mut state = self.state.lock().unwrap();
+        if state.users.values().any(|u| u.disc_id == disc_id) {
+            return Err(Error::AlreadyExists);
+        }
+
+        state.next_user += 1;
+        let user = User {
+            id: state.next_user,
+            disc_id: disc_id,
+            name: name.to_owned(),
+            kind: UserType::Player,
+            vults: 0,
+            joined: now(),
+            energy: MAX_ENERGY,
+            energy_at: 0,
+            locale: None,
+        };
+        state.users.insert(user.id, user.clone());
+        state.publish(Event::UserRegistered { user: user.id });
+        Ok(user)
+    }
+
+    fn user(&self, id: i32) -> Result<User> {
+        self.state.lock().unwrap().users.get(&id).cloned().ok_or(Error::NotFound)
+    }
+
+    fn user_by_disc_id(&self, disc_id: i64) -> Result<User> {
+        self.state.lock().unwrap().users.values()
+            .find(|u| u.disc_id == disc_id)
+            .cloned()
+            .ok_or(Error::NotFound)
+    }
+
+    fn users(&self) -> Result<Vec<User>> {
+        Ok(self.state.lock().unwrap().users.values().cloned().collect())
+    }
+
+    fn set_locale(&self, user: i32, locale: Option<&str>) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        state.users.get_mut(&user).ok_or(Error::NotFound)?.locale = locale.map(|l| l.to_owned());
+        Ok(())
+    }
+
+    fn create_guild(&self, disc_id: i64, name: &str, admin: i32) -> Result<User> {
+        let mut state = self.state.lock().unwrap();
+        if state.users.values().any(|u| u.disc_id == disc_id) {
+            return Err(Error::AlreadyExists);
+        }
+        match state.users.get(&admin) {
+            Some(u) if u.kind == UserType::Player => {}
+            _ => return Err(Error::NotFound),
+        }
+
+        state.next_user += 1;
+        let account = User {
+            id: state.next_user,
+            disc_id: disc_id,
+            name: name.to_owned(),
+            kind: UserType::Server,
+            vults: 0,
+            joined: now(),
+            energy: MAX_ENERGY,
+            energy_at: 0,
+            locale: None,
+        };
+        state.users.insert(account.id, account.clone());
+        state.guilds.insert(account.id, Guild { account: account.id, tax: 0, channel: None });
+        state.members.push(Member { guild: account.id, user: admin, admin: true, joined: now() });
+        Ok(account)
+    }
+
+    fn guild(&self, account: i32) -> Result<Guild> {
+        self.state.lock().unwrap().guilds.get(&account).cloned().ok_or(Error::NotFound)
+    }
+
+    fn guilds(&self) -> Result<Vec<Guild>> {
+        Ok(self.state.lock().unwrap().guilds.values().cloned().collect())
+    }
+
+    fn set_guild_channel(&self, account: i32, channel: Option<i64>) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        state.guilds.get_mut(&account).ok_or(Error::NotFound)?.channel = channel;
+        Ok(())
+    }
+
+    fn set_guild_tax(&self, account: i32, tax: i32) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        state.guilds.get_mut(&account).ok_or(Error::NotFound)?.tax = tax;
+        Ok(())
+    }
+
+    fn guild_members(&self, account: i32) -> Result<Vec<Member>> {
+        Ok(self.state.lock().unwrap().members.iter().filter(|m| m.guild == account).cloned().collect())
+    }
+
+    fn join_guild(&self, account: i32, user: i32) -> Result<Member> {
+        let mut state = self.state.lock().unwrap();
+        match state.users.get(&user) {
+            Some(u) if u.kind == UserType::Player && state.guilds.contains_key(&account) => {}
+            _ => return Err(Error::NotFound),
+        }
+        if state.members.iter().any(|m| m.guild == account && m.user == user) {
+            return Err(Error::AlreadyExists);
+        }
+
+        let member = Member { guild: account, user: user, admin: false, joined: now() };
+        state.members.push(member.clone());
+        Ok(member)
+    }
+
+    fn leave_guild(&self, account: i32, user: i32) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        let i = state.members.iter().position(|m| m.guild == account && m.user == user).ok_or(Error::NotFound)?;
+        state.members.remove(i);
+        Ok(())
+    }
+
+    fn set_guild_admin(&self, account: i32, user: i32, admin: bool) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        let member = state.members.iter_mut().find(|m| m.guild == account && m.user == user).ok_or(Error::NotFound)?;
+        member.admin = admin;
+        Ok(())
+    }
+
+    fn add_deus(&self, deus: &DeusInstance) -> Result<DeusInstance> {
+        self.state.lock().unwrap().insert_deus(deus)
+    }
+
+    fn buy_deus(&self, deus: &DeusInstance, price: i32, reason: &str) -> Result<DeusInstance> {
+        let mut state = self.state.lock().unwrap();
+        state.credit(deus.owner, -price, reason)?;
+        state.insert_deus(deus)
+    }
+
+    fn fuse_deus(&self, parents: [i32; 2], child: &DeusInstance, price: i32, reason: &str) -> Result<DeusInstance> {
+        let mut state = self.state.lock().unwrap();
+        let owned = parents.iter().all(|id| state.deus.get(id).map_or(false, |d| d.owner == child.owner));
+        if parents[0] == parents[1] || !owned {
+            return Err(Error::NotFound);
+        }
+
+        if price > 0 {
+            state.credit(child.owner, -price, reason)?;
+            state.publish(Event::VultsSpent { user: child.owner, amount: price });
+        }
+        for &parent in &parents {
+            if let Some(item) = state.remove_deus(parent)?.held {
+                state.give_item(child.owner, &item, 1);
+            }
+        }
+        state.insert_deus(child)
+    }
+
+    fn ascend_deus(&self, deus: &DeusInstance, copies: &[i32], material: Option<(&str, i32)>, price: i32, reason: &str)
+                   -> Result<DeusInstance> {
+        let mut state = self.state.lock().unwrap();
+        let below = state.deus.get(&deus.id)
+            .map_or(false, |d| d.owner == deus.owner && d.deus == deus.deus && d.stars == deus.stars - 1);
+        let copied = copies.iter().enumerate().all(|(i, id)| {
+            *id != deus.id && !copies[..i].contains(id)
+                && state.deus.get(id).map_or(false, |d| d.owner == deus.owner && d.deus == deus.deus)
+        });
+        let stocked = material.map_or(true, |(item, count)| {
+            count >= 0 && state.inventory.get(&(deus.owner, item.to_owned())).cloned().unwrap_or(0) >= count
+        });
+        if !below || !copied || !stocked {
+            return Err(Error::NotFound);
+        }
+
+        if price > 0 {
+            state.credit(deus.owner, -price, reason)?;
+            state.publish(Event::VultsSpent { user: deus.owner, amount: price });
+        }
+        for &copy in copies {
+            if let Some(item) = state.remove_deus(copy)?.held {
+                state.give_item(deus.owner, &item, 1);
+            }
+        }
+        if let Some((item, count)) = material {
+            for _ in 0..count {
+                state.take_item(deus.owner, item)?;
+            }
+        }
+        let ascended = state.deus.get_mut(&deus.id).ok_or(Error::NotFound)?;
+        ascended.stars = deus.stars;
+        ascended.version += 1;
+        Ok(ascended.clone())
+    }
+
+    fn deus(&self, id: i32) -> Result<DeusInstance> {
+        self.state.lock().unwrap().deus.get(&id).cloned().ok_or(Error::NotFound)
+    }
+
+    fn deus_of(&self, owner: i32) -> Result<Vec<DeusInstance>> {
+        Ok(self.state.lock().unwrap().deus.values()
+            .filter(|d| d.owner == owner)
+            .cloned()
+            .collect())
+    }
+
+    fn update_deus(&self, deus: &DeusInstance) -> Result<()> {
+        self.state.lock().unwrap().update_deus(deus)
+    }
+
+    #[cfg(test)]
+    fn remove_deus(&self, id: i32) -> Result<()> {
+        self.state.lock().unwrap().remove_deus(id).map(|_| ())
+    }
+
+    #[cfg(test)]
+    fn add_vults(&self, user: i32, amount: i32, reason: &str) -> Result<LedgerEntry> {
+        self.state.lock().unwrap().credit(user, amount, reason)
+    }
+
+    fn transfer_vults(&self, from: i32, to: i32, amount: i32, reason: &str) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        if !state.users.contains_key(&to) {
+            return Err(Error::NotFound);
+        }
+
+        state.credit(from, -amount, reason)?;
+        state.credit(to, amount, reason)?;
+        state.publish(Event::VultsTransferred { from: from, to: to, amount: amount });
+        Ok(())
+    }
+
+    #[cfg(test)]
+    fn spend_energy(&self, user: i32, amount: i32, at: i64) -> Result<User> {
+        self.state.lock().unwrap().charge_energy(user, -amount, at)
+    }
+
+    fn refill_energy(&self, user: i32, amount: i32, price: i32, item: Option<&str>, at: i64) -> Result<User> {
+        let mut state = self.state.lock().unwrap();
+        if !state.users.contains_key(&user) {
+            return Err(Error::NotFound);
+        }
+        if let Some(item) = item {
+            if !state.inventory.contains_key(&(user, item.to_owned())) {
+                return Err(Error::NotFound);
+            }
+        }
+
+        if price > 0 {
+            state.credit(user, -price, &format!("bought {} energy", amount))?;
+            state.publish(Event::VultsSpent { user: user, amount: price });
+        }
+        if let Some(item) = item {
+            state.take_item(user, item)?;
+        }
+        state.charge_energy(user, amount, at)
+    }
+
+    fn explore(&self, user: i32, cost: i32, found: i32, tax: Option<(i32, i32)>, at: i64) -> Result<User> {
+        let mut state = self.state.lock().unwrap();
+        if tax.map_or(false, |(guild, _)| !state.users.contains_key(&guild)) {
+            return Err(Error::NotFound);
+        }
+        let mut explored = state.charge_energy(user, -cost, at)?;
+        let mut kept = found;
+        if let Some((guild, tax)) = tax {
+            state.credit(guild, tax, &format!("tax on {}'s exploring", explored.name))?;
+            kept -= tax;
+        }
+        if kept > 0 {
+            explored.vults = state.credit(user, kept, "found while exploring")?.balance;
+        }
+        Ok(explored)
+    }
+
+    fn finish_pve(&self, user: i32, cost: i32, team: &[DeusInstance], at: i64) -> Result<User> {
+        let mut state = self.state.lock().unwrap();
+        for d in team {
+            state.saveable(d)?;
+        }
+        let spent = state.charge_energy(user, -cost, at)?;
+        for d in team {
+            state.update_deus(d)?;
+        }
+        Ok(spent)
+    }
+
+    #[cfg(test)]
+    fn ledger(&self, user: i32, limit: i64) -> Result<Vec<LedgerEntry>> {
+        Ok(self.state.lock().unwrap().ledger.iter()
+            .rev()
+            .filter(|e| e.user == user)
+            .take(limit as usize)
+            .cloned()
+            .collect())
+    }
+
+    fn inventory(&self, user: i32) -> Result<Vec<ItemStack>> {
+        Ok(self.state.lock().unwrap().inventory.iter()
+            .filter(|&(&(owner, _), _)| owner == user)
+            .map(|(&(owner, ref item), &count)| ItemStack { user: owner, item: item.clone(), count: count })
+            .collect())
+    }
+
+    fn buy_item(&self, user: i32, item: &str, count: i32, price: i32) -> Result<ItemStack> {
+        let mut state = self.state.lock().unwrap();
+        state.credit(user, -price * count, &format!("bought {} {}", count, item))?;
+        if price * count > 0 {
+            state.publish(Event::VultsSpent { user: user, amount: price * count });
+        }
+        Ok(state.give_item(user, item, count))
+    }
+
+    fn use_item(&self, user: i32, item: &str, deus: &DeusInstance) -> Result<DeusInstance> {
+        let mut state = self.state.lock().unwrap();
+        if deus.owner != user {
+            return Err(Error::NotFound);
+        }
+        let held = if deus.id == 0 {
+            None
+        } else {
+            match state.deus.get(&deus.id) {
+                Some(d) if d.owner == user => d.held.clone(),
+                _ => return Err(Error::NotFound),
+            }
+        };
+        if deus.id != 0 {
+            state.saveable(deus)?;
+        }
+
+        state.take_item(user, item)?;
+        if deus.id == 0 {
+            let caught = state.insert_deus(deus)?;
+            state.publish(Event::DeusCaptured { user: user, deus: caught.id, species: caught.deus.clone() });
+            return Ok(caught);
+        }
+        state.update_deus(deus)?;
+        if let Some(held) = held {
+            if deus.held.as_ref() != Some(&held) {
+                state.give_item(user, &held, 1);
+            }
+        }
+        Ok(state.deus[&deus.id].clone())
+    }
+
+    fn profile(&self, user: i32) -> Result<Profile> {
+        self.state.lock().unwrap().profile_mut(user).map(|p| p.clone())
+    }
+
+    fn set_profile_settings(&self, user: i32, favorite: Option<i32>, hidden: i32) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        let profile = state.profile_mut(user)?;
+        profile.favorite = favorite;
+        profile.hidden = hidden;
+        Ok(())
+    }
+
+    #[cfg(test)]
+    fn record_pvp(&self, winner: i32, loser: i32) -> Result<()> {
+        self.state.lock().unwrap().record_pvp(winner, loser)
+    }
+
+    fn teams(&self, owner: i32) -> Result<Vec<Team>> {
+        Ok(self.state.lock().unwrap().teams.values()
+            .filter(|t| t.owner == owner)
+            .cloned()
+            .collect())
+    }
+
+    fn save_team(&self, team: &Team) -> Result<Team> {
+        let mut state = self.state.lock().unwrap();
+        if !state.users.contains_key(&team.owner) {
+            return Err(Error::NotFound);
+        }
+        if state.teams.values().any(|t| t.owner == team.owner && t.name == team.name && t.id != team.id) {
+            return Err(Error::AlreadyExists);
+        }
+        if team.members.iter().any(|d| !state.deus.contains_key(d)) {
+            return Err(Error::NotFound);
+        }
+
+        let mut team = team.clone();
+        if team.id == 0 {
+            state.next_team += 1;
+            team.id = state.next_team;
+        } else if !state.teams.contains_key(&team.id) {
+            return Err(Error::NotFound);
+        }
+        state.teams.insert(team.id, team.clone());
+        Ok(team)
+    }
+
+    fn delete_team(&self, id: i32) -> Result<()> {
+        self.state.lock().unwrap().teams.remove(&id).map(|_| ()).ok_or(Error::NotFound)
+    }
+
+    fn activate_team(&self, owner: i32, id: i32) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        if state.teams.get(&id).map(|t| t.owner) != Some(owner) {
+            return Err(Error::NotFound);
+        }
+        for team in state.teams.values_mut().filter(|t| t.owner == owner) {
+            team.active = team.id == id;
+        }
+        Ok(())
+    }
+
+    #[cfg(test)]
+    fn save_replay(&self, replay: &Replay) -> Result<Replay> {
+        self.state.lock().unwrap().insert_replay(replay)
+    }
+
+    fn replay(&self, id: i32) -> Result<Replay> {
+        self.state.lock().unwrap().replays.iter().find(|r| r.id == id).cloned().ok_or(Error::NotFound)
+    }
+
+    fn rating(&self, user: i32, season: i32) -> Result<Rating> {
+        self.state.lock().unwrap().rating(user, season)
+    }
+
+    #[cfg(test)]
+    fn record_match(&self, season: i32, a: i32, b: i32, outcome: Outcome) -> Result<(Rating, Rating)> {
+        self.state.lock().unwrap().record_match(season, a, b, outcome)
+    }
+
+    fn challenge(&self, from: i32, to: i32, at: i64) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        if !state.users.contains_key(&from) || !state.users.contains_key(&to) {
+            return Err(Error::NotFound);
+        }
+        state.challenges.insert((from, to), at);
+        Ok(())
+    }
+
+    fn finish_pvp(&self, season: i32, replay: &Replay, deus: &[DeusInstance], since: i64)
+                  -> Result<(Replay, Rating, Rating)> {
+        let mut state = self.state.lock().unwrap();
+        let (a, b) = (replay.players[0], replay.players[1]);
+        match state.challenges.get(&(a, b)) {
+            Some(&at) if at >= since => {}
+            _ => return Err(Error::Closed),
+        }
+        // Everything is checked before anything changes.
+        if replay.players.iter().any(|p| !state.users.contains_key(p)) {
+            return Err(Error::NotFound);
+        }
+        for d in deus {
+            state.saveable(d)?;
+        }
+
+        state.challenges.remove(&(a, b));
+        let outcome = super::outcome(replay);
+        match outcome {
+            Outcome::Win => state.record_pvp(a, b)?,
+            Outcome::Loss => state.record_pvp(b, a)?,
+            Outcome::Draw => {}
+        }
+        let (first, second) = state.record_match(season, a, b, outcome)?;
+        let saved = state.insert_replay(replay)?;
+        for d in deus {
+            state.update_deus(d)?;
+        }
+        Ok((saved, first, second))
+    }
+
+    fn ladder(&self, season: i32, limit: i64) -> Result<Vec<(Rating, String)>> {
+        let state = self.state.lock().unwrap();
+        let mut ladder: Vec<(Rating, String)> = state.ratings.values()
+            .filter(|r| r.season == season && r.games > 0)
+            .map(|r| (r.clone(), state.users[&r.user].name.clone()))
+            .collect();
+        ladder.sort_by(|&(ref a, _), &(ref b, _)| b.rating.cmp(&a.rating).then(a.user.cmp(&b.user)));
+        ladder.truncate(limit as usize);
+        Ok(ladder)
+    }
+
+    fn reward_season(&self, season: i32) -> Result<Vec<Rating>> {
+        let mut state = self.state.lock().unwrap();
+        let due: Vec<Rating> = state.ratings.values()
+            .filter(|r| r.season == season && !r.rewarded && ladder::placed(r))
+            .cloned()
+            .collect();
+
+        for rating in &due {
+            let reward = Tier::of(rating.rating).reward();
+            state.credit(rating.user, reward.vults, &ladder::reward_reason(rating))?;
+            if let Some(species) = reward.deus {
+                state.insert_deus(&DeusInstance::roll(rating.user, species, 1))?;
+            }
+            state.ratings.get_mut(&(season, rating.user)).unwrap().rewarded = true;
+        }
+        Ok(due.into_iter().map(|r| Rating { rewarded: true, ..r }).collect())
+    }
+
+    fn create_tournament(&self, tournament: &Tournament) -> Result<Tournament> {
+        let mut state = self.state.lock().unwrap();
+        if !state.users.contains_key(&tournament.organizer) {
+            return Err(Error::NotFound);
+        }
+
+        let tournament = Tournament {
+            id: state.tournaments.len() as i32 + 1,
+            created: now(),
+            ..tournament.clone()
+        };
+        state.tournaments.insert(tournament.id, tournament.clone());
+        Ok(tournament)
+    }
+
+    fn tournament(&self, id: i32) -> Result<Tournament> {
+        self.state.lock().unwrap().tournaments.get(&id).cloned().ok_or(Error::NotFound)
+    }
+
+    fn tournaments(&self) -> Result<Vec<Tournament>> {
+        Ok(self.state.lock().unwrap().tournaments.values()
+            .filter(|t| t.state != TournamentState::Finished)
+            .cloned()
+            .collect())
+    }
+
+    fn join_tournament(&self, id: i32, user: i32) -> Result<Entrant> {
+        let mut state = self.state.lock().unwrap();
+        let tournament = state.open_tournament(id)?;
+        if !state.users.contains_key(&user) {
+            return Err(Error::NotFound);
+        }
+        if state.entrants.iter().any(|e| e.tournament == id && e.user == user) {
+            return Err(Error::AlreadyExists);
+        }
+        if state.entrants.iter().filter(|e| e.tournament == id).count() >= tournament::MAX_ENTRANTS as usize {
+            return Err(Error::Closed);
+        }
+        if tournament.fee > 0 {
+            state.credit(user, -tournament.fee, &format!("tournament {} entry", id))?;
+            state.publish(Event::VultsSpent { user: user, amount: tournament.fee });
+        }
+
+        let entrant = Entrant { tournament: id, user: user, seed: 0, ready: None, claimed: None, joined: now() };
+        state.entrants.push(entrant.clone());
+        Ok(entrant)
+    }
+
+    fn leave_tournament(&self, id: i32, user: i32) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        let tournament = state.open_tournament(id)?;
+        let i = state.entrants.iter().position(|e| e.tournament == id && e.user == user).ok_or(Error::NotFound)?;
+        if tournament.fee > 0 {
+            state.credit(user, tournament.fee, &format!("tournament {} refund", id))?;
+        }
+        state.entrants.remove(i);
+        Ok(())
+    }
+
+    fn entrants(&self, id: i32) -> Result<Vec<Entrant>> {
+        let mut entrants: Vec<Entrant> = self.state.lock().unwrap().entrants.iter()
+            .filter(|e| e.tournament == id)
+            .cloned()
+            .collect();
+        entrants.sort_by_key(|e| e.seed);
+        Ok(entrants)
+    }
+
+    fn start_tournament(&self, id: i32, seeds: &[i32]) -> Result<Tournament> {
+        let mut state = self.state.lock().unwrap();
+        state.open_tournament(id)?;
+        if seeds.iter().any(|&user| !state.entrants.iter().any(|e| e.tournament == id && e.user == user)) {
+            return Err(Error::NotFound);
+        }
+
+        for entrant in state.entrants.iter_mut().filter(|e| e.tournament == id) {
+            entrant.seed = seeds.iter().position(|&user| user == entrant.user).map_or(0, |i| i as i32 + 1);
+        }
+        let tournament = state.tournaments.get_mut(&id).unwrap();
+        tournament.state = TournamentState::Running;
+        tournament.started = now();
+        Ok(tournament.clone())
+    }
+
+    fn set_ready(&self, id: i32, user: i32, number: Option<i32>) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        let entrant = state.entrants.iter_mut().find(|e| e.tournament == id && e.user == user).ok_or(Error::NotFound)?;
+        entrant.ready = number;
+        Ok(())
+    }
+
+    fn claim_match(&self, id: i32, number: i32, players: [i32; 2]) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        let ready = |e: &Entrant| e.tournament == id && players.contains(&e.user) && e.ready == Some(number);
+        if state.entrants.iter().filter(|e| ready(e)).count() < 2 {
+            return Err(Error::NotFound);
+        }
+        for entrant in state.entrants.iter_mut().filter(|e| ready(e)) {
+            entrant.ready = None;
+            entrant.claimed = Some(number);
+        }
+        Ok(())
+    }
+
+    fn record_result(&self, result: &MatchResult) -> Result<MatchResult> {
+        let mut state = self.state.lock().unwrap();
+        state.decidable(result)?;
+        let result = MatchResult { at: now(), ..result.clone() };
+        state.results.push(result.clone());
+        Ok(result)
+    }
+
+    fn finish_match(&self, result: &MatchResult, played: Option<&Replay>, drank: &[i32]) -> Result<MatchResult> {
+        let mut state = self.state.lock().unwrap();
+        state.decidable(result)?;
+        let replay = match played {
+            Some(played) => Some(state.insert_replay(played)?.id),
+            None => None,
+        };
+        let result = MatchResult { replay: replay, at: now(), ..result.clone() };
+        state.results.push(result.clone());
+        for id in drank {
+            if let Some(deus) = state.deus.get_mut(id) {
+                deus.held = None;
+                deus.version += 1;
+            }
+        }
+        Ok(result)
+    }
+
+    fn results(&self, id: i32) -> Result<Vec<MatchResult>> {
+        Ok(self.state.lock().unwrap().results.iter().filter(|r| r.tournament == id).cloned().collect())
+    }
+
+    fn finish_tournament(&self, id: i32, payouts: &[(i32, i32)], reason: &str) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        match state.tournaments.get(&id) {
+            Some(t) if t.state != TournamentState::Finished => {}
+            _ => return Err(Error::NotFound),
+        }
+        if payouts.iter().any(|&(user, _)| !state.users.contains_key(&user)) {
+            return Err(Error::NotFound);
+        }
+
+        for &(user, vults) in payouts {
+            state.credit(user, vults, reason)?;
+        }
+        state.tournaments.get_mut(&id).unwrap().state = TournamentState::Finished;
+        Ok(())
+    }
+
+    fn quest_progress(&self, user: i32, quest: &str, period: i32) -> Result<Progress> {
+        let state = self.state.lock().unwrap();
+        Ok(state.quests.get(&(user, quest.to_owned(), period)).cloned().unwrap_or_else(|| Progress::new(user, quest, period)))
+    }
+
+    fn progress_quest(&self, user: i32, quest: &Quest, period: i32, amount: i32) -> Result<bool> {
+        let mut state = self.state.lock().unwrap();
+        if !state.users.contains_key(&user) {
+            return Err(Error::NotFound);
+        }
+        let key = (user, quest.id.clone(), period);
+        let mut progress = state.quests.get(&key).cloned().unwrap_or_else(|| Progress::new(user, &quest.id, period));
+        if progress.completed || amount <= 0 {
+            return Ok(false);
+        }
+
+        let goal = quest.objective.goal();
+        progress.progress = (progress.progress + amount).min(goal);
+        progress.completed = progress.progress == goal;
+        if progress.completed {
+            if quest.reward.vults > 0 {
+                state.credit(user, quest.reward.vults, &quest.reward_reason())?;
+            }
+            for reward in &quest.reward.items {
+                state.give_item(user, &reward.item, reward.count);
+            }
+        }
+        state.quests.insert(key, progress.clone());
+        Ok(progress.completed)
+    }
+
+    fn achievements(&self, user: i32) -> Result<Vec<Unlocked>> {
+        let state = self.state.lock().unwrap();
+        Ok(state.achievements.iter().filter(|a| a.user == user).cloned().collect())
+    }
+
+    fn award_achievement(&self, user: i32, achievement: &str) -> Result<bool> {
+        let mut state = self.state.lock().unwrap();
+        if !state.users.contains_key(&user) {
+            return Err(Error::NotFound);
+        }
+        if state.achievements.iter().any(|a| a.user == user && a.achievement == achievement) {
+            return Ok(false);
+        }
+        state.achievements.push(Unlocked { user: user, achievement: achievement.to_owned(), at: now() });
+        Ok(true)
+    }
+
+    fn dex(&self, user: i32) -> Result<Vec<DexEntry>> {
+        Ok(self.state.lock().unwrap().dex.iter()
+            .filter(|&(&(u, _), _)| u == user)
+            .map(|(&(u, ref deus), &caught)| DexEntry { user: u, deus: deus.clone(), caught: caught })
+            .collect())
+    }
+
+    fn see_deus(&self, user: i32, species: &[String]) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        if !state.users.contains_key(&user) {
+            return Err(Error::NotFound);
+        }
+        for deus in species {
+            state.dex.entry((user, deus.clone())).or_insert(false);
+        }
+        Ok(())
+    }
+
+    fn reward_dex(&self, user: i32, milestone: &str, vults: i32, reason: &str) -> Result<bool> {
+        let mut state = self.state.lock().unwrap();
+        if !state.users.contains_key(&user) {
+            return Err(Error::NotFound);
+        }
+        if !state.dex_rewards.insert((user, milestone.to_owned())) {
+            return Ok(false);
+        }
+        state.credit(user, vults, reason)?;
+        Ok(true)
+    }
+
+    fn subscribe(&self, subscriber: &str) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        let end = state.outbox.len() as i64;
+        state.subscribers.entry(subscriber.to_owned()).or_insert(end);
+        Ok(())
+    }
+
+    fn pending_events(&self, subscriber: &str, limit: i64) -> Result<Vec<Envelope>> {
+        self.state.lock().unwrap().pending(subscriber, None, limit)
+    }
+
+    fn pending_events_for(&self, subscriber: &str, user: i32, limit: i64) -> Result<Vec<Envelope>> {
+        self.state.lock().unwrap().pending(subscriber, Some(user), limit)
+    }
+
+    fn mark_delivered(&self, event: i64, subscriber: &str) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        if !state.outbox.iter().any(|e| e.id == event) {
+            return Err(Error::NotFound);
+        }
+        state.deliveries.insert((event, subscriber.to_owned()));
+        Ok(())
+    }
+
+    fn fail_delivery(&self, event: i64, subscriber: &str) -> Result<i32> {
+        let mut state = self.state.lock().unwrap();
+        if !state.outbox.iter().any(|e| e.id == event) {
+            return Err(Error::NotFound);
+        }
+        let attempts = state.failures.entry((event, subscriber.to_owned())).or_insert(0);
+        *attempts += 1;
+        Ok(*attempts)
+    }
+
+    fn create_war(&self, war: &War, guilds: &[i32], stake: i32) -> Result<War> {
+        let mut state = self.state.lock().unwrap();
+        if guilds.iter().any(|g| !state.guilds.contains_key(g)) {
+            return Err(Error::NotFound);
+        }
+        if guilds.iter().any(|g| state.users[g].vults < stake) {
+            return Err(Error::NotEnoughVults);
+        }
+        for &guild in guilds {
+            state.credit(guild, -stake, "stake in a guild war")?;
+        }
+
+        let war = War { id: state.wars.len() as i32 + 1, ..war.clone() };
+        state.wars.push(war.clone());
+        for &guild in guilds {
+            state.war_scores.push(Score { war: war.id, guild: guild, score: 0 });
+        }
+        Ok(war)
+    }
+
+    fn wars(&self, limit: i64) -> Result<Vec<War>> {
+        Ok(self.state.lock().unwrap().wars.iter().rev().take(limit as usize).cloned().collect())
+    }
+
+    fn war_scores(&self, war: i32) -> Result<Vec<Score>> {
+        let mut scores: Vec<Score> = self.state.lock().unwrap().war_scores.iter().filter(|s| s.war == war).cloned().collect();
+        scores.sort_by_key(|s| (-s.score, s.guild));
+        Ok(scores)
+    }
+
+    fn score_war(&self, war: i32, guild: i32, points: i32) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        let score = state.war_scores.iter_mut().find(|s| s.war == war && s.guild == guild).ok_or(Error::NotFound)?;
+        score.score += points;
+        Ok(())
+    }
+
+    fn settle_war(&self, war: i32, payouts: &[(i32, i32)], reason: &str) -> Result<bool> {
+        let mut state = self.state.lock().unwrap();
+        match state.wars.iter().find(|w| w.id == war) {
+            Some(w) if w.settled => return Ok(false),
+            Some(_) => {}
+            None => return Err(Error::NotFound),
+        }
+        if payouts.iter().any(|&(guild, _)| !state.users.contains_key(&guild)) {
+            return Err(Error::NotFound);
+        }
+
+        for &(guild, vults) in payouts {
+            state.credit(guild, vults, reason)?;
+        }
+        if let Some(w) = state.wars.iter_mut().find(|w| w.id == war) {
+            w.settled = true;
+        }
+        Ok(true)
+    }
+
+    fn create_raid(&self, raid: &Raid) -> Result<Raid> {
+        let mut state = self.state.lock().unwrap();
+        let raid = Raid { id: state.raids.len() as i32 + 1, ..raid.clone() };
+        state.raids.push(raid.clone());
+        Ok(raid)
+    }
+
+    fn raids(&self, limit: i64) -> Result<Vec<Raid>> {
+        Ok(self.state.lock().unwrap().raids.iter().rev().take(limit as usize).cloned().collect())
+    }
+
+    fn attack_raid(&self, raid: i32, user: i32, damage: i32, energy: i32, at: i64) -> Result<(i32, Raid)> {
+        let mut state = self.state.lock().unwrap();
+        let life = match state.raids.iter().find(|r| r.id == raid) {
+            Some(r) if r.open(at) => r.life,
+            Some(_) => return Err(Error::Closed),
+            None => return Err(Error::NotFound),
+        };
+        state.charge_energy(user, -energy, at)?;
+
+        let dealt = damage.max(0).min(life);
+        match state.raid_damage.iter_mut().find(|d| d.raid == raid && d.user == user) {
+            Some(d) => {
+                d.damage += dealt;
+                d.attacks += 1;
+            }
+            None => state.raid_damage.push(Damage { raid: raid, user: user, damage: dealt, attacks: 1 }),
+        }
+        let after = state.raids.iter_mut().find(|r| r.id == raid).ok_or(Error::NotFound)?;
+        after.life -= dealt;
+        Ok((dealt, after.clone()))
+    }
+
+    fn raid_damage(&self, raid: i32) -> Result<Vec<Damage>> {
+        let mut damage: Vec<Damage> = self.state.lock().unwrap().raid_damage.iter().filter(|d| d.raid == raid).cloned().collect();
+        damage.sort_by_key(|d| (-d.damage, d.user));
+        Ok(damage)
+    }
+
+    fn settle_raid(&self, raid: i32, payouts: &[(i32, i32)], reason: &str) -> Result<bool> {
+        let mut state = self.state.lock().unwrap();
+        match state.raids.iter().find(|r| r.id == raid) {
+            Some(r) if r.settled => return Ok(false),
+            Some(_) => {}
+            None => return Err(Error::NotFound),
+        }
+        if payouts.iter().any(|&(user, _)| !state.users.contains_key(&user)) {
+            return Err(Error::NotFound);
+        }
+
+        for &(user, vults) in payouts {
+            state.credit(user, vults, reason)?;
+        }
+        if let Some(r) = state.raids.iter_mut().find(|r| r.id == raid) {
+            r.settled = true;
+        }
+        Ok(true)
+    }
+
+    fn job_next_run(&self, job: &str) -> Result<Option<i64>> {
+        Ok(self.state.lock().unwrap().jobs.get(job).cloned())
+    }
+
+    fn claim_job(&self, job: &str, due: Option<i64>, next: i64) -> Result<bool> {
+        let mut state = self.state.lock().unwrap();
+        if state.jobs.get(job).cloned() != due {
+            return Ok(false);
+        }
+        state.jobs.insert(job.to_owned(), next);
+        Ok(true)
+    }
+}
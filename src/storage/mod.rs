@@ -0,0 +1,365 @@
+#[cfg(test)]
+mod conformance;
+pub mod memory;
+pub mod postgres;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+
+use std::fmt;
+use std::sync::Arc;
+
+use serenity::client::Context;
+use typemap::Key;
+
+use battle::replay::Replay;
+use deus::Instance::DeusInstance;
+use deus::Team::Team;
+use i18n;
+use dex::Entry::Entry as DexEntry;
+use ladder::Outcome;
+use achievements::Achievement::Unlocked;
+use events::Envelope;
+use quests::Quest::{Progress, Quest};
+use tournament::Tournament::{Entrant, MatchResult, Tournament};
+use users::Guild::{Guild, Member};
+use users::Inventory::ItemStack;
+#[cfg(test)]
+use users::Ledger::LedgerEntry;
+use users::Profile::Profile;
+use users::Rating::Rating;
+use users::Users::User;
+use raids::Raid::{Damage, Raid};
+use wars::War::{Score, War};
+
+#[derive(Debug, PartialEq)]
+pub enum Error {
+    NotFound,
+    AlreadyExists,
+    NotEnoughVults,
+    NotEnoughEnergy,
+    // The raid, event or challenge is over.
+    Closed,
+    // Saved from a copy read before somebody else's change, see
+    // DeusInstance::version.
+    Changed,
+    Backend(String),
+}
+
+pub type Result<T> = ::std::result::Result<T, Error>;
+
+impl Error {
+    // The message saying what went wrong.
+    pub fn key(&self) -> &'static str {
+        match *self {
+            Error::NotFound => "storage-not-found",
+            Error::AlreadyExists => "storage-exists",
+            Error::NotEnoughVults => "storage-no-vults",
+            Error::NotEnoughEnergy => "storage-no-energy",
+            Error::Closed => "storage-closed",
+            Error::Changed => "storage-changed",
+            Error::Backend(_) => "storage-backend",
+        }
+    }
+}
+
+// For the logs, in the default language.
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&i18n::tr(i18n::default(), self.key(), &[]))?;
+        match *self {
+            Error::Backend(ref e) => write!(f, " ({})", e),
+            _ => Ok(()),
+        }
+    }
+}
+
+// Lets commands use `?` on storage calls: the error becomes its message key,
+// said in the user's language once the command is over, see
+// cmds::failed. What a backend error was about only goes to the log.
+impl From<Error> for String {
+    fn from(e: Error) -> String {
+        if let Error::Backend(_) = e {
+            println!("[ storage ] - {}", e);
+        }
+        e.key().to_owned()
+    }
+}
+
+// Everything the bot persists. Each call is atomic on its own: a failed call
+// leaves nothing behind.
+pub trait Storage: Send + Sync {
+    fn create_user(&self, disc_id: i64, name: &str) -> Result<User>;
+    fn user(&self, id: i32) -> Result<User>;
+    fn user_by_disc_id(&self, disc_id: i64) -> Result<User>;
+    // Everyone, oldest first.
+    fn users(&self) -> Result<Vec<User>>;
+    // The language replies to `user` are in, None to go back to their
+    // server's or the default.
+    fn set_locale(&self, user: i32, locale: Option<&str>) -> Result<()>;
+
+    // A server account for the discord server `disc_id`, with `admin` as its
+    // first member.
+    fn create_guild(&self, disc_id: i64, name: &str, admin: i32) -> Result<User>;
+    // `NotFound` for player accounts.
+    fn guild(&self, account: i32) -> Result<Guild>;
+    // Every guild, oldest first.
+    fn guilds(&self) -> Result<Vec<Guild>>;
+    fn set_guild_tax(&self, account: i32, tax: i32) -> Result<()>;
+    fn set_guild_channel(&self, account: i32, channel: Option<i64>) -> Result<()>;
+    // In the order they joined.
+    fn guild_members(&self, account: i32) -> Result<Vec<Member>>;
+    // Only players join, `AlreadyExists` for members.
+    fn join_guild(&self, account: i32, user: i32) -> Result<Member>;
+    // `NotFound` for someone who isn't a member.
+    fn leave_guild(&self, account: i32, user: i32) -> Result<()>;
+    fn set_guild_admin(&self, account: i32, user: i32, admin: bool) -> Result<()>;
+
+    // Stores a new instance, its id and acquisition time are filled in.
+    fn add_deus(&self, deus: &DeusInstance) -> Result<DeusInstance>;
+    // Stores a new instance paid `price` vults by its owner.
+    fn buy_deus(&self, deus: &DeusInstance, price: i32, reason: &str) -> Result<DeusInstance>;
+    // Replaces `parents` with `child`, paid `price` vults by its owner. The
+    // parents have to be two of the owner's, their held items go back to the
+    // inventory.
+    fn fuse_deus(&self, parents: [i32; 2], child: &DeusInstance, price: i32, reason: &str) -> Result<DeusInstance>;
+    // Raises `deus` to its `stars` from the star below, using up `copies` of
+    // the same species or `material` items, paid `price` vults by its owner.
+    // The copies' held items go back to the inventory.
+    fn ascend_deus(&self, deus: &DeusInstance, copies: &[i32], material: Option<(&str, i32)>, price: i32, reason: &str)
+                   -> Result<DeusInstance>;
+    fn deus(&self, id: i32) -> Result<DeusInstance>;
+    fn deus_of(&self, owner: i32) -> Result<Vec<DeusInstance>>;
+    // Variance, nature and acquisition time never change once stored. Held
+    // items are saved as they are, the inventory isn't touched.
+    fn update_deus(&self, deus: &DeusInstance) -> Result<()>;
+    #[cfg(test)]
+    fn remove_deus(&self, id: i32) -> Result<()>;
+
+    // Adds `amount` (negative to spend) to a balance, failing with
+    // `NotEnoughVults` rather than going below zero.
+    #[cfg(test)]
+    fn add_vults(&self, user: i32, amount: i32, reason: &str) -> Result<LedgerEntry>;
+    fn transfer_vults(&self, from: i32, to: i32, amount: i32, reason: &str) -> Result<()>;
+    #[cfg(test)]
+    fn ledger(&self, user: i32, limit: i64) -> Result<Vec<LedgerEntry>>;
+
+    // Takes `amount` energy as it is at `at`, failing with `NotEnoughEnergy`
+    // rather than going below zero. Returns the user as saved.
+    #[cfg(test)]
+    fn spend_energy(&self, user: i32, amount: i32, at: i64) -> Result<User>;
+    // Adds `amount` energy, up to energy::MAX_ENERGY, paying `price` vults
+    // and one `item` if given. `NotFound` without the item.
+    fn refill_energy(&self, user: i32, amount: i32, price: i32, item: Option<&str>, at: i64) -> Result<User>;
+    // Spends `cost` energy exploring and pays what was found, all at once:
+    // `found` vults to the user, less `tax` (guild account, vults) paid to the
+    // guild. Fails like spend_energy, with nothing paid.
+    fn explore(&self, user: i32, cost: i32, found: i32, tax: Option<(i32, i32)>, at: i64) -> Result<User>;
+    // Saves a battle against Deus nobody plays all at once: the `cost` energy
+    // it took and the team as the battle left it. Fails like spend_energy,
+    // with nothing saved.
+    fn finish_pve(&self, user: i32, cost: i32, team: &[DeusInstance], at: i64) -> Result<User>;
+
+    // A user's items, by name.
+    fn inventory(&self, user: i32) -> Result<Vec<ItemStack>>;
+    // Pays `price` vults for each of `count` items, returns the new stack.
+    fn buy_item(&self, user: i32, item: &str, count: i32, price: i32) -> Result<ItemStack>;
+    // Takes one `item` from the user and saves `deus` along with it: a new
+    // Deus (id 0) is added, an owned one updated. An item it held and doesn't
+    // anymore goes back to the inventory. `NotFound` without the item, or for
+    // someone else's Deus.
+    fn use_item(&self, user: i32, item: &str, deus: &DeusInstance) -> Result<DeusInstance>;
+
+    // Every user has a profile, untouched ones get the defaults.
+    fn profile(&self, user: i32) -> Result<Profile>;
+    fn set_profile_settings(&self, user: i32, favorite: Option<i32>, hidden: i32) -> Result<()>;
+    #[cfg(test)]
+    fn record_pvp(&self, winner: i32, loser: i32) -> Result<()>;
+
+    fn teams(&self, owner: i32) -> Result<Vec<Team>>;
+    // Inserts the team when its id is 0, names are unique per owner.
+    fn save_team(&self, team: &Team) -> Result<Team>;
+    fn delete_team(&self, id: i32) -> Result<()>;
+    // Makes a team the one used in battles, the owner's other teams stop being.
+    fn activate_team(&self, owner: i32, id: i32) -> Result<()>;
+
+    // Stores a finished battle, its id and time are filled in.
+    #[cfg(test)]
+    fn save_replay(&self, replay: &Replay) -> Result<Replay>;
+    fn replay(&self, id: i32) -> Result<Replay>;
+
+    // Players who haven't played a season yet start it from the soft reset
+    // of their last one, see ladder::carry.
+    fn rating(&self, user: i32, season: i32) -> Result<Rating>;
+    // Rates one game of `season` between `a` and `b`, `outcome` being a's.
+    #[cfg(test)]
+    fn record_match(&self, season: i32, a: i32, b: i32, outcome: Outcome) -> Result<(Rating, Rating)>;
+    // Challenges `to` to a rated battle, replacing an earlier challenge
+    // between them.
+    fn challenge(&self, from: i32, to: i32, at: i64) -> Result<()>;
+    // Saves a rated game of `season` all at once: the replay, both players'
+    // results and ratings, and their Deus as the battle left them. The
+    // replay's first player challenged the second at `since` or later, the
+    // challenge is used up. `Closed` when there is no such challenge.
+    fn finish_pvp(&self, season: i32, replay: &Replay, deus: &[DeusInstance], since: i64)
+                  -> Result<(Replay, Rating, Rating)>;
+    // Highest rated first with their names, only players who played the
+    // season.
+    fn ladder(&self, season: i32, limit: i64) -> Result<Vec<(Rating, String)>>;
+    // Pays every placed player of a season the reward of their final tier,
+    // once. Returns the ratings that were paid.
+    fn reward_season(&self, season: i32) -> Result<Vec<Rating>>;
+
+    // Its id and creation time are filled in.
+    fn create_tournament(&self, tournament: &Tournament) -> Result<Tournament>;
+    fn tournament(&self, id: i32) -> Result<Tournament>;
+    // The ones that aren't finished, oldest first.
+    fn tournaments(&self) -> Result<Vec<Tournament>>;
+    // Takes the entry fee. Tournaments that aren't open are not found, full
+    // ones (see tournament::MAX_ENTRANTS) are `Closed`.
+    fn join_tournament(&self, id: i32, user: i32) -> Result<Entrant>;
+    // Gives the entry fee back, only while the tournament is open.
+    fn leave_tournament(&self, id: i32, user: i32) -> Result<()>;
+    // In seed order once started, in join order before.
+    fn entrants(&self, id: i32) -> Result<Vec<Entrant>>;
+    // Closes entries and seeds the entrants, `seeds` are user ids best first.
+    fn start_tournament(&self, id: i32, seeds: &[i32]) -> Result<Tournament>;
+    // Checks an entrant in for a match, None checks them out.
+    fn set_ready(&self, id: i32, user: i32, number: Option<i32>) -> Result<()>;
+    // Checks both `players` out of match `number` and marks it claimed to play
+    // it, once. `NotFound` unless both were checked in for it.
+    fn claim_match(&self, id: i32, number: i32, players: [i32; 2]) -> Result<()>;
+    // Matches are decided once, a second result is `AlreadyExists`.
+    fn record_result(&self, result: &MatchResult) -> Result<MatchResult>;
+    // Saves a played match all at once: the replay if there is one, the
+    // result pointing to it and the potions drunk, the Deus in `drank` hold
+    // nothing after. A match decided meanwhile is `AlreadyExists` and nothing
+    // is saved.
+    fn finish_match(&self, result: &MatchResult, replay: Option<&Replay>, drank: &[i32]) -> Result<MatchResult>;
+    fn results(&self, id: i32) -> Result<Vec<MatchResult>>;
+    // Finishes a tournament and pays `payouts` (user, vults), once. Prizes
+    // and refunds both go through here.
+    fn finish_tournament(&self, id: i32, payouts: &[(i32, i32)], reason: &str) -> Result<()>;
+
+    // Nothing done yet when the user hasn't started the quest.
+    fn quest_progress(&self, user: i32, quest: &str, period: i32) -> Result<Progress>;
+    // Adds to a quest's progress, up to its goal. Reaching the goal pays the
+    // reward along with it, once. True when this call completed the quest.
+    fn progress_quest(&self, user: i32, quest: &Quest, period: i32, amount: i32) -> Result<bool>;
+
+    // In the order they were unlocked.
+    fn achievements(&self, user: i32) -> Result<Vec<Unlocked>>;
+    // True when the user didn't have the achievement yet.
+    fn award_achievement(&self, user: i32, achievement: &str) -> Result<bool>;
+
+    // The species a user has seen or caught. Storing a Deus marks its
+    // species caught by its owner.
+    fn dex(&self, user: i32) -> Result<Vec<DexEntry>>;
+    fn see_deus(&self, user: i32, species: &[String]) -> Result<()>;
+    // Pays a dex milestone, true when it wasn't paid yet.
+    fn reward_dex(&self, user: i32, milestone: &str, vults: i32, reason: &str) -> Result<bool>;
+
+    // Starts `subscriber` at the end of the outbox, so it doesn't get the
+    // history. Does nothing for a subscriber that started already.
+    fn subscribe(&self, subscriber: &str) -> Result<()>;
+    // Events are added to the outbox by the calls that cause them, in the
+    // same transaction. These are the ones `subscriber` hasn't handled yet
+    // since it started, oldest first, none before it does.
+    fn pending_events(&self, subscriber: &str, limit: i64) -> Result<Vec<Envelope>>;
+    // The pending events about `user`, see events::Event::users.
+    fn pending_events_for(&self, subscriber: &str, user: i32, limit: i64) -> Result<Vec<Envelope>>;
+    fn mark_delivered(&self, event: i64, subscriber: &str) -> Result<()>;
+    // Counts a failed delivery, returns how many there were so far.
+    fn fail_delivery(&self, event: i64, subscriber: &str) -> Result<i32>;
+
+    // Stores a war with `guilds` in it at no score, each paying `stake` from
+    // its treasury, its id is filled in. `NotEnoughVults` and no war when one
+    // of them can't.
+    fn create_war(&self, war: &War, guilds: &[i32], stake: i32) -> Result<War>;
+    // Newest first, settled or not.
+    fn wars(&self, limit: i64) -> Result<Vec<War>>;
+    // Highest first, the earliest guild going first on a tie.
+    fn war_scores(&self, war: i32) -> Result<Vec<Score>>;
+    // `NotFound` for a guild that isn't in the war.
+    fn score_war(&self, war: i32, guild: i32, points: i32) -> Result<()>;
+    // Settles a war and pays `payouts` (guild, vults), once. False when it
+    // was settled already.
+    fn settle_war(&self, war: i32, payouts: &[(i32, i32)], reason: &str) -> Result<bool>;
+
+    // Stores a raid, its id is filled in.
+    fn create_raid(&self, raid: &Raid) -> Result<Raid>;
+    // Newest first.
+    fn raids(&self, limit: i64) -> Result<Vec<Raid>>;
+    // Takes up to `damage` off the boss' life for `user`, charging `energy`,
+    // and returns how much it took along with the raid after. Concurrent
+    // attacks never take more than is left. `Closed` once the raid isn't
+    // open, see Raid::open.
+    fn attack_raid(&self, raid: i32, user: i32, damage: i32, energy: i32, at: i64) -> Result<(i32, Raid)>;
+    // Most damage first, the earliest user going first on a tie.
+    fn raid_damage(&self, raid: i32) -> Result<Vec<Damage>>;
+    // Settles a raid and pays `payouts` (user, vults), once. False when it
+    // was settled already.
+    fn settle_raid(&self, raid: i32, payouts: &[(i32, i32)], reason: &str) -> Result<bool>;
+
+    // When a scheduled job runs next, None if it never was scheduled.
+    fn job_next_run(&self, job: &str) -> Result<Option<i64>>;
+    // Moves a job's next run from `due` to `next`, `due` being None for a job
+    // that was never scheduled. False when it had moved already: someone
+    // else claimed the run.
+    fn claim_job(&self, job: &str, due: Option<i64>, next: i64) -> Result<bool>;
+}
+
+pub struct Store;
+
+impl Key for Store {
+    type Value = Arc<Storage>;
+}
+
+pub fn get(ctx: &Context) -> ::std::result::Result<Arc<Storage>, String> {
+    ctx.data.lock().unwrap()
+        .get::<Store>()
+        .cloned()
+        .ok_or_else(|| "storage is not set up".to_owned())
+}
+
+// Picks the backend from HOLLOW_STORAGE: postgres (default), sqlite or memory.
+pub fn from_env() -> Arc<Storage> {
+    use std::env;
+    use db;
+
+    match env::var("HOLLOW_STORAGE").ok().as_ref().map(|s| s.as_str()) {
+        None | Some("postgres") => {
+            let pool = db::connect(&db::Settings::from_env()).unwrap_or_else(|e| panic!("database: {}", e));
+            Arc::new(postgres::PgStorage::new(pool).expect("postgres migrations"))
+        }
+        #[cfg(feature = "sqlite")]
+        Some("sqlite") => {
+            let path = env::var("HOLLOW_SQLITE").unwrap_or_else(|_| "junkbot.sqlite".to_owned());
+            Arc::new(sqlite::SqliteStorage::open(&path).expect("sqlite database"))
+        }
+        Some("memory") => Arc::new(memory::MemoryStorage::new()),
+        Some(other) => panic!("unknown HOLLOW_STORAGE '{}'", other),
+    }
+}
+
+pub fn now() -> i64 {
+    ::time::get_time().sec
+}
+
+// How a rated game went for the first player of its replay.
+fn outcome(replay: &Replay) -> Outcome {
+    match replay.winner {
+        None => Outcome::Draw,
+        Some(winner) if winner == replay.players[0] => Outcome::Win,
+        Some(_) => Outcome::Loss,
+    }
+}
+
+// How the spells a Deus learned or takes into battle are kept, see
+// DeusInstance::spells and DeusInstance::equipped.
+pub fn encode_spells(spells: &[String]) -> String {
+    spells.join(",")
+}
+
+pub fn decode_spells(spells: &str) -> Vec<String> {
+    spells.split(',').filter(|s| !s.is_empty()).map(|s| s.to_owned()).collect()
+}
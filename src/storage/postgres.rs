@@ -0,0 +1,1555 @@
+use postgres::GenericConnection;
+use postgres::error::Error as PgError;
+use postgres::rows::Row;
+
+use achievements::Achievement::Unlocked;
+use battle::replay::{self, Replay};
+use db::{self, Conn, Pool};
+use deus::Instance::DeusInstance;
+use deus::Stats::Stats;
+use deus::Team::Team;
+use dex::Entry::Entry as DexEntry;
+use energy;
+use events::{self, Envelope, Event};
+use ladder::{self, Outcome, Tier};
+use quests::Quest::{Progress, Quest};
+use tournament;
+use tournament::Tournament::{Entrant, Format, MatchResult, State as TournamentState, Tournament};
+use users::Guild::{Guild, Member};
+use users::Inventory::ItemStack;
+use users::Ledger::LedgerEntry;
+use users::Profile::Profile;
+use users::Rating::Rating;
+use users::Users::{User, UserType};
+use raids::Raid::{Damage, Raid};
+use wars::War::{Score, War};
+use super::{decode_spells, encode_spells, now, Error, Result, Storage};
+
+const MIGRATIONS: &'static [&'static str] = &[
+    include_str!("../../migrations/postgres/001_init.sql"),
+    include_str!("../../migrations/postgres/002_profiles.sql"),
+    include_str!("../../migrations/postgres/003_teams.sql"),
+    include_str!("../../migrations/postgres/004_stats.sql"),
+    include_str!("../../migrations/postgres/005_replays.sql"),
+    include_str!("../../migrations/postgres/006_ladder.sql"),
+    include_str!("../../migrations/postgres/007_tournaments.sql"),
+    include_str!("../../migrations/postgres/008_items.sql"),
+    include_str!("../../migrations/postgres/009_quests.sql"),
+    include_str!("../../migrations/postgres/010_achievements.sql"),
+    include_str!("../../migrations/postgres/011_outbox.sql"),
+    include_str!("../../migrations/postgres/012_jobs.sql"),
+    include_str!("../../migrations/postgres/013_energy.sql"),
+    include_str!("../../migrations/postgres/014_guilds.sql"),
+    include_str!("../../migrations/postgres/015_wars.sql"),
+    include_str!("../../migrations/postgres/016_raids.sql"),
+    include_str!("../../migrations/postgres/017_fusion.sql"),
+    include_str!("../../migrations/postgres/018_move_sets.sql"),
+    include_str!("../../migrations/postgres/019_dex.sql"),
+    include_str!("../../migrations/postgres/020_locales.sql"),
+    include_str!("../../migrations/postgres/021_challenges.sql"),
+    include_str!("../../migrations/postgres/022_deliveries.sql"),
+    include_str!("../../migrations/postgres/023_claims.sql"),
+    include_str!("../../migrations/postgres/024_deus_versions.sql"),
+];
+
+impl From<PgError> for Error {
+    fn from(e: PgError) -> Error {
+        Error::Backend(e.to_string())
+    }
+}
+
+pub struct PgStorage {
+    pool: Pool,
+}
+
+impl PgStorage {
+    pub fn new(pool: Pool) -> Result<PgStorage> {
+        let storage = PgStorage { pool: pool };
+        migrate(&*storage.conn()?)?;
+        Ok(storage)
+    }
+
+    fn conn(&self) -> Result<Conn> {
+        db::get(&self.pool).map_err(Error::Backend)
+    }
+}
+
+fn migrate(conn: &GenericConnection) -> Result<()> {
+    conn.batch_execute("CREATE TABLE IF NOT EXISTS schema_version (version INT NOT NULL)")?;
+
+    let tx = conn.transaction()?;
+    // Two bots starting together must not both run the same migration.
+    tx.batch_execute("LOCK TABLE schema_version IN EXCLUSIVE MODE")?;
+    let current: i32 = tx.query("SELECT COALESCE(MAX(version), 0) FROM schema_version", &[])?
+        .get(0)
+        .get(0);
+
+    for (i, sql) in MIGRATIONS.iter().enumerate().skip(current as usize) {
+        tx.batch_execute(sql)?;
+        tx.execute("INSERT INTO schema_version (version) VALUES ($1)", &[&(i as i32 + 1)])?;
+    }
+
+    tx.commit()?;
+    Ok(())
+}
+
+fn user(row: Row) -> User {
+    User {
+        id: row.get("id"),
+        disc_id: row.get("disc_id"),
+        name: row.get("name"),
+        // The column is checked to be one of them.
+        kind: UserType::from_name(&row.get::<_, String>("kind")).unwrap_or(UserType::Player),
+        vults: row.get("vults"),
+        joined: row.get("joined"),
+        energy: row.get("energy"),
+        energy_at: row.get("energy_at"),
+        locale: row.get("locale"),
+    }
+}
+
+fn deus_instance(row: Row) -> DeusInstance {
+    DeusInstance {
+        id: row.get("id"),
+        owner: row.get("owner"),
+        deus: row.get("deus"),
+        exp: row.get("exp"),
+        stars: row.get("stars"),
+        acquired: row.get("acquired"),
+        variance: Stats {
+            life: row.get("var_life"),
+            atk: row.get("var_atk"),
+            def: row.get("var_def"),
+            speed: row.get("var_speed"),
+            attr: row.get("var_attr"),
+        },
+        nature: row.get("nature"),
+        points: row.get("points"),
+        training: Stats {
+            life: row.get("train_life"),
+            atk: row.get("train_atk"),
+            def: row.get("train_def"),
+            speed: row.get("train_speed"),
+            attr: row.get("train_attr"),
+        },
+        held: row.get("held"),
+        spells: decode_spells(&row.get::<_, String>("spells")),
+        equipped: decode_spells(&row.get::<_, String>("equipped")),
+        version: row.get("version"),
+    }
+}
+
+fn guild(row: Row) -> Guild {
+    Guild {
+        account: row.get("account"),
+        tax: row.get("tax"),
+        channel: row.get("channel"),
+    }
+}
+
+fn member(row: Row) -> Member {
+    Member {
+        guild: row.get("guild"),
+        user: row.get("user_id"),
+        admin: row.get("admin"),
+        joined: row.get("joined"),
+    }
+}
+
+fn war_row(row: Row) -> War {
+    War {
+        id: row.get("id"),
+        starts: row.get("starts"),
+        ends: row.get("ends"),
+        prize: row.get("prize"),
+        settled: row.get("settled"),
+    }
+}
+
+fn war_score(row: Row) -> Score {
+    Score {
+        war: row.get("war_id"),
+        guild: row.get("guild"),
+        score: row.get("score"),
+    }
+}
+
+fn raid_row(row: Row) -> Raid {
+    Raid {
+        id: row.get("id"),
+        boss: row.get("boss"),
+        level: row.get("level"),
+        life: row.get("life"),
+        max_life: row.get("max_life"),
+        starts: row.get("starts"),
+        ends: row.get("ends"),
+        settled: row.get("settled"),
+    }
+}
+
+fn raid_damage(row: Row) -> Damage {
+    Damage {
+        raid: row.get("raid_id"),
+        user: row.get("user_id"),
+        damage: row.get("damage"),
+        attacks: row.get("attacks"),
+    }
+}
+
+fn item_stack(row: Row) -> ItemStack {
+    ItemStack {
+        user: row.get("user_id"),
+        item: row.get("item"),
+        count: row.get("count"),
+    }
+}
+
+fn ledger_entry(row: Row) -> LedgerEntry {
+    LedgerEntry {
+        id: row.get("id"),
+        user: row.get("user_id"),
+        amount: row.get("amount"),
+        balance: row.get("balance"),
+        reason: row.get("reason"),
+        at: row.get("at"),
+    }
+}
+
+fn profile(row: Row) -> Profile {
+    Profile {
+        user: row.get("user_id"),
+        favorite: row.get("favorite_deus"),
+        wins: row.get("wins"),
+        losses: row.get("losses"),
+        win_streak: row.get("win_streak"),
+        best_streak: row.get("best_streak"),
+        hidden: row.get("hidden"),
+    }
+}
+
+fn team(row: Row) -> Team {
+    Team {
+        id: row.get("id"),
+        owner: row.get("owner"),
+        name: row.get("name"),
+        members: Vec::new(),
+        lead: row.get("lead"),
+        active: row.get("active"),
+    }
+}
+
+fn rating(row: Row) -> Rating {
+    Rating {
+        user: row.get("user_id"),
+        season: row.get("season"),
+        rating: row.get("rating"),
+        games: row.get("games"),
+        wins: row.get("wins"),
+        losses: row.get("losses"),
+        peak: row.get("peak"),
+        rewarded: row.get("rewarded"),
+    }
+}
+
+fn tournament_row(row: Row) -> Result<Tournament> {
+    let format: String = row.get("format");
+    let state: String = row.get("state");
+    Ok(Tournament {
+        id: row.get("id"),
+        name: row.get("name"),
+        format: Format::from_name(&format).ok_or_else(|| Error::Backend(format!("unknown format {}", format)))?,
+        organizer: row.get("organizer"),
+        fee: row.get("fee"),
+        state: TournamentState::from_name(&state).ok_or_else(|| Error::Backend(format!("unknown state {}", state)))?,
+        created: row.get("created"),
+        started: row.get("started"),
+    })
+}
+
+fn entrant(row: Row) -> Entrant {
+    Entrant {
+        tournament: row.get("tournament_id"),
+        user: row.get("user_id"),
+        seed: row.get("seed"),
+        ready: row.get("ready"),
+        claimed: row.get("claimed"),
+        joined: row.get("joined"),
+    }
+}
+
+fn match_result(row: Row) -> MatchResult {
+    MatchResult {
+        tournament: row.get("tournament_id"),
+        number: row.get("number"),
+        winner: row.get("winner"),
+        walkover: row.get("walkover"),
+        replay: row.get("replay"),
+        at: row.get("at"),
+    }
+}
+
+fn unlocked(row: Row) -> Unlocked {
+    Unlocked {
+        user: row.get("user_id"),
+        achievement: row.get("achievement"),
+        at: row.get("at"),
+    }
+}
+
+fn dex_entry(row: Row) -> DexEntry {
+    DexEntry {
+        user: row.get("user_id"),
+        deus: row.get("deus"),
+        caught: row.get("caught"),
+    }
+}
+
+fn quest_progress(row: Row) -> Progress {
+    Progress {
+        user: row.get("user_id"),
+        quest: row.get("quest"),
+        period: row.get("period"),
+        progress: row.get("progress"),
+        completed: row.get("completed"),
+    }
+}
+
+fn envelope(row: Row) -> Result<Envelope> {
+    let event: String = row.get("event");
+    Ok(Envelope {
+        id: row.get("id"),
+        event: events::decode(&event).map_err(Error::Backend)?,
+        at: row.get("at"),
+    })
+}
+
+fn replay_row(row: Row) -> Result<Replay> {
+    let seed: i64 = row.get("seed");
+    let teams: String = row.get("teams");
+    let actions: Vec<u8> = row.get("actions");
+    Ok(Replay {
+        id: row.get("id"),
+        players: [row.get("player_a"), row.get("player_b")],
+        seed: seed as u64,
+        teams: replay::decode_teams(&teams).map_err(Error::Backend)?,
+        actions: replay::decode_actions(&actions),
+        winner: row.get("winner"),
+        at: row.get("at"),
+    })
+}
+
+// Adds an event to the outbox, in the caller's transaction.
+fn publish(conn: &GenericConnection, event: &Event) -> Result<()> {
+    let id: i64 = conn.query("INSERT INTO outbox (event, at) VALUES ($1, $2) RETURNING id",
+                             &[&events::encode(event), &now()])?.get(0).get(0);
+    for user in event.users() {
+        conn.execute("INSERT INTO outbox_users (event_id, user_id) VALUES ($1, $2) ON CONFLICT DO NOTHING",
+                     &[&id, &user])?;
+    }
+    Ok(())
+}
+
+// Profile rows are created lazily, the first time something changes.
+fn ensure_profile(conn: &GenericConnection, user: i32) -> Result<()> {
+    let rows = conn.query("INSERT INTO profiles (user_id) SELECT id FROM users WHERE id = $1
+                           ON CONFLICT (user_id) DO NOTHING
+                           RETURNING user_id",
+                          &[&user])?;
+    if rows.is_empty() {
+        let exists = conn.query("SELECT 1 FROM profiles WHERE user_id = $1", &[&user])?;
+        if exists.is_empty() {
+            return Err(Error::NotFound);
+        }
+    }
+    Ok(())
+}
+
+fn credit(conn: &GenericConnection, user: i32, amount: i32, reason: &str) -> Result<LedgerEntry> {
+    let rows = conn.query("UPDATE users SET vults = vults + $2
+                           WHERE id = $1 AND vults + $2 >= 0
+                           RETURNING vults",
+                          &[&user, &amount])?;
+
+    let balance: i32 = match rows.iter().next() {
+        Some(row) => row.get(0),
+        None => {
+            let exists = conn.query("SELECT 1 FROM users WHERE id = $1", &[&user])?;
+            return Err(if exists.is_empty() { Error::NotFound } else { Error::NotEnoughVults });
+        }
+    };
+
+    let rows = conn.query("INSERT INTO ledger (user_id, amount, balance, reason, at)
+                           VALUES ($1, $2, $3, $4, $5)
+                           RETURNING *",
+                          &[&user, &amount, &balance, &reason, &now()])?;
+    Ok(ledger_entry(rows.get(0)))
+}
+
+// Energy is counted as of `at` under the user's row lock, see energy::add.
+fn charge_energy(conn: &GenericConnection, user: i32, amount: i32, at: i64) -> Result<User> {
+    let rows = conn.query("SELECT energy, energy_at FROM users WHERE id = $1 FOR UPDATE", &[&user])?;
+    let (energy, since): (i32, i64) = match rows.iter().next() {
+        Some(row) => (row.get(0), row.get(1)),
+        None => return Err(Error::NotFound),
+    };
+    let (energy, since) = energy::add(energy, since, amount, at).ok_or(Error::NotEnoughEnergy)?;
+    let rows = conn.query("UPDATE users SET energy = $2, energy_at = $3 WHERE id = $1 RETURNING *",
+                          &[&user, &energy, &since])?;
+    Ok(self::user(rows.get(0)))
+}
+
+fn insert_deus(conn: &GenericConnection, deus: &DeusInstance) -> Result<DeusInstance> {
+    let rows = conn.query("INSERT INTO deus_instances (owner, deus, exp, stars, acquired,
+                               var_life, var_atk, var_def, var_speed, var_attr, nature, points,
+                               train_life, train_atk, train_def, train_speed, train_attr, held, spells, equipped)
+                           SELECT id, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, $12, $13, $14, $15, $16, $17, $18, $19, $20
+                           FROM users WHERE id = $1
+                           RETURNING *",
+                          &[&deus.owner, &deus.deus, &deus.exp, &deus.stars, &now(),
+                            &deus.variance.life, &deus.variance.atk, &deus.variance.def,
+                            &deus.variance.speed, &deus.variance.attr, &deus.nature, &deus.points,
+                            &deus.training.life, &deus.training.atk, &deus.training.def,
+                            &deus.training.speed, &deus.training.attr, &deus.held, &encode_spells(&deus.spells),
+                            &encode_spells(&deus.equipped)])?;
+    let inserted = rows.iter().next().map(deus_instance).ok_or(Error::NotFound)?;
+    catch(conn, inserted.owner, &inserted.deus)?;
+    Ok(inserted)
+}
+
+// A new species is an evolution, it goes in the outbox along with it.
+fn save_deus(conn: &GenericConnection, deus: &DeusInstance) -> Result<()> {
+    let rows = conn.query("SELECT deus FROM deus_instances WHERE id = $1 FOR UPDATE", &[&deus.id])?;
+    let species: String = match rows.iter().next() {
+        Some(row) => row.get(0),
+        None => return Err(Error::NotFound),
+    };
+    let updated = conn.execute("UPDATE deus_instances SET owner = $2, deus = $3, exp = $4, stars = $5, points = $6,
+                                train_life = $7, train_atk = $8, train_def = $9, train_speed = $10, train_attr = $11,
+                                held = $12, spells = $13, equipped = $14, version = version + 1
+                                WHERE id = $1 AND version = $15",
+                               &[&deus.id, &deus.owner, &deus.deus, &deus.exp, &deus.stars, &deus.points,
+                                 &deus.training.life, &deus.training.atk, &deus.training.def,
+                                 &deus.training.speed, &deus.training.attr, &deus.held, &encode_spells(&deus.spells),
+                                 &encode_spells(&deus.equipped), &deus.version])?;
+    if updated == 0 {
+        return Err(Error::Changed);
+    }
+    if species != deus.deus {
+        publish(conn, &Event::DeusEvolved { user: deus.owner, deus: deus.id, from: species, into: deus.deus.clone() })?;
+    }
+    catch(conn, deus.owner, &deus.deus)
+}
+
+// Marks `species` caught by `user`, see Storage::dex.
+fn catch(conn: &GenericConnection, user: i32, species: &str) -> Result<()> {
+    conn.execute("INSERT INTO dex (user_id, deus, caught) VALUES ($1, $2, TRUE)
+                  ON CONFLICT (user_id, deus) DO UPDATE SET caught = TRUE",
+                 &[&user, &species])?;
+    Ok(())
+}
+
+fn give_item(conn: &GenericConnection, user: i32, item: &str, count: i32) -> Result<ItemStack> {
+    let rows = conn.query("INSERT INTO inventory (user_id, item, count) VALUES ($1, $2, $3)
+                           ON CONFLICT (user_id, item) DO UPDATE SET count = inventory.count + EXCLUDED.count
+                           RETURNING *",
+                          &[&user, &item, &count])?;
+    Ok(item_stack(rows.get(0)))
+}
+
+fn take_item(conn: &GenericConnection, user: i32, item: &str) -> Result<()> {
+    let rows = conn.query("UPDATE inventory SET count = count - 1
+                           WHERE user_id = $1 AND item = $2 AND count > 0
+                           RETURNING count",
+                          &[&user, &item])?;
+    let left: i32 = match rows.iter().next() {
+        Some(row) => row.get(0),
+        None => return Err(Error::NotFound),
+    };
+    if left == 0 {
+        conn.execute("DELETE FROM inventory WHERE user_id = $1 AND item = $2", &[&user, &item])?;
+    }
+    Ok(())
+}
+
+fn last_rating(conn: &GenericConnection, user: i32, season: i32) -> Result<Rating> {
+    let rows = conn.query("SELECT * FROM ratings WHERE user_id = $1 AND season <= $2 ORDER BY season DESC LIMIT 1",
+                          &[&user, &season])?;
+    match rows.iter().next() {
+        Some(row) => Ok(ladder::carry(user, season, Some(&rating(row)))),
+        None if conn.query("SELECT 1 FROM users WHERE id = $1", &[&user])?.is_empty() => Err(Error::NotFound),
+        None => Ok(ladder::carry(user, season, None)),
+    }
+}
+
+// The season's rating row of a player, created if needed and locked until
+// the transaction ends.
+fn lock_rating(conn: &GenericConnection, user: i32, season: i32) -> Result<Rating> {
+    let carried = last_rating(conn, user, season)?;
+    conn.execute("INSERT INTO ratings (user_id, season, rating, peak) VALUES ($1, $2, $3, $3)
+                  ON CONFLICT (user_id, season) DO NOTHING",
+                 &[&user, &season, &carried.rating])?;
+    let rows = conn.query("SELECT * FROM ratings WHERE user_id = $1 AND season = $2 FOR UPDATE", &[&user, &season])?;
+    Ok(rating(rows.get(0)))
+}
+
+fn record_match(conn: &GenericConnection, season: i32, a: i32, b: i32, outcome: Outcome) -> Result<(Rating, Rating)> {
+    // Always lock the lower id first, so two games can't deadlock.
+    let (mut first, mut second) = if a <= b {
+        let first = lock_rating(conn, a, season)?;
+        (first, lock_rating(conn, b, season)?)
+    } else {
+        let second = lock_rating(conn, b, season)?;
+        (lock_rating(conn, a, season)?, second)
+    };
+    ladder::record(&mut first, &mut second, outcome);
+    save_rating(conn, &first)?;
+    save_rating(conn, &second)?;
+    Ok((first, second))
+}
+
+fn record_pvp(conn: &GenericConnection, winner: i32, loser: i32) -> Result<()> {
+    ensure_profile(conn, winner)?;
+    ensure_profile(conn, loser)?;
+    conn.execute("UPDATE profiles SET wins = wins + 1, win_streak = win_streak + 1,
+                                      best_streak = GREATEST(best_streak, win_streak + 1)
+                  WHERE user_id = $1",
+                 &[&winner])?;
+    conn.execute("UPDATE profiles SET losses = losses + 1, win_streak = 0 WHERE user_id = $1", &[&loser])?;
+    Ok(())
+}
+
+fn insert_replay(conn: &GenericConnection, r: &Replay) -> Result<Replay> {
+    let saved = match conn.query("INSERT INTO replays (player_a, player_b, seed, teams, actions, winner, at)
+                                  SELECT $1, $2, $3, $4, $5, $6, $7
+                                  WHERE EXISTS (SELECT 1 FROM users WHERE id = $1)
+                                    AND EXISTS (SELECT 1 FROM users WHERE id = $2)
+                                  RETURNING *",
+                                 &[&r.players[0], &r.players[1], &(r.seed as i64), &replay::encode_teams(&r.teams),
+                                   &replay::encode_actions(&r.actions), &r.winner, &now()])?.iter().next() {
+        Some(row) => replay_row(row)?,
+        None => return Err(Error::NotFound),
+    };
+    publish(conn, &Event::BattleFinished { players: saved.players, winner: saved.winner })?;
+    Ok(saved)
+}
+
+fn record_result(conn: &GenericConnection, r: &MatchResult) -> Result<MatchResult> {
+    let rows = conn.query("INSERT INTO tournament_results (tournament_id, number, winner, walkover, replay, at)
+                           SELECT id, $2, $3, $4, $5, $6 FROM tournaments
+                           WHERE id = $1 AND state = 'running'
+                             AND EXISTS (SELECT 1 FROM users WHERE id = $3)
+                           ON CONFLICT (tournament_id, number) DO NOTHING
+                           RETURNING *",
+                          &[&r.tournament, &r.number, &r.winner, &r.walkover, &r.replay, &now()])?;
+    if let Some(row) = rows.iter().next() {
+        return Ok(match_result(row));
+    }
+    let decided = conn.query("SELECT 1 FROM tournament_results WHERE tournament_id = $1 AND number = $2",
+                             &[&r.tournament, &r.number])?;
+    Err(if decided.is_empty() { Error::NotFound } else { Error::AlreadyExists })
+}
+
+fn save_rating(conn: &GenericConnection, r: &Rating) -> Result<()> {
+    conn.execute("UPDATE ratings SET rating = $3, games = $4, wins = $5, losses = $6, peak = $7
+                  WHERE user_id = $1 AND season = $2",
+                 &[&r.user, &r.season, &r.rating, &r.games, &r.wins, &r.losses, &r.peak])?;
+    Ok(())
+}
+
+// The entry fee of an open tournament. Joining and leaving hold the row lock,
+// so entrants are counted one join at a time and starting waits for them.
+fn open_fee(conn: &GenericConnection, id: i32) -> Result<i32> {
+    let rows = conn.query("SELECT fee FROM tournaments WHERE id = $1 AND state = 'open' FOR UPDATE", &[&id])?;
+    rows.iter().next().map(|row| row.get(0)).ok_or(Error::NotFound)
+}
+
+impl Storage for PgStorage {
+    fn create_user(&self, disc_id: i64, name: &str) -> Result<User> {
+        let conn = self.conn()?;
+        let tx = conn.transaction()?;
+        let created = match tx.query("INSERT INTO users (disc_id, name, joined) VALUES ($1, $2, $3)
+                                      ON CONFLICT (disc_id) DO NOTHING
+                                      RETURNING *",
+                                     &[&disc_id, &name, &now()])?.iter().next() {
+            Some(row) => user(row),
+            None => return Err(Error::AlreadyExists),
+        };
+        publish(&tx, &Event::UserRegistered { user: created.id })?;
+        tx.commit()?;
+        Ok(created)
+    }
+
+    fn user(&self, id: i32) -> Result<User> {
+        let conn = self.conn()?;
+        let rows = conn.query("SELECT * FROM users WHERE id = $1", &[&id])?;
+        rows.iter().next().map(user).ok_or(Error::NotFound)
+    }
+
+    fn user_by_disc_id(&self, disc_id: i64) -> Result<User> {
+        let conn = self.conn()?;
+        let rows = conn.query("SELECT * FROM users WHERE disc_id = $1", &[&disc_id])?;
+        rows.iter().next().map(user).ok_or(Error::NotFound)
+    }
+
+    fn users(&self) -> Result<Vec<User>> {
+        let conn = self.conn()?;
+        let rows = conn.query("SELECT * FROM users ORDER BY id", &[])?;
+        Ok(rows.iter().map(user).collect())
+    }
+
+    fn set_locale(&self, user: i32, locale: Option<&str>) -> Result<()> {
+        let conn = self.conn()?;
+        let updated = conn.execute("UPDATE users SET locale = $2 WHERE id = $1", &[&user, &locale])?;
+        if updated == 0 { Err(Error::NotFound) } else { Ok(()) }
+    }
+
+    fn create_guild(&self, disc_id: i64, name: &str, admin: i32) -> Result<User> {
+        let conn = self.conn()?;
+        let tx = conn.transaction()?;
+        if tx.query("SELECT 1 FROM users WHERE id = $1 AND kind = 'player'", &[&admin])?.is_empty() {
+            return Err(Error::NotFound);
+        }
+        let account = match tx.query("INSERT INTO users (disc_id, name, kind, joined) VALUES ($1, $2, 'server', $3)
+                                      ON CONFLICT (disc_id) DO NOTHING
+                                      RETURNING *",
+                                     &[&disc_id, &name, &now()])?.iter().next() {
+            Some(row) => user(row),
+            None => return Err(Error::AlreadyExists),
+        };
+        tx.execute("INSERT INTO guilds (account) VALUES ($1)", &[&account.id])?;
+        tx.execute("INSERT INTO guild_members (guild, user_id, admin, joined) VALUES ($1, $2, TRUE, $3)",
+                   &[&account.id, &admin, &now()])?;
+        tx.commit()?;
+        Ok(account)
+    }
+
+    fn guild(&self, account: i32) -> Result<Guild> {
+        let conn = self.conn()?;
+        let rows = conn.query("SELECT * FROM guilds WHERE account = $1", &[&account])?;
+        rows.iter().next().map(guild).ok_or(Error::NotFound)
+    }
+
+    fn guilds(&self) -> Result<Vec<Guild>> {
+        let conn = self.conn()?;
+        let rows = conn.query("SELECT * FROM guilds ORDER BY account", &[])?;
+        Ok(rows.iter().map(guild).collect())
+    }
+
+    fn set_guild_channel(&self, account: i32, channel: Option<i64>) -> Result<()> {
+        let conn = self.conn()?;
+        let updated = conn.execute("UPDATE guilds SET channel = $2 WHERE account = $1", &[&account, &channel])?;
+        if updated == 0 { Err(Error::NotFound) } else { Ok(()) }
+    }
+
+    fn set_guild_tax(&self, account: i32, tax: i32) -> Result<()> {
+        let conn = self.conn()?;
+        let updated = conn.execute("UPDATE guilds SET tax = $2 WHERE account = $1", &[&account, &tax])?;
+        if updated == 0 { Err(Error::NotFound) } else { Ok(()) }
+    }
+
+    fn guild_members(&self, account: i32) -> Result<Vec<Member>> {
+        let conn = self.conn()?;
+        let rows = conn.query("SELECT * FROM guild_members WHERE guild = $1 ORDER BY joined, user_id", &[&account])?;
+        Ok(rows.iter().map(member).collect())
+    }
+
+    fn join_guild(&self, account: i32, user: i32) -> Result<Member> {
+        let conn = self.conn()?;
+        let rows = conn.query("INSERT INTO guild_members (guild, user_id, joined)
+                               SELECT g.account, u.id, $3 FROM guilds g, users u
+                               WHERE g.account = $1 AND u.id = $2 AND u.kind = 'player'
+                               ON CONFLICT (guild, user_id) DO NOTHING
+                               RETURNING *",
+                              &[&account, &user, &now()])?;
+        match rows.iter().next() {
+            Some(row) => Ok(member(row)),
+            None if conn.query("SELECT 1 FROM guild_members WHERE guild = $1 AND user_id = $2",
+                               &[&account, &user])?.is_empty() => Err(Error::NotFound),
+            None => Err(Error::AlreadyExists),
+        }
+    }
+
+    fn leave_guild(&self, account: i32, user: i32) -> Result<()> {
+        let conn = self.conn()?;
+        let removed = conn.execute("DELETE FROM guild_members WHERE guild = $1 AND user_id = $2", &[&account, &user])?;
+        if removed == 0 { Err(Error::NotFound) } else { Ok(()) }
+    }
+
+    fn set_guild_admin(&self, account: i32, user: i32, admin: bool) -> Result<()> {
+        let conn = self.conn()?;
+        let updated = conn.execute("UPDATE guild_members SET admin = $3 WHERE guild = $1 AND user_id = $2",
+                                   &[&account, &user, &admin])?;
+        if updated == 0 { Err(Error::NotFound) } else { Ok(()) }
+    }
+
+    fn add_deus(&self, deus: &DeusInstance) -> Result<DeusInstance> {
+        insert_deus(&*self.conn()?, deus)
+    }
+
+    fn buy_deus(&self, deus: &DeusInstance, price: i32, reason: &str) -> Result<DeusInstance> {
+        let conn = self.conn()?;
+        let tx = conn.transaction()?;
+        credit(&tx, deus.owner, -price, reason)?;
+        let bought = insert_deus(&tx, deus)?;
+        tx.commit()?;
+        Ok(bought)
+    }
+
+    fn fuse_deus(&self, parents: [i32; 2], child: &DeusInstance, price: i32, reason: &str) -> Result<DeusInstance> {
+        if parents[0] == parents[1] {
+            return Err(Error::NotFound);
+        }
+        let conn = self.conn()?;
+        let tx = conn.transaction()?;
+        for parent in &parents {
+            let rows = tx.query("DELETE FROM deus_instances WHERE id = $1 AND owner = $2 RETURNING held",
+                                &[parent, &child.owner])?;
+            let held: Option<String> = match rows.iter().next() {
+                Some(row) => row.get(0),
+                None => return Err(Error::NotFound),
+            };
+            if let Some(item) = held {
+                give_item(&tx, child.owner, &item, 1)?;
+            }
+        }
+        if price > 0 {
+            credit(&tx, child.owner, -price, reason)?;
+            publish(&tx, &Event::VultsSpent { user: child.owner, amount: price })?;
+        }
+        let fused = insert_deus(&tx, child)?;
+        tx.commit()?;
+        Ok(fused)
+    }
+
+    fn ascend_deus(&self, deus: &DeusInstance, copies: &[i32], material: Option<(&str, i32)>, price: i32, reason: &str)
+                   -> Result<DeusInstance> {
+        if copies.iter().enumerate().any(|(i, id)| *id == deus.id || copies[..i].contains(id)) {
+            return Err(Error::NotFound);
+        }
+        let conn = self.conn()?;
+        let tx = conn.transaction()?;
+        let ascended = {
+            let rows = tx.query("UPDATE deus_instances SET stars = $4, version = version + 1 WHERE id = $1 AND owner = $2 AND deus = $3 AND stars = $4 - 1
+                                 RETURNING *",
+                                &[&deus.id, &deus.owner, &deus.deus, &deus.stars])?;
+            match rows.iter().next() {
+                Some(row) => deus_instance(row),
+                None => return Err(Error::NotFound),
+            }
+        };
+        for copy in copies {
+            let rows = tx.query("DELETE FROM deus_instances WHERE id = $1 AND owner = $2 AND deus = $3 RETURNING held",
+                                &[copy, &deus.owner, &deus.deus])?;
+            let held: Option<String> = match rows.iter().next() {
+                Some(row) => row.get(0),
+                None => return Err(Error::NotFound),
+            };
+            if let Some(item) = held {
+                give_item(&tx, deus.owner, &item, 1)?;
+            }
+        }
+        if let Some((item, count)) = material {
+            for _ in 0..count {
+                take_item(&tx, deus.owner, item)?;
+            }
+        }
+        if price > 0 {
+            credit(&tx, deus.owner, -price, reason)?;
+            publish(&tx, &Event::VultsSpent { user: deus.owner, amount: price })?;
+        }
+        tx.commit()?;
+        Ok(ascended)
+    }
+
+    fn deus(&self, id: i32) -> Result<DeusInstance> {
+        let conn = self.conn()?;
+        let rows = conn.query("SELECT * FROM deus_instances WHERE id = $1", &[&id])?;
+        rows.iter().next().map(deus_instance).ok_or(Error::NotFound)
+    }
+
+    fn deus_of(&self, owner: i32) -> Result<Vec<DeusInstance>> {
+        let conn = self.conn()?;
+        let rows = conn.query("SELECT * FROM deus_instances WHERE owner = $1 ORDER BY id", &[&owner])?;
+        Ok(rows.iter().map(deus_instance).collect())
+    }
+
+    fn update_deus(&self, deus: &DeusInstance) -> Result<()> {
+        let conn = self.conn()?;
+        let tx = conn.transaction()?;
+        save_deus(&tx, deus)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    #[cfg(test)]
+    fn remove_deus(&self, id: i32) -> Result<()> {
+        let conn = self.conn()?;
+        let removed = conn.execute("DELETE FROM deus_instances WHERE id = $1", &[&id])?;
+        if removed == 0 { Err(Error::NotFound) } else { Ok(()) }
+    }
+
+    #[cfg(test)]
+    fn add_vults(&self, user: i32, amount: i32, reason: &str) -> Result<LedgerEntry> {
+        let conn = self.conn()?;
+        let tx = conn.transaction()?;
+        let entry = credit(&tx, user, amount, reason)?;
+        tx.commit()?;
+        Ok(entry)
+    }
+
+    fn transfer_vults(&self, from: i32, to: i32, amount: i32, reason: &str) -> Result<()> {
+        let conn = self.conn()?;
+        let tx = conn.transaction()?;
+        credit(&tx, from, -amount, reason)?;
+        credit(&tx, to, amount, reason)?;
+        publish(&tx, &Event::VultsTransferred { from: from, to: to, amount: amount })?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    #[cfg(test)]
+    fn spend_energy(&self, user: i32, amount: i32, at: i64) -> Result<User> {
+        let conn = self.conn()?;
+        let tx = conn.transaction()?;
+        let spent = charge_energy(&tx, user, -amount, at)?;
+        tx.commit()?;
+        Ok(spent)
+    }
+
+    fn refill_energy(&self, user: i32, amount: i32, price: i32, item: Option<&str>, at: i64) -> Result<User> {
+        let conn = self.conn()?;
+        let tx = conn.transaction()?;
+        if price > 0 {
+            credit(&tx, user, -price, &format!("bought {} energy", amount))?;
+            publish(&tx, &Event::VultsSpent { user: user, amount: price })?;
+        }
+        if let Some(item) = item {
+            take_item(&tx, user, item)?;
+        }
+        let refilled = charge_energy(&tx, user, amount, at)?;
+        tx.commit()?;
+        Ok(refilled)
+    }
+
+    fn explore(&self, user: i32, cost: i32, found: i32, tax: Option<(i32, i32)>, at: i64) -> Result<User> {
+        let conn = self.conn()?;
+        let tx = conn.transaction()?;
+        let mut explored = charge_energy(&tx, user, -cost, at)?;
+        let mut kept = found;
+        if let Some((guild, tax)) = tax {
+            credit(&tx, guild, tax, &format!("tax on {}'s exploring", explored.name))?;
+            kept -= tax;
+        }
+        if kept > 0 {
+            explored.vults = credit(&tx, user, kept, "found while exploring")?.balance;
+        }
+        tx.commit()?;
+        Ok(explored)
+    }
+
+    fn finish_pve(&self, user: i32, cost: i32, team: &[DeusInstance], at: i64) -> Result<User> {
+        let conn = self.conn()?;
+        let tx = conn.transaction()?;
+        let spent = charge_energy(&tx, user, -cost, at)?;
+        for d in team {
+            save_deus(&tx, d)?;
+        }
+        tx.commit()?;
+        Ok(spent)
+    }
+
+    #[cfg(test)]
+    fn ledger(&self, user: i32, limit: i64) -> Result<Vec<LedgerEntry>> {
+        let conn = self.conn()?;
+        let rows = conn.query("SELECT * FROM ledger WHERE user_id = $1 ORDER BY id DESC LIMIT $2",
+                              &[&user, &limit])?;
+        Ok(rows.iter().map(ledger_entry).collect())
+    }
+
+    fn inventory(&self, user: i32) -> Result<Vec<ItemStack>> {
+        let conn = self.conn()?;
+        let rows = conn.query("SELECT * FROM inventory WHERE user_id = $1 AND count > 0 ORDER BY item", &[&user])?;
+        Ok(rows.iter().map(item_stack).collect())
+    }
+
+    fn buy_item(&self, user: i32, item: &str, count: i32, price: i32) -> Result<ItemStack> {
+        let conn = self.conn()?;
+        let tx = conn.transaction()?;
+        credit(&tx, user, -price * count, &format!("bought {} {}", count, item))?;
+        let stack = give_item(&tx, user, item, count)?;
+        if price * count > 0 {
+            publish(&tx, &Event::VultsSpent { user: user, amount: price * count })?;
+        }
+        tx.commit()?;
+        Ok(stack)
+    }
+
+    fn use_item(&self, user: i32, item: &str, deus: &DeusInstance) -> Result<DeusInstance> {
+        if deus.owner != user {
+            return Err(Error::NotFound);
+        }
+        let conn = self.conn()?;
+        let tx = conn.transaction()?;
+        let saved = if deus.id == 0 {
+            take_item(&tx, user, item)?;
+            let caught = insert_deus(&tx, deus)?;
+            publish(&tx, &Event::DeusCaptured { user: user, deus: caught.id, species: caught.deus.clone() })?;
+            caught
+        } else {
+            let rows = tx.query("SELECT held FROM deus_instances WHERE id = $1 AND owner = $2 FOR UPDATE",
+                                &[&deus.id, &user])?;
+            let held: Option<String> = match rows.iter().next() {
+                Some(row) => row.get(0),
+                None => return Err(Error::NotFound),
+            };
+            take_item(&tx, user, item)?;
+            save_deus(&tx, deus)?;
+            if let Some(held) = held {
+                if deus.held.as_ref() != Some(&held) {
+                    give_item(&tx, user, &held, 1)?;
+                }
+            }
+            let rows = tx.query("SELECT * FROM deus_instances WHERE id = $1", &[&deus.id])?;
+            deus_instance(rows.get(0))
+        };
+        tx.commit()?;
+        Ok(saved)
+    }
+
+    fn profile(&self, user: i32) -> Result<Profile> {
+        let conn = self.conn()?;
+        let rows = conn.query("SELECT u.id AS user_id, p.favorite_deus,
+                                      COALESCE(p.wins, 0) AS wins, COALESCE(p.losses, 0) AS losses,
+                                      COALESCE(p.win_streak, 0) AS win_streak,
+                                      COALESCE(p.best_streak, 0) AS best_streak,
+                                      COALESCE(p.hidden, 0) AS hidden
+                               FROM users u LEFT JOIN profiles p ON p.user_id = u.id
+                               WHERE u.id = $1",
+                              &[&user])?;
+        rows.iter().next().map(profile).ok_or(Error::NotFound)
+    }
+
+    fn set_profile_settings(&self, user: i32, favorite: Option<i32>, hidden: i32) -> Result<()> {
+        let conn = self.conn()?;
+        let tx = conn.transaction()?;
+        ensure_profile(&tx, user)?;
+        tx.execute("UPDATE profiles SET favorite_deus = $2, hidden = $3 WHERE user_id = $1",
+                   &[&user, &favorite, &hidden])?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    #[cfg(test)]
+    fn record_pvp(&self, winner: i32, loser: i32) -> Result<()> {
+        let conn = self.conn()?;
+        let tx = conn.transaction()?;
+        record_pvp(&tx, winner, loser)?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn teams(&self, owner: i32) -> Result<Vec<Team>> {
+        let conn = self.conn()?;
+        let mut teams: Vec<Team> = conn.query("SELECT * FROM teams WHERE owner = $1 ORDER BY id", &[&owner])?
+            .iter()
+            .map(team)
+            .collect();
+
+        let members = conn.query("SELECT m.team_id, m.deus_id FROM team_members m
+                                  JOIN teams t ON t.id = m.team_id
+                                  WHERE t.owner = $1
+                                  ORDER BY m.team_id, m.slot",
+                                 &[&owner])?;
+        for row in &members {
+            let id: i32 = row.get(0);
+            if let Some(team) = teams.iter_mut().find(|t| t.id == id) {
+                team.members.push(row.get(1));
+            }
+        }
+        Ok(teams)
+    }
+
+    fn save_team(&self, team: &Team) -> Result<Team> {
+        let conn = self.conn()?;
+        let tx = conn.transaction()?;
+
+        let taken = !tx.query("SELECT 1 FROM teams WHERE owner = $1 AND name = $2 AND id <> $3",
+                              &[&team.owner, &team.name, &team.id])?.is_empty();
+        if taken {
+            return Err(Error::AlreadyExists);
+        }
+
+        let rows = if team.id == 0 {
+            tx.query("INSERT INTO teams (owner, name, lead, active)
+                      SELECT id, $2, $3, $4 FROM users WHERE id = $1
+                      RETURNING id",
+                     &[&team.owner, &team.name, &team.lead, &team.active])?
+        } else {
+            tx.query("UPDATE teams SET name = $2, lead = $3, active = $4 WHERE id = $1 RETURNING id",
+                     &[&team.id, &team.name, &team.lead, &team.active])?
+        };
+        let id: i32 = match rows.iter().next() {
+            Some(row) => row.get(0),
+            None => return Err(Error::NotFound),
+        };
+        drop(rows);
+
+        tx.execute("DELETE FROM team_members WHERE team_id = $1", &[&id])?;
+        for (slot, deus) in team.members.iter().enumerate() {
+            let added = tx.execute("INSERT INTO team_members (team_id, slot, deus_id)
+                                    SELECT $1, $2, id FROM deus_instances WHERE id = $3",
+                                   &[&id, &(slot as i32), deus])?;
+            if added == 0 {
+                return Err(Error::NotFound);
+            }
+        }
+
+        tx.commit()?;
+        Ok(Team { id: id, ..team.clone() })
+    }
+
+    fn delete_team(&self, id: i32) -> Result<()> {
+        let conn = self.conn()?;
+        let removed = conn.execute("DELETE FROM teams WHERE id = $1", &[&id])?;
+        if removed == 0 { Err(Error::NotFound) } else { Ok(()) }
+    }
+
+    fn activate_team(&self, owner: i32, id: i32) -> Result<()> {
+        let conn = self.conn()?;
+        let tx = conn.transaction()?;
+        if tx.query("SELECT 1 FROM teams WHERE id = $1 AND owner = $2", &[&id, &owner])?.is_empty() {
+            return Err(Error::NotFound);
+        }
+        tx.execute("UPDATE teams SET active = (id = $2) WHERE owner = $1", &[&owner, &id])?;
+        tx.commit()?;
+        Ok(())
+    }
+
+    #[cfg(test)]
+    fn save_replay(&self, r: &Replay) -> Result<Replay> {
+        let conn = self.conn()?;
+        let tx = conn.transaction()?;
+        let saved = insert_replay(&tx, r)?;
+        tx.commit()?;
+        Ok(saved)
+    }
+
+    fn replay(&self, id: i32) -> Result<Replay> {
+        let conn = self.conn()?;
+        let rows = conn.query("SELECT * FROM replays WHERE id = $1", &[&id])?;
+        match rows.iter().next() {
+            Some(row) => replay_row(row),
+            None => Err(Error::NotFound),
+        }
+    }
+
+    fn rating(&self, user: i32, season: i32) -> Result<Rating> {
+        last_rating(&*self.conn()?, user, season)
+    }
+
+    #[cfg(test)]
+    fn record_match(&self, season: i32, a: i32, b: i32, outcome: Outcome) -> Result<(Rating, Rating)> {
+        let conn = self.conn()?;
+        let tx = conn.transaction()?;
+        let rated = record_match(&tx, season, a, b, outcome)?;
+        tx.commit()?;
+        Ok(rated)
+    }
+
+    fn challenge(&self, from: i32, to: i32, at: i64) -> Result<()> {
+        let conn = self.conn()?;
+        let added = conn.execute("INSERT INTO challenges (challenger, opponent, at)
+                                  SELECT $1, $2, $3
+                                  WHERE EXISTS (SELECT 1 FROM users WHERE id = $1)
+                                    AND EXISTS (SELECT 1 FROM users WHERE id = $2)
+                                  ON CONFLICT (challenger, opponent) DO UPDATE SET at = EXCLUDED.at",
+                                 &[&from, &to, &at])?;
+        if added == 0 { Err(Error::NotFound) } else { Ok(()) }
+    }
+
+    fn finish_pvp(&self, season: i32, r: &Replay, deus: &[DeusInstance], since: i64)
+                  -> Result<(Replay, Rating, Rating)> {
+        let conn = self.conn()?;
+        let tx = conn.transaction()?;
+        let (a, b) = (r.players[0], r.players[1]);
+        if tx.execute("DELETE FROM challenges WHERE challenger = $1 AND opponent = $2 AND at >= $3",
+                      &[&a, &b, &since])? == 0 {
+            return Err(Error::Closed);
+        }
+
+        let outcome = super::outcome(r);
+        match outcome {
+            Outcome::Win => record_pvp(&tx, a, b)?,
+            Outcome::Loss => record_pvp(&tx, b, a)?,
+            Outcome::Draw => {}
+        }
+        let (first, second) = record_match(&tx, season, a, b, outcome)?;
+        let saved = insert_replay(&tx, r)?;
+        for d in deus {
+            save_deus(&tx, d)?;
+        }
+        tx.commit()?;
+        Ok((saved, first, second))
+    }
+
+    fn ladder(&self, season: i32, limit: i64) -> Result<Vec<(Rating, String)>> {
+        let conn = self.conn()?;
+        let rows = conn.query("SELECT ratings.*, users.name FROM ratings JOIN users ON users.id = ratings.user_id
+                               WHERE season = $1 AND games > 0
+                               ORDER BY rating DESC, user_id LIMIT $2",
+                              &[&season, &limit])?;
+        Ok(rows.iter().map(|row| {
+            let name = row.get("name");
+            (rating(row), name)
+        }).collect())
+    }
+
+    fn reward_season(&self, season: i32) -> Result<Vec<Rating>> {
+        let conn = self.conn()?;
+        let tx = conn.transaction()?;
+        let due: Vec<Rating> = tx.query("UPDATE ratings SET rewarded = TRUE
+                                         WHERE season = $1 AND NOT rewarded AND games >= $2
+                                         RETURNING *",
+                                        &[&season, &ladder::PLACEMENT_GAMES])?
+            .iter()
+            .map(rating)
+            .collect();
+
+        for r in &due {
+            let reward = Tier::of(r.rating).reward();
+            credit(&tx, r.user, reward.vults, &ladder::reward_reason(r))?;
+            if let Some(species) = reward.deus {
+                insert_deus(&tx, &DeusInstance::roll(r.user, species, 1))?;
+            }
+        }
+        tx.commit()?;
+        Ok(due)
+    }
+
+    fn create_tournament(&self, t: &Tournament) -> Result<Tournament> {
+        let conn = self.conn()?;
+        let rows = conn.query("INSERT INTO tournaments (name, format, organizer, fee, state, created)
+                               SELECT $2, $3, id, $4, $5, $6 FROM users WHERE id = $1
+                               RETURNING *",
+                              &[&t.organizer, &t.name, &t.format.name(), &t.fee, &t.state.name(), &now()])?;
+        match rows.iter().next() {
+            Some(row) => tournament_row(row),
+            None => Err(Error::NotFound),
+        }
+    }
+
+    fn tournament(&self, id: i32) -> Result<Tournament> {
+        let conn = self.conn()?;
+        let rows = conn.query("SELECT * FROM tournaments WHERE id = $1", &[&id])?;
+        match rows.iter().next() {
+            Some(row) => tournament_row(row),
+            None => Err(Error::NotFound),
+        }
+    }
+
+    fn tournaments(&self) -> Result<Vec<Tournament>> {
+        let conn = self.conn()?;
+        let rows = conn.query("SELECT * FROM tournaments WHERE state <> 'finished' ORDER BY id", &[])?;
+        rows.iter().map(tournament_row).collect()
+    }
+
+    fn join_tournament(&self, id: i32, user: i32) -> Result<Entrant> {
+        let conn = self.conn()?;
+        let tx = conn.transaction()?;
+        let fee = open_fee(&tx, id)?;
+        let entrants: i64 = tx.query("SELECT COUNT(*) FROM tournament_entrants WHERE tournament_id = $1 AND user_id <> $2",
+                                     &[&id, &user])?.get(0).get(0);
+        if entrants >= tournament::MAX_ENTRANTS as i64 {
+            return Err(Error::Closed);
+        }
+        let joined = match tx.query("INSERT INTO tournament_entrants (tournament_id, user_id, joined)
+                                     SELECT $1, id, $3 FROM users WHERE id = $2
+                                     ON CONFLICT (tournament_id, user_id) DO NOTHING
+                                     RETURNING *",
+                                    &[&id, &user, &now()])?.iter().next() {
+            Some(row) => entrant(row),
+            None if tx.query("SELECT 1 FROM users WHERE id = $1", &[&user])?.is_empty() => return Err(Error::NotFound),
+            None => return Err(Error::AlreadyExists),
+        };
+        if fee > 0 {
+            credit(&tx, user, -fee, &format!("tournament {} entry", id))?;
+            publish(&tx, &Event::VultsSpent { user: user, amount: fee })?;
+        }
+        tx.commit()?;
+        Ok(joined)
+    }
+
+    fn leave_tournament(&self, id: i32, user: i32) -> Result<()> {
+        let conn = self.conn()?;
+        let tx = conn.transaction()?;
+        let fee = open_fee(&tx, id)?;
+        if tx.execute("DELETE FROM tournament_entrants WHERE tournament_id = $1 AND user_id = $2", &[&id, &user])? == 0 {
+            return Err(Error::NotFound);
+        }
+        if fee > 0 {
+            credit(&tx, user, fee, &format!("tournament {} refund", id))?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn entrants(&self, id: i32) -> Result<Vec<Entrant>> {
+        let conn = self.conn()?;
+        let rows = conn.query("SELECT * FROM tournament_entrants WHERE tournament_id = $1 ORDER BY seed, joined, user_id",
+                              &[&id])?;
+        Ok(rows.iter().map(entrant).collect())
+    }
+
+    fn start_tournament(&self, id: i32, seeds: &[i32]) -> Result<Tournament> {
+        let conn = self.conn()?;
+        let tx = conn.transaction()?;
+        let started = match tx.query("UPDATE tournaments SET state = 'running', started = $2
+                                      WHERE id = $1 AND state = 'open'
+                                      RETURNING *",
+                                     &[&id, &now()])?.iter().next() {
+            Some(row) => tournament_row(row)?,
+            None => return Err(Error::NotFound),
+        };
+        for (i, user) in seeds.iter().enumerate() {
+            let seeded = tx.execute("UPDATE tournament_entrants SET seed = $3 WHERE tournament_id = $1 AND user_id = $2",
+                                    &[&id, user, &(i as i32 + 1)])?;
+            if seeded == 0 {
+                return Err(Error::NotFound);
+            }
+        }
+        tx.commit()?;
+        Ok(started)
+    }
+
+    fn set_ready(&self, id: i32, user: i32, number: Option<i32>) -> Result<()> {
+        let conn = self.conn()?;
+        let updated = conn.execute("UPDATE tournament_entrants SET ready = $3 WHERE tournament_id = $1 AND user_id = $2",
+                                   &[&id, &user, &number])?;
+        if updated == 0 { Err(Error::NotFound) } else { Ok(()) }
+    }
+
+    fn claim_match(&self, id: i32, number: i32, players: [i32; 2]) -> Result<()> {
+        let conn = self.conn()?;
+        let tx = conn.transaction()?;
+        let claimed = tx.execute("UPDATE tournament_entrants SET ready = NULL, claimed = $4
+                                  WHERE tournament_id = $1 AND user_id IN ($2, $3) AND ready = $4",
+                                 &[&id, &players[0], &players[1], &number])?;
+        if claimed < 2 {
+            return Err(Error::NotFound);
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn record_result(&self, r: &MatchResult) -> Result<MatchResult> {
+        record_result(&*self.conn()?, r)
+    }
+
+    fn finish_match(&self, r: &MatchResult, played: Option<&Replay>, drank: &[i32]) -> Result<MatchResult> {
+        let conn = self.conn()?;
+        let tx = conn.transaction()?;
+        let replay = match played {
+            Some(played) => Some(insert_replay(&tx, played)?.id),
+            None => None,
+        };
+        let recorded = record_result(&tx, &MatchResult { replay: replay, ..r.clone() })?;
+        for id in drank {
+            tx.execute("UPDATE deus_instances SET held = NULL, version = version + 1 WHERE id = $1", &[id])?;
+        }
+        tx.commit()?;
+        Ok(recorded)
+    }
+
+    fn results(&self, id: i32) -> Result<Vec<MatchResult>> {
+        let conn = self.conn()?;
+        let rows = conn.query("SELECT * FROM tournament_results WHERE tournament_id = $1 ORDER BY number", &[&id])?;
+        Ok(rows.iter().map(match_result).collect())
+    }
+
+    fn finish_tournament(&self, id: i32, payouts: &[(i32, i32)], reason: &str) -> Result<()> {
+        let conn = self.conn()?;
+        let tx = conn.transaction()?;
+        if tx.execute("UPDATE tournaments SET state = 'finished' WHERE id = $1 AND state <> 'finished'", &[&id])? == 0 {
+            return Err(Error::NotFound);
+        }
+        for &(user, vults) in payouts {
+            credit(&tx, user, vults, reason)?;
+        }
+        tx.commit()?;
+        Ok(())
+    }
+
+    fn quest_progress(&self, user: i32, quest: &str, period: i32) -> Result<Progress> {
+        let conn = self.conn()?;
+        let rows = conn.query("SELECT * FROM quest_progress WHERE user_id = $1 AND quest = $2 AND period = $3",
+                              &[&user, &quest, &period])?;
+        Ok(rows.iter().next().map(quest_progress).unwrap_or_else(|| Progress::new(user, quest, period)))
+    }
+
+    fn progress_quest(&self, user: i32, quest: &Quest, period: i32, amount: i32) -> Result<bool> {
+        let conn = self.conn()?;
+        let tx = conn.transaction()?;
+        tx.execute("INSERT INTO quest_progress (user_id, quest, period) SELECT id, $2, $3 FROM users WHERE id = $1
+                    ON CONFLICT (user_id, quest, period) DO NOTHING",
+                   &[&user, &quest.id, &period])?;
+        let mut progress = match tx.query("SELECT * FROM quest_progress WHERE user_id = $1 AND quest = $2 AND period = $3 FOR UPDATE",
+                                          &[&user, &quest.id, &period])?.iter().next() {
+            Some(row) => quest_progress(row),
+            None => return Err(Error::NotFound),
+        };
+        if progress.completed || amount <= 0 {
+            return Ok(false);
+        }
+
+        let goal = quest.objective.goal();
+        progress.progress = (progress.progress + amount).min(goal);
+        progress.completed = progress.progress == goal;
+        tx.execute("UPDATE quest_progress SET progress = $4, completed = $5 WHERE user_id = $1 AND quest = $2 AND period = $3",
+                   &[&user, &quest.id, &period, &progress.progress, &progress.completed])?;
+        if progress.completed {
+            if quest.reward.vults > 0 {
+                credit(&tx, user, quest.reward.vults, &quest.reward_reason())?;
+            }
+            for reward in &quest.reward.items {
+                give_item(&tx, user, &reward.item, reward.count)?;
+            }
+        }
+        tx.commit()?;
+        Ok(progress.completed)
+    }
+
+    fn achievements(&self, user: i32) -> Result<Vec<Unlocked>> {
+        let conn = self.conn()?;
+        let rows = conn.query("SELECT * FROM achievements WHERE user_id = $1 ORDER BY id", &[&user])?;
+        Ok(rows.iter().map(unlocked).collect())
+    }
+
+    fn award_achievement(&self, user: i32, achievement: &str) -> Result<bool> {
+        let conn = self.conn()?;
+        let inserted = conn.execute("INSERT INTO achievements (user_id, achievement, at) SELECT id, $2, $3 FROM users WHERE id = $1
+                                     ON CONFLICT (user_id, achievement) DO NOTHING",
+                                    &[&user, &achievement, &now()])?;
+        if inserted == 0 && conn.query("SELECT 1 FROM users WHERE id = $1", &[&user])?.is_empty() {
+            return Err(Error::NotFound);
+        }
+        Ok(inserted > 0)
+    }
+
+    fn dex(&self, user: i32) -> Result<Vec<DexEntry>> {
+        let conn = self.conn()?;
+        let rows = conn.query("SELECT * FROM dex WHERE user_id = $1 ORDER BY deus", &[&user])?;
+        Ok(rows.iter().map(dex_entry).collect())
+    }
+
+    fn see_deus(&self, user: i32, species: &[String]) -> Result<()> {
+        let conn = self.conn()?;
+        if conn.query("SELECT 1 FROM users WHERE id = $1", &[&user])?.is_empty() {
+            return Err(Error::NotFound);
+        }
+        for deus in species {
+            conn.execute("INSERT INTO dex (user_id, deus, caught) VALUES ($1, $2, FALSE) ON CONFLICT (user_id, deus) DO NOTHING",
+                         &[&user, deus])?;
+        }
+        Ok(())
+    }
+
+    fn reward_dex(&self, user: i32, milestone: &str, vults: i32, reason: &str) -> Result<bool> {
+        let conn = self.conn()?;
+        let tx = conn.transaction()?;
+        let inserted = tx.execute("INSERT INTO dex_rewards (user_id, milestone, at) SELECT id, $2, $3 FROM users WHERE id = $1
+                                   ON CONFLICT (user_id, milestone) DO NOTHING",
+                                  &[&user, &milestone, &now()])?;
+        if inserted == 0 {
+            if tx.query("SELECT 1 FROM users WHERE id = $1", &[&user])?.is_empty() {
+                return Err(Error::NotFound);
+            }
+            return Ok(false);
+        }
+        credit(&tx, user, vults, reason)?;
+        tx.commit()?;
+        Ok(true)
+    }
+
+    fn subscribe(&self, subscriber: &str) -> Result<()> {
+        let conn = self.conn()?;
+        conn.execute("INSERT INTO outbox_subscribers (name, since)
+                      SELECT $1, COALESCE(MAX(id), 0) FROM outbox
+                      ON CONFLICT (name) DO NOTHING",
+                     &[&subscriber])?;
+        Ok(())
+    }
+
+    fn pending_events(&self, subscriber: &str, limit: i64) -> Result<Vec<Envelope>> {
+        let conn = self.conn()?;
+        let rows = conn.query("SELECT o.* FROM outbox o
+                               JOIN outbox_subscribers s ON s.name = $1 AND o.id > s.since
+                               WHERE NOT EXISTS (SELECT 1 FROM outbox_deliveries d
+                                                 WHERE d.event_id = o.id AND d.subscriber = $1)
+                               ORDER BY o.id LIMIT $2",
+                              &[&subscriber, &limit])?;
+        rows.iter().map(envelope).collect()
+    }
+
+    fn pending_events_for(&self, subscriber: &str, user: i32, limit: i64) -> Result<Vec<Envelope>> {
+        let conn = self.conn()?;
+        let rows = conn.query("SELECT o.* FROM outbox o
+                               JOIN outbox_users u ON u.event_id = o.id AND u.user_id = $2
+                               JOIN outbox_subscribers s ON s.name = $1 AND o.id > s.since
+                               WHERE NOT EXISTS (SELECT 1 FROM outbox_deliveries d
+                                                 WHERE d.event_id = o.id AND d.subscriber = $1)
+                               ORDER BY o.id LIMIT $3",
+                              &[&subscriber, &user, &limit])?;
+        rows.iter().map(envelope).collect()
+    }
+
+    fn mark_delivered(&self, event: i64, subscriber: &str) -> Result<()> {
+        let conn = self.conn()?;
+        let rows = conn.query("SELECT 1 FROM outbox WHERE id = $1", &[&event])?;
+        if rows.is_empty() {
+            return Err(Error::NotFound);
+        }
+        conn.execute("INSERT INTO outbox_deliveries (event_id, subscriber) VALUES ($1, $2)
+                      ON CONFLICT (event_id, subscriber) DO NOTHING",
+                     &[&event, &subscriber])?;
+        Ok(())
+    }
+
+    fn fail_delivery(&self, event: i64, subscriber: &str) -> Result<i32> {
+        let conn = self.conn()?;
+        let rows = conn.query("INSERT INTO outbox_failures (event_id, subscriber, attempts)
+                               SELECT id, $2, 1 FROM outbox WHERE id = $1
+                               ON CONFLICT (event_id, subscriber)
+                               DO UPDATE SET attempts = outbox_failures.attempts + 1
+                               RETURNING attempts",
+                              &[&event, &subscriber])?;
+        rows.iter().next().map(|row| row.get(0)).ok_or(Error::NotFound)
+    }
+
+    fn create_war(&self, war: &War, guilds: &[i32], stake: i32) -> Result<War> {
+        let conn = self.conn()?;
+        let tx = conn.transaction()?;
+        let created = war_row(tx.query("INSERT INTO wars (starts, ends, prize) VALUES ($1, $2, $3) RETURNING *",
+                                       &[&war.starts, &war.ends, &war.prize])?.get(0));
+        for guild in guilds {
+            let added = tx.execute("INSERT INTO war_scores (war_id, guild) SELECT $1, account FROM guilds WHERE account = $2",
+                                   &[&created.id, guild])?;
+            if added == 0 {
+                return Err(Error::NotFound);
+            }
+            credit(&tx, *guild, -stake, "stake in a guild war")?;
+        }
+        tx.commit()?;
+        Ok(created)
+    }
+
+    fn wars(&self, limit: i64) -> Result<Vec<War>> {
+        let conn = self.conn()?;
+        let rows = conn.query("SELECT * FROM wars ORDER BY id DESC LIMIT $1", &[&limit])?;
+        Ok(rows.iter().map(war_row).collect())
+    }
+
+    fn war_scores(&self, war: i32) -> Result<Vec<Score>> {
+        let conn = self.conn()?;
+        let rows = conn.query("SELECT * FROM war_scores WHERE war_id = $1 ORDER BY score DESC, guild", &[&war])?;
+        Ok(rows.iter().map(war_score).collect())
+    }
+
+    fn score_war(&self, war: i32, guild: i32, points: i32) -> Result<()> {
+        let conn = self.conn()?;
+        let updated = conn.execute("UPDATE war_scores SET score = score + $3 WHERE war_id = $1 AND guild = $2",
+                                   &[&war, &guild, &points])?;
+        if updated == 0 { Err(Error::NotFound) } else { Ok(()) }
+    }
+
+    fn settle_war(&self, war: i32, payouts: &[(i32, i32)], reason: &str) -> Result<bool> {
+        let conn = self.conn()?;
+        let tx = conn.transaction()?;
+        if tx.execute("UPDATE wars SET settled = TRUE WHERE id = $1 AND NOT settled", &[&war])? == 0 {
+            return if tx.query("SELECT 1 FROM wars WHERE id = $1", &[&war])?.is_empty() {
+                Err(Error::NotFound)
+            } else {
+                Ok(false)
+            };
+        }
+        for &(guild, vults) in payouts {
+            credit(&tx, guild, vults, reason)?;
+        }
+        tx.commit()?;
+        Ok(true)
+    }
+
+    fn create_raid(&self, raid: &Raid) -> Result<Raid> {
+        let conn = self.conn()?;
+        let rows = conn.query("INSERT INTO raids (boss, level, life, max_life, starts, ends)
+                               VALUES ($1, $2, $3, $4, $5, $6)
+                               RETURNING *",
+                              &[&raid.boss, &raid.level, &raid.life, &raid.max_life, &raid.starts, &raid.ends])?;
+        Ok(raid_row(rows.get(0)))
+    }
+
+    fn raids(&self, limit: i64) -> Result<Vec<Raid>> {
+        let conn = self.conn()?;
+        let rows = conn.query("SELECT * FROM raids ORDER BY id DESC LIMIT $1", &[&limit])?;
+        Ok(rows.iter().map(raid_row).collect())
+    }
+
+    fn attack_raid(&self, raid: i32, user: i32, damage: i32, energy: i32, at: i64) -> Result<(i32, Raid)> {
+        let conn = self.conn()?;
+        let tx = conn.transaction()?;
+        // Attacks on the same raid queue up here, each seeing the life the
+        // one before left.
+        let life = match tx.query("SELECT * FROM raids WHERE id = $1 FOR UPDATE", &[&raid])?.iter().next().map(raid_row) {
+            Some(ref r) if r.open(at) => r.life,
+            Some(_) => return Err(Error::Closed),
+            None => return Err(Error::NotFound),
+        };
+        charge_energy(&tx, user, -energy, at)?;
+
+        let dealt = damage.max(0).min(life);
+        tx.execute("INSERT INTO raid_damage (raid_id, user_id, damage, attacks) VALUES ($1, $2, $3, 1)
+                    ON CONFLICT (raid_id, user_id)
+                    DO UPDATE SET damage = raid_damage.damage + EXCLUDED.damage, attacks = raid_damage.attacks + 1",
+                   &[&raid, &user, &dealt])?;
+        let after = raid_row(tx.query("UPDATE raids SET life = life - $2 WHERE id = $1 RETURNING *", &[&raid, &dealt])?.get(0));
+        tx.commit()?;
+        Ok((dealt, after))
+    }
+
+    fn raid_damage(&self, raid: i32) -> Result<Vec<Damage>> {
+        let conn = self.conn()?;
+        let rows = conn.query("SELECT * FROM raid_damage WHERE raid_id = $1 ORDER BY damage DESC, user_id", &[&raid])?;
+        Ok(rows.iter().map(raid_damage).collect())
+    }
+
+    fn settle_raid(&self, raid: i32, payouts: &[(i32, i32)], reason: &str) -> Result<bool> {
+        let conn = self.conn()?;
+        let tx = conn.transaction()?;
+        if tx.execute("UPDATE raids SET settled = TRUE WHERE id = $1 AND NOT settled", &[&raid])? == 0 {
+            return if tx.query("SELECT 1 FROM raids WHERE id = $1", &[&raid])?.is_empty() {
+                Err(Error::NotFound)
+            } else {
+                Ok(false)
+            };
+        }
+        for &(user, vults) in payouts {
+            credit(&tx, user, vults, reason)?;
+        }
+        tx.commit()?;
+        Ok(true)
+    }
+
+    fn job_next_run(&self, job: &str) -> Result<Option<i64>> {
+        let conn = self.conn()?;
+        let rows = conn.query("SELECT next_run FROM jobs WHERE name = $1", &[&job])?;
+        Ok(rows.iter().next().map(|row| row.get(0)))
+    }
+
+    fn claim_job(&self, job: &str, due: Option<i64>, next: i64) -> Result<bool> {
+        let conn = self.conn()?;
+        let claimed = match due {
+            Some(due) => conn.execute("UPDATE jobs SET next_run = $3 WHERE name = $1 AND next_run = $2", &[&job, &due, &next])?,
+            None => conn.execute("INSERT INTO jobs (name, next_run) VALUES ($1, $2) ON CONFLICT (name) DO NOTHING", &[&job, &next])?,
+        };
+        Ok(claimed > 0)
+    }
+}
@@ -0,0 +1,22 @@
+// What the tests share. Each module keeps its own in a `tests` module, run by
+// `cargo test`, and the storage backends are checked against
+// storage::conformance.
+
+// ensure!(condition, "format", args...) fails a test with that message.
+macro_rules! ensure {
+    ($cond:expr, $($arg:tt)*) => {
+        if !$cond {
+            return Err(format!($($arg)*));
+        }
+    };
+}
+
+use storage::memory::MemoryStorage;
+
+// Thursday 2017-06-01, 12:07:30 UTC, where the tests' clocks stand.
+pub const NOON: i64 = 1496318850;
+
+// A storage of the test's own.
+pub fn store() -> MemoryStorage {
+    MemoryStorage::new()
+}
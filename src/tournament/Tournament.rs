@@ -0,0 +1,109 @@
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    Single,
+    Double,
+    Swiss,
+}
+
+pub const FORMATS: &'static [Format] = &[Format::Single, Format::Double, Format::Swiss];
+
+impl Format {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Format::Single => "single",
+            Format::Double => "double",
+            Format::Swiss => "swiss",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Format> {
+        FORMATS.iter().cloned().find(|f| f.name() == name)
+    }
+
+    pub fn min_entrants(&self) -> usize {
+        match *self {
+            Format::Double => 3,
+            _ => 2,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum State {
+    // Taking entrants.
+    Open,
+    Running,
+    // Prizes paid, or entry fees refunded if it never started.
+    Finished,
+}
+
+pub const STATES: &'static [State] = &[State::Open, State::Running, State::Finished];
+
+impl State {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            State::Open => "open",
+            State::Running => "running",
+            State::Finished => "finished",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<State> {
+        STATES.iter().cloned().find(|s| s.name() == name)
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Tournament {
+    pub id: i32,
+    pub name: String,
+    pub format: Format,
+    pub organizer: i32,
+    // Vults each entrant pays in, the pool goes to the prizes.
+    pub fee: i32,
+    pub state: State,
+    pub created: i64,
+    // When it left the Open state, 0 until then.
+    pub started: i64,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Entrant {
+    pub tournament: i32,
+    pub user: i32,
+    // 1 for the best rated, set when the tournament starts.
+    pub seed: i32,
+    // The match the entrant checked in for, if any.
+    pub ready: Option<i32>,
+    // The match being played, once claimed, so it isn't settled as a no-show.
+    pub claimed: Option<i32>,
+    pub joined: i64,
+}
+
+// The outcome of one bracket match. Matches are numbered by the bracket, see
+// tournament::bracket.
+#[derive(Clone, Debug, PartialEq)]
+pub struct MatchResult {
+    pub tournament: i32,
+    pub number: i32,
+    pub winner: i32,
+    // Won without playing, the other side didn't show up in time.
+    pub walkover: bool,
+    pub replay: Option<i32>,
+    pub at: i64,
+}
+
+impl Tournament {
+    pub fn new(name: &str, format: Format, organizer: i32, fee: i32) -> Tournament {
+        Tournament {
+            id: 0,
+            name: name.to_owned(),
+            format: format,
+            organizer: organizer,
+            fee: fee,
+            state: State::Open,
+            created: 0,
+            started: 0,
+        }
+    }
+}
@@ -0,0 +1,395 @@
+// Brackets are never stored. They are worked out from the seeds and the
+// results recorded so far, the same way a replay is played from its actions,
+// so match numbers stay the same every time.
+
+use std::collections::BTreeMap;
+
+use tournament::Tournament::{Format, MatchResult};
+
+// How long the players of a match have to check in once both are known.
+pub const MATCH_TIMEOUT: i64 = 30 * 60;
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Stage {
+    Winners,
+    Losers,
+    Final,
+    Swiss,
+}
+
+impl Stage {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Stage::Winners => "winners",
+            Stage::Losers => "losers",
+            Stage::Final => "grand final",
+            Stage::Swiss => "swiss",
+        }
+    }
+}
+
+// Who fills one side of a match.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Side {
+    Player(i32),
+    // Nobody, the other side goes through without playing.
+    Bye,
+    // Decided by a match that isn't over yet.
+    Pending,
+}
+
+impl Side {
+    pub fn player(&self) -> Option<i32> {
+        match *self {
+            Side::Player(user) => Some(user),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Match {
+    pub number: i32,
+    pub stage: Stage,
+    pub round: i32,
+    pub sides: [Side; 2],
+    pub winner: Side,
+    pub loser: Side,
+    pub walkover: bool,
+    pub replay: Option<i32>,
+    // When both sides became known, or when a decided match was decided.
+    pub since: i64,
+    // How far into the bracket the match is, losing it later places better.
+    depth: i32,
+}
+
+impl Match {
+    pub fn players(&self) -> Option<[i32; 2]> {
+        match (self.sides[0], self.sides[1]) {
+            (Side::Player(a), Side::Player(b)) => Some([a, b]),
+            _ => None,
+        }
+    }
+
+    // Both players are known and it hasn't been played.
+    pub fn playable(&self) -> bool {
+        self.players().is_some() && self.winner == Side::Pending
+    }
+
+    pub fn deadline(&self) -> i64 {
+        self.since + MATCH_TIMEOUT
+    }
+
+    pub fn has(&self, user: i32) -> bool {
+        self.sides.iter().any(|s| *s == Side::Player(user))
+    }
+}
+
+// Where a side of an elimination match comes from.
+#[derive(Clone, Copy)]
+enum Feed {
+    // An index in the seeds, past the end is a bye.
+    Seed(usize),
+    Winner(usize),
+    Loser(usize),
+}
+
+struct Slot {
+    stage: Stage,
+    round: i32,
+    depth: i32,
+    feeds: [Feed; 2],
+    // The second grand final, only played if the losers bracket side won
+    // the first.
+    reset: bool,
+}
+
+pub struct Bracket {
+    pub format: Format,
+    pub matches: Vec<Match>,
+    // User ids, best seed first.
+    pub seeds: Vec<i32>,
+    // Swiss only, how many rounds are played.
+    pub rounds: i32,
+}
+
+impl Bracket {
+    pub fn new(format: Format, seeds: &[i32], results: &[MatchResult], started: i64) -> Bracket {
+        let results: BTreeMap<i32, &MatchResult> = results.iter().map(|r| (r.number, r)).collect();
+        let mut bracket = Bracket {
+            format: format,
+            matches: Vec::new(),
+            seeds: seeds.to_vec(),
+            rounds: swiss_rounds(seeds.len()),
+        };
+        match format {
+            Format::Single => bracket.eliminate(&elimination(seeds.len(), false), &results, started),
+            Format::Double => bracket.eliminate(&elimination(seeds.len(), true), &results, started),
+            Format::Swiss => bracket.swiss(&results, started),
+        }
+        bracket
+    }
+
+    pub fn finished(&self) -> bool {
+        let decided = self.matches.iter().all(|m| m.winner != Side::Pending);
+        match self.format {
+            Format::Swiss => decided && self.matches.last().map_or(false, |m| m.round == self.rounds),
+            _ => decided,
+        }
+    }
+
+    pub fn playable(&self) -> Vec<&Match> {
+        self.matches.iter().filter(|m| m.playable()).collect()
+    }
+
+    // The match a player has to play next, if any.
+    pub fn match_of(&self, user: i32) -> Option<&Match> {
+        self.matches.iter().find(|m| m.playable() && m.has(user))
+    }
+
+    // 1 for the top seed.
+    pub fn seed_of(&self, user: i32) -> usize {
+        self.seeds.iter().position(|&s| s == user).map_or(self.seeds.len(), |i| i) + 1
+    }
+
+    // Final places, best first. Players knocked out at the same depth of an
+    // elimination bracket share their place.
+    pub fn standings(&self) -> Vec<Vec<i32>> {
+        if self.format == Format::Swiss {
+            return self.swiss_table().into_iter().map(|(user, _, _)| vec![user]).collect();
+        }
+
+        let champion = self.matches.iter().rev().filter_map(|m| m.winner.player()).next();
+        let mut out: BTreeMap<i32, Vec<i32>> = BTreeMap::new();
+        for &user in &self.seeds {
+            if Some(user) == champion {
+                continue;
+            }
+            let depth = self.matches.iter().rev()
+                .find(|m| m.loser == Side::Player(user))
+                .map_or(-1, |m| m.depth);
+            out.entry(depth).or_insert_with(Vec::new).push(user);
+        }
+
+        let mut standings: Vec<Vec<i32>> = champion.into_iter().map(|c| vec![c]).collect();
+        standings.extend(out.into_iter().rev().map(|(_, users)| users));
+        standings
+    }
+
+    fn eliminate(&mut self, slots: &[Slot], results: &BTreeMap<i32, &MatchResult>, started: i64) {
+        for (i, slot) in slots.iter().enumerate() {
+            let mut since = started;
+            let mut sides = [Side::Pending; 2];
+            for (side, feed) in sides.iter_mut().zip(slot.feeds.iter()) {
+                *side = match *feed {
+                    Feed::Seed(s) => self.seeds.get(s).map_or(Side::Bye, |&user| Side::Player(user)),
+                    Feed::Winner(m) => self.matches[m].winner,
+                    Feed::Loser(m) => self.matches[m].loser,
+                };
+                match *feed {
+                    Feed::Winner(m) | Feed::Loser(m) => since = since.max(self.matches[m].since),
+                    Feed::Seed(_) => {}
+                }
+            }
+
+            let mut m = Match {
+                number: i as i32 + 1,
+                stage: slot.stage,
+                round: slot.round,
+                sides: sides,
+                winner: Side::Pending,
+                loser: Side::Pending,
+                walkover: false,
+                replay: None,
+                since: since,
+                depth: slot.depth,
+            };
+            // The winners bracket champion won the first grand final.
+            let settled = slot.reset && sides[0] != Side::Pending && sides[0] == self.matches[i - 1].sides[0];
+            if settled {
+                m.sides = [Side::Bye, Side::Bye];
+            }
+            decide(&mut m, results);
+            self.matches.push(m);
+        }
+    }
+
+    fn swiss(&mut self, results: &BTreeMap<i32, &MatchResult>, started: i64) {
+        for round in 1..self.rounds + 1 {
+            if self.matches.iter().any(|m| m.winner == Side::Pending) {
+                return;
+            }
+            let since = self.matches.iter().map(|m| m.since).max().unwrap_or(started).max(started);
+
+            let mut order: Vec<i32> = self.swiss_table().into_iter().map(|(user, _, _)| user).collect();
+            let mut pairs = Vec::new();
+            if order.len() % 2 == 1 {
+                // The lowest ranked player who hasn't had a bye yet sits out.
+                let i = order.iter().rposition(|&u| !self.had_bye(u)).unwrap_or(order.len() - 1);
+                pairs.push([Side::Player(order.remove(i)), Side::Bye]);
+            }
+            // Rematches only when there's no way around them.
+            let paired = self.pair(&order).unwrap_or_else(|| order.chunks(2).map(|p| [p[0], p[1]]).collect());
+            pairs.extend(paired.into_iter().map(|p| [Side::Player(p[0]), Side::Player(p[1])]));
+
+            for sides in pairs {
+                let mut m = Match {
+                    number: self.matches.len() as i32 + 1,
+                    stage: Stage::Swiss,
+                    round: round,
+                    sides: sides,
+                    winner: Side::Pending,
+                    loser: Side::Pending,
+                    walkover: false,
+                    replay: None,
+                    since: since,
+                    depth: round,
+                };
+                decide(&mut m, results);
+                self.matches.push(m);
+            }
+        }
+    }
+
+    // Pairs each player with the first one below them they haven't met,
+    // going back on earlier pairs when that leaves someone out.
+    fn pair(&self, order: &[i32]) -> Option<Vec<[i32; 2]>> {
+        let a = match order.first() {
+            Some(&a) => a,
+            None => return Some(Vec::new()),
+        };
+        for (i, &b) in order.iter().enumerate().skip(1) {
+            if self.met(a, b) {
+                continue;
+            }
+            let rest: Vec<i32> = order[1..].iter().enumerate().filter(|&(j, _)| j + 1 != i).map(|(_, &u)| u).collect();
+            if let Some(mut pairs) = self.pair(&rest) {
+                pairs.insert(0, [a, b]);
+                return Some(pairs);
+            }
+        }
+        None
+    }
+
+    fn met(&self, a: i32, b: i32) -> bool {
+        self.matches.iter().any(|m| m.has(a) && m.has(b))
+    }
+
+    fn had_bye(&self, user: i32) -> bool {
+        self.matches.iter().any(|m| m.has(user) && m.sides.contains(&Side::Bye))
+    }
+
+    // (user, points, opponents' points) ordered by points, then by the
+    // points of the opponents met, then by seed.
+    fn swiss_table(&self) -> Vec<(i32, i32, i32)> {
+        let points = |user: i32| self.matches.iter().filter(|m| m.winner == Side::Player(user)).count() as i32;
+        let mut table: Vec<(i32, i32, i32)> = self.seeds.iter()
+            .map(|&user| {
+                let opponents: i32 = self.matches.iter()
+                    .filter_map(|m| m.players())
+                    .filter(|p| p.contains(&user))
+                    .map(|p| points(if p[0] == user { p[1] } else { p[0] }))
+                    .sum();
+                (user, points(user), opponents)
+            })
+            .collect();
+        table.sort_by(|a, b| b.1.cmp(&a.1).then(b.2.cmp(&a.2)).then(self.seed_of(a.0).cmp(&self.seed_of(b.0))));
+        table
+    }
+}
+
+// Fills in the winner from the recorded results, byes go through on their
+// own.
+fn decide(m: &mut Match, results: &BTreeMap<i32, &MatchResult>) {
+    match (m.sides[0], m.sides[1]) {
+        (Side::Pending, _) | (_, Side::Pending) => {}
+        (Side::Player(a), Side::Player(b)) => {
+            if let Some(result) = results.get(&m.number).cloned().into_iter().find(|r| r.winner == a || r.winner == b) {
+                m.winner = Side::Player(result.winner);
+                m.loser = Side::Player(if result.winner == a { b } else { a });
+                m.walkover = result.walkover;
+                m.replay = result.replay;
+                m.since = result.at;
+            }
+        }
+        (player, Side::Bye) | (Side::Bye, player) => {
+            m.winner = player;
+            m.loser = Side::Bye;
+        }
+    }
+}
+
+fn swiss_rounds(entrants: usize) -> i32 {
+    let mut rounds = 1;
+    while 1 << rounds < entrants {
+        rounds += 1;
+    }
+    rounds
+}
+
+// Round one order of a bracket of `size`, so the top seeds meet last:
+// 1 v 8, 4 v 5, 2 v 7, 3 v 6.
+fn seeding(size: usize) -> Vec<usize> {
+    let mut order = vec![0];
+    while order.len() < size {
+        let n = order.len() * 2;
+        order = order.iter().flat_map(|&s| vec![s, n - 1 - s]).collect();
+    }
+    order
+}
+
+fn pairs(feeds: &[Feed]) -> Vec<[Feed; 2]> {
+    feeds.chunks(2).map(|pair| [pair[0], pair[1]]).collect()
+}
+
+fn push(slots: &mut Vec<Slot>, stage: Stage, round: i32, depth: i32, feeds: [Feed; 2], reset: bool) -> usize {
+    slots.push(Slot { stage: stage, round: round, depth: depth, feeds: feeds, reset: reset });
+    slots.len() - 1
+}
+
+// The matches of an elimination bracket, every match after the matches it's
+// fed by.
+fn elimination(entrants: usize, double: bool) -> Vec<Slot> {
+    let size = entrants.next_power_of_two().max(2);
+    let mut slots = Vec::new();
+    let mut feeds: Vec<Feed> = seeding(size).into_iter().map(Feed::Seed).collect();
+    let mut winners: Vec<Vec<usize>> = Vec::new();
+    while feeds.len() > 1 {
+        let round = winners.len() as i32 + 1;
+        let depth = if double { 0 } else { round };
+        let matches: Vec<usize> = pairs(&feeds).into_iter()
+            .map(|pair| push(&mut slots, Stage::Winners, round, depth, pair, false))
+            .collect();
+        feeds = matches.iter().map(|&m| Feed::Winner(m)).collect();
+        winners.push(matches);
+    }
+    if !double {
+        return slots;
+    }
+
+    // The losers of the first round play each other, then each round of
+    // survivors meets the players dropping from the next winners round.
+    let mut round = 1;
+    let first: Vec<Feed> = winners[0].iter().map(|&m| Feed::Loser(m)).collect();
+    let mut survivors: Vec<Feed> = pairs(&first).into_iter()
+        .map(|pair| Feed::Winner(push(&mut slots, Stage::Losers, round, round, pair, false)))
+        .collect();
+    for (j, dropped) in winners.iter().enumerate().skip(1) {
+        round += 1;
+        // Dropped players are reversed so they don't meet who they just beat.
+        survivors = survivors.iter().zip(dropped.iter().rev())
+            .map(|(&s, &d)| Feed::Winner(push(&mut slots, Stage::Losers, round, round, [s, Feed::Loser(d)], false)))
+            .collect();
+        if j + 1 < winners.len() {
+            round += 1;
+            survivors = pairs(&survivors).into_iter()
+                .map(|pair| Feed::Winner(push(&mut slots, Stage::Losers, round, round, pair, false)))
+                .collect();
+        }
+    }
+
+    let champion = Feed::Winner(*winners.last().unwrap().last().unwrap());
+    let last = push(&mut slots, Stage::Final, 1, round + 1, [champion, survivors[0]], false);
+    push(&mut slots, Stage::Final, 2, round + 2, [Feed::Winner(last), Feed::Loser(last)], true);
+    slots
+}
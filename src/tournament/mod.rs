@@ -0,0 +1,229 @@
+pub mod bracket;
+pub mod Tournament;
+
+use self::bracket::{Bracket, Match};
+
+// Percent of the entry fee pool paid to each place. Shares nobody is placed
+// to take, and what rounding leaves, go to the winner.
+pub const PRIZES: &'static [i32] = &[60, 30, 10];
+pub const MAX_ENTRANTS: i32 = 32;
+// The most an entry can cost, a full pool stays far from overflowing.
+pub const MAX_FEE: i32 = 10_000;
+
+// The vults `entrants` paid in.
+pub fn pool(fee: i32, entrants: usize) -> i64 {
+    fee as i64 * entrants as i64
+}
+
+// (user, vults) for everyone who wins something. Players sharing a place
+// split the shares of the places they take up.
+pub fn prizes(pool: i64, standings: &[Vec<i32>]) -> Vec<(i32, i32)> {
+    let mut paid = Vec::new();
+    let mut place = 0;
+    for users in standings {
+        let share: i32 = PRIZES.iter().skip(place).take(users.len()).sum();
+        place += users.len();
+        for &user in users {
+            paid.push((user, pool * share as i64 / 100 / users.len() as i64));
+        }
+    }
+
+    let rest = pool - paid.iter().map(|&(_, vults)| vults).sum::<i64>();
+    if let Some(first) = paid.first_mut() {
+        first.1 += rest;
+    }
+    paid.into_iter().filter(|&(_, vults)| vults > 0).map(|(user, vults)| (user, vults as i32)).collect()
+}
+
+// Who goes through a match: the better seed, used when the game was a draw or
+// nobody showed up.
+pub fn better_seed(bracket: &Bracket, players: [i32; 2]) -> i32 {
+    if bracket.seed_of(players[0]) <= bracket.seed_of(players[1]) { players[0] } else { players[1] }
+}
+
+// The winner of a match that timed out: the only player who checked in, or
+// the better seed when neither did.
+pub fn no_show(bracket: &Bracket, m: &Match, ready: &[i32]) -> Option<i32> {
+    let players = match m.players() {
+        Some(players) => players,
+        None => return None,
+    };
+    match (ready.contains(&players[0]), ready.contains(&players[1])) {
+        (true, false) => Some(players[0]),
+        (false, true) => Some(players[1]),
+        _ => Some(better_seed(bracket, players)),
+    }
+}
+
+// Bracket logic.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::bracket::Stage;
+    use super::Tournament::{Format, MatchResult};
+
+    // Users 1 to n, seeded in that order.
+    fn seeds(n: i32) -> Vec<i32> {
+        (1..n + 1).collect()
+    }
+
+    fn result(number: i32, winner: i32) -> MatchResult {
+        MatchResult { tournament: 1, number: number, winner: winner, walkover: false, replay: None, at: number as i64 }
+    }
+
+    // Plays a whole tournament, `pick` choosing the winner of every match.
+    fn run<F>(format: Format, n: i32, pick: F) -> Result<(Bracket, Vec<MatchResult>), String>
+        where F: Fn(&Match, [i32; 2]) -> i32
+    {
+        let mut results = Vec::new();
+        for _ in 0..100 {
+            let bracket = Bracket::new(format, &seeds(n), &results, 0);
+            if bracket.finished() {
+                return Ok((bracket, results));
+            }
+            let playable: Vec<(i32, i32)> = bracket.playable().iter()
+                .map(|m| (m.number, pick(m, m.players().unwrap())))
+                .collect();
+            ensure!(!playable.is_empty(), "{} with {} players got stuck", format.name(), n);
+            results.extend(playable.into_iter().map(|(number, winner)| result(number, winner)));
+        }
+        Err(format!("{} with {} players never finished", format.name(), n))
+    }
+
+    fn favourite(_: &Match, players: [i32; 2]) -> i32 {
+        players[0].min(players[1])
+    }
+
+    #[test]
+    fn top_seeds_meet_in_the_final() -> Result<(), String> {
+        for &n in &[2, 5, 8, 13] {
+            let (bracket, _) = run(Format::Single, n, favourite)?;
+            let last = bracket.matches.last().unwrap();
+            ensure!(last.players() == Some([1, 2]), "final of {} was {:?}", n, last.sides);
+            let standings = bracket.standings();
+            ensure!(standings[0] == vec![1] && standings[1] == vec![2], "{} players placed {:?}", n, standings);
+            if n > 2 {
+                let mut third = standings[2].clone();
+                third.sort();
+                ensure!(third == vec![3, 4], "{} players share third as {:?}", n, third);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn byes_go_to_the_top_seeds() -> Result<(), String> {
+        let bracket = Bracket::new(Format::Single, &seeds(5), &[], 0);
+        let played: Vec<[i32; 2]> = bracket.matches.iter().filter(|m| m.round == 1).filter_map(|m| m.players()).collect();
+        ensure!(played == vec![[4, 5]], "round one plays {:?}", played);
+        ensure!(bracket.matches.len() == 7, "{} matches for 5 players", bracket.matches.len());
+        Ok(())
+    }
+
+    #[test]
+    fn double_elimination_takes_two_losses() -> Result<(), String> {
+        for &n in &[3, 4, 6, 8] {
+            // The top seed drops its first game, then wins everything.
+            let (bracket, results) = run(Format::Double, n, |m, players| {
+                if m.stage == Stage::Winners && players.contains(&1) { players[0].max(players[1]) } else { favourite(m, players) }
+            })?;
+            let standings = bracket.standings();
+            ensure!(standings[0] == vec![1], "{} players won by {:?}", n, standings[0]);
+            for user in 1..n + 1 {
+                let losses = bracket.matches.iter().filter(|m| m.loser.player() == Some(user)).count();
+                let expected = if user == 1 { 1 } else { 2 };
+                ensure!(losses == expected, "{} of {} went out after {} losses", user, n, losses);
+            }
+            ensure!(results.len() as i32 <= 2 * n, "{} games for {} players", results.len(), n);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn the_grand_final_is_only_reset_when_needed() -> Result<(), String> {
+        // The losers bracket side taking the first final forces a second one.
+        let (bracket, _) = run(Format::Double, 4, |m, players| {
+            if m.stage == Stage::Final && m.round == 1 { players[0].max(players[1]) } else { favourite(m, players) }
+        })?;
+        let finals: Vec<&Match> = bracket.matches.iter().filter(|m| m.stage == Stage::Final).collect();
+        ensure!(finals[1].players().is_some(), "no reset after the losers side won");
+
+        let (bracket, _) = run(Format::Double, 4, favourite)?;
+        let reset = bracket.matches.last().unwrap();
+        ensure!(reset.players().is_none(), "reset played after the winners side won");
+        ensure!(bracket.standings()[0] == vec![1], "won by {:?}", bracket.standings()[0]);
+        Ok(())
+    }
+
+    #[test]
+    fn swiss_avoids_rematches() -> Result<(), String> {
+        for &n in &[4, 6, 8] {
+            let (bracket, _) = run(Format::Swiss, n, favourite)?;
+            let mut pairs: Vec<[i32; 2]> = bracket.matches.iter()
+                .filter_map(|m| m.players())
+                .map(|p| [p[0].min(p[1]), p[0].max(p[1])])
+                .collect();
+            let games = pairs.len();
+            pairs.sort();
+            pairs.dedup();
+            ensure!(pairs.len() == games, "{} players had a rematch", n);
+            ensure!(bracket.standings()[0] == vec![1], "{} players won by {:?}", n, bracket.standings()[0]);
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn swiss_byes_go_around() -> Result<(), String> {
+        let (bracket, _) = run(Format::Swiss, 5, favourite)?;
+        ensure!(bracket.rounds == 3, "5 players play {} rounds", bracket.rounds);
+        let mut byes: Vec<i32> = bracket.matches.iter()
+            .filter(|m| m.players().is_none())
+            .filter_map(|m| m.winner.player())
+            .collect();
+        ensure!(byes.len() == 3, "{} byes in 3 rounds", byes.len());
+        byes.sort();
+        byes.dedup();
+        ensure!(byes.len() == 3, "the same player sat out twice");
+        Ok(())
+    }
+
+    #[test]
+    fn no_shows_lose_their_match() -> Result<(), String> {
+        let bracket = Bracket::new(Format::Single, &seeds(4), &[], 0);
+        let first = bracket.match_of(4).ok_or("seed 4 has no match")?;
+        ensure!(first.players() == Some([1, 4]), "seed 4 plays {:?}", first.sides);
+        ensure!(no_show(&bracket, first, &[4]) == Some(4), "the player who showed up lost");
+        ensure!(no_show(&bracket, first, &[]) == Some(1), "nobody showed up and the better seed lost");
+        ensure!(first.deadline() == bracket::MATCH_TIMEOUT, "deadline is {}", first.deadline());
+
+        // The next match starts its clock once both of its players are known.
+        let walkover = MatchResult { walkover: true, ..result(first.number, 4) };
+        let bracket = Bracket::new(Format::Single, &seeds(4), &[walkover, result(2, 2)], 0);
+        let next = bracket.match_of(4).ok_or("seed 4 didn't go through")?;
+        ensure!(next.players() == Some([4, 2]), "the final is {:?}", next.sides);
+        ensure!(next.deadline() == 2 + bracket::MATCH_TIMEOUT, "final deadline is {}", next.deadline());
+        Ok(())
+    }
+
+    #[test]
+    fn prizes_add_up_to_the_pool() -> Result<(), String> {
+        let full = pool(MAX_FEE, MAX_ENTRANTS as usize);
+        let cases: &[(i64, &[&[i32]])] = &[
+            (1000, &[&[1], &[2], &[3, 4], &[5, 6, 7, 8]]),
+            (100, &[&[1], &[2]]),
+            (99, &[&[1], &[2], &[3], &[4]]),
+            (7, &[&[1], &[2], &[3, 4]]),
+            (full, &[&[1], &[2], &[3]]),
+        ];
+        for &(pool, standings) in cases {
+            let standings: Vec<Vec<i32>> = standings.iter().map(|s| s.to_vec()).collect();
+            let paid = prizes(pool, &standings);
+            let total: i64 = paid.iter().map(|&(_, vults)| vults as i64).sum();
+            ensure!(total == pool, "paid {} out of {}", total, pool);
+            ensure!(paid[0].0 == 1, "the winner isn't paid first");
+        }
+        let paid = prizes(1000, &[vec![1], vec![2], vec![3, 4]]);
+        ensure!(paid == vec![(1, 600), (2, 300), (3, 50), (4, 50)], "paid {:?}", paid);
+        Ok(())
+    }
+}
@@ -0,0 +1,30 @@
+// What a server account has on top of a player's: its vults are the guild
+// treasury and the Deus it owns its guardians.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Guild {
+    // The server account.
+    pub account: i32,
+    // Percent of the vults members find exploring in the server that goes to
+    // the treasury.
+    pub tax: i32,
+    // Where the guild hears about wars, none keeps it out of them.
+    pub channel: Option<i64>,
+}
+
+pub const MAX_TAX: i32 = 50;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Member {
+    pub guild: i32,
+    pub user: i32,
+    // Admins run the guild with !server.
+    pub admin: bool,
+    pub joined: i64,
+}
+
+impl Guild {
+    // The treasury's cut of `found` vults, rounded down.
+    pub fn tax_on(&self, found: i32) -> i32 {
+        found * self.tax / 100
+    }
+}
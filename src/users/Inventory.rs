@@ -0,0 +1,8 @@
+// How many of one item a user has, items are named as in data/items.json.
+// Stacks that run out are dropped.
+#[derive(Clone, Debug, PartialEq)]
+pub struct ItemStack {
+    pub user: i32,
+    pub item: String,
+    pub count: i32,
+}
@@ -0,0 +1,11 @@
+// One movement of vults on a user's balance. Transfers between users are
+// recorded as two entries, one on each side.
+#[derive(Clone, Debug, PartialEq)]
+pub struct LedgerEntry {
+    pub id: i64,
+    pub user: i32,
+    pub amount: i32,
+    pub balance: i32,
+    pub reason: String,
+    pub at: i64,
+}
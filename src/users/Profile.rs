@@ -0,0 +1,63 @@
+// Parts of the profile card a player can hide from others.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Section {
+    Vults,
+    Collection,
+    Pvp,
+    Streaks,
+    Badges,
+}
+
+pub const SECTIONS: &'static [Section] = &[
+    Section::Vults,
+    Section::Collection,
+    Section::Pvp,
+    Section::Streaks,
+    Section::Badges,
+];
+
+impl Section {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            Section::Vults => "vults",
+            Section::Collection => "collection",
+            Section::Pvp => "pvp",
+            Section::Streaks => "streaks",
+            Section::Badges => "badges",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<Section> {
+        SECTIONS.iter().cloned().find(|s| s.name() == name)
+    }
+
+    fn bit(&self) -> i32 {
+        1 << SECTIONS.iter().position(|s| s == self).unwrap()
+    }
+}
+
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Profile {
+    pub user: i32,
+    pub favorite: Option<i32>,
+    pub wins: i32,
+    pub losses: i32,
+    pub win_streak: i32,
+    pub best_streak: i32,
+    // Bit set of hidden sections.
+    pub hidden: i32,
+}
+
+impl Profile {
+    pub fn is_hidden(&self, section: Section) -> bool {
+        self.hidden & section.bit() != 0
+    }
+
+    pub fn set_hidden(&mut self, section: Section, hidden: bool) {
+        if hidden {
+            self.hidden |= section.bit();
+        } else {
+            self.hidden &= !section.bit();
+        }
+    }
+}
@@ -0,0 +1,20 @@
+// A user's standing in one ladder season. Draws are the games that are
+// neither wins nor losses.
+#[derive(Clone, Debug, PartialEq)]
+pub struct Rating {
+    pub user: i32,
+    pub season: i32,
+    pub rating: i32,
+    pub games: i32,
+    pub wins: i32,
+    pub losses: i32,
+    pub peak: i32,
+    // Set once the season's reward has been paid.
+    pub rewarded: bool,
+}
+
+impl Rating {
+    pub fn draws(&self) -> i32 {
+        self.games - self.wins - self.losses
+    }
+}
@@ -1,6 +1,39 @@
-struct User {
-    id: i32,
-    disc_id: i32,
-    name: String,
-    vults: i32,
+// The `Type(server, player)` of USERS in the diagram. Server accounts belong
+// to a discord server rather than a person, see users::Guild.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum UserType {
+    Player,
+    Server,
+}
+
+pub const USER_TYPES: &'static [UserType] = &[UserType::Player, UserType::Server];
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct User {
+    pub id: i32,
+    // The discord server's id for server accounts.
+    pub disc_id: i64,
+    pub name: String,
+    pub kind: UserType,
+    pub vults: i32,
+    pub joined: i64,
+    // As last counted, at `energy_at`, see energy::count.
+    pub energy: i32,
+    pub energy_at: i64,
+    // Language picked with !language, or by the admins of a server account,
+    // see i18n::resolve.
+    pub locale: Option<String>,
+}
+
+impl UserType {
+    pub fn name(&self) -> &'static str {
+        match *self {
+            UserType::Player => "player",
+            UserType::Server => "server",
+        }
+    }
+
+    pub fn from_name(name: &str) -> Option<UserType> {
+        USER_TYPES.iter().cloned().find(|t| t.name() == name)
+    }
 }
@@ -1 +1,6 @@
-mod Users;
+pub mod Users;
+pub mod Guild;
+pub mod Inventory;
+pub mod Ledger;
+pub mod Profile;
+pub mod Rating;
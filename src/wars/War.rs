@@ -0,0 +1,37 @@
+// Guilds with an announcement channel, see users::Guild::Guild, score a point
+// for each battle one of their members wins between `starts` and `ends`.
+#[derive(Clone, Debug, PartialEq)]
+pub struct War {
+    pub id: i32,
+    pub starts: i64,
+    pub ends: i64,
+    // Vults for the winning treasury, split on a tie.
+    pub prize: i32,
+    // Set once the prize is paid.
+    pub settled: bool,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Score {
+    pub war: i32,
+    // The server account.
+    pub guild: i32,
+    pub score: i32,
+}
+
+impl War {
+    // Storage fills in the id.
+    pub fn new(starts: i64, ends: i64, prize: i32) -> War {
+        War {
+            id: 0,
+            starts: starts,
+            ends: ends,
+            prize: prize,
+            settled: false,
+        }
+    }
+
+    pub fn running(&self, at: i64) -> bool {
+        self.starts <= at && at < self.ends
+    }
+}
@@ -0,0 +1,281 @@
+pub mod War;
+
+use serenity::model::ChannelId;
+
+use events::Event;
+use storage::{self, Storage};
+use self::War::{Score, War as GuildWar};
+
+// Wars open on Fridays, see scheduler::JOBS, and last the weekend.
+pub const LENGTH: i64 = 2 * 86400;
+// What each guild puts in from its treasury, the prize is all of it.
+pub const STAKE: i32 = 1000;
+// Fewer guilds with a channel and the stake than this and there's no war.
+pub const MIN_GUILDS: usize = 2;
+// How far back running and ended wars are looked for.
+pub const RECENT: i64 = 10;
+
+// Wars that aren't settled and run at `at`.
+pub fn running(store: &Storage, at: i64) -> Result<Vec<GuildWar>, String> {
+    Ok(store.wars(RECENT)?.into_iter().filter(|w| !w.settled && w.running(at)).collect())
+}
+
+// Subscribed to game events, see events::SUBSCRIBERS. Battles are only
+// finished once both players agreed to them, accepted challenges and
+// tournament matches. One won while a war runs scores a point for each guild
+// in it the winner is a member of, when the loser fights for another guild in
+// the war and not that one.
+pub fn on_event(store: &Storage, _: &str, event: &Event, at: i64) -> Result<Vec<String>, String> {
+    let (players, winner) = match *event {
+        Event::BattleFinished { players, winner: Some(winner) } => (players, winner),
+        _ => return Ok(Vec::new()),
+    };
+    let loser = if players[0] == winner { players[1] } else { players[0] };
+    for war in running(store, at)? {
+        let (mut winners, mut losers) = (Vec::new(), Vec::new());
+        for score in store.war_scores(war.id)? {
+            let members = store.guild_members(score.guild)?;
+            if members.iter().any(|m| m.user == winner) {
+                winners.push(score.guild);
+            }
+            if members.iter().any(|m| m.user == loser) {
+                losers.push(score.guild);
+            }
+        }
+        if losers.is_empty() {
+            continue;
+        }
+        for guild in winners.into_iter().filter(|g| !losers.contains(g)) {
+            store.score_war(war.id, guild, 1)?;
+        }
+    }
+    Ok(Vec::new())
+}
+
+// Opens a war at `at` between every guild with a channel that can pay the
+// stake, unless one is running already or there aren't enough of them.
+pub fn open(store: &Storage, at: i64) -> Result<Option<GuildWar>, String> {
+    if !running(store, at)?.is_empty() {
+        return Ok(None);
+    }
+    loop {
+        let mut guilds = Vec::new();
+        for g in store.guilds()?.iter().filter(|g| g.channel.is_some()) {
+            if store.user(g.account)?.vults >= STAKE {
+                guilds.push(g.account);
+            }
+        }
+        if guilds.len() < MIN_GUILDS {
+            return Ok(None);
+        }
+        let war = GuildWar::new(at, at + LENGTH, STAKE * guilds.len() as i32);
+        match store.create_war(&war, &guilds, STAKE) {
+            Ok(war) => return Ok(Some(war)),
+            // A treasury was spent since, try again without it.
+            Err(storage::Error::NotEnoughVults) => continue,
+            Err(e) => return Err(e.into()),
+        }
+    }
+}
+
+// What each guild gets out of a war as (guild, vults), `scores` being the
+// standings: the best score takes the prize, split on a tie. When nobody
+// scored every guild gets its stake back.
+pub fn payouts(scores: &[Score], prize: i32) -> Vec<(i32, i32)> {
+    let best = scores.iter().map(|s| s.score).max().unwrap_or(0);
+    if best <= 0 {
+        return split(scores.iter().map(|s| s.guild).collect(), prize);
+    }
+    split(scores.iter().filter(|s| s.score == best).map(|s| s.guild).collect(), prize)
+}
+
+// `prize` shared evenly, what doesn't divide going a vult at a time to the
+// first guilds.
+fn split(guilds: Vec<i32>, prize: i32) -> Vec<(i32, i32)> {
+    let count = guilds.len() as i32;
+    if count == 0 {
+        return Vec::new();
+    }
+    let (share, left) = (prize / count, prize % count);
+    guilds.into_iter().enumerate().map(|(i, guild)| (guild, share + if (i as i32) < left { 1 } else { 0 })).collect()
+}
+
+// Pays out a war that ended and returns its results, None when someone
+// settled it first.
+pub fn settle(store: &Storage, lang: &str, war: &GuildWar) -> Result<Option<String>, String> {
+    let scores = store.war_scores(war.id)?;
+    let paid = payouts(&scores, war.prize);
+    let nobody = scores.iter().all(|s| s.score <= 0);
+    let reason = if nobody { "stake back from a guild war" } else { "won a guild war" };
+    if !store.settle_war(war.id, &paid, reason)? {
+        return Ok(None);
+    }
+
+    let mut names = Vec::new();
+    for &(guild, _) in &paid {
+        names.push(format!("**{}**", store.user(guild)?.name));
+    }
+    let mut lines = vec![match paid.len() {
+        _ if nobody => tr!(lang, "war-over-nobody"),
+        1 => tr!(lang, "war-over-won", guild = &names[0], prize = paid[0].1),
+        _ => tr!(lang, "war-over-tied", guilds = names.join(&format!(" {} ", tr!(lang, "and"))), prize = paid[0].1),
+    }];
+    lines.extend(standings(store, lang, &scores, None)?);
+    Ok(Some(lines.join("\n")))
+}
+
+// "1. **The Hollow**, 12 wins", `here` being marked.
+pub fn standings(store: &Storage, lang: &str, scores: &[Score], here: Option<i32>) -> Result<Vec<String>, String> {
+    let mut lines = Vec::new();
+    for (i, s) in scores.iter().enumerate() {
+        let mark = if Some(s.guild) == here { " ◀" } else { "" };
+        lines.push(format!("{}{}", tr!(lang, "war-standing", place = i + 1, guild = store.user(s.guild)?.name, wins = s.score), mark));
+    }
+    Ok(lines)
+}
+
+// Posts `text` in the channel of every guild in the war, returns lines for
+// the log about the ones it couldn't.
+pub fn announce(store: &Storage, war: &GuildWar, text: &str) -> Result<Vec<String>, String> {
+    let mut failed = Vec::new();
+    for score in store.war_scores(war.id)? {
+        if let Some(channel) = store.guild(score.guild)?.channel {
+            if let Err(why) = ChannelId(channel as u64).say(text) {
+                failed.push(format!("couldn't announce war {} to guild {}: {:?}", war.id, score.guild, why));
+            }
+        }
+    }
+    Ok(failed)
+}
+
+// Scoring and payouts.
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use testing::{self, NOON};
+
+    fn score(guild: i32, score: i32) -> Score {
+        Score { war: 1, guild: guild, score: score }
+    }
+
+    // Two guilds with a channel and the stake, and a player in each, as
+    // ([first account, second account], [first player, second player]).
+    fn guilds(store: &Storage) -> Result<([i32; 2], [i32; 2]), String> {
+        let a = store.create_user(1, "a")?;
+        let b = store.create_user(2, "b")?;
+        let first = store.create_guild(11, "First", a.id)?;
+        let second = store.create_guild(12, "Second", b.id)?;
+        for &(guild, channel) in &[(first.id, 21), (second.id, 22)] {
+            store.set_guild_channel(guild, Some(channel))?;
+            store.add_vults(guild, STAKE, "check")?;
+        }
+        Ok(([first.id, second.id], [a.id, b.id]))
+    }
+
+    #[test]
+    fn payouts_add_up_to_the_prize() -> Result<(), String> {
+        let standings = [vec![score(1, 2), score(2, 2), score(3, 2)], vec![score(1, 0), score(2, 0), score(3, 0)],
+                         vec![score(1, 3), score(2, 3), score(3, 1)], vec![score(1, 1)]];
+        for scores in &standings {
+            for &prize in &[1000, 2000, 3000, 3001, 7] {
+                let paid = payouts(scores, prize);
+                ensure!(paid.iter().map(|&(_, vults)| vults).sum::<i32>() == prize, "paid {:?} out of {}", paid, prize);
+            }
+        }
+        Ok(())
+    }
+
+    #[test]
+    fn the_best_score_takes_the_prize() -> Result<(), String> {
+        ensure!(payouts(&[score(1, 5), score(2, 3)], 2000) == vec![(1, 2000)], "the winner didn't take it all");
+        ensure!(payouts(&[score(1, 4), score(2, 4), score(3, 1)], 2000) == vec![(1, 1000), (2, 1000)], "the tie wasn't split");
+        ensure!(payouts(&[score(1, 1), score(2, 1), score(3, 1)], 2000) == vec![(1, 667), (2, 667), (3, 666)],
+                "a three way split paid {:?}", payouts(&[score(1, 1), score(2, 1), score(3, 1)], 2000));
+        ensure!(payouts(&[score(1, 0), score(2, 0)], 2000) == vec![(1, 1000), (2, 1000)], "stakes weren't given back");
+        Ok(())
+    }
+
+    #[test]
+    fn wars_open_between_guilds_with_a_channel() -> Result<(), String> {
+        let store = testing::store();
+        let a = store.create_user(1, "a")?;
+        let first = store.create_guild(11, "First", a.id)?;
+        let second = store.create_guild(12, "Second", a.id)?;
+        let quiet = store.create_guild(13, "Quiet", a.id)?;
+        let poor = store.create_guild(14, "Poor", a.id)?;
+        for guild in &[first.id, second.id, quiet.id] {
+            store.add_vults(*guild, STAKE, "check")?;
+        }
+        store.add_vults(poor.id, STAKE - 1, "check")?;
+        store.set_guild_channel(first.id, Some(21))?;
+        store.set_guild_channel(poor.id, Some(24))?;
+        ensure!(open(&store, NOON)?.is_none(), "a guild went to war alone");
+
+        store.set_guild_channel(second.id, Some(22))?;
+        let war = open(&store, NOON)?.ok_or("no war opened")?;
+        ensure!(war.ends == NOON + LENGTH && war.prize == 2 * STAKE, "opened {:?}", war);
+        let guilds: Vec<i32> = store.war_scores(war.id)?.iter().map(|s| s.guild).collect();
+        ensure!(guilds == vec![first.id, second.id], "{:?} went to war", guilds);
+        ensure!(store.user(first.id)?.vults == 0 && store.user(poor.id)?.vults == STAKE - 1, "treasuries hold {} and {}",
+                store.user(first.id)?.vults, store.user(poor.id)?.vults);
+        ensure!(open(&store, NOON + 60)?.is_none(), "a war opened while one ran");
+        store.add_vults(first.id, STAKE, "check")?;
+        store.add_vults(second.id, STAKE, "check")?;
+        ensure!(open(&store, NOON + LENGTH)?.is_some(), "no war opened after the last one ended");
+        Ok(())
+    }
+
+    #[test]
+    fn members_wins_over_other_guilds_score_while_the_war_runs() -> Result<(), String> {
+        let store = testing::store();
+        let ([first, second], [a, b]) = guilds(&store)?;
+        let outsider = store.create_user(3, "c")?.id;
+        let teammate = store.create_user(4, "d")?.id;
+        store.join_guild(first, teammate)?;
+        let won = |winner: i32, loser: i32| Event::BattleFinished { players: [loser, winner], winner: Some(winner) };
+
+        on_event(&store, "en", &won(a, b), NOON)?;
+        let war = open(&store, NOON)?.ok_or("no war opened")?;
+        on_event(&store, "en", &won(a, b), NOON + 60)?;
+        on_event(&store, "en", &won(a, b), NOON + 120)?;
+        on_event(&store, "en", &won(b, a), NOON + 120)?;
+        on_event(&store, "en", &won(a, outsider), NOON + 120)?;
+        on_event(&store, "en", &won(outsider, b), NOON + 120)?;
+        on_event(&store, "en", &won(a, teammate), NOON + 120)?;
+        on_event(&store, "en", &Event::BattleFinished { players: [a, b], winner: None }, NOON + 120)?;
+        on_event(&store, "en", &won(b, a), NOON + LENGTH)?;
+        let scores: Vec<(i32, i32)> = store.war_scores(war.id)?.iter().map(|s| (s.guild, s.score)).collect();
+        ensure!(scores == vec![(first, 2), (second, 1)], "scores are {:?}", scores);
+
+        // Beating a member of both guilds scores for neither.
+        store.join_guild(second, teammate)?;
+        on_event(&store, "en", &won(a, teammate), NOON + 180)?;
+        on_event(&store, "en", &won(b, teammate), NOON + 180)?;
+        let scores: Vec<(i32, i32)> = store.war_scores(war.id)?.iter().map(|s| (s.guild, s.score)).collect();
+        ensure!(scores == vec![(first, 2), (second, 1)], "scores are {:?} after battles with a member of both", scores);
+        Ok(())
+    }
+
+    #[test]
+    fn wars_are_settled_once() -> Result<(), String> {
+        let store = testing::store();
+        let ([first, second], [a, b]) = guilds(&store)?;
+        let war = open(&store, NOON)?.ok_or("no war opened")?;
+        on_event(&store, "en", &Event::BattleFinished { players: [a, b], winner: Some(a) }, NOON + 60)?;
+
+        let results = settle(&store, "en", &war)?.ok_or("the war wasn't settled")?;
+        ensure!(results.contains("**First** wins 2000 vults"), "results are {}", results);
+        ensure!(settle(&store, "en", &war)?.is_none(), "the war was settled twice");
+        ensure!(store.user(first)?.vults == 2 * STAKE && store.user(second)?.vults == 0, "treasuries hold {} and {}",
+                store.user(first)?.vults, store.user(second)?.vults);
+
+        store.add_vults(second, STAKE, "check")?;
+        let quiet = open(&store, NOON + LENGTH)?.ok_or("no second war opened")?;
+        let results = settle(&store, "en", &quiet)?.ok_or("the quiet war wasn't settled")?;
+        ensure!(results.contains("nobody beat another server"), "results are {}", results);
+        ensure!(store.user(first)?.vults == 2 * STAKE && store.user(second)?.vults == STAKE, "stakes back left {} and {}",
+                store.user(first)?.vults, store.user(second)?.vults);
+        ensure!(store.ledger(second, 1)?[0].reason == "stake back from a guild war", "the stake back isn't in the ledger");
+        Ok(())
+    }
+}